@@ -38,6 +38,8 @@ pub enum CardType {
     EM4x50,
     Hitag,
     // HF types
+    /// MIFARE Mini: 5 sectors (320 bytes), SAK 0x09.
+    MifareMini,
     MifareClassic1K,
     MifareClassic4K,
     MifareUltralight,
@@ -75,7 +77,8 @@ impl CardType {
             | CardType::EM4x50
             | CardType::Hitag => Frequency::LF,
 
-            CardType::MifareClassic1K
+            CardType::MifareMini
+            | CardType::MifareClassic1K
             | CardType::MifareClassic4K
             | CardType::MifareUltralight
             | CardType::NTAG
@@ -113,6 +116,7 @@ impl CardType {
             CardType::COTAG => "COTAG",
             CardType::EM4x50 => "EM4x50",
             CardType::Hitag => "Hitag",
+            CardType::MifareMini => "MIFARE Mini",
             CardType::MifareClassic1K => "MIFARE Classic 1K",
             CardType::MifareClassic4K => "MIFARE Classic 4K",
             CardType::MifareUltralight => "MIFARE Ultralight",
@@ -132,12 +136,27 @@ impl CardType {
         }
     }
 
+    /// Sector count of a MIFARE Classic family card (`None` for other types).
+    pub fn mifare_sectors(&self) -> Option<u16> {
+        match self {
+            CardType::MifareMini => Some(5),
+            CardType::MifareClassic1K => Some(16),
+            CardType::MifareClassic4K => Some(40),
+            _ => None,
+        }
+    }
+
+    /// Keys to recover on a MIFARE Classic family card: A and B per sector.
+    pub fn mifare_key_total(&self) -> Option<u32> {
+        self.mifare_sectors().map(|s| u32::from(s) * 2)
+    }
+
     /// Reason why a card type cannot be cloned, if applicable.
     /// Provided for frontend display on non-cloneable card identification.
     #[allow(dead_code)]
     pub fn non_cloneable_reason(&self) -> Option<&str> {
         match self {
-            CardType::DESFire => Some("DESFire uses AES encryption; cloning not supported"),
+            CardType::DESFire => Some("DESFire uses AES encryption; only a UID-only clone is possible"),
             CardType::COTAG => Some("Read-only, no clone commands available"),
             CardType::EM4x50 => Some("Requires native EM4x50 blank, not T5577-compatible"),
             CardType::Hitag => Some("Requires native Hitag chip, not T5577-compatible"),
@@ -167,6 +186,26 @@ impl CardType {
         )
     }
 
+    /// Whether this card type can be cloned onto a Q5/T5555 blank (via the
+    /// `--q5` flag). Every LF type with a clone command takes it.
+    pub fn supports_q5(&self) -> bool {
+        self.supports_em4305()
+            || matches!(
+                self,
+                CardType::Presco
+                    | CardType::Nedap
+                    | CardType::GProxII
+                    | CardType::Gallagher
+                    | CardType::PAC
+                    | CardType::Noralsy
+                    | CardType::Jablotron
+                    | CardType::SecuraKey
+                    | CardType::Visa2000
+                    | CardType::Motorola
+                    | CardType::IDTECK
+            )
+    }
+
     pub fn recommended_blank(&self) -> BlankType {
         match self {
             // All LF cloneable types use T5577 by default
@@ -195,18 +234,222 @@ impl CardType {
             // Non-cloneable LF: return T5577 as placeholder (won't actually be used)
             CardType::COTAG | CardType::EM4x50 | CardType::Hitag => BlankType::T5577,
             // HF types
-            CardType::MifareClassic1K | CardType::MifareClassic4K => BlankType::MagicMifareGen1a,
+            CardType::MifareMini | CardType::MifareClassic1K | CardType::MifareClassic4K => {
+                BlankType::MagicMifareGen1a
+            }
             CardType::MifareUltralight => BlankType::MagicUltralight,
             CardType::NTAG => BlankType::MagicUltralight,
             CardType::DESFire => BlankType::MagicMifareGen4GTU,
             CardType::IClass => BlankType::IClassBlank,
         }
     }
+
+    /// Every card type, in declaration order. Kept complete by `all_index`.
+    pub const ALL: [CardType; 32] = [
+        CardType::EM4100,
+        CardType::HIDProx,
+        CardType::Indala,
+        CardType::IOProx,
+        CardType::AWID,
+        CardType::FDX_B,
+        CardType::Paradox,
+        CardType::Viking,
+        CardType::Pyramid,
+        CardType::Keri,
+        CardType::NexWatch,
+        CardType::Presco,
+        CardType::Nedap,
+        CardType::GProxII,
+        CardType::Gallagher,
+        CardType::PAC,
+        CardType::Noralsy,
+        CardType::Jablotron,
+        CardType::SecuraKey,
+        CardType::Visa2000,
+        CardType::Motorola,
+        CardType::IDTECK,
+        CardType::COTAG,
+        CardType::EM4x50,
+        CardType::Hitag,
+        CardType::MifareMini,
+        CardType::MifareClassic1K,
+        CardType::MifareClassic4K,
+        CardType::MifareUltralight,
+        CardType::NTAG,
+        CardType::DESFire,
+        CardType::IClass,
+    ];
+
+    /// Position in `ALL`. Exhaustive on purpose: a new variant doesn't
+    /// compile until it gets a position here, and the const check below
+    /// fails until `ALL` holds it at that position.
+    const fn all_index(&self) -> usize {
+        match self {
+            CardType::EM4100 => 0,
+            CardType::HIDProx => 1,
+            CardType::Indala => 2,
+            CardType::IOProx => 3,
+            CardType::AWID => 4,
+            CardType::FDX_B => 5,
+            CardType::Paradox => 6,
+            CardType::Viking => 7,
+            CardType::Pyramid => 8,
+            CardType::Keri => 9,
+            CardType::NexWatch => 10,
+            CardType::Presco => 11,
+            CardType::Nedap => 12,
+            CardType::GProxII => 13,
+            CardType::Gallagher => 14,
+            CardType::PAC => 15,
+            CardType::Noralsy => 16,
+            CardType::Jablotron => 17,
+            CardType::SecuraKey => 18,
+            CardType::Visa2000 => 19,
+            CardType::Motorola => 20,
+            CardType::IDTECK => 21,
+            CardType::COTAG => 22,
+            CardType::EM4x50 => 23,
+            CardType::Hitag => 24,
+            CardType::MifareMini => 25,
+            CardType::MifareClassic1K => 26,
+            CardType::MifareClassic4K => 27,
+            CardType::MifareUltralight => 28,
+            CardType::NTAG => 29,
+            CardType::DESFire => 30,
+            CardType::IClass => 31,
+        }
+    }
+
+    /// What the card type is used for, for grouping in the UI.
+    pub fn category(&self) -> CardCategory {
+        match self {
+            CardType::EM4100
+            | CardType::HIDProx
+            | CardType::Indala
+            | CardType::IOProx
+            | CardType::AWID
+            | CardType::Paradox
+            | CardType::Viking
+            | CardType::Pyramid
+            | CardType::Keri
+            | CardType::NexWatch
+            | CardType::Presco
+            | CardType::Nedap
+            | CardType::GProxII
+            | CardType::Gallagher
+            | CardType::PAC
+            | CardType::Noralsy
+            | CardType::Jablotron
+            | CardType::SecuraKey
+            | CardType::Visa2000
+            | CardType::Motorola
+            | CardType::IDTECK
+            | CardType::COTAG
+            | CardType::EM4x50
+            | CardType::MifareMini
+            | CardType::MifareClassic1K
+            | CardType::MifareClassic4K
+            | CardType::DESFire
+            | CardType::IClass => CardCategory::Access,
+            CardType::FDX_B => CardCategory::Animal,
+            CardType::Hitag => CardCategory::Industrial,
+            CardType::MifareUltralight
+            | CardType::NTAG => CardCategory::Nfc,
+        }
+    }
+
+    /// One-line description for UI display.
+    pub fn description(&self) -> &'static str {
+        match self {
+            CardType::EM4100 => "Read-only 64-bit ID badge, the most common 125 kHz tag",
+            CardType::HIDProx => "HID ProxCard / ISOProx with a Wiegand facility code and number",
+            CardType::Indala => "Motorola/HID Indala PSK badge",
+            CardType::IOProx => "Kantech ioProx FSK badge",
+            CardType::AWID => "AWID FSK badge with a Wiegand facility code and card number",
+            CardType::FDX_B => "ISO 11784/11785 animal ID implant or tag",
+            CardType::Paradox => "Paradox alarm and access FSK badge",
+            CardType::Viking => "Viking 32-bit ID badge",
+            CardType::Pyramid => "Farpointe Pyramid FSK badge",
+            CardType::Keri => "Keri Systems PSK badge",
+            CardType::NexWatch => "Honeywell NexWatch / Nexkey PSK badge",
+            CardType::Presco => "Presco badge with a site code and user code",
+            CardType::Nedap => "Nedap badge with a subtype, customer code and ID",
+            CardType::GProxII => "Guardall G-Prox II badge",
+            CardType::Gallagher => "Gallagher (Cardax) badge with region, facility and issue level",
+            CardType::PAC => "PAC / Stanley badge",
+            CardType::Noralsy => "Noralsy residential access badge",
+            CardType::Jablotron => "Jablotron alarm system key fob",
+            CardType::SecuraKey => "SecuraKey Radio Key badge",
+            CardType::Visa2000 => "Visa2000 badge",
+            CardType::Motorola => "Motorola Flexpass badge",
+            CardType::IDTECK => "IDTECK PSK badge",
+            CardType::COTAG => "Cotag 132 kHz hands-free badge (read-only)",
+            CardType::EM4x50 => "EM4x50 read/write chip with password protection",
+            CardType::Hitag => "NXP Hitag chip used in immobilisers and industrial ID",
+            CardType::MifareMini => "MIFARE Classic Mini, 5 sectors",
+            CardType::MifareClassic1K => "MIFARE Classic 1K, 16 sectors, Crypto1 keys",
+            CardType::MifareClassic4K => "MIFARE Classic 4K, 40 sectors, Crypto1 keys",
+            CardType::MifareUltralight => "MIFARE Ultralight NFC tag, ticket or wristband",
+            CardType::NTAG => "NTAG21x NFC tag or sticker",
+            CardType::DESFire => "MIFARE DESFire with AES/3DES application keys",
+            CardType::IClass => "HID iCLASS 13.56 MHz badge",
+        }
+    }
+
+    /// Display metadata for the UI, so labels follow the enum instead of
+    /// being kept in sync by hand on the frontend.
+    pub fn metadata(&self) -> CardMetadata {
+        CardMetadata {
+            card_type: self.clone(),
+            name: self.display_name().to_string(),
+            frequency: self.frequency(),
+            category: self.category(),
+            description: self.description(),
+            cloneable: self.is_cloneable(),
+        }
+    }
+}
+
+// `ALL` lists every variant exactly once, at its `all_index`
+const _: () = {
+    let mut i = 0;
+    while i < CardType::ALL.len() {
+        assert!(CardType::ALL[i].all_index() == i);
+        i += 1;
+    }
+};
+
+/// What a card type is used for.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CardCategory {
+    /// Door and building access badges and fobs.
+    Access,
+    /// Animal identification (pets, livestock).
+    Animal,
+    /// Immobilisers, asset tags and other industrial ID.
+    Industrial,
+    /// General-purpose NFC tags.
+    Nfc,
+}
+
+/// Returned by `CardType::metadata` and the `card_metadata` command.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CardMetadata {
+    pub card_type: CardType,
+    pub name: String,
+    pub frequency: Frequency,
+    pub category: CardCategory,
+    pub description: &'static str,
+    pub cloneable: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum BlankType {
     T5577,
+    /// Q5 (T5555): cheap T55xx-family blank whose config block differs, so
+    /// clone commands need `--q5`.
+    Q5,
     EM4305,
     MagicMifareGen1a,
     MagicMifareGen2,
@@ -218,11 +461,11 @@ pub enum BlankType {
 }
 
 impl BlankType {
-    /// Human-readable name for UI display. Used by frontend via serde serialization.
-    #[allow(dead_code)]
+    /// Human-readable name for UI display and blank compatibility errors.
     pub fn display_name(&self) -> &str {
         match self {
             BlankType::T5577 => "T5577",
+            BlankType::Q5 => "Q5 (T5555)",
             BlankType::EM4305 => "EM4305",
             BlankType::MagicMifareGen1a => "Magic MIFARE Gen1a",
             BlankType::MagicMifareGen2 => "Magic MIFARE Gen2 (CUID)",
@@ -243,6 +486,22 @@ pub struct T5577Status {
     pub password_set: bool,
     pub block0: Option<String>,
     pub modulation: Option<String>,
+    /// Block 0 has the OTP flag set: the chip is write-protected, so the
+    /// clone keeps the blank's current modulation.
+    pub locked: bool,
+}
+
+impl T5577Status {
+    /// Blank type `lf t55xx detect` points to: a T5555 chip (reported as
+    /// "Q5/T5555" by current clients) is a Q5.
+    pub fn blank_type(&self) -> BlankType {
+        let chip = self.chip_type.to_ascii_uppercase();
+        if chip == "T5555" || chip == "Q5/T5555" {
+            BlankType::Q5
+        } else {
+            BlankType::T5577
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -265,6 +524,10 @@ pub enum RecoveryAction {
     GoBack,
     Reconnect,
     Manual,
+    /// Ask the user for a T5577 password and retry with it.
+    EnterPassword,
+    /// Show the UID warning and retry the write once the user accepts it.
+    AcknowledgeUidWarning,
 }
 
 /// HF card processing phases for autopwn progress tracking.
@@ -313,3 +576,21 @@ pub enum MagicGeneration {
     Gen4GTU,
     Gen4GDM,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_card_type_has_metadata() {
+        for card_type in CardType::ALL {
+            let meta = card_type.metadata();
+            assert_eq!(meta.card_type, card_type);
+            assert!(!meta.name.is_empty(), "{:?}", card_type);
+            assert!(!meta.description.is_empty(), "{:?}", card_type);
+            assert_eq!(meta.frequency, card_type.frequency());
+        }
+        assert_eq!(CardType::FDX_B.metadata().category, CardCategory::Animal);
+        assert_eq!(CardType::HIDProx.metadata().name, "HID Prox");
+    }
+}
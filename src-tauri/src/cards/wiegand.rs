@@ -0,0 +1,327 @@
+use serde::Serialize;
+
+// Wiegand frame decoding for raw values pasted from reader diagnostics.
+// Bits are numbered from the MSB (bit 0 = leading parity bit), matching the
+// HID format documentation.
+
+/// How a format's parity bits are checked.
+enum Parity {
+    /// Leading even and trailing odd parity bit, each over a contiguous run
+    /// `(first_bit, width)` that includes the parity bit itself.
+    Split { even: (u32, u32), odd: (u32, u32) },
+    /// Formats whose parity covers interleaved bits.
+    Custom(fn(u64) -> bool),
+}
+
+/// Field layout of one Wiegand format. Ranges are `(first_bit, width)`.
+struct WiegandFormat {
+    name: &'static str,
+    bits: u32,
+    facility: Option<(u32, u32)>,
+    card: (u32, u32),
+    parity: Parity,
+}
+
+const FORMATS: &[WiegandFormat] = &[
+    WiegandFormat {
+        name: "H10301",
+        bits: 26,
+        facility: Some((1, 8)),
+        card: (9, 16),
+        parity: Parity::Split {
+            even: (0, 13),
+            odd: (13, 13),
+        },
+    },
+    WiegandFormat {
+        name: "H10306",
+        bits: 34,
+        facility: Some((1, 16)),
+        card: (17, 16),
+        parity: Parity::Split {
+            even: (0, 17),
+            odd: (17, 17),
+        },
+    },
+    WiegandFormat {
+        name: "H10304",
+        bits: 37,
+        facility: Some((1, 16)),
+        card: (17, 19),
+        parity: Parity::Split {
+            even: (0, 19),
+            odd: (18, 19),
+        },
+    },
+    WiegandFormat {
+        name: "H10302",
+        bits: 37,
+        facility: None,
+        card: (1, 35),
+        parity: Parity::Split {
+            even: (0, 19),
+            odd: (18, 19),
+        },
+    },
+    WiegandFormat {
+        name: "C1k48s",
+        bits: 48,
+        facility: Some((2, 22)),
+        card: (24, 23),
+        parity: Parity::Custom(corporate_1000_48_parity),
+    },
+];
+
+/// Formats known by name whose bit layout isn't public (Indala 224-bit
+/// frames are scrambled per site). Accepted as a hint, never decoded.
+const UNDOCUMENTED_FORMATS: &[&str] = &["Indala224"];
+
+/// Mask with the given MSB-first bit positions of a `bits`-long frame set.
+fn bit_mask(bits: u32, positions: impl Iterator<Item = u32>) -> u64 {
+    positions.fold(0, |mask, p| mask | 1 << (bits - 1 - p))
+}
+
+/// HID Corporate 1000 48-bit: bit 1 is even parity over bits 2,3,5,6..44,45
+/// (two of every three), bit 47 is odd parity over bits 1,2,4,5..43,44,46,
+/// and bit 0 is odd parity over the whole frame.
+fn corporate_1000_48_parity(frame: u64) -> bool {
+    let even = bit_mask(48, (1..46).filter(|i| *i == 1 || i % 3 != 1));
+    let odd = bit_mask(48, (1..48).filter(|i| *i == 47 || i % 3 != 0));
+    (frame & even).count_ones().is_multiple_of(2)
+        && (frame & odd).count_ones() % 2 == 1
+        && (frame & ((1u64 << 48) - 1)).count_ones() % 2 == 1
+}
+
+/// One interpretation of a raw value under a specific format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WiegandDecode {
+    pub format: &'static str,
+    pub bit_length: u32,
+    pub facility_code: Option<u32>,
+    pub card_number: u64,
+    pub parity_valid: bool,
+    /// The frame sat behind a sentinel/preamble bit (HID raw, iCLASS block 7)
+    /// rather than being the bare Wiegand bits.
+    pub sentinel: bool,
+}
+
+impl WiegandFormat {
+    fn field(&self, frame: u64, (start, width): (u32, u32)) -> u64 {
+        (frame >> (self.bits - start - width)) & ((1u64 << width) - 1)
+    }
+
+    fn parity_valid(&self, frame: u64) -> bool {
+        match self.parity {
+            Parity::Split { even, odd } => {
+                self.field(frame, even).count_ones().is_multiple_of(2)
+                    && self.field(frame, odd).count_ones() % 2 == 1
+            }
+            Parity::Custom(check) => check(frame),
+        }
+    }
+
+    fn matches_hint(&self, hint: &str) -> bool {
+        self.name.eq_ignore_ascii_case(hint) || hint.parse::<u32>().ok() == Some(self.bits)
+    }
+
+    /// Split `value` into the Wiegand frame and whether it carried a sentinel.
+    /// Bits above the frame must be empty, a single sentinel bit, or a
+    /// sentinel plus the HID long-format header bit at 37.
+    fn frame(&self, value: u64) -> Option<(u64, bool)> {
+        let frame = value & ((1u64 << self.bits) - 1);
+        let upper = value >> self.bits;
+        let hid_header = if self.bits < 37 {
+            1 | (1 << (37 - self.bits))
+        } else {
+            1
+        };
+        match upper {
+            0 => Some((frame, false)),
+            u if u == 1 || u == hid_header => Some((frame, true)),
+            _ => None,
+        }
+    }
+
+    fn decode(&self, value: u64) -> Option<WiegandDecode> {
+        let (frame, sentinel) = self.frame(value)?;
+        Some(WiegandDecode {
+            format: self.name,
+            bit_length: self.bits,
+            facility_code: self.facility.map(|f| self.field(frame, f) as u32),
+            card_number: self.field(frame, self.card),
+            parity_valid: self.parity_valid(frame),
+            sentinel,
+        })
+    }
+}
+
+/// Names accepted as a format hint (bit lengths are accepted too).
+pub fn known_formats() -> Vec<&'static str> {
+    FORMATS
+        .iter()
+        .map(|f| f.name)
+        .chain(UNDOCUMENTED_FORMATS.iter().copied())
+        .collect()
+}
+
+/// Whether the format named `name` has a facility code field. `None` for
+/// names not in the table.
+pub fn has_facility_code(name: &str) -> Option<bool> {
+    FORMATS
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(name))
+        .map(|f| f.facility.is_some())
+}
+
+fn is_undocumented(hint: &str) -> bool {
+    UNDOCUMENTED_FORMATS
+        .iter()
+        .any(|f| f.eq_ignore_ascii_case(hint))
+        || hint == "224"
+}
+
+/// Decode a raw hex value under every known format (or only those matching
+/// `hint`, by name or bit length). Results are ranked: valid parity first,
+/// then sentinel-framed values (their length is unambiguous), then shorter
+/// formats. Spaces, colons and a `0x` prefix are ignored. A hint naming a
+/// format with an undocumented layout (e.g. `Indala224`) yields no results.
+pub fn decode_hex(hex: &str, hint: Option<&str>) -> Result<Vec<WiegandDecode>, String> {
+    let clean: String = hex
+        .trim()
+        .trim_start_matches("0x")
+        .trim_start_matches("0X")
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect();
+    let hint = hint.map(str::trim).filter(|h| !h.is_empty());
+    if hint.is_some_and(is_undocumented) {
+        // Any length: these frames don't fit in 64 bits anyway
+        if clean.is_empty() || !clean.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid raw value: must be hex, got '{}'", hex));
+        }
+        return Ok(Vec::new());
+    }
+    if clean.is_empty() || clean.len() > 16 || !clean.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid raw value: must be 1-16 hex characters, got '{}'",
+            hex
+        ));
+    }
+    let value = u64::from_str_radix(&clean, 16).map_err(|e| e.to_string())?;
+
+    if let Some(h) = hint {
+        if !FORMATS.iter().any(|f| f.matches_hint(h)) {
+            return Err(format!(
+                "Unknown Wiegand format '{}'. Known: {}",
+                h,
+                known_formats().join(", ")
+            ));
+        }
+    }
+
+    let mut results: Vec<WiegandDecode> = FORMATS
+        .iter()
+        .filter(|f| hint.is_none_or(|h| f.matches_hint(h)))
+        .filter_map(|f| f.decode(value))
+        .collect();
+    results.sort_by_key(|d| (!d.parity_valid, !d.sentinel, d.bit_length));
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn h10301_from_hid_raw() {
+        // PM3 `lf hid` raw for FC 55 CN 1337: header + sentinel + 26-bit frame
+        let results = decode_hex("20066E0A73", None).unwrap();
+        let best = &results[0];
+        assert_eq!(best.format, "H10301");
+        assert_eq!(best.facility_code, Some(55));
+        assert_eq!(best.card_number, 1337);
+        assert!(best.parity_valid && best.sentinel);
+    }
+
+    #[test]
+    fn ambiguous_value_26_and_37_bit() {
+        // Bare 26-bit frame FC 100 CN 1 — read as 37 bits with leading zeros
+        // its parity also checks out, giving a different FC/CN
+        let results = decode_hex("0x02C80002", None).unwrap();
+        let h10301 = results.iter().find(|d| d.format == "H10301").unwrap();
+        assert_eq!((h10301.facility_code, h10301.card_number), (Some(100), 1));
+        assert!(h10301.parity_valid);
+
+        let h10304 = results.iter().find(|d| d.format == "H10304").unwrap();
+        assert_eq!(h10304.bit_length, 37);
+        assert_eq!(
+            (h10304.facility_code, h10304.card_number),
+            (Some(44), 262145)
+        );
+        assert!(h10304.parity_valid);
+
+        let h10302 = results.iter().find(|d| d.format == "H10302").unwrap();
+        assert_eq!((h10302.facility_code, h10302.card_number), (None, 23330817));
+
+        // All parse with valid parity; the shorter format ranks first
+        assert_eq!(results[0].format, "H10301");
+    }
+
+    #[test]
+    fn hint_restricts_formats() {
+        let results = decode_hex("2C80002", Some("37")).unwrap();
+        assert!(results.iter().all(|d| d.bit_length == 37));
+        assert_eq!(results.len(), 2);
+        assert_eq!(decode_hex("2C80002", Some("h10301")).unwrap().len(), 1);
+        assert!(decode_hex("2C80002", Some("H99999")).is_err());
+    }
+
+    #[test]
+    fn bad_parity_ranked_last() {
+        // FC 100 CN 1 with the trailing odd parity bit flipped
+        let results = decode_hex("2C80003", Some("26")).unwrap();
+        assert!(!results[0].parity_valid);
+    }
+
+    #[test]
+    fn corporate_1000_48_bit() {
+        // C1k48s FC 123 CN 4567, bare frame and behind the HID sentinel bit
+        for raw in ["40007B0023AE", "140007B0023AE"] {
+            let results = decode_hex(raw, None).unwrap();
+            let best = &results[0];
+            assert_eq!(best.format, "C1k48s", "{}", raw);
+            assert_eq!(best.bit_length, 48);
+            assert_eq!(best.facility_code, Some(123));
+            assert_eq!(best.card_number, 4567);
+            assert!(best.parity_valid);
+        }
+        assert!(decode_hex("140007B0023AE", None).unwrap()[0].sentinel);
+
+        // Full-width 22-bit company ID
+        let best = &decode_hex("FFFFFF000002", Some("48")).unwrap()[0];
+        assert_eq!((best.facility_code, best.card_number), (Some(4_194_303), 1));
+        assert!(best.parity_valid);
+
+        // Any single flipped bit breaks one of the three parity checks
+        let flipped = &decode_hex("40007B0023AF", Some("C1k48s")).unwrap()[0];
+        assert!(!flipped.parity_valid);
+    }
+
+    #[test]
+    fn undocumented_layout_not_guessed() {
+        let indala224 = "80000001B23523A6C2E31EBA3CBEE4AFB3C6AD1FCF649393928C14E5";
+        assert_eq!(decode_hex(indala224, Some("Indala224")).unwrap(), vec![]);
+        assert_eq!(decode_hex(indala224, Some("224")).unwrap(), vec![]);
+        assert!(known_formats().contains(&"Indala224"));
+        // Without the hint it's just too long for a Wiegand frame
+        assert!(decode_hex(indala224, None).is_err());
+    }
+
+    #[test]
+    fn invalid_hex_rejected() {
+        assert!(decode_hex("", None).is_err());
+        assert!(decode_hex("XYZ", None).is_err());
+        assert!(decode_hex("11223344556677889", None).is_err());
+    }
+}
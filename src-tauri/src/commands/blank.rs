@@ -1,8 +1,11 @@
+use serde::Serialize;
 use std::sync::Mutex;
 use tauri::{AppHandle, State};
 
-use crate::cards::types::{BlankType, MagicGeneration, RecoveryAction};
+use crate::cards::types::{BlankType, CardType, MagicGeneration, RecoveryAction};
 use crate::error::AppError;
+use crate::pm3::blank_compat::{self, BlankCompatibility, WriteTestVerdict};
+use crate::pm3::command_builder::KeyType;
 use crate::pm3::{command_builder, connection, output_parser};
 use crate::state::{WizardAction, WizardMachine, WizardState};
 
@@ -37,7 +40,7 @@ pub async fn detect_blank(
 
     // Detect based on expected blank type
     match expected_blank {
-        BlankType::T5577 => detect_t5577(&app, &port, &machine).await,
+        BlankType::T5577 | BlankType::Q5 => detect_t5577(&app, &port, &machine).await,
         BlankType::EM4305 => detect_em4305(&app, &port, &machine).await,
         BlankType::MagicMifareGen1a
         | BlankType::MagicMifareGen2
@@ -51,8 +54,157 @@ pub async fn detect_blank(
     }
 }
 
+/// Check whether the magic blank on the reader can take a MIFARE Classic
+/// clone of `source_uid`: its generation must support the source UID length
+/// and, for 4K sources, have 4K of memory. Read-only; does not touch the FSM.
+#[tauri::command]
+pub async fn recommend_hf_blank(
+    app: AppHandle,
+    port: String,
+    source_card_type: CardType,
+    source_uid: String,
+) -> Result<BlankCompatibility, AppError> {
+    let source_is_4k = match source_card_type {
+        CardType::MifareMini | CardType::MifareClassic1K => false,
+        CardType::MifareClassic4K => true,
+        other => {
+            return Err(AppError::CommandFailed(format!(
+                "Blank recommendation only supports MIFARE Classic, got {:?}",
+                other
+            )));
+        }
+    };
+    let source_uid_len = source_uid.chars().filter(|c| c.is_ascii_hexdigit()).count() / 2;
+
+    let output = connection::run_command(&app, &port, command_builder::build_hf_mf_info()).await?;
+    let blank = output_parser::parse_14a_identity(&output).ok_or_else(|| {
+        AppError::CommandFailed(
+            "No card found. Place the magic blank on the reader and try again.".into(),
+        )
+    })?;
+    let generation = output_parser::parse_magic_detection(&output);
+
+    Ok(blank_compat::check_compatibility(
+        source_uid_len,
+        source_is_4k,
+        generation,
+        &blank,
+    ))
+}
+
+/// Outcome of `mifare_write_test`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteTestResult {
+    pub verdict: WriteTestVerdict,
+    /// Block 0 before the test, so it can be rewritten by hand if the
+    /// restore couldn't be confirmed.
+    pub original_block0: String,
+    pub message: String,
+}
+
+/// Default key the write test authenticates block 0 with.
+const WRITE_TEST_KEY: &str = "FFFFFFFFFFFF";
+
+/// Find out whether a MIFARE Classic card that `hf mf info` didn't flag as
+/// magic accepts direct block 0 writes (Gen2/CUID). This WRITES to the card:
+/// byte 15 of block 0 is changed, read back and restored, so the caller must
+/// pass `consent` after telling the user. Does not touch the FSM.
+#[tauri::command]
+pub async fn mifare_write_test(
+    app: AppHandle,
+    port: String,
+    consent: bool,
+) -> Result<WriteTestResult, AppError> {
+    if !consent {
+        return Err(AppError::CommandFailed(
+            "The write test briefly modifies block 0 and needs explicit consent.".into(),
+        ));
+    }
+
+    let info = connection::run_command(&app, &port, command_builder::build_hf_mf_info()).await?;
+    if let Some(gen) = output_parser::parse_magic_detection(&info) {
+        return Err(AppError::CommandFailed(format!(
+            "Card already identified as {:?}; no write test needed.",
+            gen
+        )));
+    }
+
+    let read_block0 = || async {
+        let cmd = command_builder::build_mf_rdbl(0, WRITE_TEST_KEY, KeyType::A);
+        connection::run_command(&app, &port, &cmd)
+            .await
+            .ok()
+            .and_then(|out| output_parser::parse_mf_rdbl(&out, 0))
+    };
+    let to_hex = |block: &[u8; 16]| block.iter().map(|b| format!("{:02X}", b)).collect::<String>();
+
+    let original = read_block0().await.ok_or_else(|| {
+        AppError::CommandFailed(
+            "Could not read block 0 with the default key; the write test needs it.".into(),
+        )
+    })?;
+    let original_hex = to_hex(&original);
+
+    // A genuine card rejects the write; PM3 reports that in the output, so
+    // the readback decides either way.
+    let pattern = blank_compat::write_test_pattern(&original);
+    let _ = connection::run_command(
+        &app,
+        &port,
+        &command_builder::build_mf_wrbl0(WRITE_TEST_KEY, &to_hex(&pattern)),
+    )
+    .await;
+    let after_write = read_block0().await;
+
+    let mut after_restore = None;
+    if after_write != Some(original) {
+        // Retry once: a failed restore leaves a modified manufacturer byte
+        for _ in 0..2 {
+            let _ = connection::run_command(
+                &app,
+                &port,
+                &command_builder::build_mf_wrbl0(WRITE_TEST_KEY, &original_hex),
+            )
+            .await;
+            after_restore = read_block0().await;
+            if after_restore == Some(original) {
+                break;
+            }
+        }
+    }
+
+    let verdict = blank_compat::write_test_verdict(&original, after_write, after_restore);
+    let message = match verdict {
+        WriteTestVerdict::Gen2 => {
+            "Block 0 accepted a direct write: this is a Gen2 (CUID) card. Block 0 was restored."
+                .to_string()
+        }
+        WriteTestVerdict::NotWritable => {
+            "Block 0 is read-only: not a direct-write card.".to_string()
+        }
+        WriteTestVerdict::Inconclusive => {
+            "Could not tell from the readback. Block 0 was restored.".to_string()
+        }
+        WriteTestVerdict::RestoreFailed => format!(
+            "Could not confirm block 0 was restored. Keep the card on the reader and rewrite it with: {}",
+            command_builder::build_mf_wrbl0(WRITE_TEST_KEY, &original_hex)
+        ),
+    };
+    if verdict == WriteTestVerdict::RestoreFailed {
+        log::error!("Write test restore failed; original block 0 {}", original_hex);
+    }
+
+    Ok(WriteTestResult {
+        verdict,
+        original_block0: original_hex,
+        message,
+    })
+}
+
 /// Run `lf t55xx detect` to confirm a T5577 is present, then `lf search` to
-/// check if the card already has data written to it.
+/// check if the card already has data written to it. A T5555 chip is
+/// reported as a Q5 blank so the write uses `--q5`.
 async fn detect_t5577(
     app: &AppHandle,
     port: &str,
@@ -75,8 +227,9 @@ async fn detect_t5577(
             AppError::CommandFailed(format!("State lock poisoned: {}", e))
         })?;
         m.transition(WizardAction::BlankReady {
-            blank_type: BlankType::T5577,
+            blank_type: status.blank_type(),
             existing_data_type,
+            uid_length_warning: None,
         })?;
         Ok(m.current.clone())
     } else {
@@ -135,6 +288,7 @@ async fn detect_em4305(
         m.transition(WizardAction::BlankReady {
             blank_type: BlankType::EM4305,
             existing_data_type,
+            uid_length_warning: None,
         })?;
         Ok(m.current.clone())
     } else {
@@ -185,12 +339,10 @@ async fn detect_magic_mifare(
     expected_blank: BlankType,
 ) -> Result<WizardState, AppError> {
     // Step 1: Check if any HF card is present via `hf 14a info`
-    let card_present = match connection::run_command(app, port, command_builder::build_hf_14a_info())
+    let identity_out = connection::run_command(app, port, command_builder::build_hf_14a_info())
         .await
-    {
-        Ok(output) => output_parser::is_hf_card_present(&output),
-        Err(_) => false,
-    };
+        .unwrap_or_default();
+    let card_present = output_parser::is_hf_card_present(&identity_out);
 
     if !card_present {
         let mut m = machine.lock().map_err(|e| {
@@ -216,6 +368,20 @@ async fn detect_magic_mifare(
 
     let expected_gen = expected_generation(&expected_blank);
 
+    // Warn now, not mid-write, if the blank can't present the source's UID length
+    let source_uid_len = machine
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))?
+        .source_uid_len;
+    let uid_length_warning = blank_compat::early_uid_length_warning(
+        source_uid_len,
+        detected_gen.as_ref(),
+        output_parser::parse_14a_identity(&identity_out).as_ref(),
+    );
+    if let Some(warning) = &uid_length_warning {
+        log::warn!("Blank UID length mismatch: {}", warning);
+    }
+
     match detected_gen {
         Some(ref gen) if Some(gen) == expected_gen.as_ref() => {
             // Perfect match — detected generation matches expected.
@@ -227,6 +393,7 @@ async fn detect_magic_mifare(
             m.transition(WizardAction::BlankReady {
                 blank_type: expected_blank,
                 existing_data_type: existing_data,
+                uid_length_warning,
             })?;
             Ok(m.current.clone())
         }
@@ -245,6 +412,7 @@ async fn detect_magic_mifare(
             m.transition(WizardAction::BlankReady {
                 blank_type: actual_blank.clone(),
                 existing_data_type: data_msg,
+                uid_length_warning,
             })?;
             Ok(m.current.clone())
         }
@@ -257,6 +425,7 @@ async fn detect_magic_mifare(
             m.transition(WizardAction::BlankReady {
                 blank_type: expected_blank,
                 existing_data_type: Some("No magic detected — card may be genuine".to_string()),
+                uid_length_warning: None,
             })?;
             Ok(m.current.clone())
         }
@@ -280,7 +449,7 @@ async fn check_mifare_data(
         }
         _ => {
             // Gen2/Gen3/Gen4GTU: try default key
-            command_builder::build_mf_rdbl(4, "FFFFFFFFFFFF")
+            command_builder::build_mf_rdbl(4, "FFFFFFFFFFFF", KeyType::A)
         }
     };
 
@@ -363,6 +532,7 @@ async fn detect_magic_ultralight(
                 m.transition(WizardAction::BlankReady {
                     blank_type: BlankType::MagicUltralight,
                     existing_data_type,
+                    uid_length_warning: None,
                 })?;
                 Ok(m.current.clone())
             } else {
@@ -415,6 +585,7 @@ async fn detect_iclass_blank(
         m.transition(WizardAction::BlankReady {
             blank_type: BlankType::IClassBlank,
             existing_data_type: None,
+            uid_length_warning: None,
         })?;
         Ok(m.current.clone())
     } else {
@@ -1,8 +1,13 @@
 use std::sync::Mutex;
 use tauri::{AppHandle, State};
 
+use crate::commands::firmware::FlashState;
 use crate::error::AppError;
-use crate::pm3::connection;
+use crate::pm3::capabilities::{
+    self, AboutInfo, DeviceCapabilities, DeviceSnapshot, FeatureStatus, Operation,
+};
+use crate::pm3::hw_status::{self, DeviceStatus};
+use crate::pm3::connection::{self, HfOperationState};
 use crate::state::{WizardAction, WizardMachine, WizardState};
 
 #[tauri::command]
@@ -19,7 +24,7 @@ pub async fn detect_device(
     }
 
     match connection::detect_device(&app).await {
-        Ok((port, model, firmware)) => {
+        Ok((port, model, firmware, fork)) => {
             let mut m = machine.lock().map_err(|e| {
                 AppError::CommandFailed(format!("State lock poisoned: {}", e))
             })?;
@@ -28,11 +33,14 @@ pub async fn detect_device(
                 model,
                 firmware,
             })?;
+            m.fork = Some(fork);
             Ok(m.current.clone())
         }
         Err(e) => {
             let err_msg = e.to_string();
-            let user_message = if err_msg.contains("spawn")
+            let user_message = if let Some(guidance) = e.user_message() {
+                guidance
+            } else if err_msg.contains("spawn")
                 || err_msg.contains("not found")
                 || err_msg.contains("No such file")
                 || err_msg.contains("program not found")
@@ -55,3 +63,138 @@ pub async fn detect_device(
         }
     }
 }
+
+/// Connection facts for the capability check, without touching the device.
+fn device_snapshot(
+    machine: &Mutex<WizardMachine>,
+    hf_state: &HfOperationState,
+    flash_state: &FlashState,
+) -> Result<DeviceSnapshot, AppError> {
+    let m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    let wizard_busy = matches!(
+        m.current,
+        WizardState::DetectingDevice
+            | WizardState::ScanningCard
+            | WizardState::HfProcessing { .. }
+            | WizardState::Writing { .. }
+            | WizardState::Verifying
+    );
+    let hf_busy = hf_state.child.lock().map(|c| c.is_some()).unwrap_or(true);
+    let flash_busy = flash_state.child.lock().map(|c| c.is_some()).unwrap_or(true);
+    Ok(DeviceSnapshot {
+        port: m.port.clone(),
+        model: m.model.clone(),
+        firmware: m.firmware.clone(),
+        busy: wizard_busy || hf_busy || flash_busy,
+    })
+}
+
+/// Report device model/firmware, optional hardware (flash, smartcard) and the
+/// operations currently available, so the frontend can feature-gate its UI.
+/// Runs `hw version` only when a device is connected and idle.
+#[tauri::command]
+pub async fn get_capabilities(
+    app: AppHandle,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+    flash_state: State<'_, FlashState>,
+) -> Result<DeviceCapabilities, AppError> {
+    let snapshot = device_snapshot(&machine, &hf_state, &flash_state)?;
+
+    let hw_version = match &snapshot.port {
+        Some(port) if !snapshot.busy => connection::run_command(&app, port, "hw version").await.ok(),
+        _ => None,
+    };
+
+    Ok(capabilities::build_capabilities(snapshot, hw_version.as_deref()))
+}
+
+/// Device housekeeping from `hw status`: uptime, free memory, flash
+/// dictionaries, standalone mode and battery where reported. Only offered on
+/// RDV4 hardware (`Operation::DeviceStatus`).
+#[tauri::command]
+pub async fn device_status(
+    app: AppHandle,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+    flash_state: State<'_, FlashState>,
+) -> Result<DeviceStatus, AppError> {
+    let snapshot = device_snapshot(&machine, &hf_state, &flash_state)?;
+    let port = match &snapshot.port {
+        Some(port) if !snapshot.busy => port.clone(),
+        Some(_) => return Err(AppError::CommandFailed("Device is busy".into())),
+        None => return Err(AppError::DeviceNotFound),
+    };
+
+    let hw_version = connection::run_command(&app, &port, "hw version").await?;
+    let caps = capabilities::build_capabilities(snapshot, Some(&hw_version));
+    if !caps.available_operations.contains(&Operation::DeviceStatus) {
+        return Err(AppError::CommandFailed(
+            "Device status is only available on Proxmark3 RDV4".into(),
+        ));
+    }
+
+    let output = connection::run_command(&app, &port, "hw status").await?;
+    Ok(hw_status::parse_hw_status(&output))
+}
+
+/// Whether the connected client/firmware knows `cmd_prefix` (e.g.
+/// `lf gallagher clone`), from its `-h` output. When that shows a command
+/// table instead of a usage block, it's compared with the parent's help: an
+/// unknown command falls back to the parent's table.
+#[tauri::command]
+pub async fn probe_command_support(
+    app: AppHandle,
+    cmd_prefix: String,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+    flash_state: State<'_, FlashState>,
+) -> Result<bool, AppError> {
+    let help_cmd = capabilities::build_help_probe(&cmd_prefix).map_err(AppError::CommandFailed)?;
+    let snapshot = device_snapshot(&machine, &hf_state, &flash_state)?;
+    let port = match &snapshot.port {
+        Some(port) if !snapshot.busy => port.clone(),
+        Some(_) => return Err(AppError::CommandFailed("Device is busy".into())),
+        None => return Err(AppError::DeviceNotFound),
+    };
+
+    // PM3 may exit non-zero for an unknown command; its message is the output
+    let help = match connection::run_command(&app, &port, &help_cmd).await {
+        Ok(output) => output,
+        Err(AppError::CommandFailed(detail)) => detail,
+        Err(e) => return Err(e),
+    };
+    if let Some(known) = capabilities::help_recognizes(&cmd_prefix, &help) {
+        return Ok(known);
+    }
+
+    let parent_cmd =
+        capabilities::build_parent_help_probe(&cmd_prefix).map_err(AppError::CommandFailed)?;
+    let parent_help = connection::run_command(&app, &port, &parent_cmd).await?;
+    Ok(!capabilities::same_help_listing(&help, &parent_help))
+}
+
+/// App version, cached PM3 model/version, build target and enabled features
+/// for bug reports and the About dialog. Never touches the device.
+#[tauri::command]
+pub fn about_info(machine: State<'_, Mutex<WizardMachine>>) -> Result<AboutInfo, AppError> {
+    let m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    Ok(capabilities::build_about_info(m.model.clone(), m.firmware.clone()))
+}
+
+/// Which firmware-dependent features the connected PM3 is expected to
+/// support, from the built-in compatibility matrix. Uses the version cached
+/// by `detect_device`; never touches the device.
+#[tauri::command]
+pub fn feature_availability(
+    machine: State<'_, Mutex<WizardMachine>>,
+) -> Result<Vec<FeatureStatus>, AppError> {
+    let m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    Ok(capabilities::feature_availability(m.firmware.as_deref(), m.fork))
+}
@@ -0,0 +1,665 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::models::SavedCard;
+use crate::db::{self, DataDir, Database};
+use crate::error::AppError;
+use crate::pm3::connection::HfOperationState;
+use crate::pm3::{dump_report, scan_cache};
+use crate::state::WizardMachine;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpReport {
+    /// Where the markdown report was written (next to the dump).
+    pub path: String,
+    pub report: String,
+}
+
+/// Write a human-readable markdown report for a MIFARE Classic dump (`.bin`
+/// or `.eml`). Keys come from `key_path`, or the PM3 key file that sits next
+/// to the dump (`hf-mf-<UID>-key.bin`), or the dump's own sector trailers.
+#[tauri::command]
+pub fn export_dump_report(
+    dump_path: String,
+    key_path: Option<String>,
+) -> Result<DumpReport, AppError> {
+    let dump_path = PathBuf::from(dump_path);
+    let dump = dump_report::load_dump(&dump_path)?;
+
+    let key_path = key_path.map(PathBuf::from).or_else(|| sibling_key_file(&dump_path));
+    let keys = match &key_path {
+        Some(path) => match std::fs::read(path) {
+            Ok(data) => Some(dump_report::parse_key_file(&data).ok_or_else(|| {
+                AppError::CommandFailed(format!("Malformed key file '{}'", path.display()))
+            })?),
+            Err(e) => {
+                log::warn!("Key file {} unreadable, using trailer keys: {}", path.display(), e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let report = dump_report::build_report(&dump, keys.as_deref())?;
+    let out_path = dump_path.with_file_name(format!(
+        "{}-report.md",
+        dump_path.file_stem().and_then(|s| s.to_str()).unwrap_or("dump")
+    ));
+    std::fs::write(&out_path, &report).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to write report {}: {}", out_path.display(), e))
+    })?;
+
+    Ok(DumpReport {
+        path: out_path.to_string_lossy().into_owned(),
+        report,
+    })
+}
+
+/// Delete the dump and key files from the last HF read and any archived
+/// source bundles, forget their paths, and drop cached scan results. Returns
+/// the files that were deleted. Of the HF read's files, only those PM3 itself
+/// names (`hf-*-dump*`, `hf-*-key*`) inside the data dir are ever removed.
+#[tauri::command]
+pub fn clear_sensitive_state(
+    app: AppHandle,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<Vec<String>, AppError> {
+    let data_dir = app.try_state::<DataDir>().ok_or_else(|| {
+        AppError::CommandFailed("Data directory not initialised".into())
+    })?;
+    let data_dir = &data_dir.inner().0;
+    let mut deleted = purge_tracked_files(&hf_state, data_dir)?;
+    if let Ok(mut sector_keys) = hf_state.sector_keys.lock() {
+        *sector_keys = None;
+    }
+    deleted.extend(purge_source_archives(data_dir)?);
+    scan_cache::invalidate(&app);
+    Ok(deleted)
+}
+
+/// Delete the bundles `archive_source_dump` kept: they carry the source's
+/// dump and recovered keys. Their saved library entries stay.
+fn purge_source_archives(data_dir: &Path) -> Result<Vec<String>, AppError> {
+    let dir = data_dir.join(SOURCE_ARCHIVE_DIR);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AppError::CommandFailed(format!(
+                "Failed to read {}: {}",
+                dir.display(),
+                e
+            )))
+        }
+    };
+    let mut deleted = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_bundle = path.extension().is_some_and(|ext| ext == "json")
+            && entry.file_type().is_ok_and(|t| t.is_file());
+        if !is_bundle {
+            continue;
+        }
+        std::fs::remove_file(&path).map_err(|e| {
+            AppError::CommandFailed(format!("Failed to delete {}: {}", path.display(), e))
+        })?;
+        deleted.push(path.to_string_lossy().into_owned());
+    }
+    Ok(deleted)
+}
+
+fn purge_tracked_files(
+    hf_state: &HfOperationState,
+    data_dir: &Path,
+) -> Result<Vec<String>, AppError> {
+    let [dump, key] = [&hf_state.dump_path, &hf_state.key_path].map(|slot| {
+        slot.lock()
+            .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))
+            .map(|mut path| path.take().map(PathBuf::from))
+    });
+    let (dump, key) = (dump?, key?);
+    // The key file PM3 wrote next to the dump goes too, even if never tracked
+    let key = key.or_else(|| dump.as_deref().and_then(sibling_key_file));
+
+    let mut deleted = Vec::new();
+    for path in [dump, key].into_iter().flatten() {
+        let confined = is_pm3_output_file(&path) && db::path_in_data_dir(data_dir, &path).is_some();
+        if !confined {
+            if path.exists() {
+                log::warn!(
+                    "Not deleting {}: not a PM3 dump or key file in the data directory",
+                    path.display()
+                );
+            }
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => deleted.push(path.to_string_lossy().into_owned()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(AppError::CommandFailed(format!(
+                    "Failed to delete {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// A regular file (not a symlink) named the way PM3 names its dumps and key
+/// files: `hf-<type>-<UID>-dump.bin`, `hf-mf-<UID>-key-001.bin`, etc.
+fn is_pm3_output_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return false;
+    };
+    let named = name.starts_with("hf-")
+        && matches!(ext, "bin" | "eml" | "json")
+        && (stem.contains("-dump") || stem.contains("-key"));
+    named
+        && std::fs::symlink_metadata(path)
+            .map(|m| m.file_type().is_file())
+            .unwrap_or(true) // already gone: nothing to guard
+}
+
+/// `hf-mf-<UID>-dump.bin` -> `hf-mf-<UID>-key.bin`, if that file exists.
+pub(crate) fn sibling_key_file(dump_path: &Path) -> Option<PathBuf> {
+    let name = dump_path.file_name()?.to_str()?;
+    let (prefix, _) = name.rsplit_once("-dump")?;
+    let candidate = dump_path.with_file_name(format!("{}-key.bin", prefix));
+    candidate.exists().then_some(candidate)
+}
+
+// ---------------------------------------------------------------------------
+// Card bundles
+// ---------------------------------------------------------------------------
+
+/// Bumped on incompatible changes to `CardBundle`.
+const CARD_BUNDLE_VERSION: u32 = 1;
+
+/// Bundles are small (a 4K dump is 8 KiB of hex); anything bigger isn't one.
+const MAX_BUNDLE_BYTES: u64 = 1024 * 1024;
+
+/// A file carried inside a bundle, bytes as hex.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub name: String,
+    pub data: String,
+}
+
+/// One card, self-contained: the library entry plus the dump and key files
+/// needed to write it, as a single JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardBundle {
+    pub version: u32,
+    pub exported_at: String,
+    /// PM3 model and firmware the card was read with, when known.
+    pub device: Option<String>,
+    pub card: SavedCard,
+    pub dump: Option<BundleFile>,
+    pub key: Option<BundleFile>,
+}
+
+fn bundle_file(path: &Path) -> Result<BundleFile, AppError> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::CommandFailed(format!("Bad file name: {}", path.display())))?;
+    let data = std::fs::read(path).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    Ok(BundleFile {
+        name: name.to_string(),
+        data: data.iter().map(|b| format!("{:02X}", b)).collect(),
+    })
+}
+
+/// Package `card` and the dump/key files (if any) into a bundle.
+fn build_card_bundle(
+    card: SavedCard,
+    device: Option<String>,
+    dump_path: Option<&Path>,
+    key_path: Option<&Path>,
+) -> Result<CardBundle, AppError> {
+    Ok(CardBundle {
+        version: CARD_BUNDLE_VERSION,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        device,
+        card: SavedCard { id: None, ..card },
+        dump: dump_path.map(bundle_file).transpose()?,
+        key: key_path.map(bundle_file).transpose()?,
+    })
+}
+
+/// Length of the header newer PM3 clients put before the pages in an
+/// `hf mfu dump` `.bin` (version, counters, signature).
+const MFU_DUMP_HEADER_LEN: usize = 56;
+
+/// Whether `dump` starts with `uid`: block 0 of a MIFARE Classic or iClass
+/// dump, or pages 0-1 (BCC0 skipped) of an Ultralight/NTAG dump.
+fn dump_carries_uid(dump: &[u8], uid: &[u8]) -> bool {
+    let ultralight = |pages: &[u8]| {
+        uid.len() == 7 && pages.len() >= 8 && pages[..3] == uid[..3] && pages[4..8] == uid[3..]
+    };
+    !uid.is_empty()
+        && (dump.starts_with(uid)
+            || ultralight(dump)
+            || dump.get(MFU_DUMP_HEADER_LEN..).is_some_and(ultralight))
+}
+
+/// Refuse to bundle files from a different card than `uid`: the dump must
+/// carry the UID (see `dump_carries_uid`; PM3's JSON dumps name it), and the
+/// key file must have PM3's `hf-<type>-<UID>-key` name.
+fn check_bundle_files(
+    uid: &str,
+    dump_path: Option<&Path>,
+    key_path: Option<&Path>,
+) -> Result<(), AppError> {
+    let uid: String = uid
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_uppercase();
+    let foreign = |path: &Path| {
+        AppError::CommandFailed(format!(
+            "{} is not from card {}, so it can't go in this card's bundle",
+            path.display(),
+            uid
+        ))
+    };
+    if let Some(path) = dump_path {
+        let read_err = |e: std::io::Error| {
+            AppError::CommandFailed(format!("Failed to read {}: {}", path.display(), e))
+        };
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let matches = if ext.eq_ignore_ascii_case("json") {
+            let text = std::fs::read_to_string(path).map_err(read_err)?;
+            !uid.is_empty() && text.to_uppercase().contains(&uid)
+        } else {
+            let data = if ext.eq_ignore_ascii_case("eml") {
+                let text = std::fs::read_to_string(path).map_err(read_err)?;
+                let digits: String = text.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+                hex_bytes(&digits).unwrap_or_default()
+            } else {
+                std::fs::read(path).map_err(read_err)?
+            };
+            dump_carries_uid(&data, &hex_bytes(&uid).unwrap_or_default())
+        };
+        if !matches {
+            return Err(foreign(path));
+        }
+    }
+    if let Some(path) = key_path {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_uppercase();
+        if uid.is_empty() || !name.contains(&format!("-{}-KEY", uid)) {
+            return Err(foreign(path));
+        }
+    }
+    Ok(())
+}
+
+fn hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        })
+        .collect()
+}
+
+/// `hf-mf-<UID>-dump.bin` -> `hf-mf-<UID>-dump-001.bin`, `-002`, ... PM3's
+/// own numbering for a name that's taken.
+fn numbered_file_name(name: &str, n: u32) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}-{:03}.{}", stem, n, ext),
+        None => format!("{}-{:03}", name, n),
+    }
+}
+
+/// Write a bundled file into `dir` under its original name. Only PM3-style
+/// dump/key names are accepted, so a bundle can't drop arbitrary files. A
+/// different file already under that name is kept: the bundled one gets the
+/// next free PM3-style number instead.
+fn restore_bundle_file(file: &BundleFile, dir: &Path) -> Result<PathBuf, AppError> {
+    let name = Path::new(&file.name);
+    if name.components().count() != 1 || !is_pm3_output_file(name) {
+        return Err(AppError::CommandFailed(format!(
+            "Bundle contains an unexpected file: {}",
+            file.name
+        )));
+    }
+    let data = hex_bytes(&file.data)
+        .ok_or_else(|| AppError::CommandFailed(format!("Corrupt data for {}", file.name)))?;
+
+    let mut path = dir.join(name);
+    let mut n = 0;
+    while std::fs::symlink_metadata(&path).is_ok() {
+        if std::fs::read(&path).is_ok_and(|existing| existing == data) {
+            return Ok(path);
+        }
+        n += 1;
+        path = dir.join(numbered_file_name(&file.name, n));
+    }
+    std::fs::write(&path, data).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    Ok(path)
+}
+
+fn parse_card_bundle(json: &str) -> Result<CardBundle, AppError> {
+    let bundle: CardBundle = serde_json::from_str(json)
+        .map_err(|e| AppError::CommandFailed(format!("Not a card bundle: {}", e)))?;
+    if bundle.version > CARD_BUNDLE_VERSION {
+        return Err(AppError::CommandFailed(format!(
+            "Card bundle version {} is newer than this app supports ({})",
+            bundle.version, CARD_BUNDLE_VERSION
+        )));
+    }
+    Ok(bundle)
+}
+
+/// Where `archive_source_dump` keeps source bundles, under the data dir.
+pub const SOURCE_ARCHIVE_DIR: &str = "source-dumps";
+
+/// Archive the source of an HF clone before it's written: `card` goes into
+/// the saved library and, with the dump and key files, into a bundle under
+/// `data_dir/source-dumps`. Returns the bundle path. The bundle is named by
+/// UID and dump contents, so a retried write reuses the existing archive
+/// instead of adding another bundle and library entry.
+pub(crate) fn archive_source_dump(
+    db: &Database,
+    data_dir: &Path,
+    card: SavedCard,
+    device: Option<String>,
+    dump_path: &Path,
+    key_path: Option<&Path>,
+) -> Result<PathBuf, AppError> {
+    let bundle = build_card_bundle(card, device, Some(dump_path), key_path)?;
+    let dir = data_dir.join(SOURCE_ARCHIVE_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to create {}: {}", dir.display(), e))
+    })?;
+    let uid: String = bundle.card.uid.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let mut hasher = DefaultHasher::new();
+    bundle.dump.as_ref().map(|f| &f.data).hash(&mut hasher);
+    let path = dir.join(format!("{}-{:016X}.json", uid.to_uppercase(), hasher.finish()));
+    if path.exists() {
+        return Ok(path);
+    }
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to encode bundle: {}", e)))?;
+    std::fs::write(&path, json).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to write bundle {}: {}", path.display(), e))
+    })?;
+    db.insert_saved_card(&bundle.card)?;
+    Ok(path)
+}
+
+/// Save `card` plus the dump and key files from the last HF read as a JSON
+/// bundle at `path`, for handing a specific clone to someone else. Files
+/// from a card other than `card` are refused.
+#[tauri::command]
+pub fn export_card_bundle(
+    card: SavedCard,
+    path: String,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<String, AppError> {
+    let device = {
+        let m = machine
+            .lock()
+            .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))?;
+        match (&m.model, &m.firmware) {
+            (Some(model), Some(firmware)) => Some(format!("{} ({})", model, firmware)),
+            (model, _) => model.clone(),
+        }
+    };
+    let tracked = |slot: &Mutex<Option<String>>| -> Result<Option<PathBuf>, AppError> {
+        Ok(slot
+            .lock()
+            .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))?
+            .clone()
+            .map(PathBuf::from))
+    };
+    let dump_path = tracked(&hf_state.dump_path)?;
+    let key_path = tracked(&hf_state.key_path)?;
+    check_bundle_files(&card.uid, dump_path.as_deref(), key_path.as_deref())?;
+
+    let bundle = build_card_bundle(card, device, dump_path.as_deref(), key_path.as_deref())?;
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to encode bundle: {}", e)))?;
+    std::fs::write(&path, json)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to write bundle {}: {}", path, e)))?;
+    Ok(path)
+}
+
+/// Load a bundle from `export_card_bundle`: the card goes into the saved
+/// library, the dump and key files into the data dir, and those become the
+/// current HF files so the card can be written straight away. Returns the
+/// saved card with its new id.
+#[tauri::command]
+pub fn import_card_bundle(
+    path: String,
+    db: State<'_, Database>,
+    data_dir: State<'_, DataDir>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<SavedCard, AppError> {
+    let size = std::fs::metadata(&path)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to read bundle {}: {}", path, e)))?
+        .len();
+    if size > MAX_BUNDLE_BYTES {
+        return Err(AppError::CommandFailed("File is too large to be a card bundle".into()));
+    }
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to read bundle {}: {}", path, e)))?;
+    let bundle = parse_card_bundle(&json)?;
+
+    let dir = &data_dir.inner().0;
+    let dump = bundle.dump.as_ref().map(|f| restore_bundle_file(f, dir)).transpose()?;
+    let key = bundle.key.as_ref().map(|f| restore_bundle_file(f, dir)).transpose()?;
+    let id = db.insert_saved_card(&bundle.card)?;
+
+    if dump.is_some() {
+        for (slot, file) in [(&hf_state.dump_path, dump), (&hf_state.key_path, key)] {
+            *slot
+                .lock()
+                .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))? =
+                file.map(|p| p.to_string_lossy().into_owned());
+        }
+    }
+    Ok(SavedCard { id: Some(id), ..bundle.card })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn purge_deletes_tracked_files_and_clears_slots() {
+        let dir = TempDir::new("purge");
+        let dump = dir.join("hf-mf-01020304-dump.bin");
+        std::fs::write(&dump, [0u8; 1024]).unwrap();
+        std::fs::write(dir.join("hf-mf-01020304-key.bin"), [0xFFu8; 192]).unwrap();
+
+        let hf_state = HfOperationState::new();
+        let key = sibling_key_file(&dump).expect("key file next to dump");
+        *hf_state.dump_path.lock().unwrap() = Some(dump.to_string_lossy().into_owned());
+        *hf_state.key_path.lock().unwrap() = Some(key.to_string_lossy().into_owned());
+
+        let deleted = purge_tracked_files(&hf_state, &dir).unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(!dump.exists());
+        assert!(!key.exists());
+        assert!(hf_state.dump_path.lock().unwrap().is_none());
+        assert!(hf_state.key_path.lock().unwrap().is_none());
+
+        // Nothing tracked: a no-op
+        assert!(purge_tracked_files(&hf_state, &dir).unwrap().is_empty());
+
+        // Untracked key file next to the dump is scrubbed with it
+        std::fs::write(&dump, [0u8; 1024]).unwrap();
+        std::fs::write(&key, [0xFFu8; 192]).unwrap();
+        *hf_state.dump_path.lock().unwrap() = Some(dump.to_string_lossy().into_owned());
+        assert_eq!(purge_tracked_files(&hf_state, &dir).unwrap().len(), 2);
+        assert!(!key.exists());
+    }
+
+    #[test]
+    fn purge_only_touches_pm3_files() {
+        let dir = TempDir::new("purge-guard");
+        let other = dir.join("notes.txt");
+        std::fs::write(&other, "keep me").unwrap();
+
+        let hf_state = HfOperationState::new();
+        *hf_state.dump_path.lock().unwrap() = Some(other.to_string_lossy().into_owned());
+        assert!(purge_tracked_files(&hf_state, &dir).unwrap().is_empty());
+        assert!(other.exists());
+        assert!(hf_state.dump_path.lock().unwrap().is_none());
+
+        // A PM3-named file outside the data dir, directly or via `..`, stays
+        let data_dir = dir.join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let outside = dir.join("hf-mf-01020304-dump.bin");
+        std::fs::write(&outside, [0u8; 1024]).unwrap();
+        for path in [outside.clone(), data_dir.join("..").join("hf-mf-01020304-dump.bin")] {
+            *hf_state.dump_path.lock().unwrap() = Some(path.to_string_lossy().into_owned());
+            assert!(purge_tracked_files(&hf_state, &data_dir).unwrap().is_empty());
+            assert!(outside.exists());
+        }
+
+        assert!(is_pm3_output_file(Path::new("hf-iclass-AABBCCDD-dump-001.eml")));
+        assert!(!is_pm3_output_file(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn purge_deletes_source_archives() {
+        let dir = TempDir::new("purge-archive");
+        // No archive folder yet: nothing to do
+        assert!(purge_source_archives(&dir).unwrap().is_empty());
+
+        let archives = dir.join(SOURCE_ARCHIVE_DIR);
+        std::fs::create_dir_all(&archives).unwrap();
+        let bundle = archives.join("7DE9254E-0123456789ABCDEF.json");
+        std::fs::write(&bundle, "{}").unwrap();
+        let other = archives.join("README.txt");
+        std::fs::write(&other, "keep me").unwrap();
+
+        let deleted = purge_source_archives(&dir).unwrap();
+        assert_eq!(deleted, vec![bundle.to_string_lossy().into_owned()]);
+        assert!(!bundle.exists());
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn card_bundle_round_trip() {
+        let dir = TempDir::new("bundle");
+        let out_dir = dir.join("import");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let dump: Vec<u8> = (0..1024u32).map(|i| (i * 7) as u8).collect();
+        let dump_path = dir.join("hf-mf-01020304-dump.bin");
+        std::fs::write(&dump_path, &dump).unwrap();
+        let keys = [0xFFu8; 96];
+        let key_path = dir.join("hf-mf-01020304-key.bin");
+        std::fs::write(&key_path, keys).unwrap();
+
+        let card = SavedCard {
+            id: Some(7),
+            name: "Office fob".into(),
+            card_type: "MifareClassic1K".into(),
+            frequency: "HF".into(),
+            uid: "01020304".into(),
+            raw: String::new(),
+            decoded: r#"{"sak":"08"}"#.into(),
+            cloneable: true,
+            recommended_blank: "MagicMifareGen1a".into(),
+            created_at: "2026-01-01T00:00:00Z".into(),
+        };
+        let bundle = build_card_bundle(
+            card.clone(),
+            Some("Proxmark3 (v4.20728)".into()),
+            Some(&dump_path),
+            Some(&key_path),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+
+        let imported = parse_card_bundle(&json).unwrap();
+        assert_eq!(imported.card, SavedCard { id: None, ..card });
+        assert_eq!(imported.device.as_deref(), Some("Proxmark3 (v4.20728)"));
+        let restored_dump = restore_bundle_file(imported.dump.as_ref().unwrap(), &out_dir).unwrap();
+        let restored_key = restore_bundle_file(imported.key.as_ref().unwrap(), &out_dir).unwrap();
+        assert_eq!(restored_dump, out_dir.join("hf-mf-01020304-dump.bin"));
+        assert_eq!(std::fs::read(&restored_dump).unwrap(), dump);
+        assert_eq!(std::fs::read(&restored_key).unwrap(), keys);
+    }
+
+    #[test]
+    fn bundle_refuses_another_cards_files() {
+        let dir = TempDir::new("bundle-uid");
+        let mut block0 = vec![0x01, 0x02, 0x03, 0x04, 0x04, 0x08, 0x04, 0x00];
+        block0.resize(1024, 0);
+        let dump = dir.join("hf-mf-01020304-dump.bin");
+        std::fs::write(&dump, &block0).unwrap();
+        let key = dir.join("hf-mf-01020304-key.bin");
+        std::fs::write(&key, [0xFFu8; 96]).unwrap();
+        let other_key = dir.join("hf-mf-AABBCCDD-key.bin");
+        std::fs::write(&other_key, [0xFFu8; 96]).unwrap();
+
+        assert!(check_bundle_files("01 02 03 04", Some(&dump), Some(&key)).is_ok());
+        assert!(check_bundle_files("01020304", None, None).is_ok());
+        assert!(check_bundle_files("AABBCCDD", Some(&dump), None).is_err());
+        assert!(check_bundle_files("01020304", Some(&dump), Some(&other_key)).is_err());
+
+        // Ultralight: UID0-2, BCC0, UID3-6, with or without the dump header
+        let uid = [0x04, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+        let pages = [0x04, 0x11, 0x22, 0xBF, 0x33, 0x44, 0x55, 0x66];
+        assert!(dump_carries_uid(&pages, &uid));
+        let headed: Vec<u8> = [0u8; MFU_DUMP_HEADER_LEN].iter().chain(&pages).copied().collect();
+        assert!(dump_carries_uid(&headed, &uid));
+        assert!(!dump_carries_uid(&pages, &uid[..4]));
+    }
+
+    #[test]
+    fn import_keeps_an_existing_file() {
+        let dir = TempDir::new("bundle-clash");
+        let existing = dir.join("hf-mf-01020304-dump.bin");
+        std::fs::write(&existing, [0x11u8; 64]).unwrap();
+
+        let file = BundleFile { name: "hf-mf-01020304-dump.bin".into(), data: "2222".into() };
+        let restored = restore_bundle_file(&file, &dir).unwrap();
+        assert_eq!(restored, dir.join("hf-mf-01020304-dump-001.bin"));
+        assert_eq!(std::fs::read(&existing).unwrap(), [0x11u8; 64]);
+        assert_eq!(std::fs::read(&restored).unwrap(), [0x22, 0x22]);
+        // The same bundle again: reuses its earlier copy
+        assert_eq!(restore_bundle_file(&file, &dir).unwrap(), restored);
+    }
+
+    #[test]
+    fn card_bundle_rejects_foreign_files() {
+        let dir = std::env::temp_dir();
+        for name in ["../hf-mf-01020304-dump.bin", "notes.txt"] {
+            let file = BundleFile { name: name.into(), data: "00".into() };
+            assert!(restore_bundle_file(&file, &dir).is_err(), "{}", name);
+        }
+        let odd = BundleFile { name: "hf-mf-01020304-dump.bin".into(), data: "0".into() };
+        assert!(restore_bundle_file(&odd, &dir).is_err());
+        assert!(parse_card_bundle(r#"{"version":99}"#).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::AppError;
+use crate::pm3::{command_builder, connection, output_parser, scan_cache};
+
+// EM4x50 can't be emulated on a T5577, so it's cloned natively: dump the
+// source's words, swap in an EM4x50 blank, write them back. Independent of
+// the wizard FSM, like the chip tools in `erase`.
+
+/// Returned by `em4x50_clone`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Em4x50CloneResult {
+    pub success: bool,
+    pub message: String,
+    /// Data words written, in order (the control word is written last).
+    /// On a failed write, the ones written before it.
+    pub words_written: Vec<u8>,
+    /// Written words that read back differently.
+    pub mismatched_words: Vec<u8>,
+}
+
+async fn read_words(app: &AppHandle, port: &str) -> Result<Vec<String>, AppError> {
+    let output = connection::run_command(app, port, command_builder::build_em4x50_dump()).await?;
+    output_parser::parse_em4x50_dump(&output).ok_or_else(|| {
+        AppError::CommandFailed(
+            "Could not read every EM4x50 word. Place the card flat on the LF antenna.".into(),
+        )
+    })
+}
+
+/// Read words 0-33 of the EM4x50 on the reader (MSB-first hex).
+#[tauri::command]
+pub async fn em4x50_dump(app: AppHandle, port: String) -> Result<Vec<String>, AppError> {
+    connection::check_port(&port)?;
+    read_words(&app, &port).await
+}
+
+/// Clone `words` (an `em4x50_dump` of the source) onto the EM4x50 blank on
+/// the reader, then read it back. The blank must still have the factory
+/// password; the source's password and protection words aren't copied.
+#[tauri::command]
+pub async fn em4x50_clone(
+    app: AppHandle,
+    port: String,
+    words: Vec<String>,
+) -> Result<Em4x50CloneResult, AppError> {
+    connection::check_port(&port)?;
+    let data_words = command_builder::em4x50_clonable_words(&words)
+        .map_err(|e| AppError::CommandFailed(format!("Invalid EM4x50 dump: {}", e)))?;
+    let cmds = command_builder::build_em4x50_clone(&words)
+        .map_err(|e| AppError::CommandFailed(format!("Invalid EM4x50 dump: {}", e)))?;
+    scan_cache::invalidate(&app);
+
+    // cmds: the wipe, one write per data word, then the control word
+    let mut words_written = Vec::with_capacity(data_words.len());
+    for (i, cmd) in cmds.iter().enumerate() {
+        let failure = match connection::run_command(&app, &port, cmd).await {
+            Ok(output) if output_parser::em4x50_write_ok(&output) => None,
+            Ok(_) => Some("not confirmed".to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(reason) = failure {
+            return Ok(Em4x50CloneResult {
+                success: false,
+                message: format!(
+                    "EM4x50 write failed at `{}` ({}). Check the blank still has the factory \
+                     password.",
+                    cmd, reason
+                ),
+                words_written,
+                mismatched_words: Vec::new(),
+            });
+        }
+        if let Some(&w) = i.checked_sub(1).and_then(|j| data_words.get(j)) {
+            words_written.push(w as u8);
+        }
+    }
+
+    let readback = read_words(&app, &port).await?;
+    let mismatched_words = mismatched_words(&data_words, &words, &readback);
+    let success = mismatched_words.is_empty();
+    let message = if success {
+        format!("EM4x50 cloned: {} data words written and verified", words_written.len())
+    } else {
+        format!("{} EM4x50 words read back differently", mismatched_words.len())
+    };
+    Ok(Em4x50CloneResult {
+        success,
+        message,
+        words_written,
+        mismatched_words,
+    })
+}
+
+/// Data words whose readback differs from the dump; a word missing from the
+/// readback counts as a mismatch.
+fn mismatched_words(data_words: &[usize], words: &[String], readback: &[String]) -> Vec<u8> {
+    data_words
+        .iter()
+        .filter(|&&w| !readback.get(w).is_some_and(|r| r.eq_ignore_ascii_case(&words[w])))
+        .map(|&w| w as u8)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_readback_counts_as_mismatch() {
+        let words: Vec<String> = (0..34).map(|w| format!("{:08X}", w)).collect();
+        let data_words = [3, 4, 5];
+        let mut readback = words.clone();
+        readback[4] = "FFFFFFFF".into();
+        assert_eq!(mismatched_words(&data_words, &words, &readback), vec![4]);
+        readback.truncate(5);
+        assert_eq!(mismatched_words(&data_words, &words, &readback), vec![4, 5]);
+        assert_eq!(mismatched_words(&data_words, &words, &[]), vec![3, 4, 5]);
+    }
+}
@@ -1,8 +1,14 @@
+use std::future::Future;
+
 use serde::Serialize;
 use tauri::AppHandle;
 
+use crate::cards::types::T5577Status;
+use crate::commands::write::T5577_PASSWORD_UNKNOWN_HINT;
 use crate::error::AppError;
-use crate::pm3::{command_builder, connection, output_parser};
+use crate::pm3::connection::ChainedCommand;
+use crate::pm3::output_parser::T5577Unlock;
+use crate::pm3::{command_builder, connection, output_parser, scan_cache};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +23,78 @@ pub struct DetectChipResult {
 pub struct WipeResult {
     pub success: bool,
     pub message: String,
+    /// T5577 is locked with a password `lf t55xx chk` couldn't find; retry
+    /// `wipe_chip` with a user-supplied `password`.
+    pub password_required: bool,
+    /// A read-back after the wipe shows a blank chip.
+    pub wiped: bool,
+    /// T5577 block 0 / EM4305 word 0 before and after the wipe.
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl WipeResult {
+    fn failed(message: String, password_required: bool, before: Option<String>) -> Self {
+        Self {
+            success: false,
+            message,
+            password_required,
+            wiped: false,
+            before,
+            after: None,
+        }
+    }
+}
+
+/// Returned by `write_t55xx_config`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T55xxConfigResult {
+    pub success: bool,
+    pub message: String,
+    /// Block 0 before the write and as read back after it.
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// One EM4305 word as read by `em4305_dump`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Em4305Word {
+    pub address: u8,
+    /// 8 hex characters, `None` if the word couldn't be read (the password
+    /// word and read-protected words never read back).
+    pub value: Option<String>,
+}
+
+/// EM4305 user memory: words 0-15.
+const EM4305_WORD_COUNT: u8 = 16;
+
+/// Block 0 `lf t55xx wipe` writes: the T5577 factory config.
+const T5577_WIPED_BLOCK0: &str = "000880E8";
+
+/// Check the read-back taken after a wipe (`lf t55xx detect` for T5577,
+/// `lf em 4x05 read -a 0` for EM4305). Returns (wiped, value read).
+/// A wiped T5577 is unlocked with the factory (or an all-zero) block 0; a
+/// wiped EM4305 reads word 0 as zero. An unreadable chip is not wiped.
+fn verify_wipe(chip_type: &str, after_output: &str) -> (bool, Option<String>) {
+    match chip_type {
+        "T5577" => {
+            let status = output_parser::parse_t5577_detect(after_output);
+            let block0 = status.block0.map(|b| b.to_uppercase());
+            let wiped = status.detected
+                && !status.password_set
+                && block0
+                    .as_deref()
+                    .is_some_and(|b| b == T5577_WIPED_BLOCK0 || b == "00000000");
+            (wiped, block0)
+        }
+        "EM4305" => {
+            let word0 = output_parser::parse_em4305_word0(after_output);
+            (word0.as_deref() == Some("00000000"), word0)
+        }
+        _ => (false, None),
+    }
 }
 
 /// Detect the underlying chip type on the reader (T5577 or EM4305).
@@ -64,20 +142,62 @@ pub async fn detect_chip(app: AppHandle, port: String) -> Result<DetectChipResul
     ))
 }
 
+/// Read every EM4305 word (0-15) for inspection. Independent of the wizard
+/// FSM. Fails if no word at all could be read.
+#[tauri::command]
+pub async fn em4305_dump(app: AppHandle, port: String) -> Result<Vec<Em4305Word>, AppError> {
+    connection::check_port(&port)?;
+
+    let words = read_em4305_words(|cmd| {
+        let app = app.clone();
+        let port = port.clone();
+        async move { connection::run_command(&app, &port, &cmd).await }
+    })
+    .await;
+
+    if words.iter().all(|w| w.value.is_none()) {
+        return Err(AppError::CommandFailed(
+            "No EM4305 words could be read. Place an EM4305 card on the reader.".into(),
+        ));
+    }
+    Ok(words)
+}
+
+/// Read words 0-15 through `run`. A protected word fails the client, so it
+/// comes back `None` and the rest are still read.
+async fn read_em4305_words<R, Fut>(mut run: R) -> Vec<Em4305Word>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let mut words = Vec::with_capacity(usize::from(EM4305_WORD_COUNT));
+    for address in 0..EM4305_WORD_COUNT {
+        let value = run(command_builder::build_em4305_read_word(address))
+            .await
+            .ok()
+            .and_then(|output| output_parser::parse_em4305_word(&output, address));
+        words.push(Em4305Word { address, value });
+    }
+    words
+}
+
 /// Wipe a chip that was previously detected by `detect_chip`.
-/// Independent of the wizard FSM.
+/// Independent of the wizard FSM. `password` unlocks a password-protected
+/// T5577 when the built-in dictionary check fails.
 #[tauri::command]
 pub async fn wipe_chip(
     app: AppHandle,
     port: String,
     chip_type: String,
+    password: Option<String>,
 ) -> Result<WipeResult, AppError> {
     // Validate port
     if port.is_empty() || port.len() > 32 {
         return Err(AppError::CommandFailed("Invalid port".into()));
     }
+    scan_cache::invalidate(&app);
 
-    let wipe_cmd = match chip_type.as_str() {
+    let (wipe_cmd, before) = match chip_type.as_str() {
         "T5577" => {
             // Re-detect to check for password (card might have been swapped)
             let output =
@@ -85,17 +205,55 @@ pub async fn wipe_chip(
             let status = output_parser::parse_t5577_detect(&output);
 
             if !status.detected {
-                return Ok(WipeResult {
-                    success: false,
-                    message: "T5577 no longer detected. Do not remove card during erase.".into(),
-                });
+                return Ok(WipeResult::failed(
+                    "T5577 no longer detected. Do not remove card during erase.".into(),
+                    false,
+                    None,
+                ));
             }
+            let before = status.block0;
 
-            // T5577 wipe — works for unprotected cards.
-            // For password-protected cards, PM3 `lf t55xx wipe` tries default passwords.
-            command_builder::build_t5577_wipe().to_string()
+            let cmd = if !status.password_set {
+                command_builder::build_t5577_wipe().to_string()
+            } else {
+                // Locked: use the user's password, otherwise try PM3's dictionary
+                let pw = match password {
+                    Some(pw) => pw,
+                    None => {
+                        let chk_output = connection::run_command(
+                            &app,
+                            &port,
+                            command_builder::build_t5577_chk(),
+                        )
+                        .await?;
+                        match output_parser::resolve_t5577_unlock(&chk_output) {
+                            T5577Unlock::Password(found) => found,
+                            T5577Unlock::PasswordUnknown => {
+                                return Ok(WipeResult::failed(
+                                    T5577_PASSWORD_UNKNOWN_HINT.into(),
+                                    true,
+                                    before,
+                                ));
+                            }
+                        }
+                    }
+                };
+                command_builder::build_t5577_wipe_with_password(&pw).map_err(|e| {
+                    AppError::CommandFailed(format!("Password validation failed: {}", e))
+                })?
+            };
+            (cmd, before)
+        }
+        "EM4305" => {
+            let word0 =
+                connection::run_command(&app, &port, &command_builder::build_em4305_read_word(0))
+                    .await
+                    .unwrap_or_default();
+            (
+                command_builder::build_em4305_wipe().to_string(),
+                output_parser::parse_em4305_word0(&word0),
+            )
         }
-        "EM4305" => command_builder::build_em4305_wipe().to_string(),
         other => {
             return Err(AppError::CommandFailed(format!(
                 "Unsupported chip type for wipe: {}",
@@ -108,9 +266,8 @@ pub async fn wipe_chip(
 
     // Check for errors in output
     if wipe_output.contains("[!!]") || wipe_output.to_lowercase().contains("error") {
-        return Ok(WipeResult {
-            success: false,
-            message: format!(
+        return Ok(WipeResult::failed(
+            format!(
                 "Wipe may have failed: {}",
                 wipe_output
                     .lines()
@@ -118,11 +275,252 @@ pub async fn wipe_chip(
                     .unwrap_or("unknown error")
                     .trim()
             ),
-        });
+            false,
+            before,
+        ));
+    }
+
+    // Read the chip back: PM3 can exit 0 on a wipe that didn't take
+    let verify_cmd = match chip_type.as_str() {
+        "T5577" => command_builder::build_t5577_detect().to_string(),
+        _ => command_builder::build_em4305_read_word(0),
+    };
+    let verify_output = connection::run_command(&app, &port, &verify_cmd).await;
+    Ok(wipe_result(&chip_type, before, verify_output))
+}
+
+/// Outcome of a wipe that ran, from the read-back after it. A failed
+/// read-back leaves the wipe unverified but keeps the `before` snapshot.
+fn wipe_result(
+    chip_type: &str,
+    before: Option<String>,
+    verify_output: Result<String, AppError>,
+) -> WipeResult {
+    let verify_output = match verify_output {
+        Ok(output) => output,
+        Err(e) => {
+            return WipeResult::failed(
+                format!(
+                    "{} wipe could not be verified: reading it back failed ({}). Keep the card \
+                     on the reader and detect it again.",
+                    chip_type, e
+                ),
+                false,
+                before,
+            );
+        }
+    };
+    let (wiped, after) = verify_wipe(chip_type, &verify_output);
+
+    let message = if wiped {
+        format!("{} erased successfully", chip_type)
+    } else {
+        format!(
+            "{} wipe could not be verified (read back {}). Keep the card on the reader and try again.",
+            chip_type,
+            after.as_deref().unwrap_or("nothing")
+        )
+    };
+    WipeResult {
+        success: wiped,
+        message,
+        password_required: false,
+        wiped,
+        before,
+        after,
+    }
+}
+
+/// `lf t55xx detect`, retried with `password` when the tag only answers in
+/// password mode.
+async fn detect_t5577(
+    app: &AppHandle,
+    port: &str,
+    password: Option<&str>,
+) -> Result<T5577Status, AppError> {
+    let output = connection::run_command(app, port, command_builder::build_t5577_detect()).await?;
+    let status = output_parser::parse_t5577_detect(&output);
+    let Some(pw) = password.filter(|_| !status.detected) else {
+        return Ok(status);
+    };
+    let cmd = command_builder::build_t5577_detect_with_password(pw)
+        .map_err(|e| AppError::CommandFailed(format!("Password validation failed: {}", e)))?;
+    let output = connection::run_command(app, port, &cmd).await?;
+    Ok(output_parser::parse_t5577_detect(&output))
+}
+
+/// Write a T5577 config block (block 0) directly and read it back, for
+/// experts forcing a modulation the app didn't detect. Independent of the
+/// wizard FSM. `password` is the tag's current password, if it has one, or
+/// the password to set when `config` turns password mode on.
+#[tauri::command]
+pub async fn write_t55xx_config(
+    app: AppHandle,
+    port: String,
+    config: String,
+    password: Option<String>,
+) -> Result<T55xxConfigResult, AppError> {
+    connection::check_port(&port)?;
+    let config = config.trim().to_uppercase();
+    let value = u32::from_str_radix(&config, 16)
+        .map_err(|_| AppError::CommandFailed(format!("Invalid T5577 config: {}", config)))?;
+    scan_cache::invalidate(&app);
+
+    let status = detect_t5577(&app, &port, password.as_deref()).await?;
+    if !status.detected {
+        return Err(AppError::CommandFailed(
+            "No T5577 detected. Place the card on the LF antenna.".into(),
+        ));
     }
+    let before = status.block0;
+    let write_cmds =
+        command_builder::build_t55xx_write_config(&config, password.as_deref(), status.password_set)
+            .map_err(|e| AppError::CommandFailed(format!("Invalid T5577 config: {}", e)))?;
 
-    Ok(WipeResult {
-        success: true,
-        message: format!("{} erased successfully", chip_type),
+    for cmd in &write_cmds {
+        let output = connection::run_command(&app, &port, cmd).await?;
+        if output.contains("[!!]") {
+            return Ok(T55xxConfigResult {
+                success: false,
+                message: format!("Config write failed: {}", output.trim()),
+                before,
+                after: None,
+            });
+        }
+    }
+
+    // The tag answers with the password from here on only in password mode
+    let password = password.filter(|_| value & command_builder::T55XX_PWD_BIT != 0);
+    let mut after = detect_t5577(&app, &port, password.as_deref()).await?.block0;
+    if after.as_deref() != Some(config.as_str()) {
+        // Detect doesn't know every modulation: read block 0 demodulated as written
+        let chain = forced_config_read(value, password.as_deref())?;
+        let output = connection::run_chained(&app, &port, &chain).await?;
+        let read = chain
+            .part_output(&output, 1)
+            .and_then(|read| output_parser::parse_t55xx_dump(&read))
+            .and_then(|blocks| blocks.into_iter().next());
+        after = read.or(after);
+    }
+    let success = after.as_deref() == Some(config.as_str());
+    let message = if success {
+        format!("Config block {} written and verified", config)
+    } else {
+        format!(
+            "Config block read back as {}, not {}. Check the tag with `lf t55xx detect`.",
+            after.as_deref().unwrap_or("nothing"),
+            config
+        )
+    };
+    Ok(T55xxConfigResult {
+        success,
+        message,
+        before,
+        after,
     })
 }
+
+/// `lf t55xx read -b 0` with the client set to the bit rate and modulation
+/// `config` selects, in one PM3 run.
+fn forced_config_read(config: u32, password: Option<&str>) -> Result<ChainedCommand, AppError> {
+    let invalid = |e: String| AppError::CommandFailed(format!("Invalid T5577 config: {}", e));
+    let set_config = command_builder::build_t55xx_config_for_block0(config).map_err(invalid)?;
+    let read = command_builder::build_t55xx_read_block(0, password).map_err(invalid)?;
+    ChainedCommand::new(&[&set_config, &read])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undetected_config_read_back_as_written() {
+        let chain = forced_config_read(0x0010_7060, None).unwrap();
+        assert_eq!(chain.script(), "lf t55xx config --rate 50 --FSK2A; lf t55xx read -b 0");
+        let output = "\
+[usb|script] pm3 --> lf t55xx config --rate 50 --FSK2A
+[=]  Chip type......... T55x7
+[=]  Modulation........ FSK2a
+[usb|script] pm3 --> lf t55xx read -b 0
+[+] Reading Page 0:
+[+] blk | hex data | binary                           | ascii
+[+] ----+----------+----------------------------------+-------
+[+]  00 | 00107060 | 00000000000100000111000001100000 | ..p`";
+        let read = chain.part_output(output, 1).unwrap();
+        assert_eq!(output_parser::parse_t55xx_dump(&read).unwrap(), vec!["00107060"]);
+
+        let locked = forced_config_read(0x0014_8050, Some("51243648")).unwrap();
+        assert!(locked.script().ends_with("lf t55xx read -b 0 -p 51243648"));
+    }
+
+    #[test]
+    fn t5577_verified_after_wipe() {
+        let after = "\
+[=] Chip type......... T55x7
+[=] Modulation........ ASK/Manchester
+[=] Block0............ 000880E8
+[=] Password set...... No";
+        assert_eq!(verify_wipe("T5577", after), (true, Some("000880E8".into())));
+
+        // Old clone config survived
+        let stale = after.replace("000880E8", "00148040");
+        assert_eq!(verify_wipe("T5577", &stale), (false, Some("00148040".into())));
+
+        // Still locked
+        let locked = after.replace("Password set...... No", "Password set...... Yes");
+        assert!(!verify_wipe("T5577", &locked).0);
+    }
+
+    #[test]
+    fn em4305_verified_after_wipe() {
+        assert_eq!(
+            verify_wipe("EM4305", "[+] Address 00 | 00000000 | ok"),
+            (true, Some("00000000".into()))
+        );
+        assert_eq!(
+            verify_wipe("EM4305", "[+] Address 00 | 0F0368AB | ok"),
+            (false, Some("0F0368AB".into()))
+        );
+        assert_eq!(verify_wipe("EM4305", "[!] Read failed"), (false, None));
+    }
+
+    #[test]
+    fn failed_readback_keeps_before_snapshot() {
+        let before = Some("00148040".to_string());
+        let result = wipe_result(
+            "T5577",
+            before.clone(),
+            Err(AppError::CommandFailed("device timeout".into())),
+        );
+        assert!(!result.success && !result.wiped);
+        assert_eq!(result.before, before);
+        assert_eq!(result.after, None);
+        assert!(result.message.contains("device timeout"), "{}", result.message);
+
+        let ok = "[=] Chip type......... T55x7\n[=] Block0............ 000880E8\n\
+                  [=] Password set...... No";
+        let result = wipe_result("T5577", before.clone(), Ok(ok.into()));
+        assert!(result.wiped);
+        assert_eq!((result.before, result.after), (before, Some("000880E8".into())));
+    }
+
+    #[tokio::test]
+    async fn protected_em4305_word_left_unread() {
+        let words = read_em4305_words(|cmd| {
+            let address: u8 = cmd.rsplit(' ').next().unwrap().parse().unwrap();
+            let output = if address == 2 {
+                // Read-protected word: the client exits non-zero
+                Err(AppError::CommandFailed("PM3 exited with code 1".into()))
+            } else {
+                Ok(format!("[+] Address {:02} | {:08X} | ok", address, address))
+            };
+            async move { output }
+        })
+        .await;
+
+        assert_eq!(words.len(), 16);
+        assert_eq!(words[2].value, None);
+        assert_eq!(words[15].address, 15);
+        assert_eq!(words[15].value.as_deref(), Some("0000000F"));
+    }
+}
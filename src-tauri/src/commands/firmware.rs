@@ -1,13 +1,14 @@
-use std::sync::{LazyLock, Mutex};
+use std::sync::Mutex;
 
-use regex::Regex;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_shell::ShellExt;
 
 use crate::error::AppError;
-use crate::pm3::connection;
-use crate::pm3::version::parse_detailed_hw_version;
+use crate::pm3::version::{
+    self, is_bootloader_mode, parse_detailed_hw_version, ClientBinaryInfo, FirmwareFork,
+};
+use crate::pm3::{command_builder, connection};
 
 // ---------------------------------------------------------------------------
 // State — holds the running flash child process (if any) for cancellation
@@ -38,6 +39,9 @@ pub struct FirmwareCheckResult {
     pub device_firmware_version: String,
     pub hardware_variant: String,
     pub firmware_path_exists: bool,
+    /// Device only answers from its bootloader — offer `recover_firmware`.
+    pub bootloader_mode: bool,
+    pub fork: FirmwareFork,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -54,11 +58,6 @@ pub struct FirmwareProgress {
 
 const VALID_VARIANTS: &[&str] = &["rdv4", "rdv4-bt", "generic", "generic-256"];
 
-static PORT_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^(COM[1-9]\d*|/dev/tty(ACM|USB)\d{1,2}|/dev/tty\.usbmodem\w+)$")
-        .expect("bad port regex")
-});
-
 // ---------------------------------------------------------------------------
 // Commands
 // ---------------------------------------------------------------------------
@@ -84,6 +83,21 @@ pub async fn check_firmware_version(
                     device_firmware_version: "incompatible".to_string(),
                     hardware_variant: "unknown".to_string(),
                     firmware_path_exists: false,
+                    bootloader_mode: false,
+                    fork: FirmwareFork::Unknown,
+                });
+            }
+            // Interrupted flash — the OS image is gone and only the
+            // bootloader responds. The frontend offers a recovery flash.
+            if is_bootloader_mode(&err_msg) {
+                return Ok(FirmwareCheckResult {
+                    matched: false,
+                    client_version: "bundled".to_string(),
+                    device_firmware_version: "bootloader".to_string(),
+                    hardware_variant: "unknown".to_string(),
+                    firmware_path_exists: false,
+                    bootloader_mode: true,
+                    fork: FirmwareFork::Unknown,
                 });
             }
             return Err(e);
@@ -99,16 +113,33 @@ pub async fn check_firmware_version(
         device_firmware_version: info.os_version,
         hardware_variant: info.hardware_variant,
         firmware_path_exists: fw_exists,
+        bootloader_mode: is_bootloader_mode(&output),
+        fork: info.fork,
     })
 }
 
+/// Check the `proxmark3` binary the app resolves (bundled, PATH or a known
+/// install location) is a supported Iceman client, from its client-only
+/// `--version`. Needs no device, so it can run on first launch and catch a
+/// stale binary on PATH before its commands fail in confusing ways.
+#[tauri::command]
+pub async fn verify_pm3_binary(app: AppHandle) -> Result<ClientBinaryInfo, AppError> {
+    let (binary, output) = connection::run_client_version(&app).await?;
+    let info = version::parse_client_version(&binary, &output);
+    if let Some(warning) = &info.warning {
+        log::warn!("{}", warning);
+    }
+    Ok(info)
+}
+
 /// Start flashing firmware to the connected PM3 device.
 ///
 /// Spawns the sidecar binary in flash mode and streams progress to the
 /// frontend via Tauri events:
 /// - `firmware-progress` — phase/percent updates during flash
 /// - `firmware-complete` — flash finished successfully
-/// - `firmware-failed` — flash failed with error
+/// - `firmware-failed` — flash failed with error (phase `"bootloader"` when the
+///   device was left in bootloader mode and `recover_firmware` should be offered)
 ///
 /// Returns immediately after spawning. Use `cancel_flash` to abort.
 #[tauri::command]
@@ -117,6 +148,101 @@ pub async fn flash_firmware(
     port: String,
     hardware_variant: String,
     flash_state: State<'_, FlashState>,
+) -> Result<(), AppError> {
+    check_flash_request(&port, &hardware_variant, &flash_state)?;
+
+    let fw_path = resolve_firmware_image(&app, &hardware_variant, "fullimage.elf")?;
+
+    // Emit initial progress
+    let _ = app.emit(
+        "firmware-progress",
+        FirmwareProgress {
+            phase: "connecting".into(),
+            percent: 5,
+            message: "Connecting to device...".into(),
+        },
+    );
+
+    let flash_args = command_builder::build_flash_args(&port, &[&fw_path], false);
+    run_flash(
+        &app,
+        &flash_args,
+        "Flashing firmware (this may take up to 60 seconds)...",
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Recover a device stuck in bootloader mode (e.g. the cable was pulled
+/// mid-flash) by re-flashing the bootrom together with the full image.
+///
+/// Same event contract as `flash_firmware`. The bootloader has to be unlocked
+/// to rewrite the bootrom, so this is only offered once bootloader mode has
+/// been detected by `check_firmware_version` or a failed flash.
+#[tauri::command]
+pub async fn recover_firmware(
+    app: AppHandle,
+    port: String,
+    hardware_variant: String,
+    flash_state: State<'_, FlashState>,
+) -> Result<(), AppError> {
+    check_flash_request(&port, &hardware_variant, &flash_state)?;
+
+    let bootrom_path = resolve_firmware_image(&app, &hardware_variant, "bootrom.elf")?;
+    let fw_path = resolve_firmware_image(&app, &hardware_variant, "fullimage.elf")?;
+
+    let _ = app.emit(
+        "firmware-progress",
+        FirmwareProgress {
+            phase: "recovering".into(),
+            percent: 5,
+            message: "Connecting to bootloader...".into(),
+        },
+    );
+
+    let flash_args = command_builder::build_flash_args(&port, &[&bootrom_path, &fw_path], true);
+    run_flash(
+        &app,
+        &flash_args,
+        "Re-flashing bootloader and firmware — do not unplug the device...",
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Cancel an in-progress firmware flash by killing the child process.
+#[tauri::command]
+pub async fn cancel_flash(flash_state: State<'_, FlashState>) -> Result<(), AppError> {
+    let child = {
+        let mut lock = flash_state.child.lock().map_err(|e| {
+            AppError::CommandFailed(format!("Flash state lock poisoned: {}", e))
+        })?;
+        lock.take()
+    };
+
+    match child {
+        Some(child) => {
+            child.kill().map_err(|e| {
+                AppError::CommandFailed(format!("Failed to kill flash process: {}", e))
+            })?;
+            Ok(())
+        }
+        None => Ok(()), // No flash running — no-op
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// Shared guards for `flash_firmware` / `recover_firmware`: no concurrent
+/// flash, valid port, known hardware variant (prevents path traversal).
+fn check_flash_request(
+    port: &str,
+    hardware_variant: &str,
+    flash_state: &FlashState,
 ) -> Result<(), AppError> {
     // Reject if a flash is already running
     {
@@ -130,27 +256,32 @@ pub async fn flash_firmware(
         }
     }
 
-    // Validate port
-    if !PORT_RE.is_match(&port) {
-        return Err(AppError::CommandFailed(format!("Invalid port: {}", port)));
-    }
+    connection::check_port(port)?;
 
-    // Validate hardware variant (prevent path traversal)
-    if !VALID_VARIANTS.contains(&hardware_variant.as_str()) {
+    if !VALID_VARIANTS.contains(&hardware_variant) {
         return Err(AppError::CommandFailed(format!(
             "Invalid hardware variant: {}",
             hardware_variant
         )));
     }
 
-    // Resolve firmware path from bundled resources
+    Ok(())
+}
+
+/// Resolve a bundled firmware image (`fullimage.elf` / `bootrom.elf`) to a
+/// path string PM3 can parse.
+fn resolve_firmware_image(
+    app: &AppHandle,
+    hardware_variant: &str,
+    file_name: &str,
+) -> Result<String, AppError> {
     let resource_dir = app.path().resource_dir().map_err(|e| {
         AppError::CommandFailed(format!("Failed to resolve resource dir: {}", e))
     })?;
     let fw_path = resource_dir
         .join("firmware")
-        .join(&hardware_variant)
-        .join("fullimage.elf");
+        .join(hardware_variant)
+        .join(file_name);
 
     if !fw_path.exists() {
         return Err(AppError::CommandFailed(format!(
@@ -161,36 +292,22 @@ pub async fn flash_firmware(
 
     // Strip Windows extended-length path prefix (\\?\) — PM3 can't parse it.
     // Tauri's resource_dir() returns canonicalized paths with this prefix.
-    let fw_path_str = fw_path
-        .to_string_lossy()
+    let fw_path_str = fw_path.to_string_lossy();
+    Ok(fw_path_str
         .strip_prefix(r"\\?\")
-        .unwrap_or(&fw_path.to_string_lossy())
-        .to_string();
-
-    // Emit initial progress
-    let _ = app.emit(
-        "firmware-progress",
-        FirmwareProgress {
-            phase: "connecting".into(),
-            percent: 5,
-            message: "Connecting to device...".into(),
-        },
-    );
-
-    let flash_args = [
-        port.as_str(),
-        "--flash",
-        "--image",
-        fw_path_str.as_str(),
-        "-w",
-    ];
+        .unwrap_or(&fw_path_str)
+        .to_string())
+}
 
+/// Run the PM3 binary in flasher mode and report the outcome through
+/// `firmware-complete` / `firmware-failed` events.
+async fn run_flash(app: &AppHandle, flash_args: &[String], writing_message: &str) {
     // Try sidecar first (works in dev mode). In NSIS installs the sidecar
     // binary lives in the root install dir, NOT in binaries/, so the sidecar
     // lookup fails with os error 3. Fall back to scope-based lookup — same
     // strategy as connection::run_command().
     let sidecar_result = match app.shell().sidecar("binaries/proxmark3") {
-        Ok(cmd) => cmd.args(&flash_args).output().await.ok(),
+        Ok(cmd) => cmd.args(flash_args).output().await.ok(),
         Err(_) => None,
     };
 
@@ -211,14 +328,14 @@ pub async fn flash_firmware(
             FirmwareProgress {
                 phase: "writing".into(),
                 percent: 30,
-                message: "Flashing firmware (this may take up to 60 seconds)...".into(),
+                message: writing_message.into(),
             },
         );
 
         let mut last_err = String::from("No PM3 binary found");
         let mut found = None;
         for name in &scope_names {
-            match app.shell().command(name).args(&flash_args).output().await {
+            match app.shell().command(name).args(flash_args).output().await {
                 Ok(out) => {
                     found = Some(out);
                     break;
@@ -240,7 +357,7 @@ pub async fn flash_firmware(
                         message: format!("PM3 binary not found for flash: {}", last_err),
                     },
                 );
-                return Ok(());
+                return;
             }
         }
     };
@@ -250,13 +367,28 @@ pub async fn flash_firmware(
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
     if !stdout.is_empty() {
-        connection::emit_output(&app, &stdout, false);
+        connection::emit_output(app, &stdout, false);
     }
     if !stderr.is_empty() {
-        connection::emit_output(&app, &stderr, true);
+        connection::emit_output(app, &stderr, true);
     }
 
     let success = output.status.success();
+
+    // An interrupted flash can leave only the bootloader running — tell the
+    // frontend so it can offer `recover_firmware` instead of a plain retry.
+    if !success && (is_bootloader_mode(&stdout) || is_bootloader_mode(&stderr)) {
+        let _ = app.emit(
+            "firmware-failed",
+            FirmwareProgress {
+                phase: "bootloader".into(),
+                percent: 0,
+                message: "Device is stuck in bootloader mode. Run recovery to re-flash the bootloader and firmware.".into(),
+            },
+        );
+        return;
+    }
+
     let event_name = if success {
         "firmware-complete"
     } else {
@@ -280,35 +412,8 @@ pub async fn flash_firmware(
             },
         },
     );
-
-    Ok(())
 }
 
-/// Cancel an in-progress firmware flash by killing the child process.
-#[tauri::command]
-pub async fn cancel_flash(flash_state: State<'_, FlashState>) -> Result<(), AppError> {
-    let child = {
-        let mut lock = flash_state.child.lock().map_err(|e| {
-            AppError::CommandFailed(format!("Flash state lock poisoned: {}", e))
-        })?;
-        lock.take()
-    };
-
-    match child {
-        Some(child) => {
-            child.kill().map_err(|e| {
-                AppError::CommandFailed(format!("Failed to kill flash process: {}", e))
-            })?;
-            Ok(())
-        }
-        None => Ok(()), // No flash running — no-op
-    }
-}
-
-// ---------------------------------------------------------------------------
-// Internal helpers
-// ---------------------------------------------------------------------------
-
 fn firmware_file_exists(app: &AppHandle, variant: &str) -> bool {
     if !VALID_VARIANTS.contains(&variant) {
         return false;
@@ -1,14 +1,31 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::Instant;
 
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, State};
-
-use crate::cards::types::{AutopwnEvent, BlankType, CardType, ProcessPhase, RecoveryAction};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::cards::types::{
+    AutopwnEvent, BlankType, CardType, MagicGeneration, ProcessPhase, RecoveryAction,
+};
+use crate::commands::{dump, keys, write};
+use crate::db::models::SavedCard;
+use crate::db::{DataDir, Database};
 use crate::error::AppError;
+use crate::pm3::command_builder::{AutopwnPlan, KeyType};
 use crate::pm3::connection::HfOperationState;
-use crate::pm3::{command_builder, connection, output_parser};
-use crate::state::{WizardAction, WizardMachine, WizardState};
+use crate::pm3::dictionaries::{DictionaryKind, SelectedDictionaries};
+use crate::pm3::dump_report::{self, SectorKeys};
+use crate::pm3::keyfile;
+use crate::pm3::ntag_lock::{self, NtagLockStatus, NtagVariant};
+use crate::pm3::operation::{self, OperationResult};
+use crate::pm3::uid_safety::{self, UidWarning};
+use crate::pm3::{
+    blank_compat, capabilities, clone_summary, command_builder, connection, output_parser,
+    scan_cache,
+};
+use crate::state::{WizardAction, WizardCheckpoint, WizardMachine, WizardState};
 
 /// Payload emitted as `hf-progress` events during autopwn.
 #[derive(Debug, Clone, Serialize)]
@@ -17,6 +34,9 @@ struct HfProgressPayload {
     keys_found: u32,
     keys_total: u32,
     elapsed_secs: u32,
+    /// Seconds since the HF job started (see `HfOperationState::start_session`).
+    total_elapsed_secs: u64,
+    operation_id: Option<u64>,
 }
 
 /// Run `hf mf autopwn` with streaming progress. Recovers all keys and dumps
@@ -28,16 +48,28 @@ pub async fn hf_autopwn(
     app: AppHandle,
     machine: State<'_, Mutex<WizardMachine>>,
     hf_state: State<'_, HfOperationState>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    operation::run_operation(run_hf_autopwn(app, machine, hf_state)).await
+}
+
+async fn run_hf_autopwn(
+    app: AppHandle,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
-    // Extract port + card_type from current state, then transition to HfProcessing
-    let (port, card_type) = {
+    // Extract port + card_type + scan fields from current state, then transition to HfProcessing
+    let (port, card_type, decoded) = {
         let mut m = machine.lock().map_err(|e| {
             AppError::CommandFailed(format!("State lock poisoned: {}", e))
         })?;
-        let (port, card_type) = match &m.current {
-            WizardState::CardIdentified { card_type, .. } => {
+        let (port, card_type, decoded) = match &m.current {
+            WizardState::CardIdentified {
+                card_type,
+                card_data,
+                ..
+            } => {
                 match card_type {
-                    CardType::MifareClassic1K | CardType::MifareClassic4K => {}
+                    CardType::MifareMini | CardType::MifareClassic1K | CardType::MifareClassic4K => {}
                     _ => {
                         return Err(AppError::CommandFailed(format!(
                             "Autopwn only supports MIFARE Classic, got {:?}",
@@ -45,10 +77,11 @@ pub async fn hf_autopwn(
                         )));
                     }
                 }
+                capabilities::require_iceman(m.fork, "Autopwn")?;
                 let port = m.port.clone().ok_or_else(|| {
                     AppError::InvalidTransition("No port in machine state".to_string())
                 })?;
-                (port, card_type.clone())
+                (port, card_type.clone(), card_data.decoded.clone())
             }
             _ => {
                 return Err(AppError::InvalidTransition(
@@ -57,11 +90,30 @@ pub async fn hf_autopwn(
             }
         };
         m.transition(WizardAction::StartHfProcess)?;
-        (port, card_type)
+        (port, card_type, decoded)
     };
 
-    let cmd = command_builder::build_hf_autopwn(&card_type);
+    // Pick dictionary from the scan (PRNG / magic); keys recovered on earlier
+    // cards are used when the fallback attack would be slow
+    let project_keys = keys::project_keyfile(&app)?;
+    let project_keys = match keyfile::read_keys(&project_keys) {
+        Ok(k) if !k.is_empty() => Some(project_keys.to_string_lossy().to_string()),
+        _ => None,
+    };
+    let selected = app
+        .try_state::<SelectedDictionaries>()
+        .and_then(|s| s.get(DictionaryKind::MifareClassic));
+    let (selected, project_keys) = (selected.as_deref(), project_keys.as_deref());
+    let plan = command_builder::select_autopwn_plan(&decoded, selected, project_keys);
+    for line in plan.describe() {
+        connection::emit_output(&app, &format!("[=] {}", line), false);
+    }
+    let cmd = match autopwn_dictionary(&app, &plan)? {
+        Some(dict) => command_builder::build_hf_autopwn_with_keys(&card_type, &dict),
+        None => command_builder::build_hf_autopwn(&card_type),
+    };
     let start_time = Instant::now();
+    hf_state.start_session();
 
     // Progress state tracked across lines via the closure
     let mut current_phase = ProcessPhase::KeyCheck;
@@ -70,15 +122,14 @@ pub async fn hf_autopwn(
     // produce visible progress (Classic 1K = 16 sectors × 2 keys = 32,
     // Classic 4K = 40 sectors × 2 keys = 80). Without this, keys_total stays 0
     // until the summary line "found X/Y keys (D)" which arrives at the END.
-    let mut keys_total: u32 = match card_type {
-        CardType::MifareClassic4K => 80,
-        _ => 32,
-    };
+    let mut keys_total: u32 = card_type.mifare_key_total().unwrap_or(32);
     let mut dump_file: Option<String> = None;
     let mut dump_complete = false;
     let mut dump_partial = false;
 
-    let app_for_closure = app.clone();
+    let throttle = connection::ProgressThrottle::new(connection::PROGRESS_EMIT_INTERVAL, |p| {
+        let _ = app.emit("hf-progress", p);
+    });
 
     // Emit initial progress so the frontend shows 0/32 (or 0/80) immediately
     let _ = app.emit(
@@ -88,6 +139,8 @@ pub async fn hf_autopwn(
             keys_found: 0,
             keys_total,
             elapsed_secs: 0,
+            total_elapsed_secs: 0,
+            operation_id: operation::current_operation_id(),
         },
     );
 
@@ -140,28 +193,39 @@ pub async fn hf_autopwn(
                     AutopwnEvent::Failed { .. } | AutopwnEvent::Finished { .. } => {}
                 }
 
-                // Emit progress event to frontend
-                let _ = app_for_closure.emit(
-                    "hf-progress",
-                    HfProgressPayload {
-                        phase: format!("{:?}", current_phase),
-                        keys_found,
-                        keys_total,
-                        elapsed_secs: elapsed,
-                    },
-                );
+                // Emit progress event to frontend, coalescing bursts
+                let payload = HfProgressPayload {
+                    phase: format!("{:?}", current_phase),
+                    keys_found,
+                    keys_total,
+                    elapsed_secs: elapsed,
+                    total_elapsed_secs: hf_state.total_elapsed_secs(),
+                    operation_id: operation::current_operation_id(),
+                };
+                throttle.offer(payload);
             }
         },
     )
     .await;
+    drop(throttle);
 
     match result {
-        Ok(_output) => {
+        Ok(output) => {
+            // Keep the per-sector key table so a partial run can be resumed
+            let sector_keys = output_parser::parse_mf_key_table(&output);
+            if let Ok(mut lock) = hf_state.sector_keys.lock() {
+                *lock = (!sector_keys.is_empty()).then_some(sector_keys);
+            }
+
             // Store dump file path in HfOperationState for the write phase
             if let Some(ref path) = dump_file {
                 if let Ok(mut lock) = hf_state.dump_path.lock() {
                     *lock = Some(path.clone());
                 }
+                if let Ok(mut lock) = hf_state.key_path.lock() {
+                    *lock = dump::sibling_key_file(Path::new(path))
+                        .map(|p| p.to_string_lossy().into_owned());
+                }
             }
 
             let dump_info = if dump_complete {
@@ -179,6 +243,19 @@ pub async fn hf_autopwn(
             } else {
                 "Key recovery completed.".to_string()
             };
+            let dump_info = match dump_file
+                .as_deref()
+                .and_then(|path| dump_report::load_dump(Path::new(path)).ok())
+                .and_then(|dump| dump_report::dump_manufacturer_block(&dump))
+            {
+                Some(block0) => {
+                    if !block0.bcc_valid {
+                        log::warn!("Dumped block 0 has a bad BCC: {:?}", block0);
+                    }
+                    format!("{} Block 0: {}.", dump_info, block0.describe())
+                }
+                None => dump_info,
+            };
 
             // Transition to HfDumpReady
             let mut m = machine.lock().map_err(|e| {
@@ -203,11 +280,32 @@ pub async fn hf_autopwn(
     }
 }
 
-/// Cancel a running HF operation (autopwn, dump, write) by killing the child process.
+/// The one `-f` file for `plan`: its dictionary, or its dictionaries merged
+/// into `keyfile::AUTOPWN_KEYFILE_NAME` next to the project keyfile.
+fn autopwn_dictionary(app: &AppHandle, plan: &AutopwnPlan) -> Result<Option<String>, AppError> {
+    match plan.dictionaries.as_slice() {
+        [] => Ok(None),
+        [dict] => Ok(Some(dict.clone())),
+        dicts => {
+            let merged = keys::project_keyfile(app)?.with_file_name(keyfile::AUTOPWN_KEYFILE_NAME);
+            keyfile::merge_keyfiles(dicts, &merged)?;
+            Ok(Some(merged.to_string_lossy().into_owned()))
+        }
+    }
+}
+
+/// Cancel a running HF operation (autopwn, dump, write, verify) by killing the child process.
 #[tauri::command]
 pub async fn cancel_hf_operation(
     hf_state: tauri::State<'_, HfOperationState>,
 ) -> Result<(), AppError> {
+    kill_hf_child(&hf_state)?;
+    Ok(())
+}
+
+/// Kill the running HF child process, if there is one. Returns whether a
+/// process was killed; commands run without streaming have none to kill.
+pub(crate) fn kill_hf_child(hf_state: &HfOperationState) -> Result<bool, AppError> {
     let child = {
         let mut lock = hf_state.child.lock().map_err(|e| {
             AppError::CommandFailed(format!("HF state lock poisoned: {}", e))
@@ -217,15 +315,312 @@ pub async fn cancel_hf_operation(
 
     match child {
         Some(child) => {
+            hf_state
+                .cancelled
+                .store(true, std::sync::atomic::Ordering::SeqCst);
             child.kill().map_err(|e| {
                 AppError::CommandFailed(format!("Failed to kill HF process: {}", e))
             })?;
-            Ok(())
+            Ok(true)
         }
-        None => Ok(()),
+        None => Ok(false),
     }
 }
 
+// ---------------------------------------------------------------------------
+// Resume a partial autopwn
+// ---------------------------------------------------------------------------
+
+/// Each per-sector nested/hardnested run gets the full autopwn budget.
+const HF_RESUME_SECTOR_TIMEOUT_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeResult {
+    /// Keys recovered by this resume.
+    pub recovered: u32,
+    /// Sectors still missing key A or key B.
+    pub still_missing: Vec<u16>,
+    /// New dump taken with the completed key file, if any key was recovered.
+    pub dump_path: Option<String>,
+}
+
+/// After a partial `hf_autopwn`, attack only the sectors whose keys are
+/// still missing (nested for a weak PRNG, hardnested otherwise), update the
+/// key file and re-dump the card with it. Progress goes out as `hf-progress`;
+/// the wizard goes from `HfDumpReady` back through `HfProcessing`. Keys found
+/// before a cancel or failure are kept in the key file.
+#[tauri::command]
+pub async fn resume_autopwn(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    prng: Option<String>,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<OperationResult<ResumeResult>, AppError> {
+    operation::run_operation(run_resume_autopwn(app, port, card_type, prng, machine, hf_state))
+        .await
+}
+
+async fn run_resume_autopwn(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    prng: Option<String>,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<ResumeResult, AppError> {
+    let mut sectors = hf_state
+        .sector_keys
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))?
+        .clone()
+        .ok_or_else(|| {
+            AppError::CommandFailed("No key table from a previous autopwn. Run key recovery first.".into())
+        })?;
+    let key_path = hf_state
+        .key_path
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))?
+        .clone()
+        .ok_or_else(|| AppError::CommandFailed("No key file from a previous autopwn".into()))?;
+    let plan = command_builder::build_resume_plan(&sectors, prng.as_deref())
+        .map_err(AppError::CommandFailed)?;
+    machine
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))?
+        .transition(WizardAction::StartHfProcess)?;
+
+    let keys_total = sectors.len() as u32 * 2;
+    let phase = match prng.as_deref().map(str::to_uppercase).as_deref() {
+        Some("WEAK") => ProcessPhase::Nested,
+        _ => ProcessPhase::Hardnested,
+    };
+    let start_time = Instant::now();
+    let run = |cmd: String| {
+        let (app, port, hf_state) = (app.clone(), port.clone(), &hf_state);
+        async move {
+            connection::run_command_streaming(
+                &app,
+                &port,
+                &cmd,
+                HF_RESUME_SECTOR_TIMEOUT_SECS,
+                hf_state,
+                |_| {},
+            )
+            .await
+        }
+    };
+    let progress = |keys_found: u32| {
+        let _ = app.emit(
+            "hf-progress",
+            HfProgressPayload {
+                phase: format!("{:?}", phase),
+                keys_found,
+                keys_total,
+                elapsed_secs: start_time.elapsed().as_secs() as u32,
+                total_elapsed_secs: hf_state.total_elapsed_secs(),
+                operation_id: operation::current_operation_id(),
+            },
+        );
+    };
+    let (recovered, attacked) = attack_missing_keys(run, &plan, &mut sectors, progress).await;
+
+    // Whatever was found is kept, even if the run was cancelled or failed
+    let saved = if recovered > 0 {
+        std::fs::write(&key_path, dump_report::encode_key_file(&sectors)).map_err(|e| {
+            AppError::CommandFailed(format!("Failed to update key file {}: {}", key_path, e))
+        })
+    } else {
+        Ok(())
+    };
+    let still_missing: Vec<u16> = sectors
+        .iter()
+        .enumerate()
+        .filter(|(_, k)| k.key_a.is_none() || k.key_b.is_none())
+        .map(|(sector, _)| sector as u16)
+        .collect();
+    if let Ok(mut lock) = hf_state.sector_keys.lock() {
+        *lock = Some(sectors);
+    }
+
+    let mut dump_path = None;
+    let outcome = match attacked.and(saved) {
+        Ok(()) if recovered > 0 => {
+            let cmd = command_builder::build_mf_dump_with_keys(&card_type, &key_path);
+            connection::run_command(&app, &port, &cmd).await.map(|output| {
+                dump_path = output_parser::extract_dump_file_path(&output);
+                if let (Some(path), Ok(mut lock)) = (&dump_path, hf_state.dump_path.lock()) {
+                    *lock = Some(path.clone());
+                }
+            })
+        }
+        other => other,
+    };
+
+    let mut m = machine
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))?;
+    match outcome {
+        Ok(()) => {
+            let dump_info = format!(
+                "Resume recovered {} key(s); {} sector(s) still missing keys.{}",
+                recovered,
+                still_missing.len(),
+                if dump_path.is_some() { " New dump saved." } else { "" }
+            );
+            m.transition(WizardAction::HfProcessComplete { dump_info })?;
+            Ok(ResumeResult {
+                recovered,
+                still_missing,
+                dump_path,
+            })
+        }
+        Err(e) => {
+            m.transition(WizardAction::ReportError {
+                message: e.to_string(),
+                user_message: format!(
+                    "Key recovery stopped. {} new key(s) were saved; resume to continue.",
+                    recovered
+                ),
+                recoverable: true,
+                recovery_action: Some(RecoveryAction::Retry),
+            })?;
+            Err(e)
+        }
+    }
+}
+
+/// Run the resume plan's attacks in order, filling `sectors` with each key
+/// found and reporting the running key count through `progress`. Stops at
+/// the first failed run (a cancel included), keeping the keys found before
+/// it. Returns how many keys were recovered and what stopped the run.
+async fn attack_missing_keys<R, Fut>(
+    mut run: R,
+    plan: &[command_builder::ResumeStep],
+    sectors: &mut [SectorKeys],
+    mut progress: impl FnMut(u32),
+) -> (u32, Result<(), AppError>)
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let count_found = |sectors: &[SectorKeys]| {
+        sectors
+            .iter()
+            .map(|k| u32::from(k.key_a.is_some()) + u32::from(k.key_b.is_some()))
+            .sum::<u32>()
+    };
+    let mut recovered = 0;
+    for step in plan {
+        progress(count_found(sectors));
+        let output = match run(step.cmd.clone()).await {
+            Ok(output) => output,
+            Err(e) => return (recovered, Err(e)),
+        };
+        if let Some(key) = output_parser::parse_found_key(&output) {
+            let keys = &mut sectors[usize::from(step.sector)];
+            if step.key_b {
+                keys.key_b = Some(key);
+            } else {
+                keys.key_a = Some(key);
+            }
+            recovered += 1;
+        }
+    }
+    (recovered, Ok(()))
+}
+
+/// One sector read by `dump_sectors`; `None` for a block the key couldn't read.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectorDump {
+    pub sector: u16,
+    pub blocks: Vec<Option<String>>,
+}
+
+/// Read only `first_sector..=last_sector` of a Classic card with the keys
+/// from the last autopwn — for the few sectors of interest on a 4K, or to
+/// re-read sectors just changed while testing. Sectors 32-39 of a 4K are 16
+/// blocks, the rest 4.
+#[tauri::command]
+pub async fn dump_sectors(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    first_sector: u16,
+    last_sector: u16,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<Vec<SectorDump>, AppError> {
+    let keys = hf_state
+        .sector_keys
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))?
+        .clone()
+        .ok_or_else(|| {
+            AppError::CommandFailed("No key table from a previous autopwn. Run key recovery first.".into())
+        })?;
+    let cmds = command_builder::build_mf_dump_range(&card_type, first_sector, last_sector, &keys)
+        .map_err(AppError::CommandFailed)?;
+
+    let run = |cmd: String| {
+        let app = app.clone();
+        let port = port.clone();
+        async move { connection::run_command(&app, &port, &cmd).await }
+    };
+    Ok(read_sectors(run, first_sector, &cmds).await)
+}
+
+/// Run one read command per sector from `first_sector` through `run`. A
+/// sector the key doesn't open fails the client, which leaves its blocks
+/// `None` rather than ending the range.
+async fn read_sectors<R, Fut>(mut run: R, first_sector: u16, cmds: &[String]) -> Vec<SectorDump>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let mut sectors = Vec::with_capacity(cmds.len());
+    for (sector, cmd) in (first_sector..).zip(cmds) {
+        let output = run(cmd.clone()).await.unwrap_or_else(|e| {
+            log::warn!("Sector {} read failed: {}", sector, e);
+            String::new()
+        });
+        let (first_block, count) = dump_report::sector_blocks(usize::from(sector));
+        let blocks = (first_block..first_block + count)
+            .map(|blk| {
+                output_parser::parse_mf_rdbl(&output, blk as u16).map(|b| dump_report::hex(&b))
+            })
+            .collect();
+        sectors.push(SectorDump { sector, blocks });
+    }
+    sectors
+}
+
+/// Read one Classic block with a given key (`key_type` defaults to key A) and
+/// return its 32 hex characters. Errs when the key doesn't open the block.
+#[tauri::command]
+pub async fn read_mf_block(
+    app: AppHandle,
+    port: String,
+    block: u16,
+    key: String,
+    key_type: Option<KeyType>,
+) -> Result<String, AppError> {
+    let key = keyfile::normalize_key(&key)?;
+    let key_type = key_type.unwrap_or_default();
+    let cmd = command_builder::build_mf_rdbl(block, &key, key_type);
+    let output = connection::run_command(&app, &port, &cmd).await?;
+    output_parser::parse_mf_rdbl(&output, block)
+        .map(|b| dump_report::hex(&b))
+        .ok_or_else(|| {
+            AppError::CommandFailed(format!(
+                "Block {} could not be read with key {:?} {}",
+                block, key_type, key
+            ))
+        })
+}
+
 // ---------------------------------------------------------------------------
 // HF Write Clone — 7 workflows
 // ---------------------------------------------------------------------------
@@ -238,33 +633,53 @@ pub async fn cancel_hf_operation(
 ///
 /// `source_uid` is passed from the frontend XState context because the Rust FSM
 /// doesn't persist `card_data` after state transitions.
+///
+/// A UID readers may mishandle (random ID, reserved prefix, bad BCC) stops the
+/// write with `AcknowledgeUidWarning`; retry with `acknowledge_uid_warning`
+/// set to write it anyway.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn hf_write_clone(
     app: AppHandle,
     source_uid: String,
     card_type: CardType,
     blank_type: BlankType,
+    acknowledge_uid_warning: Option<bool>,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    scan_cache::invalidate(&app);
+    operation::run_operation(run_hf_write_clone(
+        app,
+        source_uid,
+        card_type,
+        blank_type,
+        acknowledge_uid_warning.unwrap_or(false),
+        machine,
+        hf_state,
+    ))
+    .await
+}
+
+async fn run_hf_write_clone(
+    app: AppHandle,
+    source_uid: String,
+    card_type: CardType,
+    blank_type: BlankType,
+    acknowledge_uid_warning: bool,
     machine: State<'_, Mutex<WizardMachine>>,
     hf_state: State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
+    // Wrong-technology pairs (an LF source on an HF blank) fail here with
+    // an explanation instead of a cryptic PM3 error mid-write
+    blank_compat::check_clone_target(&card_type, &blank_type).map_err(AppError::CommandFailed)?;
+
     // Extract port from machine, validate state
     let port = {
         let mut m = machine.lock().map_err(|e| {
             AppError::CommandFailed(format!("State lock poisoned: {}", e))
         })?;
-        match &m.current {
-            WizardState::BlankDetected { .. } => {}
-            _ => {
-                return Err(AppError::InvalidTransition(
-                    "Must be in BlankDetected to write clone".to_string(),
-                ));
-            }
-        }
-        let port = m.port.clone().ok_or_else(|| {
-            AppError::InvalidTransition("No port in machine state".to_string())
-        })?;
-        m.transition(WizardAction::StartWrite)?;
-        port
+        start_write(&mut m)?
     };
 
     // Get dump file path from HfOperationState (set by hf_autopwn or hf_dump)
@@ -276,23 +691,151 @@ pub async fn hf_write_clone(
             AppError::CommandFailed("No dump file available. Run key recovery first.".to_string())
         })?
     };
+    // The file may have been deleted (by hand or by dump retention) since
+    // key recovery; catch that before any command touches the blank
+    if let Err(reason) = check_dump_file(&dump_path) {
+        return report_error(
+            &machine,
+            &reason,
+            DUMP_FILE_MISSING_MESSAGE,
+            true,
+            Some(RecoveryAction::Retry),
+        );
+    }
+
+    // Every magic workflow writes the UID (block 0, gen3uid or the UL UID
+    // pages); iCLASS keeps the blank's CSN
+    let block0 = match blank_type {
+        BlankType::IClassBlank => None,
+        BlankType::MagicUltralight => Some(None),
+        _ => Some(read_block0_from_dump(&dump_path).ok()),
+    };
+    let checked = block0.map(|block0| {
+        uid_write_check(&source_uid, &card_type, block0.as_deref(), acknowledge_uid_warning)
+    });
+    let uid_warning = match checked {
+        Some(Err(warning)) => return report_unsafe_uid(&machine, &warning),
+        Some(Ok(warning)) => warning,
+        None => None,
+    };
+
+    // Keep the archival copy of the source before anything touches the blank
+    let archived =
+        archive_source(&app, &machine, &hf_state, &card_type, &source_uid, &dump_path);
+    let source_dump = match archived {
+        Ok(path) => path,
+        Err(e) => {
+            return report_error(
+                &machine,
+                &e.to_string(),
+                "Could not archive the source dump, so nothing was written. Check the data folder is writable.",
+                true,
+                Some(RecoveryAction::Retry),
+            );
+        }
+    };
+
+    // Fixed-UID blanks: read the blank once, for the UID length check and to
+    // spot ZUID / ONUID dual-magic cards sold as CUID.
+    let blank_info = match blank_type {
+        BlankType::MagicMifareGen1a | BlankType::MagicMifareGen2 => {
+            match connection::run_command(&app, &port, command_builder::build_hf_mf_info()).await {
+                Ok(output) => Some(output),
+                Err(e) => {
+                    return report_error(
+                        &machine,
+                        &e.to_string(),
+                        "Could not inspect the blank. Check it is on the reader and try again.",
+                        true,
+                        Some(RecoveryAction::Retry),
+                    );
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Refuse before writing if the silicon can't present the source UID
+    // length (anticollision would report the wrong UID).
+    if let Some(reason) = blank_info
+        .as_deref()
+        .and_then(|info| fixed_uid_mismatch(info, &source_uid, &blank_type))
+    {
+        return report_error(
+            &machine,
+            &format!("Blank UID length mismatch: {}", reason),
+            &format!(
+                "{} Nothing was written. Use a Gen3 or Gen4 blank, or a blank with the matching UID size.",
+                reason
+            ),
+            true,
+            Some(RecoveryAction::GoBack),
+        );
+    }
+    let workflow = blank_info
+        .as_deref()
+        .map_or(blank_type.clone(), |info| blank_compat::write_workflow(&blank_type, info));
+
+    clone_summary::begin(&app, &card_type, &source_uid, &blank_type);
+    if let Some(path) = &source_dump {
+        clone_summary::set_source_dump(&app, path);
+    }
+    if let Some(warning) = &uid_warning {
+        connection::emit_output(&app, &format!("[!] Writing UID anyway: {}", warning), true);
+        clone_summary::warn(&app, &format!("Written despite UID warning: {}", warning));
+    }
+    let missing_keys = hf_state
+        .sector_keys
+        .lock()
+        .ok()
+        .and_then(|keys| {
+            keys.as_ref().map(|k| {
+                k.iter()
+                    .map(|s| usize::from(s.key_a.is_none()) + usize::from(s.key_b.is_none()))
+                    .sum::<usize>()
+            })
+        })
+        .unwrap_or(0);
+    if missing_keys > 0 {
+        clone_summary::warn(
+            &app,
+            &format!("{} sector key(s) were never recovered; those sectors may not match", missing_keys),
+        );
+    }
+
+    if workflow != blank_type {
+        connection::emit_output(
+            &app,
+            "[=] ZUID / ONUID dual-magic blank: writing through the Gen1a backdoor",
+            false,
+        );
+    }
 
     // Run the write workflow, catching errors to report via FSM
-    let result = match blank_type {
+    let result = match workflow {
         BlankType::MagicMifareGen1a => {
-            write_gen1a(&app, &port, &dump_path, &machine).await
+            write_gen1a(&app, &port, &dump_path, &card_type, &machine).await
         }
         BlankType::MagicMifareGen2 => {
-            write_gen2(&app, &port, &dump_path, &source_uid, &card_type, &machine).await
+            write_gen2(
+                &app,
+                &port,
+                &dump_path,
+                &card_type,
+                blank_info.as_deref(),
+                &machine,
+                &hf_state,
+            )
+            .await
         }
         BlankType::MagicMifareGen3 => {
-            write_gen3(&app, &port, &dump_path, &source_uid, &card_type, &machine).await
+            write_gen3(&app, &port, &dump_path, &source_uid, &card_type, &machine, &hf_state).await
         }
         BlankType::MagicMifareGen4GTU => {
-            write_gen4_gtu(&app, &port, &dump_path, &machine).await
+            write_gen4_gtu(&app, &port, &dump_path, &card_type, &machine).await
         }
         BlankType::MagicMifareGen4GDM => {
-            write_gen4_gdm(&app, &port, &dump_path, &machine).await
+            write_gen4_gdm(&app, &port, &dump_path, &card_type, &machine).await
         }
         BlankType::MagicUltralight => {
             write_ultralight(&app, &port, &dump_path, &machine).await
@@ -308,7 +851,7 @@ pub async fn hf_write_clone(
         }
     };
 
-    match result {
+    let state = match result {
         Ok(state) => Ok(state),
         Err(e) => {
             report_error(
@@ -319,7 +862,11 @@ pub async fn hf_write_clone(
                 Some(RecoveryAction::Retry),
             )
         }
-    }
+    };
+
+    // A write that failed or ended in Error never reaches verification
+    clone_summary::end_write(&app, &state);
+    state
 }
 
 // ---------------------------------------------------------------------------
@@ -336,6 +883,14 @@ pub async fn hf_dump(
     app: AppHandle,
     machine: State<'_, Mutex<WizardMachine>>,
     hf_state: State<'_, HfOperationState>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    operation::run_operation(run_hf_dump(app, machine, hf_state)).await
+}
+
+async fn run_hf_dump(
+    app: AppHandle,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
     // Extract port + card_type, transition to HfProcessing
     let (port, card_type) = {
@@ -373,11 +928,23 @@ pub async fn hf_dump(
         CardType::IClass => command_builder::build_iclass_dump(),
         _ => command_builder::build_mfu_dump(), // UL + NTAG
     };
+    hf_state.start_session();
+
+    // The exact UL/NTAG chip tells us how many pages a complete dump has
+    let mfu_variant = match card_type {
+        CardType::IClass => None,
+        _ => connection::run_command(&app, &port, command_builder::build_hf_mfu_info())
+            .await
+            .ok()
+            .and_then(|output| output_parser::parse_mfu_version(&output)),
+    };
 
     let result = connection::run_command(&app, &port, cmd).await;
 
     match result {
         Ok(output) => {
+            let pages_read = output_parser::parse_mfu_dump_pages(&output);
+
             // Extract dump file path from output
             let dump_file = output_parser::extract_dump_file_path(&output);
 
@@ -388,9 +955,20 @@ pub async fn hf_dump(
                 }
             }
 
-            let dump_info = match &card_type {
-                CardType::IClass => "iCLASS dump complete.".to_string(),
-                CardType::NTAG => "NTAG dump complete.".to_string(),
+            let dump_info = match (&card_type, mfu_variant) {
+                (CardType::IClass, _) => {
+                    // Personalized/locked config explains many failed iCLASS writes
+                    let config = dump_file
+                        .as_deref()
+                        .and_then(|path| std::fs::read(path).ok())
+                        .and_then(|bytes| output_parser::decode_iclass_config(&bytes));
+                    match config {
+                        Some(config) => format!("iCLASS dump complete. {}.", config.summary()),
+                        None => "iCLASS dump complete.".to_string(),
+                    }
+                }
+                (_, Some(variant)) => mfu_dump_info(variant, pages_read),
+                (CardType::NTAG, None) => "NTAG dump complete.".to_string(),
                 _ => "Ultralight dump complete.".to_string(),
             };
 
@@ -424,50 +1002,242 @@ async fn write_gen1a(
     app: &AppHandle,
     port: &str,
     dump_path: &str,
+    card_type: &CardType,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     update_write_progress(app, machine, 0.3, Some(1), Some(2))?;
 
-    let cmd = command_builder::build_mf_cload(dump_path);
+    let cmd = command_builder::build_mf_cload(card_type, dump_path);
     let output = connection::run_command(app, port, &cmd).await?;
     check_write_output(&output)?;
 
     finish_write(app, machine).await
 }
 
-/// Gen2/CUID: config force -> wrbl0 -> restore -> config reset.
+/// How a GDM write got the dump onto the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GdmWrite {
+    Cload,
+    BlockByBlock,
+}
+
+/// Try `cload` through the Gen1a backdoor and fall back to `blocks` when the
+/// card refuses it. The backdoor is a GDM config option; cards shipped (or
+/// later reconfigured) without it refuse cload, but still take gdmsetblk.
+async fn gdm_cload_or_blocks<C, CFut, B, BFut>(cload: C, blocks: B) -> Result<GdmWrite, AppError>
+where
+    C: FnOnce() -> CFut,
+    CFut: Future<Output = Result<String, AppError>>,
+    B: FnOnce() -> BFut,
+    BFut: Future<Output = Result<(), AppError>>,
+{
+    match cload().await.and_then(|output| check_write_output(&output)) {
+        Ok(()) => Ok(GdmWrite::Cload),
+        Err(e) => {
+            log::warn!("GDM cload failed: {}", e);
+            blocks().await?;
+            Ok(GdmWrite::BlockByBlock)
+        }
+    }
+}
+
+/// GDM fallback: `gdmsetblk` every block of the dump, then read the data
+/// blocks back with each sector's new key A.
+async fn write_gen4_gdm_blocks(
+    app: &AppHandle,
+    port: &str,
+    dump_path: &str,
+    machine: &State<'_, Mutex<WizardMachine>>,
+) -> Result<(), AppError> {
+    let dump = dump_report::load_dump(Path::new(dump_path))?;
+    let writes = command_builder::build_mf_gdm_setblk_all(&dump).map_err(AppError::CommandFailed)?;
+    let total = writes.len() as u16;
+
+    for (i, (blk, cmd)) in writes.iter().enumerate() {
+        let progress = 0.3 + 0.5 * (i as f32 / f32::from(total));
+        update_write_progress(app, machine, progress, Some(blk + 1), Some(total))?;
+        let output = connection::run_command(app, port, cmd).await?;
+        check_write_output(&output)?;
+    }
+
+    update_write_progress(app, machine, 0.85, None, None)?;
+    let mut mismatched = Vec::new();
+    for sector in 0..dump_report::sector_count(dump.len()).unwrap_or(0) {
+        let (first, count) = dump_report::sector_blocks(sector);
+        let trailer = &dump[(first + count - 1) * 16..(first + count) * 16];
+        let key_a = dump_report::hex(&trailer[..6]);
+        let key_b = dump_report::hex(&trailer[10..]);
+        // Trailers read back with key A masked, so only data blocks are compared
+        for blk in first..first + count - 1 {
+            let block = read_block_key_a_or_b(
+                |cmd| async move { connection::run_command(app, port, &cmd).await },
+                blk as u16,
+                &key_a,
+                &key_b,
+            )
+            .await;
+            if block.as_ref().map(|b| &b[..]) != Some(&dump[blk * 16..(blk + 1) * 16]) {
+                mismatched.push(blk);
+            }
+        }
+    }
+    if !mismatched.is_empty() {
+        return Err(AppError::CommandFailed(format!(
+            "GDM block write readback mismatch on block(s) {:?}",
+            mismatched
+        )));
+    }
+    Ok(())
+}
+
+/// Read `blk` through `run` with key A, falling back to key B for blocks
+/// whose access bits only allow key B reads. A failed auth exits the client
+/// non-zero, so an `Err` counts as unread. `None` when neither key reads it.
+async fn read_block_key_a_or_b<R, Fut>(
+    mut run: R,
+    blk: u16,
+    key_a: &str,
+    key_b: &str,
+) -> Option<[u8; 16]>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    for (key, key_type) in [(key_a, KeyType::A), (key_b, KeyType::B)] {
+        let cmd = command_builder::build_mf_rdbl(blk, key, key_type);
+        let block = run(cmd)
+            .await
+            .ok()
+            .and_then(|output| output_parser::parse_mf_rdbl(&output, blk));
+        if block.is_some() {
+            return block;
+        }
+    }
+    None
+}
+
+/// MIFARE Classic readback: `hf mf dump` reads each sector with one key, so
+/// data blocks of key-B-only sectors come back blank. Re-read the
+/// `mismatched` data blocks with the original dump's sector keys (A, then B)
+/// and keep the ones that still differ.
+async fn recheck_mismatched_blocks<R, Fut>(
+    mut run: R,
+    original: &[u8],
+    mismatched: Vec<u16>,
+) -> Vec<u16>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let Some(sectors) = dump_report::sector_count(original.len()) else {
+        return mismatched;
+    };
+    let mut still = Vec::new();
+    for sector in 0..sectors {
+        let (first, count) = dump_report::sector_blocks(sector);
+        let trailer_blk = first + count - 1;
+        let trailer = &original[trailer_blk * 16..(trailer_blk + 1) * 16];
+        let key_a = dump_report::hex(&trailer[..6]);
+        let key_b = dump_report::hex(&trailer[10..]);
+        for blk in first..=trailer_blk {
+            if !mismatched.contains(&(blk as u16)) {
+                continue;
+            }
+            // Trailers read back with the keys masked: nothing to re-read
+            let matches = blk != trailer_blk
+                && read_block_key_a_or_b(&mut run, blk as u16, &key_a, &key_b)
+                    .await
+                    .is_some_and(|b| b[..] == original[blk * 16..(blk + 1) * 16]);
+            if !matches {
+                still.push(blk as u16);
+            }
+        }
+    }
+    still
+}
+
+/// How `write_gen2` gets block 0 onto the blank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gen2Block0 {
+    /// True CUID: try `wrbl0` straight away, forcing the 14a config only if
+    /// the card refuses.
+    DirectFirst,
+    /// Other Gen2 variants: force the 14a config before `wrbl0`.
+    ConfigForce,
+}
+
+/// Pick the block 0 path from the blank's `hf mf info` output.
+fn gen2_block0_strategy(blank_info: Option<&str>) -> Gen2Block0 {
+    if blank_info.is_some_and(output_parser::parse_cuid) {
+        Gen2Block0::DirectFirst
+    } else {
+        Gen2Block0::ConfigForce
+    }
+}
+
+/// Gen2/CUID: [config force ->] wrbl0 -> restore [-> config reset]. True
+/// CUID blanks skip the config commands, which fail on some firmware builds,
+/// unless the direct block 0 write is refused.
+#[allow(clippy::too_many_arguments)]
 async fn write_gen2(
     app: &AppHandle,
     port: &str,
     dump_path: &str,
-    _source_uid: &str,
-    _card_type: &CardType,
+    card_type: &CardType,
+    blank_info: Option<&str>,
     machine: &State<'_, Mutex<WizardMachine>>,
+    hf_state: &State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
     let total: u16 = 5;
 
-    // Step 1: Force 14a config to allow block 0 write
+    let block0 = read_block0_from_dump(dump_path)?;
+    let wrbl0 = command_builder::build_mf_wrbl0("FFFFFFFFFFFF", &block0);
+
+    // Step 1: CUID blanks take block 0 directly
     update_write_progress(app, machine, 0.1, Some(1), Some(total))?;
-    let cmd = command_builder::build_mf_gen2_config_force();
-    connection::run_command(app, port, cmd).await?;
+    let mut config_forced = false;
+    let direct_ok = match gen2_block0_strategy(blank_info) {
+        Gen2Block0::DirectFirst => {
+            // A refused or failed write (the client can also exit non-zero)
+            // falls through to the config force path
+            let ok = connection::run_command(app, port, &wrbl0)
+                .await
+                .and_then(|output| check_write_output(&output))
+                .is_ok();
+            if !ok {
+                connection::emit_output(
+                    app,
+                    "[=] Direct block 0 write refused; retrying with 14a config force",
+                    false,
+                );
+            }
+            ok
+        }
+        Gen2Block0::ConfigForce => false,
+    };
 
-    // Step 2: Read block 0 from dump and force-write it
-    update_write_progress(app, machine, 0.3, Some(2), Some(total))?;
-    let block0 = read_block0_from_dump(dump_path)?;
-    let cmd = command_builder::build_mf_wrbl0("FFFFFFFFFFFF", &block0);
-    let output = connection::run_command(app, port, &cmd).await?;
-    check_write_output(&output)?;
+    if !direct_ok {
+        // Force 14a config to allow block 0 write
+        let cmd = command_builder::build_mf_gen2_config_force();
+        connection::run_command(app, port, cmd).await?;
+        config_forced = true;
+
+        // Step 2: Force-write block 0 from the dump
+        update_write_progress(app, machine, 0.3, Some(2), Some(total))?;
+        let output = connection::run_command(app, port, &wrbl0).await?;
+        check_write_output(&output)?;
+    }
 
     // Step 3: Restore all blocks from dump
     update_write_progress(app, machine, 0.6, Some(3), Some(total))?;
-    let cmd = command_builder::build_mf_restore(dump_path);
-    let output = connection::run_command(app, port, &cmd).await?;
-    check_write_output(&output)?;
+    restore_streaming(app, port, dump_path, card_type, machine, hf_state, 0.6, 0.85).await?;
 
     // Step 4: Reset 14a config to standard
-    update_write_progress(app, machine, 0.85, Some(4), Some(total))?;
-    let cmd = command_builder::build_mf_gen2_config_reset();
-    connection::run_command(app, port, cmd).await?;
+    if config_forced {
+        update_write_progress(app, machine, 0.85, Some(4), Some(total))?;
+        let cmd = command_builder::build_mf_gen2_config_reset();
+        connection::run_command(app, port, cmd).await?;
+    }
 
     finish_write(app, machine).await
 }
@@ -478,45 +1248,141 @@ async fn write_gen3(
     port: &str,
     dump_path: &str,
     source_uid: &str,
-    _card_type: &CardType,
+    card_type: &CardType,
     machine: &State<'_, Mutex<WizardMachine>>,
+    hf_state: &State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
     let total: u16 = 4;
 
-    // Step 1: Set UID via APDU
-    update_write_progress(app, machine, 0.1, Some(1), Some(total))?;
+    let block0 = read_block0_from_dump(dump_path)?;
+
     // Extract UID without spaces/colons for gen3uid command
-    let clean_uid: String = source_uid.chars().filter(|c| c.is_ascii_hexdigit()).collect();
-    let cmd = command_builder::build_mf_gen3uid(&clean_uid);
+    let clean_uid: String = source_uid
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_uppercase();
+    let cmd = command_builder::build_mf_gen3uid(&clean_uid).map_err(AppError::CommandFailed)?;
+
+    // Step 1: Set UID via APDU. gen3uid also switches the UID size, so a
+    // blank last used with the other length is re-checked before block 0.
+    update_write_progress(app, machine, 0.1, Some(1), Some(total))?;
+    let current_len = read_14a_uid(app, port).await.map(|uid| uid.len());
+    let length_change = current_len.is_some_and(|len| len != clean_uid.len());
+    if length_change {
+        connection::emit_output(
+            app,
+            &format!(
+                "[=] Switching Gen3 UID from {} to {} bytes",
+                current_len.unwrap_or_default() / 2,
+                clean_uid.len() / 2
+            ),
+            false,
+        );
+    }
     let output = connection::run_command(app, port, &cmd).await?;
     check_write_output(&output)?;
+    if length_change {
+        let now = read_14a_uid(app, port).await;
+        if now.as_deref() != Some(clean_uid.as_str()) {
+            return Err(AppError::CommandFailed(format!(
+                "Gen3 UID length change failed: card answers with {}, expected {}",
+                now.as_deref().unwrap_or("no UID"),
+                clean_uid
+            )));
+        }
+    }
 
     // Step 2: Write block 0 via APDU
     update_write_progress(app, machine, 0.35, Some(2), Some(total))?;
-    let block0 = read_block0_from_dump(dump_path)?;
     let cmd = command_builder::build_mf_gen3blk(&block0);
     let output = connection::run_command(app, port, &cmd).await?;
     check_write_output(&output)?;
 
     // Step 3: Restore all blocks from dump
     update_write_progress(app, machine, 0.65, Some(3), Some(total))?;
-    let cmd = command_builder::build_mf_restore(dump_path);
-    let output = connection::run_command(app, port, &cmd).await?;
-    check_write_output(&output)?;
+    restore_streaming(app, port, dump_path, card_type, machine, hf_state, 0.65, 1.0).await?;
 
     finish_write(app, machine).await
 }
 
+/// UID the card on the reader anticollides with, hex without separators.
+async fn read_14a_uid(app: &AppHandle, port: &str) -> Option<String> {
+    let output = connection::run_command(app, port, command_builder::build_hf_14a_info())
+        .await
+        .ok()?;
+    let (_, data) = output_parser::parse_hf_search(&output)?;
+    Some(
+        data.uid
+            .chars()
+            .filter(|c| c.is_ascii_hexdigit())
+            .collect::<String>()
+            .to_uppercase(),
+    )
+}
+
+/// `hf mf restore` on a 4K card writes 256 blocks one at a time.
+const HF_RESTORE_TIMEOUT_SECS: u64 = 300;
+
+/// Stream `hf mf restore`, mapping each written block onto the
+/// `progress_from..progress_to` span of the overall write progress.
+/// Streamed so the restore is cancellable via `cancel_hf_operation`.
+#[allow(clippy::too_many_arguments)]
+async fn restore_streaming(
+    app: &AppHandle,
+    port: &str,
+    dump_path: &str,
+    card_type: &CardType,
+    machine: &State<'_, Mutex<WizardMachine>>,
+    hf_state: &State<'_, HfOperationState>,
+    progress_from: f32,
+    progress_to: f32,
+) -> Result<(), AppError> {
+    let total_blocks = std::fs::metadata(dump_path)
+        .map(|m| (m.len() / 16) as u16)
+        .unwrap_or(64)
+        .max(1);
+
+    let cmd = command_builder::build_mf_restore(card_type, dump_path);
+    // One line per block: coalesce the events, the FSM still sees every block
+    let throttle = connection::ProgressThrottle::new(connection::PROGRESS_EMIT_INTERVAL, |p| {
+        let _ = app.emit("write-progress", p);
+    });
+    let output = connection::run_command_streaming(
+        app,
+        port,
+        &cmd,
+        HF_RESTORE_TIMEOUT_SECS,
+        hf_state,
+        |line| {
+            if let Some(block) = output_parser::parse_restore_line(line) {
+                let done = f32::from(block.saturating_add(1)).min(f32::from(total_blocks)) / f32::from(total_blocks);
+                let progress = progress_from + (progress_to - progress_from) * done;
+                // Progress is cosmetic — never abort the restore over it
+                let payload =
+                    record_write_progress(app, machine, progress, Some(block), Some(total_blocks));
+                if let Ok(payload) = payload {
+                    throttle.offer(payload);
+                }
+            }
+        },
+    )
+    .await;
+    drop(throttle);
+    check_write_output(&output?)
+}
+
 /// Gen4 GTU/UMC: single `hf mf gload` (GTU-specific file load).
 async fn write_gen4_gtu(
     app: &AppHandle,
     port: &str,
     dump_path: &str,
+    card_type: &CardType,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     update_write_progress(app, machine, 0.3, Some(1), Some(2))?;
 
-    let cmd = command_builder::build_mf_gload(dump_path);
+    let cmd = command_builder::build_mf_gload(card_type, dump_path);
     let output = connection::run_command(app, port, &cmd).await?;
     check_write_output(&output)?;
 
@@ -524,18 +1390,30 @@ async fn write_gen4_gtu(
 }
 
 /// Gen4 GDM: uses `hf mf cload` via Gen1a backdoor (factory default 7AFF
-/// has Gen1a enabled). Single command instead of block-by-block gdmsetblk.
+/// has Gen1a enabled). Single command instead of block-by-block gdmsetblk,
+/// which is kept as the fallback for cards with the backdoor disabled.
 async fn write_gen4_gdm(
     app: &AppHandle,
     port: &str,
     dump_path: &str,
+    card_type: &CardType,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     update_write_progress(app, machine, 0.3, Some(1), Some(2))?;
 
-    let cmd = command_builder::build_mf_cload(dump_path);
-    let output = connection::run_command(app, port, &cmd).await?;
-    check_write_output(&output)?;
+    let cmd = command_builder::build_mf_cload(card_type, dump_path);
+    gdm_cload_or_blocks(
+        || connection::run_command(app, port, &cmd),
+        || async {
+            connection::emit_output(
+                app,
+                "[=] Gen1a backdoor unavailable on this GDM card, writing block by block...",
+                false,
+            );
+            write_gen4_gdm_blocks(app, port, dump_path, machine).await
+        },
+    )
+    .await?;
 
     finish_write(app, machine).await
 }
@@ -547,8 +1425,34 @@ async fn write_ultralight(
     dump_path: &str,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
-    update_write_progress(app, machine, 0.3, Some(1), Some(2))?;
+    update_write_progress(app, machine, 0.1, Some(1), Some(2))?;
+
+    // Lock bits are irreversible — refuse a blank that can't take the full clone
+    if let Some(status) = read_ntag_locks(app, port).await {
+        if status.has_critical_locks() {
+            let pages = ntag_lock::format_page_ranges(&status.locked_pages);
+            return report_error(
+                machine,
+                &format!("{:?} blank has locked pages: {}", status.variant, pages),
+                &format!(
+                    "This NTAG blank has permanently locked pages ({}). The clone would be \
+                     incomplete. Use a fresh, unlocked blank.",
+                    pages
+                ),
+                true,
+                Some(RecoveryAction::GoBack),
+            );
+        }
+        if status.config_locked {
+            connection::emit_output(
+                app,
+                "[!] Blank has CFGLCK set: config pages are read-only, password/auth settings will not be cloned",
+                true,
+            );
+        }
+    }
 
+    update_write_progress(app, machine, 0.3, Some(1), Some(2))?;
     let cmd = command_builder::build_mfu_restore(dump_path);
     let output = connection::run_command(app, port, &cmd).await?;
     check_write_output(&output)?;
@@ -576,6 +1480,189 @@ async fn write_iclass(
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Read the lock-byte pages of an NTAG21x blank. Returns `None` for non-NTAG
+/// blanks (no recognisable CC) or when a page can't be read — the restore
+/// itself will then surface any write failure.
+async fn read_ntag_locks(app: &AppHandle, port: &str) -> Option<NtagLockStatus> {
+    let read_page = |page: u8| async move {
+        let cmd = command_builder::build_mfu_rdbl(page);
+        match connection::run_command(app, port, &cmd).await {
+            Ok(out) => output_parser::parse_mfu_rdbl(&out, page),
+            Err(e) => {
+                log::warn!("NTAG lock check: reading page {} failed: {}", page, e);
+                None
+            }
+        }
+    };
+    let variant = NtagVariant::from_cc_size(read_page(ntag_lock::CC_PAGE).await?[2])?;
+
+    let mut pages = [[0u8; 4]; 3];
+    let to_read = [
+        ntag_lock::STATIC_LOCK_PAGE,
+        variant.dynamic_lock_page(),
+        variant.cfg1_page(),
+    ];
+    for (bytes, page) in pages.iter_mut().zip(to_read) {
+        let Some(b) = read_page(page).await else {
+            log::warn!("NTAG lock check: page {} unreadable, skipping", page);
+            return None;
+        };
+        *bytes = b;
+    }
+    Some(ntag_lock::decode_locks(variant, pages[0], pages[1], pages[2]))
+}
+
+/// Shown when the dump from key recovery is gone or empty at write time.
+const DUMP_FILE_MISSING_MESSAGE: &str =
+    "Dump file missing — please re-run key recovery, then write again.";
+
+/// `dump_info` for a UL/NTAG dump of `pages_read` pages. A short dump
+/// (read-protected pages) can still be cloned, but the user is told which
+/// part is missing.
+fn mfu_dump_info(variant: output_parser::MfuVariant, pages_read: usize) -> String {
+    let readable = variant.readable_pages();
+    if pages_read == 0 || pages_read >= readable {
+        return format!("{} dump complete ({} pages).", variant.name(), readable);
+    }
+    log::warn!("Truncated {} dump: {} of {} pages", variant.name(), pages_read, readable);
+    format!(
+        "Warning: only {} of the {}'s {} pages could be read, so pages {}-{} won't be \
+         cloned. They may be password-protected.",
+        pages_read,
+        variant.name(),
+        readable,
+        pages_read,
+        readable - 1
+    )
+}
+
+/// BlankDetected -> Writing, returning the port to write through.
+fn start_write(m: &mut WizardMachine) -> Result<String, AppError> {
+    if !matches!(m.current, WizardState::BlankDetected { .. }) {
+        return Err(AppError::InvalidTransition(
+            "Must be in BlankDetected to write clone".to_string(),
+        ));
+    }
+    let port = m.port.clone().ok_or_else(|| {
+        AppError::InvalidTransition("No port in machine state".to_string())
+    })?;
+    m.transition(WizardAction::StartWrite)?;
+    Ok(port)
+}
+
+/// Add the HF dump, key file and key table the write needs to `checkpoint`.
+pub(crate) fn checkpoint_hf_context(
+    hf_state: &HfOperationState,
+    checkpoint: &mut WizardCheckpoint,
+) {
+    checkpoint.dump_path = hf_state.dump_path.lock().ok().and_then(|p| p.clone());
+    checkpoint.key_path = hf_state.key_path.lock().ok().and_then(|p| p.clone());
+    checkpoint.sector_keys = hf_state.sector_keys.lock().ok().and_then(|k| k.clone());
+}
+
+/// Put a resumed session's dump, key file and key table back.
+pub(crate) fn restore_hf_context(hf_state: &HfOperationState, checkpoint: &WizardCheckpoint) {
+    if let Ok(mut path) = hf_state.dump_path.lock() {
+        *path = checkpoint.dump_path.clone();
+    }
+    if let Ok(mut path) = hf_state.key_path.lock() {
+        *path = checkpoint.key_path.clone();
+    }
+    if let Ok(mut keys) = hf_state.sector_keys.lock() {
+        *keys = checkpoint.sector_keys.clone();
+    }
+}
+
+/// Pre-flight check that the dump to write still exists and has data.
+/// Err holds the technical detail for the log.
+pub(crate) fn check_dump_file(dump_path: &str) -> Result<(), String> {
+    match std::fs::metadata(dump_path) {
+        Ok(meta) if meta.is_file() && meta.len() > 0 => Ok(()),
+        Ok(_) => Err(format!("Dump file is empty: {}", dump_path)),
+        Err(e) => Err(format!("Dump file missing: {} ({})", dump_path, e)),
+    }
+}
+
+/// Library entry for an archived HF source; the dump itself is in the bundle.
+fn source_archive_card(card_type: &CardType, source_uid: &str) -> SavedCard {
+    let now = chrono::Local::now();
+    SavedCard {
+        id: None,
+        name: format!(
+            "Source {} {} ({})",
+            card_type.display_name(),
+            source_uid,
+            now.format("%Y-%m-%d %H:%M")
+        ),
+        card_type: format!("{:?}", card_type),
+        frequency: "HF".into(),
+        uid: source_uid.to_string(),
+        raw: String::new(),
+        decoded: "{}".into(),
+        cloneable: card_type.is_cloneable(),
+        recommended_blank: format!("{:?}", card_type.recommended_blank()),
+        created_at: now.to_rfc3339(),
+    }
+}
+
+/// With `always_dump_source` on, archive the dump being written as the
+/// source's record (see `dump::archive_source_dump`). Returns the bundle
+/// path, or `None` when the setting is off.
+fn archive_source(
+    app: &AppHandle,
+    machine: &State<'_, Mutex<WizardMachine>>,
+    hf_state: &State<'_, HfOperationState>,
+    card_type: &CardType,
+    source_uid: &str,
+    dump_path: &str,
+) -> Result<Option<String>, AppError> {
+    let enabled = app
+        .try_state::<write::WriteSettings>()
+        .is_some_and(|s| s.always_dump_source());
+    let db = app.try_state::<Database>();
+    let data_dir = app.try_state::<DataDir>();
+    let library = db.as_deref().zip(data_dir.as_ref().map(|d| d.inner().0.as_path()));
+    let device = machine
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("State lock poisoned: {}", e)))?
+        .model
+        .clone();
+    let key_path = hf_state
+        .key_path
+        .lock()
+        .map_err(|e| AppError::CommandFailed(format!("HF state lock poisoned: {}", e)))?
+        .clone();
+    let source = source_archive_card(card_type, source_uid);
+    let dump_path = Path::new(dump_path);
+    let archived = archive_source_in(enabled, library, source, device, dump_path, key_path)?;
+    let Some(path) = archived else {
+        return Ok(None);
+    };
+    let path = path.to_string_lossy().into_owned();
+    connection::emit_output(app, &format!("[=] Source dump archived to {}", path), false);
+    Ok(Some(path))
+}
+
+/// `archive_source` once the app state is resolved: `library` is the saved
+/// card database and the data dir, if both are available.
+fn archive_source_in(
+    enabled: bool,
+    library: Option<(&Database, &Path)>,
+    source: SavedCard,
+    device: Option<String>,
+    dump_path: &Path,
+    key_path: Option<String>,
+) -> Result<Option<PathBuf>, AppError> {
+    if !enabled {
+        return Ok(None);
+    }
+    let Some((db, data_dir)) = library else {
+        return Err(AppError::CommandFailed("Saved card library unavailable".into()));
+    };
+    let key_path = key_path.as_deref().map(Path::new);
+    dump::archive_source_dump(db, data_dir, source, device, dump_path, key_path).map(Some)
+}
+
 /// Read the first 16 bytes of a binary dump file and return as a 32-char hex string.
 /// Used by Gen2 (`wrbl0 --force`) and Gen3 (`gen3blk`) to extract block 0 data.
 fn read_block0_from_dump(dump_path: &str) -> Result<String, AppError> {
@@ -594,6 +1681,84 @@ fn read_block0_from_dump(dump_path: &str) -> Result<String, AppError> {
     Ok(data[..16].iter().map(|b| format!("{:02X}", b)).collect())
 }
 
+/// Given a Gen1a/Gen2 blank's `hf mf info` output, return why it can't take
+/// `source_uid`, if it can't. Programmable generations always pass, and so
+/// does a blank whose identity can't be parsed (the write itself will fail).
+fn fixed_uid_mismatch(output: &str, source_uid: &str, blank_type: &BlankType) -> Option<String> {
+    let expected_gen = match blank_type {
+        BlankType::MagicMifareGen1a => MagicGeneration::Gen1a,
+        BlankType::MagicMifareGen2 => MagicGeneration::Gen2,
+        _ => return None,
+    };
+    let Some(blank) = output_parser::parse_14a_identity(output) else {
+        log::warn!("Could not parse blank identity before write; skipping UID length check");
+        return None;
+    };
+    let gen = output_parser::parse_magic_detection(output).unwrap_or(expected_gen);
+    let source_uid_len = source_uid.chars().filter(|c| c.is_ascii_hexdigit()).count() / 2;
+    blank_compat::check_uid_length(source_uid_len, &gen, &blank).err()
+}
+
+/// Check the UID (and the Classic block 0, when there is one) about to be
+/// written to a magic blank. Block 0 writes are not reversible on every CUID,
+/// so a bad BCC can brick the blank.
+fn check_uid_safety(
+    source_uid: &str,
+    card_type: &CardType,
+    block0: Option<&str>,
+) -> Result<(), UidWarning> {
+    uid_safety::validate_uid_for_write(source_uid, card_type)?;
+    let uid_hex_len = source_uid.chars().filter(|c| c.is_ascii_hexdigit()).count();
+    match block0 {
+        Some(block0) if uid_hex_len == 8 => uid_safety::check_block0_bcc(block0),
+        _ => Ok(()),
+    }
+}
+
+/// `Err` stops the write: a blocking warning, or one the user hasn't
+/// acknowledged yet. `Ok(Some(_))` is an acknowledged warning to write through.
+fn uid_write_check(
+    source_uid: &str,
+    card_type: &CardType,
+    block0: Option<&str>,
+    acknowledged: bool,
+) -> Result<Option<UidWarning>, UidWarning> {
+    match check_uid_safety(source_uid, card_type, block0) {
+        Ok(()) => Ok(None),
+        Err(warning) if acknowledged && !warning.is_blocking() => Ok(Some(warning)),
+        Err(warning) => Err(warning),
+    }
+}
+
+fn report_unsafe_uid(
+    machine: &State<'_, Mutex<WizardMachine>>,
+    warning: &UidWarning,
+) -> Result<WizardState, AppError> {
+    if warning.is_blocking() {
+        return report_error(
+            machine,
+            &format!("Unsafe UID for block 0 write: {}", warning),
+            &format!(
+                "{}. Nothing was written to the blank. Re-read the source card or check the dump.",
+                warning
+            ),
+            true,
+            Some(RecoveryAction::GoBack),
+        );
+    }
+    report_error(
+        machine,
+        &format!("Unsafe UID for block 0 write: {}", warning),
+        &format!(
+            "{}. Nothing was written yet. Write it anyway only if the source card really \
+             uses this UID.",
+            warning
+        ),
+        true,
+        Some(RecoveryAction::AcknowledgeUidWarning),
+    )
+}
+
 /// Transition FSM: Writing -> Verifying (write finished).
 async fn finish_write(
     app: &AppHandle,
@@ -616,6 +1781,20 @@ fn update_write_progress(
     current_step: Option<u16>,
     total_steps: Option<u16>,
 ) -> Result<(), AppError> {
+    let payload = record_write_progress(app, machine, progress, current_step, total_steps)?;
+    let _ = app.emit("write-progress", payload);
+    Ok(())
+}
+
+/// Move the FSM's write progress on and build the `write-progress` payload,
+/// for callers that emit it through a `ProgressThrottle`.
+fn record_write_progress(
+    app: &AppHandle,
+    machine: &State<'_, Mutex<WizardMachine>>,
+    progress: f32,
+    current_step: Option<u16>,
+    total_steps: Option<u16>,
+) -> Result<serde_json::Value, AppError> {
     let mut m = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
     })?;
@@ -624,15 +1803,16 @@ fn update_write_progress(
         current_block: current_step,
         total_blocks: total_steps,
     })?;
-    let _ = app.emit(
-        "write-progress",
-        serde_json::json!({
-            "progress": progress,
-            "current_block": current_step,
-            "total_blocks": total_steps,
-        }),
-    );
-    Ok(())
+    let total_elapsed_secs = app
+        .try_state::<HfOperationState>()
+        .map(|s| s.total_elapsed_secs());
+    Ok(serde_json::json!({
+        "progress": progress,
+        "current_block": current_step,
+        "total_blocks": total_steps,
+        "total_elapsed_secs": total_elapsed_secs,
+        "operation_id": operation::current_operation_id(),
+    }))
 }
 
 /// Check PM3 write output for critical errors (`[!!]`).
@@ -675,6 +1855,12 @@ fn report_error(
 // HF Verification — read back + compare
 // ---------------------------------------------------------------------------
 
+/// `hf search` during verification — same budget as a regular PM3 command.
+const HF_VERIFY_SEARCH_TIMEOUT_SECS: u64 = 30;
+
+/// Full readback (`hf mf dump` / `cview` on a 4K card can take minutes).
+const HF_VERIFY_READBACK_TIMEOUT_SECS: u64 = 300;
+
 /// Verify an HF clone by reading back the card and comparing with the source.
 ///
 /// Strategy:
@@ -683,13 +1869,60 @@ fn report_error(
 /// 3. Readback via type-appropriate command (cview for Gen1a, dump for others)
 /// 4. Dump file comparison if both original and readback files are available
 ///
+/// `reference_dump_path` (a saved card's dump) replaces the dump from this
+/// session as the original in step 4, so re-cloning a saved card is checked
+/// against what was saved rather than against a fresh read. It must be a
+/// file inside the data dir.
+///
 /// Transitions: Verifying -> VerificationComplete.
 #[tauri::command]
 pub async fn hf_verify_clone(
     app: AppHandle,
     source_uid: String,
-    _card_type: CardType,
+    card_type: CardType,
+    blank_type: BlankType,
+    reference_dump_path: Option<String>,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    let reference_dump_path = match reference_dump_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => {
+            let data_dir = app.try_state::<DataDir>().ok_or_else(|| {
+                AppError::CommandFailed("Data directory not initialised".into())
+            })?;
+            Some(reference_dump_in(&data_dir.inner().0, &path)?)
+        }
+        None => None,
+    };
+    operation::run_operation(run_hf_verify_clone(
+        app,
+        source_uid,
+        card_type,
+        blank_type,
+        reference_dump_path,
+        machine,
+        hf_state,
+    ))
+    .await
+}
+
+/// Resolve a reference dump path from the frontend: an existing file inside
+/// `data_dir` (the saved library), never an arbitrary file on disk.
+fn reference_dump_in(data_dir: &Path, path: &str) -> Result<String, AppError> {
+    crate::db::path_in_data_dir(data_dir, Path::new(path))
+        .filter(|p| p.is_file())
+        .map(|p| p.to_string_lossy().into_owned())
+        .ok_or_else(|| {
+            AppError::CommandFailed(format!("Reference dump not found in the library: {}", path))
+        })
+}
+
+async fn run_hf_verify_clone(
+    app: AppHandle,
+    source_uid: String,
+    card_type: CardType,
     blank_type: BlankType,
+    reference_dump_path: Option<String>,
     machine: State<'_, Mutex<WizardMachine>>,
     hf_state: State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
@@ -712,9 +1945,26 @@ pub async fn hf_verify_clone(
         })?
     };
 
-    // Step 1: hf search — confirm card responds and extract UID
-    let search_output =
-        connection::run_command(&app, &port, command_builder::build_hf_search()).await;
+    clone_summary::join(&app);
+
+    // Step 1: hf search — confirm card responds and extract UID.
+    // Streamed (not `run_command`) so `cancel_hf_operation` can abort it.
+    emit_verify_progress(&app, "search", &hf_state);
+    let search_output = connection::run_command_streaming(
+        &app,
+        &port,
+        command_builder::build_hf_search(),
+        HF_VERIFY_SEARCH_TIMEOUT_SECS,
+        &hf_state,
+        |_| {},
+    )
+    .await;
+
+    if let Err(ref e) = search_output {
+        if connection::is_cancelled(e) {
+            return report_verify_cancelled(&machine);
+        }
+    }
 
     let uid_match = match &search_output {
         Ok(output) => {
@@ -739,25 +1989,31 @@ pub async fn hf_verify_clone(
     };
 
     if !uid_match {
+        clone_summary::warn(&app, "The clone doesn't answer with the source UID");
+        let summary = clone_summary::finish(&app, false, true);
         let mut m = machine.lock().map_err(|e| {
             AppError::CommandFailed(format!("State lock poisoned: {}", e))
         })?;
         m.transition(WizardAction::VerificationResult {
             success: false,
             mismatched_blocks: vec![0], // block 0 = UID mismatch sentinel
+            summary,
         })?;
         return Ok(m.current.clone());
     }
 
     // Step 2: Deeper readback verification by blank type
+    emit_verify_progress(&app, "readback", &hf_state);
+    let reference = reference_dump_path.as_deref();
     let mismatched_blocks = match blank_type {
         BlankType::MagicMifareGen1a => {
             // Gen1a: read all blocks via backdoor (no keys needed)
             verify_readback(
                 &app,
                 &port,
-                command_builder::build_mf_cview(),
+                &command_builder::build_mf_cview(&card_type),
                 &hf_state,
+                reference,
                 16,
             )
             .await
@@ -770,8 +2026,9 @@ pub async fn hf_verify_clone(
             verify_readback(
                 &app,
                 &port,
-                command_builder::build_mf_dump(),
+                &command_builder::build_mf_dump(&card_type),
                 &hf_state,
+                reference,
                 16,
             )
             .await
@@ -783,6 +2040,7 @@ pub async fn hf_verify_clone(
                 &port,
                 command_builder::build_mfu_dump(),
                 &hf_state,
+                reference,
                 4,
             )
             .await
@@ -794,14 +2052,31 @@ pub async fn hf_verify_clone(
                 &port,
                 command_builder::build_iclass_dump(),
                 &hf_state,
+                reference,
                 8,
             )
             .await
         }
-        _ => vec![],
+        _ => Ok(vec![]),
     };
 
+    let mismatched_blocks = match mismatched_blocks {
+        Ok(blocks) => blocks,
+        Err(_) => return report_verify_cancelled(&machine),
+    };
     let success = mismatched_blocks.is_empty();
+    if !success {
+        let dump = if reference.is_some() { "saved dump" } else { "dump" };
+        clone_summary::warn(
+            &app,
+            &format!(
+                "{} block(s) read back different from the {}",
+                mismatched_blocks.len(),
+                dump
+            ),
+        );
+    }
+    let summary = clone_summary::finish(&app, success, true);
 
     let mut m = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
@@ -809,42 +2084,117 @@ pub async fn hf_verify_clone(
     m.transition(WizardAction::VerificationResult {
         success,
         mismatched_blocks,
+        summary,
     })?;
     Ok(m.current.clone())
 }
 
-/// Run a readback command and optionally compare the resulting dump with the original.
+/// `verify-progress`: which verify step is running, plus the HF job's total
+/// elapsed time so the UI timer keeps counting through verification.
+fn emit_verify_progress(app: &AppHandle, step: &str, hf_state: &HfOperationState) {
+    let _ = app.emit(
+        "verify-progress",
+        serde_json::json!({
+            "step": step,
+            "total_elapsed_secs": hf_state.total_elapsed_secs(),
+            "operation_id": operation::current_operation_id(),
+        }),
+    );
+}
+
+/// Run a readback command and optionally compare the resulting dump with the original:
+/// `reference` when given, otherwise the dump from this session.
 /// Returns empty vec on success, vec of mismatched block indices on failure.
-/// Readback errors are non-fatal — UID already matched as the primary check.
+/// Readback errors are non-fatal — UID already matched as the primary check —
+/// except a user cancellation, which is returned as `Err`.
 async fn verify_readback(
     app: &AppHandle,
     port: &str,
     readback_cmd: &str,
     hf_state: &State<'_, HfOperationState>,
+    reference: Option<&str>,
     block_size: usize,
-) -> Vec<u16> {
-    let output = match connection::run_command(app, port, readback_cmd).await {
+) -> Result<Vec<u16>, AppError> {
+    // Streamed so a hung `hf mf dump` on a 4K card can be cancelled
+    let output = connection::run_command_streaming(
+        app,
+        port,
+        readback_cmd,
+        HF_VERIFY_READBACK_TIMEOUT_SECS,
+        hf_state,
+        |_| {},
+    )
+    .await;
+    let original_path = match reference {
+        Some(path) => Some(path.to_string()),
+        None => hf_state.dump_path.lock().ok().and_then(|l| l.clone()),
+    };
+    let run = |cmd: String| async move { connection::run_command(app, port, &cmd).await };
+    let readback_failed =
+        || clone_summary::warn(app, "Readback failed; the clone was verified by UID only");
+    compare_readback(output, original_path, block_size, run, readback_failed).await
+}
+
+/// `verify_readback` once the readback ran: compare its dump with the dump
+/// at `original_path`, re-reading MIFARE Classic blocks that look different.
+/// A failed readback calls `readback_failed` and passes on the UID match.
+async fn compare_readback<R, Fut>(
+    readback: Result<String, AppError>,
+    original_path: Option<String>,
+    block_size: usize,
+    run: R,
+    readback_failed: impl FnOnce(),
+) -> Result<Vec<u16>, AppError>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let output = match readback {
         Ok(o) => o,
-        Err(_) => return vec![], // Readback failed, fall back to UID-only
+        Err(e) if connection::is_cancelled(&e) => return Err(e),
+        Err(_) => {
+            // Readback failed, fall back to UID-only
+            readback_failed();
+            return Ok(vec![]);
+        }
     };
 
     // Check for critical PM3 errors
     if output.contains("[!!]") {
-        return vec![0];
+        return Ok(vec![0]);
     }
 
     // Try dump file comparison if both original and readback files are available
     let readback_path = output_parser::extract_dump_file_path(&output);
-    let original_path = hf_state.dump_path.lock().ok().and_then(|l| l.clone());
-
     match (original_path, readback_path) {
         (Some(ref orig), Some(ref readback)) => {
-            compare_dump_files(orig, readback, block_size)
+            let mismatched = compare_dump_files(orig, readback, block_size);
+            if mismatched.is_empty() || block_size != 16 {
+                return Ok(mismatched);
+            }
+            let Ok(original) = std::fs::read(orig) else {
+                return Ok(mismatched);
+            };
+            Ok(recheck_mismatched_blocks(run, &original, mismatched).await)
         }
-        _ => vec![], // No files to compare, UID matched = success
+        _ => Ok(vec![]), // No files to compare, UID matched = success
     }
 }
 
+/// Verification aborted via `cancel_hf_operation` — surface it as a
+/// recoverable error rather than a pass/fail verdict.
+fn report_verify_cancelled(
+    machine: &State<'_, Mutex<WizardMachine>>,
+) -> Result<WizardState, AppError> {
+    report_error(
+        machine,
+        "Verification cancelled",
+        "Verification was cancelled. Place the clone on the reader and retry.",
+        true,
+        Some(RecoveryAction::Retry),
+    )
+}
+
 /// Compare two binary dump files block by block.
 /// Returns mismatched block indices (empty = all blocks match).
 fn compare_dump_files(original: &str, readback: &str, block_size: usize) -> Vec<u16> {
@@ -875,3 +2225,462 @@ fn compare_dump_files(original: &str, readback: &str, block_size: usize) -> Vec<
 
     mismatched
 }
+
+// ---------------------------------------------------------------------------
+// DESFire UID-only clone
+// ---------------------------------------------------------------------------
+
+/// Shown in the terminal and returned with every UID-only clone.
+const DESFIRE_UID_ONLY_WARNING: &str = "Only the DESFire UID was copied. Application data is \
+    encrypted and was NOT cloned — this works only with readers that check the UID alone.";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UidOnlyClone {
+    pub uid: String,
+    /// The blank answered `hf 14a info` with the written UID.
+    pub verified: bool,
+    pub warning: String,
+}
+
+/// Copy a DESFire card's UID (no application data) onto a 7-byte magic blank
+/// for UID-only access systems. `acknowledged` must be set: the caller has
+/// shown the user that the data is not cloned. A UID warning refuses the
+/// write until it is confirmed with `acknowledge_uid_warning`.
+#[tauri::command]
+pub async fn clone_desfire_uid(
+    app: AppHandle,
+    port: String,
+    uid: String,
+    blank_type: BlankType,
+    acknowledged: bool,
+    acknowledge_uid_warning: Option<bool>,
+) -> Result<OperationResult<UidOnlyClone>, AppError> {
+    if !acknowledged {
+        return Err(AppError::CommandFailed(format!(
+            "Confirm first: {}",
+            DESFIRE_UID_ONLY_WARNING
+        )));
+    }
+    let cmds = command_builder::build_desfire_uid_clone(&uid, &blank_type)
+        .map_err(AppError::CommandFailed)?;
+    let acknowledge_uid_warning = acknowledge_uid_warning.unwrap_or(false);
+    let uid_warning = uid_write_check(&uid, &CardType::DESFire, None, acknowledge_uid_warning)
+        .map_err(|w| {
+            let confirm = if w.is_blocking() { "" } else { ". Confirm to write it anyway" };
+            AppError::CommandFailed(format!("Unsafe UID: {}{}", w, confirm))
+        })?;
+
+    scan_cache::invalidate(&app);
+    operation::run_operation(run_clone_desfire_uid(app, port, uid, cmds, uid_warning)).await
+}
+
+async fn run_clone_desfire_uid(
+    app: AppHandle,
+    port: String,
+    uid: String,
+    cmds: Vec<String>,
+    uid_warning: Option<UidWarning>,
+) -> Result<UidOnlyClone, AppError> {
+    connection::emit_output(&app, &format!("[!] {}", DESFIRE_UID_ONLY_WARNING), false);
+    if let Some(warning) = uid_warning {
+        connection::emit_output(&app, &format!("[!] Writing UID anyway: {}", warning), true);
+    }
+    for cmd in &cmds {
+        let output = connection::run_command(&app, &port, cmd).await?;
+        if output.contains("[!!]") || output.to_lowercase().contains("fail") {
+            return Err(AppError::CommandFailed(format!("UID write failed: {}", cmd)));
+        }
+    }
+
+    let hex = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_ascii_hexdigit())
+            .collect::<String>()
+            .to_uppercase()
+    };
+    let verified = connection::run_command(&app, &port, command_builder::build_hf_14a_info())
+        .await
+        .ok()
+        .and_then(|out| output_parser::parse_hf_search(&out))
+        .is_some_and(|(_, data)| hex(&data.uid) == hex(&uid));
+
+    Ok(UidOnlyClone {
+        uid,
+        verified,
+        warning: DESFIRE_UID_ONLY_WARNING.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn cuid_blank_writes_block0_directly_first() {
+        assert_eq!(
+            gen2_block0_strategy(Some("[+] Magic capabilities... Gen 2 / CUID")),
+            Gen2Block0::DirectFirst
+        );
+        assert_eq!(
+            gen2_block0_strategy(Some("[+] Magic capabilities... Gen 2")),
+            Gen2Block0::ConfigForce
+        );
+        assert_eq!(
+            gen2_block0_strategy(Some("[+] Magic capabilities... Gen 4 GDM / USCUID")),
+            Gen2Block0::ConfigForce
+        );
+        assert_eq!(gen2_block0_strategy(None), Gen2Block0::ConfigForce);
+    }
+
+    #[tokio::test]
+    async fn gdm_write_falls_back_to_gdmsetblk() {
+        use std::cell::Cell;
+
+        let fell_back = Cell::new(false);
+        let refused = gdm_cload_or_blocks(
+            || async { Ok("[!!] Can't set magic card block: 0".to_string()) },
+            || async {
+                fell_back.set(true);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(refused, GdmWrite::BlockByBlock);
+        assert!(fell_back.get());
+
+        // The backdoor works: no block writes
+        let cloaded = gdm_cload_or_blocks(
+            || async { Ok("[+] Card loaded 64 blocks from file".to_string()) },
+            || async { panic!("gdmsetblk run after a successful cload") },
+        )
+        .await
+        .unwrap();
+        assert_eq!(cloaded, GdmWrite::Cload);
+
+        // A failed fallback fails the write
+        let failed = gdm_cload_or_blocks(
+            || async { Err(AppError::CommandFailed("PM3 exited with code 1".into())) },
+            || async { Err(AppError::CommandFailed("readback mismatch".into())) },
+        )
+        .await;
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn resumed_write_reaches_the_write_entry_point() {
+        use crate::cards::types::{CardData, Frequency};
+        use crate::state::CheckpointCard;
+
+        let interrupted = HfOperationState::new();
+        *interrupted.dump_path.lock().unwrap() = Some("hf-mf-01020304-dump.bin".into());
+        *interrupted.key_path.lock().unwrap() = Some("hf-mf-01020304-key.bin".into());
+        let keys = vec![
+            SectorKeys { key_a: Some("FFFFFFFFFFFF".into()), key_b: None };
+            16
+        ];
+        *interrupted.sector_keys.lock().unwrap() = Some(keys.clone());
+        let mut checkpoint = WizardCheckpoint {
+            state: WizardState::Writing {
+                progress: 0.4,
+                current_block: Some(2),
+                total_blocks: Some(5),
+            },
+            port: Some("COM3".into()),
+            source_uid_len: Some(4),
+            card: Some(CheckpointCard {
+                frequency: Frequency::HF,
+                card_type: CardType::MifareClassic1K,
+                card_data: CardData {
+                    uid: "01020304".into(),
+                    raw: String::new(),
+                    decoded: Default::default(),
+                },
+                cloneable: true,
+                recommended_blank: BlankType::MagicMifareGen1a,
+            }),
+            blank_type: Some(BlankType::MagicMifareGen2),
+            dump_path: None,
+            key_path: None,
+            sector_keys: None,
+        };
+        checkpoint_hf_context(&interrupted, &mut checkpoint);
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let checkpoint: WizardCheckpoint = serde_json::from_str(&json).unwrap();
+
+        let hf_state = HfOperationState::new();
+        restore_hf_context(&hf_state, &checkpoint);
+        let mut machine = WizardMachine::new();
+        machine.restore(checkpoint, "COM4".into(), "RDV4".into(), "v4.18994".into()).unwrap();
+
+        assert_eq!(machine.source_card().unwrap().card_data.uid, "01020304");
+        assert_eq!(hf_state.dump_path.lock().unwrap().as_deref(), Some("hf-mf-01020304-dump.bin"));
+        assert_eq!(hf_state.key_path.lock().unwrap().as_deref(), Some("hf-mf-01020304-key.bin"));
+        assert_eq!(hf_state.sector_keys.lock().unwrap().as_ref(), Some(&keys));
+
+        // The blank goes back on the reader, then hf_write_clone takes over
+        assert!(start_write(&mut machine).is_err());
+        machine
+            .transition(WizardAction::BlankReady {
+                blank_type: BlankType::MagicMifareGen2,
+                existing_data_type: None,
+                uid_length_warning: None,
+            })
+            .unwrap();
+        assert_eq!(start_write(&mut machine).unwrap(), "COM4");
+        assert!(matches!(machine.current, WizardState::Writing { .. }));
+    }
+
+    #[tokio::test]
+    async fn cancelled_resume_keeps_found_keys() {
+        let mut sectors = vec![
+            SectorKeys { key_a: Some("FFFFFFFFFFFF".into()), key_b: None },
+            SectorKeys { key_a: None, key_b: None },
+        ];
+        let plan = command_builder::build_resume_plan(&sectors, Some("weak")).unwrap();
+        assert_eq!(plan.len(), 3);
+
+        // The partial run's dump is ready; resuming goes back to processing
+        let mut machine = WizardMachine::new();
+        machine.current = WizardState::HfDumpReady { dump_info: "Partial".into() };
+        machine.transition(WizardAction::StartHfProcess).unwrap();
+        assert!(matches!(machine.current, WizardState::HfProcessing { .. }));
+
+        let mut runs = 0;
+        let mut reported = Vec::new();
+        let (recovered, result) = attack_missing_keys(
+            |_cmd: String| {
+                runs += 1;
+                let output = match runs {
+                    1 => Ok("[+] found valid key [ a0a1a2a3a4a5 ]".to_string()),
+                    _ => Err(AppError::Cancelled),
+                };
+                async move { output }
+            },
+            &plan,
+            &mut sectors,
+            |found| reported.push(found),
+        )
+        .await;
+
+        assert_eq!(recovered, 1);
+        assert!(result.is_err());
+        assert_eq!(runs, 2);
+        assert_eq!(reported, vec![1, 2]);
+        assert_eq!(sectors[0].key_b.as_deref(), Some("A0A1A2A3A4A5"));
+        assert!(sectors[1].key_a.is_none());
+    }
+
+    #[test]
+    fn short_mfu_dump_is_a_warning() {
+        use output_parser::MfuVariant;
+
+        // UL-C without the 3DES key: every readable page is there
+        assert_eq!(
+            mfu_dump_info(MfuVariant::UltralightC, 44),
+            "Ultralight C dump complete (44 pages)."
+        );
+        assert_eq!(mfu_dump_info(MfuVariant::Ntag215, 135), "NTAG215 dump complete (135 pages).");
+
+        // Read-protected NTAG213: the dump stops at the protected pages
+        let info = mfu_dump_info(MfuVariant::Ntag213, 16);
+        assert!(info.starts_with("Warning: only 16 of the NTAG213's 45 pages"), "{}", info);
+        assert!(info.contains("pages 16-44"), "{}", info);
+    }
+
+    #[test]
+    fn unsafe_uid_written_once_acknowledged() {
+        let classic = CardType::MifareClassic1K;
+        let block0 = "08123456780804006263646566676869";
+        let warning = uid_write_check("08123456", &classic, Some(block0), false).unwrap_err();
+        assert_eq!(warning, UidWarning::RandomId);
+        assert_eq!(
+            uid_write_check("08123456", &classic, Some(block0), true),
+            Ok(Some(UidWarning::RandomId))
+        );
+
+        // Bad BCC in block 0, acknowledged
+        let bad_bcc = "DEADBEEF000804006263646566676869";
+        assert!(matches!(
+            uid_write_check("DEADBEEF", &classic, Some(bad_bcc), true),
+            Ok(Some(UidWarning::BccMismatch { .. }))
+        ));
+
+        // UL has no block 0: the UID alone is checked
+        assert_eq!(uid_write_check("04A23B1C5D8000", &CardType::NTAG, None, false), Ok(None));
+
+        // A wrong length can't be written, acknowledged or not
+        assert!(uid_write_check("DEADBEEF", &CardType::NTAG, None, true)
+            .unwrap_err()
+            .is_blocking());
+    }
+
+    #[tokio::test]
+    async fn unreadable_sector_does_not_end_the_range() {
+        let cmds: Vec<String> = (1..=3).map(|s| format!("read sector {}", s)).collect();
+        let sectors = read_sectors(
+            |cmd: String| {
+                let output = if cmd.ends_with('2') {
+                    Err(AppError::CommandFailed("PM3 exited with code 1".into()))
+                } else {
+                    let first = if cmd.ends_with('1') { 4 } else { 12 };
+                    Ok((first..first + 4)
+                        .map(|blk| format!("[=] {:>3} | {}", blk, ["AB"; 16].join(" ")))
+                        .collect::<Vec<_>>()
+                        .join("\n"))
+                };
+                async move { output }
+            },
+            1,
+            &cmds,
+        )
+        .await;
+
+        assert_eq!(sectors.iter().map(|s| s.sector).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(sectors[1].blocks.iter().all(Option::is_none));
+        for read in [&sectors[0], &sectors[2]] {
+            assert_eq!(read.blocks.len(), 4);
+            assert!(read.blocks.iter().all(|b| b.as_deref() == Some(&"AB".repeat(16)[..])));
+        }
+    }
+
+    #[tokio::test]
+    async fn key_b_only_block_reread_after_key_a_auth_fails() {
+        // Mini-sized dump: sector 1 key A = A0..A5, key B = B0..B5, block 5 = 0x55
+        let mut dump = vec![0u8; 320];
+        for sector in 0..5 {
+            let trailer = (sector * 4 + 3) * 16;
+            dump[trailer..trailer + 6].copy_from_slice(&[0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5]);
+            dump[trailer + 10..trailer + 16]
+                .copy_from_slice(&[0xB0, 0xB1, 0xB2, 0xB3, 0xB4, 0xB5]);
+        }
+        dump[5 * 16..6 * 16].fill(0x55);
+        let row = |blk: u16, byte: &str| {
+            format!("[=] {:>3} | {}", blk, [byte; 16].join(" "))
+        };
+
+        let mut sent = Vec::new();
+        let still = recheck_mismatched_blocks(
+            |cmd: String| {
+                sent.push(cmd.clone());
+                let output = if cmd.ends_with(" -a") {
+                    // Key A auth fails: the client exits non-zero
+                    Err(AppError::CommandFailed("PM3 exited with code 1".into()))
+                } else if cmd.contains("--blk 5 ") {
+                    Ok(row(5, "55"))
+                } else {
+                    Ok(row(6, "FF"))
+                };
+                async move { output }
+            },
+            &dump,
+            vec![5, 6, 7],
+        )
+        .await;
+
+        // Block 5 reads back right with key B; 6 differs; the trailer isn't re-read
+        assert_eq!(still, vec![6, 7]);
+        assert_eq!(
+            sent,
+            vec![
+                "hf mf rdbl --blk 5 -k A0A1A2A3A4A5 -a",
+                "hf mf rdbl --blk 5 -k B0B1B2B3B4B5 -b",
+                "hf mf rdbl --blk 6 -k A0A1A2A3A4A5 -a",
+                "hf mf rdbl --blk 6 -k B0B1B2B3B4B5 -b",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_dump_caught_before_write() {
+        let dir = TempDir::new("predump");
+        let dump = dir.join("hf-mf-01020304-dump.bin");
+        let dump_path = dump.to_str().unwrap();
+
+        let err = check_dump_file(dump_path).unwrap_err();
+        assert!(err.starts_with("Dump file missing"), "{}", err);
+        std::fs::write(&dump, b"").unwrap();
+        assert!(check_dump_file(dump_path).unwrap_err().contains("empty"));
+        std::fs::write(&dump, [0u8; 64]).unwrap();
+        assert!(check_dump_file(dump_path).is_ok());
+    }
+
+    /// The archive step of the HF write: off by default, one bundle and
+    /// library entry per source dump however often the write is retried.
+    #[test]
+    fn source_dump_archived_once_per_dump() {
+        let dir = TempDir::new("archive");
+        let dump = dir.join("hf-mf-7DE9254E-dump.bin");
+        std::fs::write(&dump, [0x5Au8; 1024]).unwrap();
+        let key = dir.join("hf-mf-7DE9254E-key.bin");
+        std::fs::write(&key, [0xFFu8; 96]).unwrap();
+        let key = Some(key.to_string_lossy().into_owned());
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        let library = Some((&db, &*dir));
+        let source = || source_archive_card(&CardType::MifareClassic1K, "7DE9254E");
+
+        let settings = write::WriteSettings::new();
+        let enabled = settings.always_dump_source();
+        let off = archive_source_in(enabled, library, source(), None, &dump, None);
+        assert!(off.unwrap().is_none());
+        assert!(db.get_saved_cards().unwrap().is_empty());
+        assert!(archive_source_in(true, None, source(), None, &dump, None).is_err());
+
+        let archive = archive_source_in(true, library, source(), None, &dump, key.clone())
+            .unwrap()
+            .unwrap();
+        assert!(archive.starts_with(dir.join(dump::SOURCE_ARCHIVE_DIR)));
+        let bundle = std::fs::read_to_string(&archive).unwrap();
+        assert!(bundle.contains("hf-mf-7DE9254E-dump.bin") && bundle.contains("5A5A5A5A"));
+
+        // A retry with the same dump reuses the archive
+        let retry = archive_source_in(true, library, source(), None, &dump, key.clone());
+        assert_eq!(retry.unwrap().as_ref(), Some(&archive));
+        let saved = db.get_saved_cards().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].uid, "7DE9254E");
+        assert_eq!(saved[0].card_type, "MifareClassic1K");
+
+        // A fresh read of the card is a new record
+        std::fs::write(&dump, [0xA5u8; 1024]).unwrap();
+        let reread = archive_source_in(true, library, source(), None, &dump, key).unwrap();
+        assert_ne!(reread.as_ref(), Some(&archive));
+        assert_eq!(db.get_saved_cards().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reference_dump_mismatches_reported_by_block() {
+        let dir = TempDir::new("verify");
+        let library = dir.join("library");
+        std::fs::create_dir_all(&library).unwrap();
+        let reference = library.join("hf-mf-01020304-dump.bin");
+        let readback = dir.join("hf-mf-01020304-dump-001.bin");
+
+        // Four 16-byte blocks; the clone drifted in blocks 1 and 3
+        let saved: Vec<u8> = (0..64).collect();
+        let mut clone = saved.clone();
+        clone[17] ^= 0xFF;
+        clone[63] = 0;
+        std::fs::write(&reference, &saved).unwrap();
+        std::fs::write(&readback, &clone).unwrap();
+
+        let reference = reference_dump_in(&library, reference.to_str().unwrap()).unwrap();
+        let output = format!("[+] Saved 64 bytes to binary file `{}`", readback.display());
+        let verify = |original: Option<String>| {
+            let run = |_: String| async { Err(AppError::CommandFailed("no device".into())) };
+            compare_readback(Ok(output.clone()), original, 16, run, || panic!("readback ran"))
+        };
+        assert_eq!(verify(Some(reference.clone())).await.unwrap(), vec![1, 3]);
+        // No saved dump and no session dump: UID match stands
+        assert!(verify(None).await.unwrap().is_empty());
+
+        // Only files inside the library are accepted as the reference
+        let outside = readback.to_str().unwrap();
+        assert!(reference_dump_in(&library, outside).is_err());
+        let escaped = library.join("..").join("hf-mf-01020304-dump-001.bin");
+        assert!(reference_dump_in(&library, escaped.to_str().unwrap()).is_err());
+        assert!(reference_dump_in(&library, library.to_str().unwrap()).is_err());
+    }
+}
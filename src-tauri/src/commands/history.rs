@@ -1,8 +1,10 @@
+use serde::Serialize;
 use tauri::State;
 
-use crate::db::models::CloneRecord;
+use crate::db::models::{CloneRecord, LastOperation};
 use crate::db::Database;
 use crate::error::AppError;
+use crate::pm3::clone_summary::CloneTracker;
 
 #[tauri::command]
 pub fn get_history(
@@ -12,11 +14,22 @@ pub fn get_history(
     db.get_history(limit.unwrap_or(50))
 }
 
+/// Clones saved without a note get the warnings from their operation
+/// summary, so the history shows what went wrong along the way; the source
+/// bundle archived for the clone is linked the same way.
 #[tauri::command]
 pub fn save_clone_record(
     db: State<'_, Database>,
-    record: CloneRecord,
+    tracker: State<'_, CloneTracker>,
+    mut record: CloneRecord,
 ) -> Result<i64, AppError> {
+    let summary = tracker.last().filter(|s| s.source_uid == record.source_uid);
+    if record.notes.is_none() {
+        record.notes = summary.as_ref().and_then(|s| s.notes());
+    }
+    if record.source_dump.is_none() {
+        record.source_dump = summary.and_then(|s| s.source_dump);
+    }
     // Validate field lengths to prevent oversized data from being stored in SQLite
     if record.source_type.len() > 100
         || record.source_uid.len() > 200
@@ -25,6 +38,7 @@ pub fn save_clone_record(
         || record.port.len() > 50
         || record.timestamp.len() > 50
         || record.notes.as_ref().map_or(false, |n| n.len() > 1000)
+        || record.source_dump.as_ref().is_some_and(|p| p.len() > 1000)
     {
         return Err(AppError::CommandFailed(
             "Record fields too long".into(),
@@ -32,3 +46,60 @@ pub fn save_clone_record(
     }
     db.insert_record(&record)
 }
+
+/// Add, change or clear (`None`/empty) the note on a clone_log entry after
+/// the fact, e.g. "works on door 3, not door 5".
+#[tauri::command]
+pub fn update_clone_note(
+    db: State<'_, Database>,
+    id: i64,
+    note: Option<String>,
+) -> Result<(), AppError> {
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.len() > 1000) {
+        return Err(AppError::CommandFailed("Note too long".into()));
+    }
+    if !db.update_clone_note(id, note)? {
+        return Err(AppError::CommandFailed(format!("No clone record with id {}", id)));
+    }
+    Ok(())
+}
+
+/// Store the answer to "did it open the actual door reader?" for a clone.
+/// Verifying against the PM3 doesn't prove this — reader antenna strength
+/// and config blocks can still make the door reject a matching clone.
+#[tauri::command]
+pub fn record_field_test(
+    db: State<'_, Database>,
+    id: i64,
+    works: bool,
+) -> Result<(), AppError> {
+    if !db.set_field_verified(id, works)? {
+        return Err(AppError::CommandFailed(format!("No clone record with id {}", id)));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatus {
+    /// `false` when history is in-memory only and lost on exit.
+    pub persistent: bool,
+    pub warning: Option<String>,
+}
+
+/// Whether clone history is being saved, plus any startup problem with the
+/// database (reset after corruption, in-memory fallback) to show the user.
+#[tauri::command]
+pub fn database_status(db: State<'_, Database>) -> DatabaseStatus {
+    DatabaseStatus {
+        persistent: db.persistent,
+        warning: db.warning.clone(),
+    }
+}
+
+/// The last clone started, finished or not, from this or an earlier session.
+#[tauri::command]
+pub fn get_last_operation(db: State<'_, Database>) -> Result<Option<LastOperation>, AppError> {
+    db.get_last_operation()
+}
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+
+use crate::cards::types::{BlankType, CardType, RecoveryAction};
+use crate::commands::{hf_clone, write};
+use crate::error::AppError;
+use crate::pm3::connection::HfOperationState;
+use crate::state::{WizardAction, WizardMachine};
+
+// Background jobs: `submit_job` returns a job ID straight away and the clone
+// runs on its own task, so the frontend polls `get_job_status` instead of
+// holding an invoke open for minutes. Jobs take the device one at a time, in
+// submission order. A running job is never dropped mid-command: it is stopped
+// by killing its PM3 process and holds the device until it has unwound.
+
+/// Finished jobs kept for polling; older ones are dropped on submit.
+const MAX_FINISHED_JOBS: usize = 32;
+
+// ---------------------------------------------------------------------------
+// DTOs
+// ---------------------------------------------------------------------------
+
+/// Work a job runs. Arguments mirror the command each variant wraps.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum JobSpec {
+    /// `write_clone_with_data`.
+    LfClone {
+        port: String,
+        card_type: CardType,
+        uid: String,
+        #[serde(default)]
+        decoded: HashMap<String, String>,
+        blank_type: Option<BlankType>,
+        #[serde(default)]
+        prefer_raw: Option<bool>,
+        password: Option<String>,
+        set_password: Option<String>,
+    },
+    /// `hf_write_clone`.
+    HfClone {
+        source_uid: String,
+        card_type: CardType,
+        blank_type: BlankType,
+        acknowledge_uid_warning: Option<bool>,
+    },
+}
+
+impl JobSpec {
+    fn is_hf(&self) -> bool {
+        matches!(self, JobSpec::HfClone { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JobPhase {
+    /// Waiting for an earlier job to release the device.
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobPhase {
+    fn is_finished(self) -> bool {
+        matches!(self, JobPhase::Succeeded | JobPhase::Failed | JobPhase::Cancelled)
+    }
+}
+
+/// Returned by `get_job_status`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub job_id: u64,
+    pub phase: JobPhase,
+    /// The wrapped command's result once `Succeeded`.
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Managed state
+// ---------------------------------------------------------------------------
+
+struct JobEntry {
+    status: JobStatus,
+    hf: bool,
+    handle: Option<JoinHandle<()>>,
+    /// Cancel asked for while running; the job ends as `Cancelled`.
+    cancel_requested: bool,
+}
+
+/// What `JobRegistry::cancel` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// Still queued: dropped before it touched the device.
+    Dequeued,
+    /// Running: it only stops once its PM3 process is killed. `hf` marks
+    /// jobs whose child `cancel_hf_operation` can kill.
+    Running { hf: bool },
+    Finished,
+}
+
+#[derive(Default)]
+struct JobsInner {
+    jobs: Mutex<HashMap<u64, JobEntry>>,
+    /// Held by the running job, so queued jobs wait their turn.
+    device: tokio::sync::Mutex<()>,
+    next_id: AtomicU64,
+}
+
+/// Submitted jobs and their status. Stored via `app.manage()` in `lib.rs`;
+/// cheap to clone, each spawned job holds a handle to update its status.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    inner: Arc<JobsInner>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the job `make_job` builds from its ID and return the ID. It
+    /// runs once every earlier job has finished; `hf` marks jobs whose PM3
+    /// child can be killed to cancel them.
+    pub fn submit<M, F>(&self, hf: bool, make_job: M) -> Result<u64, AppError>
+    where
+        M: FnOnce(u64) -> F,
+        F: Future<Output = Result<serde_json::Value, AppError>> + Send + 'static,
+    {
+        let job_id = self.inner.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut jobs = self.lock()?;
+        prune_finished(&mut jobs);
+        jobs.insert(
+            job_id,
+            JobEntry {
+                status: JobStatus {
+                    job_id,
+                    phase: JobPhase::Queued,
+                    result: None,
+                    error: None,
+                },
+                hf,
+                handle: None,
+                cancel_requested: false,
+            },
+        );
+
+        let job = make_job(job_id);
+        let registry = self.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            let _device = registry.inner.device.lock().await;
+            registry.update(job_id, |s| s.phase = JobPhase::Running);
+            let outcome = job.await;
+            let cancelled = registry.cancel_requested(job_id);
+            registry.update(job_id, |s| match outcome {
+                Ok(value) => {
+                    s.phase = if cancelled { JobPhase::Cancelled } else { JobPhase::Succeeded };
+                    s.result = Some(value);
+                }
+                Err(e) => {
+                    s.phase = if cancelled { JobPhase::Cancelled } else { JobPhase::Failed };
+                    s.error = Some(e.to_string());
+                }
+            });
+        });
+        if let Some(entry) = jobs.get_mut(&job_id) {
+            entry.handle = Some(handle);
+        }
+        Ok(job_id)
+    }
+
+    pub fn status(&self, job_id: u64) -> Result<JobStatus, AppError> {
+        self.lock()?
+            .get(&job_id)
+            .map(|e| e.status.clone())
+            .ok_or_else(|| AppError::CommandFailed(format!("Unknown job {}", job_id)))
+    }
+
+    /// Cancel a job. A queued job is dropped; a running one is only marked,
+    /// and the caller must kill its PM3 process (or `withdraw_cancel`).
+    pub fn cancel(&self, job_id: u64) -> Result<CancelOutcome, AppError> {
+        let mut jobs = self.lock()?;
+        let entry = jobs
+            .get_mut(&job_id)
+            .ok_or_else(|| AppError::CommandFailed(format!("Unknown job {}", job_id)))?;
+        match entry.status.phase {
+            phase if phase.is_finished() => Ok(CancelOutcome::Finished),
+            JobPhase::Running => {
+                entry.cancel_requested = true;
+                Ok(CancelOutcome::Running { hf: entry.hf })
+            }
+            _ => {
+                if let Some(handle) = entry.handle.take() {
+                    handle.abort();
+                }
+                entry.status.phase = JobPhase::Cancelled;
+                Ok(CancelOutcome::Dequeued)
+            }
+        }
+    }
+
+    /// Undo a `cancel` of a running job that couldn't be stopped.
+    pub fn withdraw_cancel(&self, job_id: u64) -> Result<(), AppError> {
+        if let Some(entry) = self.lock()?.get_mut(&job_id) {
+            entry.cancel_requested = false;
+        }
+        Ok(())
+    }
+
+    pub fn cancel_requested(&self, job_id: u64) -> bool {
+        self.inner
+            .jobs
+            .lock()
+            .ok()
+            .and_then(|jobs| jobs.get(&job_id).map(|e| e.cancel_requested))
+            .unwrap_or(false)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<u64, JobEntry>>, AppError> {
+        self.inner
+            .jobs
+            .lock()
+            .map_err(|e| AppError::CommandFailed(format!("Job registry lock poisoned: {}", e)))
+    }
+
+    /// Apply `f` to a job's status unless it was cancelled meanwhile.
+    fn update(&self, job_id: u64, f: impl FnOnce(&mut JobStatus)) {
+        if let Ok(mut jobs) = self.inner.jobs.lock() {
+            if let Some(entry) = jobs.get_mut(&job_id) {
+                if entry.status.phase != JobPhase::Cancelled {
+                    f(&mut entry.status);
+                }
+            }
+        }
+    }
+}
+
+/// Drop the oldest finished jobs beyond `MAX_FINISHED_JOBS`.
+fn prune_finished(jobs: &mut HashMap<u64, JobEntry>) {
+    let mut finished: Vec<u64> = jobs
+        .iter()
+        .filter(|(_, e)| e.status.phase.is_finished())
+        .map(|(id, _)| *id)
+        .collect();
+    if finished.len() < MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort_unstable();
+    for id in &finished[..=finished.len() - MAX_FINISHED_JOBS] {
+        jobs.remove(id);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+async fn run_job(
+    app: AppHandle,
+    spec: JobSpec,
+    jobs: JobRegistry,
+    job_id: u64,
+) -> Result<serde_json::Value, AppError> {
+    let result = match spec {
+        JobSpec::LfClone {
+            port,
+            card_type,
+            uid,
+            decoded,
+            blank_type,
+            prefer_raw,
+            password,
+            set_password,
+        } => {
+            write::write_clone_with_data(
+                app.clone(),
+                port,
+                card_type,
+                uid,
+                decoded,
+                blank_type,
+                prefer_raw,
+                password,
+                set_password,
+                app.state(),
+            )
+            .await
+        }
+        JobSpec::HfClone {
+            source_uid,
+            card_type,
+            blank_type,
+            acknowledge_uid_warning,
+        } => {
+            hf_clone::hf_write_clone(
+                app.clone(),
+                source_uid,
+                card_type,
+                blank_type,
+                acknowledge_uid_warning,
+                app.state(),
+                app.state(),
+            )
+            .await
+        }
+    };
+    if jobs.cancel_requested(job_id) {
+        report_cancelled(&app)?;
+    }
+    serde_json::to_value(result?)
+        .map_err(|e| AppError::CommandFailed(format!("Failed to serialize job result: {}", e)))
+}
+
+/// Leave the wizard in a recoverable error after a killed job, whatever step
+/// the write stopped in.
+fn report_cancelled(app: &AppHandle) -> Result<(), AppError> {
+    let machine = app.state::<Mutex<WizardMachine>>();
+    let mut m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    m.transition(WizardAction::ReportError {
+        message: "Clone job cancelled".to_string(),
+        user_message: "The clone was cancelled before it finished. Put the blank back on the \
+                       reader and retry."
+            .to_string(),
+        recoverable: true,
+        recovery_action: Some(RecoveryAction::Retry),
+    })?;
+    Ok(())
+}
+
+/// Queue a clone and return its job ID without waiting for it. Events are
+/// emitted as for the wrapped command; poll `get_job_status` for the result.
+#[tauri::command]
+pub fn submit_job(
+    app: AppHandle,
+    spec: JobSpec,
+    jobs: State<'_, JobRegistry>,
+) -> Result<u64, AppError> {
+    let hf = spec.is_hf();
+    let registry = jobs.inner().clone();
+    jobs.submit(hf, |job_id| run_job(app, spec, registry, job_id))
+}
+
+#[tauri::command]
+pub fn get_job_status(job_id: u64, jobs: State<'_, JobRegistry>) -> Result<JobStatus, AppError> {
+    jobs.status(job_id)
+}
+
+/// Cancel a queued or running job. A running HF job's PM3 process is killed
+/// as with `cancel_hf_operation`, and the job ends once it has unwound. A job
+/// running a command that can't be killed (any LF write) is refused rather
+/// than abandoned with the process still on the port. Returns `false` if the
+/// job had already finished.
+#[tauri::command]
+pub async fn cancel_job(
+    job_id: u64,
+    jobs: State<'_, JobRegistry>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<bool, AppError> {
+    match jobs.cancel(job_id)? {
+        CancelOutcome::Finished => Ok(false),
+        CancelOutcome::Dequeued => Ok(true),
+        CancelOutcome::Running { hf } => {
+            if hf && hf_clone::kill_hf_child(&hf_state)? {
+                return Ok(true);
+            }
+            jobs.withdraw_cancel(job_id)?;
+            Err(AppError::CommandFailed(format!(
+                "Job {} is in the middle of a write that can't be interrupted; \
+                 wait for it to finish",
+                job_id
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn wait_for(jobs: &JobRegistry, job_id: u64, phase: JobPhase) -> JobStatus {
+        for _ in 0..200 {
+            let status = jobs.status(job_id).unwrap();
+            if status.phase == phase {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("job {} never reached {:?}", job_id, phase);
+    }
+
+    #[tokio::test]
+    async fn mock_job_polled_to_completion() {
+        let jobs = JobRegistry::new();
+        let (release, gate) = tokio::sync::oneshot::channel::<()>();
+        let first = jobs
+            .submit(false, |_| async move {
+                gate.await.ok();
+                Ok(serde_json::json!({ "step": "Verifying" }))
+            })
+            .unwrap();
+        let second = jobs
+            .submit(false, |_| async { Err(AppError::CommandFailed("blank missing".into())) })
+            .unwrap();
+        assert_ne!(first, second);
+
+        // The second job waits for the device until the first finishes
+        wait_for(&jobs, first, JobPhase::Running).await;
+        assert_eq!(jobs.status(second).unwrap().phase, JobPhase::Queued);
+        release.send(()).unwrap();
+
+        let done = wait_for(&jobs, first, JobPhase::Succeeded).await;
+        assert_eq!(done.result.unwrap()["step"], "Verifying");
+        let failed = wait_for(&jobs, second, JobPhase::Failed).await;
+        assert!(failed.error.unwrap().contains("blank missing"));
+        assert!(jobs.status(999).is_err());
+    }
+
+    #[tokio::test]
+    async fn cancelled_job_holds_the_device_until_killed() {
+        let jobs = JobRegistry::new();
+        let (kill, killed) = tokio::sync::oneshot::channel::<()>();
+        let running = jobs
+            .submit(true, |_| async move {
+                killed.await.ok();
+                Err(AppError::Cancelled)
+            })
+            .unwrap();
+        let next = jobs.submit(false, |_| async { Ok(serde_json::json!(1)) }).unwrap();
+        let queued = jobs.submit(false, |_| async { Ok(serde_json::json!(2)) }).unwrap();
+        wait_for(&jobs, running, JobPhase::Running).await;
+
+        // A queued job is simply dropped
+        assert_eq!(jobs.cancel(queued).unwrap(), CancelOutcome::Dequeued);
+        assert_eq!(jobs.status(queued).unwrap().phase, JobPhase::Cancelled);
+
+        // A running HF job keeps the device until its process is killed
+        assert_eq!(jobs.cancel(running).unwrap(), CancelOutcome::Running { hf: true });
+        assert!(jobs.cancel_requested(running));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(jobs.status(next).unwrap().phase, JobPhase::Queued);
+        kill.send(()).unwrap();
+        wait_for(&jobs, running, JobPhase::Cancelled).await;
+        wait_for(&jobs, next, JobPhase::Succeeded).await;
+        assert_eq!(jobs.cancel(next).unwrap(), CancelOutcome::Finished);
+    }
+
+    #[tokio::test]
+    async fn withdrawn_cancel_lets_the_job_finish() {
+        let jobs = JobRegistry::new();
+        let (release, gate) = tokio::sync::oneshot::channel::<()>();
+        let job = jobs
+            .submit(false, |_| async move {
+                gate.await.ok();
+                Ok(serde_json::json!("written"))
+            })
+            .unwrap();
+        wait_for(&jobs, job, JobPhase::Running).await;
+        assert_eq!(jobs.cancel(job).unwrap(), CancelOutcome::Running { hf: false });
+        jobs.withdraw_cancel(job).unwrap();
+        release.send(()).unwrap();
+        wait_for(&jobs, job, JobPhase::Succeeded).await;
+    }
+
+    #[test]
+    fn job_spec_from_frontend() {
+        let spec: JobSpec = serde_json::from_str(
+            r#"{"kind":"lfClone","port":"COM3","cardType":"EM4100","uid":"0F00112233"}"#,
+        )
+        .unwrap();
+        assert!(!spec.is_hf());
+        let spec: JobSpec = serde_json::from_str(
+            r#"{"kind":"hfClone","sourceUid":"01020304","cardType":"MifareClassic1K",
+                "blankType":"MagicMifareGen1a"}"#,
+        )
+        .unwrap();
+        assert!(spec.is_hf());
+    }
+}
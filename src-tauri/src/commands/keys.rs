@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::DataDir;
+use crate::error::AppError;
+use crate::pm3::dictionaries::{self, Dictionary, DictionaryKind, SelectedDictionaries};
+use crate::pm3::{command_builder, connection, keyfile};
+
+/// Append recovered MIFARE Classic keys to the persistent project keyfile.
+/// Keys must be 12 hex chars; duplicates are ignored. Returns the full list.
+#[tauri::command]
+pub fn add_keys_to_project(app: AppHandle, keys: Vec<String>) -> Result<Vec<String>, AppError> {
+    keyfile::add_keys(&project_keyfile(&app)?, &keys)
+}
+
+/// All keys currently in the project keyfile, in insertion order.
+#[tauri::command]
+pub fn get_project_keys(app: AppHandle) -> Result<Vec<String>, AppError> {
+    keyfile::read_keys(&project_keyfile(&app)?)
+}
+
+/// Delete every key from the project keyfile.
+#[tauri::command]
+pub fn clear_project_keys(app: AppHandle) -> Result<(), AppError> {
+    keyfile::clear_keys(&project_keyfile(&app)?)
+}
+
+/// Key and password dictionaries available to the PM3 client: its user dir
+/// (from `prefs show`), next to the binary, and the usual install prefixes.
+#[tauri::command]
+pub async fn list_dictionaries(app: AppHandle, port: String) -> Result<Vec<Dictionary>, AppError> {
+    let user_dir = match connection::run_command(&app, &port, command_builder::build_prefs_show()).await {
+        Ok(output) => dictionaries::parse_user_dir(&output),
+        Err(e) => {
+            log::warn!("prefs show failed, listing install dirs only: {}", e);
+            None
+        }
+    };
+    // The bundled sidecar lives next to the app executable
+    let client_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from));
+    let dirs = dictionaries::search_dirs(user_dir.as_deref(), client_dir.as_deref());
+    Ok(dictionaries::list_dictionaries(&dirs))
+}
+
+/// Use `path` for MIFARE Classic autopwn or T55xx password checks instead
+/// of the app's own pick. `None` restores the default.
+#[tauri::command]
+pub fn select_dictionary(
+    kind: DictionaryKind,
+    path: Option<String>,
+    selected: State<'_, SelectedDictionaries>,
+) -> Result<(), AppError> {
+    if let Some(path) = &path {
+        dictionaries::validate_dictionary_path(path)?;
+    }
+    selected.set(kind, path)
+}
+
+/// Path of the project keyfile in the data dir (see `db::DataDir`).
+pub(crate) fn project_keyfile(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let data_dir = app.try_state::<DataDir>().ok_or_else(|| {
+        AppError::CommandFailed("Data directory not initialised".into())
+    })?;
+    Ok(keyfile::project_keyfile_path(&data_dir.inner().0))
+}
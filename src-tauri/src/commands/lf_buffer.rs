@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::scan::DetectedTag;
+use crate::db::DataDir;
+use crate::error::AppError;
+use crate::pm3::connection::{self, ChainedCommand};
+use crate::pm3::{command_builder, output_parser};
+
+// Raw LF captures: `lf read` samples the field and `data save` keeps the
+// samples, so a marginal or unknown tag can be demodulated again later
+// (`data load` + `lf search -1`) without the card. The graph buffer lives in
+// the PM3 client, so each pair runs as one chained invocation.
+
+/// Capture directory, under the data dir.
+const CAPTURE_DIR: &str = "lf-captures";
+const CAPTURE_EXT: &str = ".pm3";
+
+/// One saved capture, as returned by `capture_lf_buffer` / `list_lf_captures`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LfCapture {
+    /// File name in the capture directory; pass it to `demod_lf_capture`.
+    pub file_name: String,
+    pub size_bytes: u64,
+}
+
+fn capture_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let data_dir = app.try_state::<DataDir>().ok_or_else(|| {
+        AppError::CommandFailed("Data directory not initialised".into())
+    })?;
+    Ok(data_dir.inner().0.join(CAPTURE_DIR))
+}
+
+/// A capture file name from the frontend: a bare `.pm3` name, so it can't
+/// point outside the capture directory.
+fn check_capture_name(file_name: &str) -> Result<(), AppError> {
+    let stem = file_name.strip_suffix(CAPTURE_EXT).unwrap_or_default();
+    if stem.is_empty()
+        || stem.starts_with('.')
+        || !stem.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(AppError::CommandFailed(format!(
+            "Invalid capture name '{}'",
+            file_name
+        )));
+    }
+    Ok(())
+}
+
+/// `<label>-<timestamp>.pm3`, the label cut down to file-name-safe characters.
+fn capture_file_name(label: Option<&str>) -> String {
+    let label: String = label
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .take(32)
+        .collect();
+    format!(
+        "{}-{}{}",
+        if label.is_empty() { "lf" } else { &label },
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        CAPTURE_EXT
+    )
+}
+
+/// The `data load` + `lf search -1` chain that re-demodulates a capture.
+fn demod_chain(path: &str) -> Result<ChainedCommand, AppError> {
+    let load = command_builder::build_data_load(path).map_err(AppError::CommandFailed)?;
+    ChainedCommand::new(&[&load, command_builder::build_lf_search_buffer()])
+}
+
+/// The tag `lf search -1` found in a `demod_chain` run, if any.
+fn demodulated_tag(chain: &ChainedCommand, output: &str) -> Option<DetectedTag> {
+    let search = chain.part_output(output, 1)?;
+    let (card_type, card_data) = output_parser::parse_lf_search(&search)?;
+    Some(DetectedTag {
+        frequency: card_type.frequency(),
+        cloneable: card_type.is_cloneable(),
+        card_type,
+        card_data,
+    })
+}
+
+/// Sample the LF field and save the raw samples to the capture directory.
+/// `label` prefixes the file name (e.g. the site or card the capture is of).
+#[tauri::command]
+pub async fn capture_lf_buffer(
+    app: AppHandle,
+    port: String,
+    label: Option<String>,
+) -> Result<LfCapture, AppError> {
+    connection::check_port(&port)?;
+    let dir = capture_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to create capture directory: {}", e))
+    })?;
+    let file_name = capture_file_name(label.as_deref());
+    let path = dir.join(&file_name);
+    let save = command_builder::build_data_save(&path.to_string_lossy())
+        .map_err(AppError::CommandFailed)?;
+    let chain = ChainedCommand::new(&[command_builder::build_lf_read(), &save])?;
+
+    let output = connection::run_chained(&app, &port, &chain).await?;
+    if output.contains("[!!]") || chain.part_output(&output, 1).is_none() {
+        return Err(AppError::CommandFailed(
+            "Could not save the LF sample buffer".into(),
+        ));
+    }
+    let size_bytes = std::fs::metadata(&path)
+        .map_err(|_| AppError::CommandFailed("PM3 did not write the capture file".into()))?
+        .len();
+    Ok(LfCapture {
+        file_name,
+        size_bytes,
+    })
+}
+
+/// Saved captures, newest first.
+#[tauri::command]
+pub fn list_lf_captures(app: AppHandle) -> Result<Vec<LfCapture>, AppError> {
+    let dir = capture_dir(&app)?;
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AppError::CommandFailed(format!(
+                "Failed to read capture directory: {}",
+                e
+            )))
+        }
+    };
+    let mut captures: Vec<(std::time::SystemTime, LfCapture)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            check_capture_name(&file_name).ok()?;
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified = meta.modified().unwrap_or(std::time::UNIX_EPOCH);
+            Some((
+                modified,
+                LfCapture {
+                    file_name,
+                    size_bytes: meta.len(),
+                },
+            ))
+        })
+        .collect();
+    captures.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    Ok(captures.into_iter().map(|(_, c)| c).collect())
+}
+
+/// Load a saved capture into the graph buffer and run `lf search` over it.
+/// `None` when no known modulation is found in the samples.
+#[tauri::command]
+pub async fn demod_lf_capture(
+    app: AppHandle,
+    port: String,
+    file_name: String,
+) -> Result<Option<DetectedTag>, AppError> {
+    connection::check_port(&port)?;
+    check_capture_name(&file_name)?;
+    let path = capture_dir(&app)?.join(&file_name);
+    if !path.is_file() {
+        return Err(AppError::CommandFailed(format!(
+            "Capture '{}' not found",
+            file_name
+        )));
+    }
+    let chain = demod_chain(&path.to_string_lossy())?;
+    let output = connection::run_chained(&app, &port, &chain).await?;
+    Ok(demodulated_tag(&chain, &output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::types::CardType;
+
+    #[test]
+    fn loaded_capture_is_demodulated() {
+        let path = "/data/lf-captures/lobby-20261016-101500.pm3";
+        let chain = demod_chain(path).unwrap();
+        assert_eq!(
+            chain.script(),
+            format!("data load -f \"{}\"; lf search -1", path)
+        );
+
+        let output = format!(
+            "[usb|script] pm3 --> data load -f \"{}\"\n\
+             [+] loaded 40000 samples\n\
+             [usb|script] pm3 --> lf search -1\n\
+             [+] EM 410x ID 0F00112233\n\
+             [+] Valid EM410x ID found!",
+            path
+        );
+        let tag = demodulated_tag(&chain, &output).unwrap();
+        assert_eq!(tag.card_type, CardType::EM4100);
+        assert_eq!(tag.card_data.uid, "0F00112233");
+        assert!(tag.cloneable);
+
+        // The client stopped at the load: nothing was searched
+        let failed = format!("[usb|script] pm3 --> data load -f \"{}\"\n[!!] file not found", path);
+        assert!(demodulated_tag(&chain, &failed).is_none());
+
+        assert!(check_capture_name("lobby-20261016-101500.pm3").is_ok());
+        assert!(check_capture_name("../history.db").is_err());
+        assert!(check_capture_name("../x.pm3").is_err());
+        assert!(check_capture_name(".pm3").is_err());
+    }
+}
@@ -1,11 +1,19 @@
 pub mod blank;
 pub mod device;
+pub mod dump;
+pub mod em4x50;
 pub mod erase;
 pub mod firmware;
 pub mod hf_clone;
 pub mod history;
+pub mod jobs;
+pub mod keys;
+pub mod lf_buffer;
 pub mod raw;
 pub mod saved;
 pub mod scan;
+pub mod selfcheck;
+pub mod sequence;
+pub mod wiegand;
 pub mod wizard;
 pub mod write;
@@ -1,7 +1,9 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
 use crate::error::AppError;
-use crate::pm3::connection;
+use crate::pm3::command_builder::Verbosity;
+use crate::pm3::connection::{self, TerminalSettings};
+use crate::pm3::output_parser::{self, PatternMatch};
 
 #[tauri::command]
 pub async fn run_raw_command(
@@ -9,5 +11,35 @@ pub async fn run_raw_command(
     port: String,
     command: String,
 ) -> Result<String, AppError> {
-    connection::run_command(&app, &port, &command).await
+    connection::run_terminal_command(&app, &port, &command).await
+}
+
+/// Run pasted PM3 output through every parser pattern and report which
+/// matched and what they captured. Doesn't touch the device.
+#[tauri::command]
+pub fn debug_parse(output: String) -> Vec<PatternMatch> {
+    output_parser::debug_patterns(&output)
+}
+
+/// Forward PM3's ANSI colours to the terminal panel (`pm3-output` `ansi`
+/// field). Parsing always uses the stripped text.
+#[tauri::command]
+pub fn set_terminal_ansi(
+    enabled: bool,
+    settings: State<'_, TerminalSettings>,
+) -> Result<(), AppError> {
+    settings.set_preserve_ansi(enabled);
+    Ok(())
+}
+
+/// Default PM3 output verbosity for the commands the app builds (`-v` where
+/// supported, plus client debug output at `debug`). Terminal commands run as
+/// typed.
+#[tauri::command]
+pub fn set_pm3_verbosity(
+    verbosity: Verbosity,
+    settings: State<'_, TerminalSettings>,
+) -> Result<(), AppError> {
+    settings.set_verbosity(verbosity);
+    Ok(())
 }
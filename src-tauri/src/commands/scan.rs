@@ -1,16 +1,38 @@
+use std::future::Future;
 use std::sync::Mutex;
-use tauri::{AppHandle, State};
+use std::time::{Duration, Instant};
 
-use crate::cards::types::{CardType, RecoveryAction};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::cards::types::{CardMetadata, CardType, Frequency, RecoveryAction};
+use crate::db::Database;
 use crate::error::AppError;
-use crate::pm3::{command_builder, connection, output_parser};
+use crate::pm3::connection::HfOperationState;
+use crate::pm3::output_parser::LfWatchParser;
+use crate::pm3::scan_cache::ScanCache;
+use crate::pm3::{command_builder, connection, operation, output_parser};
 use crate::state::{WizardAction, WizardMachine, WizardState};
 
+/// Scan for a card. With no `frequency` both LF and HF are searched; with one,
+/// only that frequency is — and if it finds nothing, a quick search of the
+/// other frequency tells the user when they picked the wrong mode.
+///
+/// The identified card comes back with any earlier clones of its UID from
+/// history (`previous_clones`), so the UI can say "seen before".
+///
+/// `deep` repeats the same `lf search` up to `DEEP_SCAN_ATTEMPTS` times for
+/// worn or poorly coupled cards that only read intermittently, emitting
+/// `scan-progress` (`attempt`, `total`) before each try.
 #[tauri::command]
 pub async fn scan_card(
     app: AppHandle,
     machine: State<'_, Mutex<WizardMachine>>,
+    cache: State<'_, ScanCache>,
+    db: State<'_, Database>,
+    frequency: Option<Frequency>,
+    deep: Option<bool>,
 ) -> Result<WizardState, AppError> {
+    let deep = deep.unwrap_or(false);
     // Get the port from current state, then transition to ScanningCard
     let port = {
         let mut m = machine.lock().map_err(|e| {
@@ -28,13 +50,21 @@ pub async fn scan_card(
         port
     };
 
+    if let Some(frequency) = frequency {
+        return scan_single_frequency(&app, &port, &machine, &cache, &db, frequency, deep).await;
+    }
+
     // 1. Try LF search first (fast path for 125 kHz cards)
-    let lf_result =
-        connection::run_command(&app, &port, command_builder::build_lf_search()).await;
+    let lf_result = run_lf_search(&app, &port, deep).await;
 
     if let Ok(ref output) = lf_result {
-        if let Some((card_type, card_data)) = output_parser::parse_lf_search(output) {
-            return finish_scan(&machine, card_type, card_data);
+        if let Some((card_type, mut card_data)) = output_parser::parse_lf_search(output) {
+            if let Some(hit) = cache.get(&card_type, &card_data.uid) {
+                return finish_scan(&machine, &db, card_type, hit);
+            }
+            enrich_lf_data(&app, &port, output, &mut card_data).await;
+            cache.insert(&card_type, &card_data);
+            return finish_scan(&machine, &db, card_type, card_data);
         }
     }
 
@@ -46,9 +76,16 @@ pub async fn scan_card(
         Ok(output) => {
             if let Some((card_type, mut card_data)) = output_parser::parse_hf_search(&output)
             {
+                // A hit already carries the enrichment from the earlier scan
+                if let Some(hit) = cache.get(&card_type, &card_data.uid) {
+                    return finish_scan(&machine, &db, card_type, hit);
+                }
+                let unstable = confirm_hf_read(&app, &port, &card_type, &card_data).await;
                 // Enrich HF data with protocol-specific info commands
                 enrich_hf_data(&app, &port, &card_type, &mut card_data).await;
-                return finish_scan(&machine, card_type, card_data);
+                cache.insert(&card_type, &card_data);
+                add_read_warning(&mut card_data, unstable);
+                return finish_scan(&machine, &db, card_type, card_data);
             }
 
             // Neither LF nor HF found a card
@@ -72,7 +109,9 @@ pub async fn scan_card(
                 })?;
                 m.transition(WizardAction::ReportError {
                     message: e.to_string(),
-                    user_message: "Scan failed. Check device connection.".to_string(),
+                    user_message: e
+                        .user_message()
+                        .unwrap_or_else(|| "Scan failed. Check device connection.".to_string()),
                     recoverable: true,
                     recovery_action: Some(RecoveryAction::Reconnect),
                 })?;
@@ -95,9 +134,436 @@ pub async fn scan_card(
     }
 }
 
+/// Scan one frequency. When it comes up empty, search the other one and, if a
+/// card answers there, report a wrong-frequency hint instead of "no card".
+async fn scan_single_frequency(
+    app: &AppHandle,
+    port: &str,
+    machine: &Mutex<WizardMachine>,
+    cache: &ScanCache,
+    db: &Database,
+    frequency: Frequency,
+    deep: bool,
+) -> Result<WizardState, AppError> {
+    let (search_cmd, other, other_cmd) = match frequency {
+        Frequency::LF => (
+            command_builder::build_lf_search(),
+            Frequency::HF,
+            command_builder::build_hf_search(),
+        ),
+        Frequency::HF => (
+            command_builder::build_hf_search(),
+            Frequency::LF,
+            command_builder::build_lf_search(),
+        ),
+    };
+
+    let result = if frequency == Frequency::LF {
+        run_lf_search(app, port, deep).await
+    } else {
+        connection::run_command(app, port, search_cmd).await
+    };
+    let output = match result {
+        Ok(output) => output,
+        Err(e) => {
+            let user_message = e
+                .user_message()
+                .unwrap_or_else(|| "Scan failed. Check device connection.".to_string());
+            return report_scan_error(
+                machine,
+                &e.to_string(),
+                &user_message,
+                RecoveryAction::Reconnect,
+            );
+        }
+    };
+
+    let parsed = match frequency {
+        Frequency::LF => output_parser::parse_lf_search(&output),
+        Frequency::HF => output_parser::parse_hf_search(&output),
+    };
+    if let Some((card_type, mut card_data)) = parsed {
+        if let Some(hit) = cache.get(&card_type, &card_data.uid) {
+            return finish_scan(machine, db, card_type, hit);
+        }
+        let unstable = match frequency {
+            Frequency::LF => {
+                enrich_lf_data(app, port, &output, &mut card_data).await;
+                None
+            }
+            Frequency::HF => {
+                let unstable = confirm_hf_read(app, port, &card_type, &card_data).await;
+                enrich_hf_data(app, port, &card_type, &mut card_data).await;
+                unstable
+            }
+        };
+        cache.insert(&card_type, &card_data);
+        add_read_warning(&mut card_data, unstable);
+        return finish_scan(machine, db, card_type, card_data);
+    }
+
+    // Nothing on the requested frequency — is the card on the other one?
+    if let Ok(other_output) = connection::run_command(app, port, other_cmd).await {
+        if output_parser::card_on_frequency(&other, &other_output) {
+            return report_scan_error(
+                machine,
+                &format!("No {:?} card found, but a {:?} card responded", frequency, other),
+                &output_parser::wrong_frequency_hint(&other),
+                RecoveryAction::Retry,
+            );
+        }
+    }
+
+    report_scan_error(
+        machine,
+        "No card detected",
+        "No card found. Place the card on the reader and try again.",
+        RecoveryAction::Retry,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// HF read consistency
+// ---------------------------------------------------------------------------
+
+/// Decoded key carrying a warning about how the card read (not cached with
+/// the card, since it describes this read only).
+pub const READ_WARNING_KEY: &str = "read_warning";
+
+const UNSTABLE_HF_READ_HINT: &str = "Keep phones and other readers away from the card, hold it \
+     still on the reader and scan again before cloning.";
+
+/// Run `hf search` again and compare it with the first read. A phone or
+/// another reader energising the card, or the card moving, corrupts the
+/// anticollision; such a read rarely repeats exactly, so acting on it alone
+/// would clone the wrong UID. `Some(message)` when the reads disagree; a
+/// failed second search leaves the first read standing.
+async fn confirm_hf_read(
+    app: &AppHandle,
+    port: &str,
+    card_type: &CardType,
+    card_data: &crate::cards::types::CardData,
+) -> Option<String> {
+    let output = connection::run_command(app, port, command_builder::build_hf_search())
+        .await
+        .ok()?;
+    unstable_hf_read(card_type, card_data, &output)
+}
+
+fn unstable_hf_read(
+    card_type: &CardType,
+    card_data: &crate::cards::types::CardData,
+    second_output: &str,
+) -> Option<String> {
+    let second = output_parser::parse_hf_search(second_output);
+    let differences = output_parser::hf_read_differences(card_type, card_data, second.as_ref());
+    if differences.is_empty() {
+        return None;
+    }
+    log::warn!("Unstable HF read of {}: {} changed", card_data.uid, differences.join(", "));
+    Some(format!(
+        "HF read unstable: {} changed between two reads. {}",
+        differences.join(", "),
+        UNSTABLE_HF_READ_HINT
+    ))
+}
+
+/// Attach `warning` to the identified card under `READ_WARNING_KEY`.
+fn add_read_warning(card_data: &mut crate::cards::types::CardData, warning: Option<String>) {
+    if let Some(warning) = warning {
+        card_data.decoded.insert(READ_WARNING_KEY.to_string(), warning);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Combined scan — every tag on both frequencies
+// ---------------------------------------------------------------------------
+
+/// One tag found by `scan_all`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedTag {
+    pub frequency: Frequency,
+    pub card_type: CardType,
+    pub card_data: crate::cards::types::CardData,
+    pub cloneable: bool,
+}
+
+/// Search both frequencies and return every tag found, LF first. Dual-tech
+/// cards (an EM4100 fob with a Classic inlay) come back as two entries so the
+/// user can pick which technology to clone. Does not touch the wizard FSM.
+///
+/// Errors only when both searches fail; an empty list means no card answered.
+#[tauri::command]
+pub async fn scan_all(app: AppHandle, port: String) -> Result<Vec<DetectedTag>, AppError> {
+    connection::check_port(&port)?;
+
+    let lf_result = run_lf_search(&app, &port, false).await;
+    let hf_result =
+        connection::run_command(&app, &port, command_builder::build_hf_search()).await;
+    if let (Err(e), Err(_)) = (&lf_result, &hf_result) {
+        return Err(AppError::CommandFailed(e.to_string()));
+    }
+
+    let parsed = output_parser::parse_all_frequencies(
+        lf_result.as_deref().ok(),
+        hf_result.as_deref().ok(),
+    );
+    let mut tags = Vec::with_capacity(parsed.len());
+    for (card_type, mut card_data) in parsed {
+        match card_type.frequency() {
+            Frequency::LF => {
+                let search = lf_result.as_deref().unwrap_or_default();
+                enrich_lf_data(&app, &port, search, &mut card_data).await
+            }
+            Frequency::HF => enrich_hf_data(&app, &port, &card_type, &mut card_data).await,
+        }
+        tags.push(DetectedTag {
+            frequency: card_type.frequency(),
+            cloneable: card_type.is_cloneable(),
+            card_type,
+            card_data,
+        });
+    }
+    Ok(tags)
+}
+
+// ---------------------------------------------------------------------------
+// Continuous LF read — inventory badges in the field
+// ---------------------------------------------------------------------------
+
+/// Longest a watch runs before PM3 is stopped.
+const LF_WATCH_TIMEOUT_SECS: u64 = 3600;
+
+/// One `lf search` pass of an all-protocol watch.
+const LF_WATCH_SEARCH_TIMEOUT_SECS: u64 = 30;
+
+/// Read LF badges continuously until cancelled with `cancel_hf_operation`,
+/// emitting `card-read` for each badge presented: `lf hid watch` for HID
+/// Prox, or `lf search` run over and over with `all_protocols` (the client
+/// has no continuous search). Returns every badge read. Does not touch the
+/// wizard FSM.
+#[tauri::command]
+pub async fn watch_lf(
+    app: AppHandle,
+    port: String,
+    all_protocols: Option<bool>,
+    hf_state: State<'_, HfOperationState>,
+) -> Result<Vec<DetectedTag>, AppError> {
+    connection::check_port(&port)?;
+
+    let mut reads = Vec::new();
+    let mut report = |card_type: CardType, card_data| {
+        let tag = DetectedTag {
+            frequency: card_type.frequency(),
+            cloneable: card_type.is_cloneable(),
+            card_type,
+            card_data,
+        };
+        let _ = app.emit(
+            "card-read",
+            serde_json::json!({
+                "tag": tag,
+                "count": reads.len() + 1,
+                "operation_id": operation::current_operation_id(),
+            }),
+        );
+        reads.push(tag);
+    };
+
+    let result = if all_protocols.unwrap_or(false) {
+        let deadline = Instant::now() + Duration::from_secs(LF_WATCH_TIMEOUT_SECS);
+        let (app, port, hf_state) = (&app, port.as_str(), &*hf_state);
+        let search = move || {
+            connection::run_command_streaming(
+                app,
+                port,
+                command_builder::build_lf_search(),
+                LF_WATCH_SEARCH_TIMEOUT_SECS,
+                hf_state,
+                |_| {},
+            )
+        };
+        watch_by_search(search, deadline, &mut report).await
+    } else {
+        let mut parser = LfWatchParser::new();
+        connection::run_command_streaming(
+            &app,
+            &port,
+            command_builder::build_lf_hid_watch(),
+            LF_WATCH_TIMEOUT_SECS,
+            &hf_state,
+            |line| {
+                if let Some((card_type, card_data)) = parser.feed(line, Instant::now()) {
+                    report(card_type, card_data);
+                }
+            },
+        )
+        .await
+        .map(|_| ())
+    };
+
+    // Stopping the watch is how it normally ends
+    match result {
+        Ok(()) => Ok(reads),
+        Err(e) if connection::is_cancelled(&e) => Ok(reads),
+        Err(e) => Err(e),
+    }
+}
+
+/// Run `search` (one `lf search` pass) until it's cancelled or `deadline`
+/// passes, calling `report` for each badge presented. A pass that finds
+/// nothing means the antenna is empty, so the next badge counts even if it
+/// is the last one again.
+async fn watch_by_search<S, Fut, F>(
+    mut search: S,
+    deadline: Instant,
+    mut report: F,
+) -> Result<(), AppError>
+where
+    S: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+    F: FnMut(CardType, crate::cards::types::CardData),
+{
+    let mut parser = LfWatchParser::new();
+    while Instant::now() < deadline {
+        let output = search().await?;
+        if let Some((card_type, card_data)) =
+            parser.observe(output_parser::parse_lf_search(&output))
+        {
+            report(card_type, card_data);
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Deep LF search — same command retried, for intermittent cards
+// ---------------------------------------------------------------------------
+
+/// `lf search` attempts in deep mode.
+const DEEP_SCAN_ATTEMPTS: u32 = 5;
+
+/// Pause between deep-mode attempts so the user can re-seat the card.
+const DEEP_SCAN_DELAY: Duration = Duration::from_millis(400);
+
+/// `lf search`, once or (deep) repeated until something parses.
+async fn run_lf_search(app: &AppHandle, port: &str, deep: bool) -> Result<String, AppError> {
+    let cmd = command_builder::build_lf_search();
+    if !deep {
+        return connection::run_command(app, port, cmd).await;
+    }
+    retry_search(
+        DEEP_SCAN_ATTEMPTS,
+        DEEP_SCAN_DELAY,
+        |attempt| {
+            let _ = app.emit(
+                "scan-progress",
+                serde_json::json!({
+                    "attempt": attempt,
+                    "total": DEEP_SCAN_ATTEMPTS,
+                    "operation_id": operation::current_operation_id(),
+                }),
+            );
+        },
+        || connection::run_command(app, port, cmd),
+        |output| output_parser::parse_lf_search(output).is_some(),
+    )
+    .await
+}
+
+/// Run `search` up to `attempts` times, returning the first output `found`
+/// accepts, or the last output if none did. A command error ends the loop —
+/// retrying won't fix a missing device.
+async fn retry_search<S, Fut>(
+    attempts: u32,
+    delay: Duration,
+    mut on_attempt: impl FnMut(u32),
+    mut search: S,
+    found: impl Fn(&str) -> bool,
+) -> Result<String, AppError>
+where
+    S: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let mut last = String::new();
+    for attempt in 1..=attempts.max(1) {
+        if attempt > 1 {
+            tokio::time::sleep(delay).await;
+        }
+        on_attempt(attempt);
+        last = search().await?;
+        if found(&last) {
+            break;
+        }
+    }
+    Ok(last)
+}
+
+fn report_scan_error(
+    machine: &Mutex<WizardMachine>,
+    message: &str,
+    user_message: &str,
+    recovery_action: RecoveryAction,
+) -> Result<WizardState, AppError> {
+    let mut m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    m.transition(WizardAction::ReportError {
+        message: message.to_string(),
+        user_message: user_message.to_string(),
+        recoverable: true,
+        recovery_action: Some(recovery_action),
+    })?;
+    Ok(m.current.clone())
+}
+
+/// Check whether an LF source is itself a T5577 (an earlier clone, or a
+/// vendor fob built on one). If so, and it isn't password-protected, keep its
+/// page-0 blocks so a T5577 write can copy them verbatim. Only asks the
+/// device when `search_output` (from `lf search`) reported a T55xx chipset.
+async fn enrich_lf_data(
+    app: &AppHandle,
+    port: &str,
+    search_output: &str,
+    card_data: &mut crate::cards::types::CardData,
+) {
+    if !output_parser::lf_search_found_t55xx(search_output) {
+        return;
+    }
+    let Ok(detect) = connection::run_command(app, port, command_builder::build_t5577_detect()).await
+    else {
+        return;
+    };
+    let status = output_parser::parse_t5577_detect(&detect);
+    if !status.detected || status.password_set {
+        return;
+    }
+    let Ok(dump) = connection::run_command(app, port, command_builder::build_t55xx_dump()).await
+    else {
+        return;
+    };
+    let Some(blocks) = output_parser::parse_t55xx_dump(&dump) else {
+        return;
+    };
+    // A read-only chip streaming its ID can fool detect: only trust a dump
+    // whose config block matches detect's and describes a copyable layout
+    let config_matches = status
+        .block0
+        .is_some_and(|b0| b0.eq_ignore_ascii_case(&blocks[0]));
+    if !config_matches || command_builder::build_t55xx_clone_raw(&blocks).is_err() {
+        return;
+    }
+    log::debug!("LF source is a T5577 (config {}), keeping its blocks", blocks[0]);
+    card_data
+        .decoded
+        .insert(command_builder::T55XX_BLOCKS_KEY.to_string(), blocks.join(","));
+}
+
 /// Enrich HF card data with protocol-specific info commands.
 /// For MIFARE Classic: `hf 14a info` (PRNG) + `hf mf info` (magic detection).
 /// For UL/NTAG: `hf mfu info` for subtype detection.
+/// For iCLASS: dump + decrypt with the legacy keys to decode the FC/CN credential.
 async fn enrich_hf_data(
     app: &AppHandle,
     port: &str,
@@ -105,7 +571,7 @@ async fn enrich_hf_data(
     card_data: &mut crate::cards::types::CardData,
 ) {
     match card_type {
-        CardType::MifareClassic1K | CardType::MifareClassic4K => {
+        CardType::MifareMini | CardType::MifareClassic1K | CardType::MifareClassic4K => {
             // Get PRNG info if not already present
             if !card_data.decoded.contains_key("prng") {
                 if let Ok(info_output) =
@@ -172,19 +638,64 @@ async fn enrich_hf_data(
                 }
             }
         }
+        CardType::IClass => {
+            if let Some((fc, cn)) = read_iclass_credential(app, port).await {
+                card_data
+                    .decoded
+                    .insert("format".to_string(), "H10301".to_string());
+                card_data
+                    .decoded
+                    .insert("facility_code".to_string(), fc.to_string());
+                card_data
+                    .decoded
+                    .insert("card_number".to_string(), cn.to_string());
+            }
+        }
         _ => {}
     }
 }
 
+/// Dump a legacy iCLASS card, decrypt the dump with the transport key and
+/// decode the block 7 credential. Best-effort: any failure (non-legacy keys,
+/// SE/SEOS, unsupported Wiegand format) yields `None` and the scan continues
+/// with the CSN only.
+async fn read_iclass_credential(app: &AppHandle, port: &str) -> Option<(u32, u32)> {
+    let dump_output =
+        connection::run_command(app, port, command_builder::build_iclass_dump())
+            .await
+            .ok()?;
+    let dump_path = output_parser::extract_dump_file_path(&dump_output)?;
+
+    let decrypt_output = connection::run_command(
+        app,
+        port,
+        &command_builder::build_iclass_decrypt(&dump_path),
+    )
+    .await
+    .ok()?;
+    let decrypted_path = output_parser::extract_dump_file_path(&decrypt_output)?;
+
+    let bytes = std::fs::read(&decrypted_path).ok()?;
+    output_parser::parse_iclass_pacs(&bytes)
+}
+
 /// Common finish: transition FSM to CardFound with detected card info.
 fn finish_scan(
     machine: &Mutex<WizardMachine>,
+    db: &Database,
     card_type: CardType,
     card_data: crate::cards::types::CardData,
 ) -> Result<WizardState, AppError> {
     let frequency = card_type.frequency();
     let cloneable = card_type.is_cloneable();
     let recommended_blank = card_type.recommended_blank();
+    // History is a convenience — a DB problem mustn't fail the scan
+    let previous_clones = db
+        .find_clones_by_source_uid(&card_data.uid)
+        .unwrap_or_else(|e| {
+            log::warn!("History lookup failed for {}: {}", card_data.uid, e);
+            Vec::new()
+        });
 
     let mut m = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
@@ -195,6 +706,140 @@ fn finish_scan(
         card_data,
         cloneable,
         recommended_blank,
+        previous_clones,
     })?;
     Ok(m.current.clone())
 }
+
+// ---------------------------------------------------------------------------
+// Card type metadata
+// ---------------------------------------------------------------------------
+
+/// Display metadata for every card type, for labels and icons in the UI.
+#[tauri::command]
+pub fn card_metadata() -> Vec<CardMetadata> {
+    CardType::ALL.iter().map(CardType::metadata).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTHING: &str = "[-] No known 125/134 kHz tags found!";
+    const EM4100: &str = "[+] EM 410x ID 0F0368568B\n[+] Valid EM410x ID found!";
+
+    const CLASSIC: &str = "[+] UID: 01 02 03 04\n[+] ATQA: 00 04\n[+] SAK: 08 [2]\n\
+                           [+] MIFARE Classic 1K card";
+
+    #[test]
+    fn differing_hf_reads_are_unstable() {
+        let (card_type, first) = output_parser::parse_hf_search(CLASSIC).unwrap();
+        assert_eq!(unstable_hf_read(&card_type, &first, CLASSIC), None);
+
+        // Corrupted anticollision: a bit flipped in the UID and SAK
+        let corrupted = CLASSIC.replace("03 04", "03 84").replace("SAK: 08", "SAK: 88");
+        let warning = unstable_hf_read(&card_type, &first, &corrupted).unwrap();
+        assert!(warning.contains("UID, SAK"), "{}", warning);
+
+        // The card was pulled away before the second read
+        let gone = "[-] No known/supported 13.56 MHz tags found";
+        assert!(unstable_hf_read(&card_type, &first, gone).unwrap().contains("card changed"));
+
+        // Random IDs differ on every read; only the rest is compared
+        let random = CLASSIC.replace("01 02 03 04", "08 5A 11 C3");
+        let (card_type, first) = output_parser::parse_hf_search(&random).unwrap();
+        let reread = random.replace("08 5A 11 C3", "08 E2 7F 04");
+        assert_eq!(unstable_hf_read(&card_type, &first, &reread), None);
+        let warning = unstable_hf_read(&card_type, &first, &reread.replace("SAK: 08", "SAK: 88"));
+        assert!(warning.unwrap().starts_with("HF read unstable: SAK changed"));
+
+        let mut card_data = first.clone();
+        add_read_warning(&mut card_data, Some("HF read unstable".into()));
+        assert_eq!(card_data.decoded[READ_WARNING_KEY], "HF read unstable");
+    }
+
+    #[tokio::test]
+    async fn search_watch_counts_each_presentation() {
+        let other = EM4100.replace("0F0368568B", "0102030405");
+        let mut outputs = vec![
+            Ok(EM4100.to_string()),
+            Ok(EM4100.to_string()),
+            Ok(NOTHING.to_string()),
+            Ok(EM4100.to_string()),
+            Ok(other),
+            Err(AppError::Cancelled),
+        ]
+        .into_iter();
+        let mut uids = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let result = watch_by_search(
+            || {
+                let output = outputs.next().unwrap();
+                async move { output }
+            },
+            deadline,
+            |_, card_data| uids.push(card_data.uid),
+        )
+        .await;
+
+        // Held over two passes: once; back after an empty pass: again
+        assert!(connection::is_cancelled(&result.unwrap_err()));
+        assert_eq!(uids, vec!["0F0368568B", "0F0368568B", "0102030405"]);
+    }
+
+    #[tokio::test]
+    async fn deep_search_succeeds_on_later_attempt() {
+        let mut outputs = vec![NOTHING, NOTHING, EM4100, NOTHING].into_iter();
+        let mut seen = Vec::new();
+        let output = retry_search(
+            5,
+            Duration::ZERO,
+            |attempt| seen.push(attempt),
+            || {
+                let next = outputs.next().unwrap().to_string();
+                async move { Ok(next) }
+            },
+            |o| output_parser::parse_lf_search(o).is_some(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert_eq!(output_parser::parse_lf_search(&output).unwrap().1.uid, "0F0368568B");
+    }
+
+    #[tokio::test]
+    async fn deep_search_gives_up_after_attempts() {
+        let mut calls = 0;
+        let output = retry_search(
+            3,
+            Duration::ZERO,
+            |_| {},
+            || {
+                calls += 1;
+                async { Ok(NOTHING.to_string()) }
+            },
+            |o| output_parser::parse_lf_search(o).is_some(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(calls, 3);
+        assert_eq!(output, NOTHING);
+
+        // Device errors aren't retried
+        let mut calls = 0;
+        let err = retry_search(
+            3,
+            Duration::ZERO,
+            |_| {},
+            || {
+                calls += 1;
+                async { Err(AppError::CommandFailed("port gone".into())) }
+            },
+            |_| true,
+        )
+        .await;
+        assert!(err.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use tauri::AppHandle;
+
+use crate::cards::types::CardType;
+use crate::error::AppError;
+use crate::pm3::benchmark::{self, BenchmarkReport};
+use crate::pm3::operation::{self, OperationResult};
+use crate::pm3::parser_corpus::{self, SelfCheckResult};
+use crate::pm3::{connection, scan_cache};
+
+/// Run the bundled corpus of real PM3 output samples through the LF/HF/autopwn
+/// parsers and report pass/fail per sample. Needs no hardware — a failure here
+/// means the parser regressed, not the reader or the card.
+#[tauri::command]
+pub fn run_parser_selfcheck() -> Result<Vec<SelfCheckResult>, AppError> {
+    let results = parser_corpus::run_selfcheck();
+    let failed = results.iter().filter(|r| !r.passed).count();
+    if failed > 0 {
+        log::warn!("Parser self-check: {}/{} samples failed", failed, results.len());
+    }
+    Ok(results)
+}
+
+/// Advanced: time one clone -> verify cycle of the identified LF card
+/// (`card_type`/`uid`/`decoded`, as for `write_clone_with_data`) onto the
+/// blank on the reader. Writes the blank, so it's never run implicitly. Phase
+/// timings are in milliseconds.
+#[tauri::command]
+pub async fn benchmark_clone(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    uid: String,
+    decoded: HashMap<String, String>,
+) -> Result<OperationResult<BenchmarkReport>, AppError> {
+    scan_cache::invalidate(&app);
+    operation::run_operation(benchmark::run_benchmark(
+        |cmd| {
+            let app = app.clone();
+            let port = port.clone();
+            async move { connection::run_command(&app, &port, &cmd).await }
+        },
+        card_type,
+        uid,
+        decoded,
+    ))
+    .await
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+use crate::cards::types::CardType;
+use crate::error::AppError;
+use crate::pm3::operation::{self, OperationResult};
+use crate::pm3::{command_builder, connection, output_parser, scan_cache};
+
+// ---------------------------------------------------------------------------
+// Managed state
+// ---------------------------------------------------------------------------
+
+/// Pause/resume handle for a running `clone_sequence`. Stored via `app.manage()`.
+/// Between cards the sequence waits on `resume` so the user can swap blanks.
+/// Only `notify_waiters` is used, so a stray resume never skips a later pause.
+pub struct SequenceState {
+    resume: Notify,
+    cancelled: AtomicBool,
+    running: AtomicBool,
+}
+
+impl SequenceState {
+    pub fn new() -> Self {
+        Self {
+            resume: Notify::new(),
+            cancelled: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DTOs — serialized to frontend
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceResult {
+    /// Card numbers written, in order.
+    pub written: Vec<u64>,
+    pub total: u32,
+    pub cancelled: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Clone `count` cards with consecutive card numbers (same FC) for
+/// enrollment testing. Supports FC/CN LF types (HID, AWID, ioProx, Indala).
+///
+/// Emits `sequence-progress` with `status`:
+/// - `"swap-blank"` — waiting for the next blank; call `continue_sequence`
+/// - `"writing"` — clone command sent for `cardNumber`
+/// - `"written"` — `cardNumber` written and read back
+///
+/// Each card is read back with `lf search` and checked like a single clone
+/// (`verify_match_detailed`). Stops at the first card that doesn't read back;
+/// `cancel_sequence` stops before the next PM3 command.
+#[tauri::command]
+pub async fn clone_sequence(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    decoded: HashMap<String, String>,
+    count: u32,
+    state: State<'_, SequenceState>,
+) -> Result<OperationResult<SequenceResult>, AppError> {
+    let sequence = command_builder::build_clone_sequence(&card_type, &decoded, count)
+        .map_err(|e| AppError::CommandFailed(format!("Cannot clone sequence: {}", e)))?;
+
+    if state.running.swap(true, Ordering::SeqCst) {
+        return Err(AppError::CommandFailed("A clone sequence is already running".into()));
+    }
+    state.cancelled.store(false, Ordering::SeqCst);
+    scan_cache::invalidate(&app);
+
+    let run = run_sequence(&app, &port, &card_type, &decoded, &sequence, &state);
+    let result = operation::run_operation(run).await;
+    state.running.store(false, Ordering::SeqCst);
+    result
+}
+
+/// Resume a sequence paused on `"swap-blank"`.
+#[tauri::command]
+pub fn continue_sequence(state: State<'_, SequenceState>) -> Result<(), AppError> {
+    state.resume.notify_waiters();
+    Ok(())
+}
+
+/// Stop a running sequence before its next PM3 command.
+#[tauri::command]
+pub fn cancel_sequence(state: State<'_, SequenceState>) -> Result<(), AppError> {
+    state.cancelled.store(true, Ordering::SeqCst);
+    state.resume.notify_waiters();
+    Ok(())
+}
+
+async fn run_sequence(
+    app: &AppHandle,
+    port: &str,
+    card_type: &CardType,
+    decoded: &HashMap<String, String>,
+    sequence: &[(u64, String)],
+    state: &SequenceState,
+) -> Result<SequenceResult, AppError> {
+    let total = sequence.len() as u32;
+    let mut written = Vec::with_capacity(sequence.len());
+    let cancelled = || state.cancelled.load(Ordering::SeqCst);
+
+    for (index, (card_number, cmd)) in sequence.iter().enumerate() {
+        if index > 0 {
+            // Register before emitting so a fast `continue_sequence` isn't lost
+            let resumed = state.resume.notified();
+            tokio::pin!(resumed);
+            resumed.as_mut().enable();
+            emit_progress(app, "swap-blank", index, total, *card_number);
+            resumed.await;
+        }
+        if cancelled() {
+            return Ok(SequenceResult { written, total, cancelled: true });
+        }
+
+        emit_progress(app, "writing", index, total, *card_number);
+        connection::run_command(app, port, cmd).await?;
+        if cancelled() {
+            return Ok(SequenceResult { written, total, cancelled: true });
+        }
+        let readback =
+            connection::run_command(app, port, command_builder::build_lf_search()).await?;
+        let expected = command_builder::sequence_card_fields(decoded, *card_number);
+        let (matched, _) = output_parser::verify_match_detailed(card_type, &expected, &readback);
+        if !matched {
+            return Err(AppError::CommandFailed(format!(
+                "Card number {} didn't read back after the write ({} of {} written)",
+                card_number,
+                written.len(),
+                total
+            )));
+        }
+        written.push(*card_number);
+        emit_progress(app, "written", index, total, *card_number);
+    }
+
+    Ok(SequenceResult { written, total, cancelled: false })
+}
+
+fn emit_progress(app: &AppHandle, status: &str, index: usize, total: u32, card_number: u64) {
+    let _ = app.emit(
+        "sequence-progress",
+        serde_json::json!({
+            "status": status,
+            "index": index,
+            "total": total,
+            "cardNumber": card_number,
+            "operation_id": operation::current_operation_id(),
+        }),
+    );
+}
@@ -0,0 +1,11 @@
+use crate::cards::wiegand::{self, WiegandDecode};
+use crate::error::AppError;
+
+/// Decode a raw Wiegand hex value (e.g. from reader diagnostics) without
+/// hardware. `format` restricts decoding to one format name or bit length;
+/// otherwise every known format is tried. Returns all plausible
+/// interpretations, best first.
+#[tauri::command]
+pub fn decode_raw(hex: String, format: Option<String>) -> Result<Vec<WiegandDecode>, AppError> {
+    wiegand::decode_hex(&hex, format.as_deref()).map_err(AppError::CommandFailed)
+}
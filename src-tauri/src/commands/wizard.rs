@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use crate::cards::types::{BlankType, CardSummary, CardType, Frequency};
+use crate::commands::hf_clone;
+use crate::db::Database;
 use crate::error::AppError;
-use crate::state::{WizardAction, WizardMachine, WizardState};
+use crate::pm3::connection::{self, HfOperationState};
+use crate::state::{CheckpointCard, WizardAction, WizardMachine, WizardState};
 
 #[tauri::command]
 pub fn get_wizard_state(
@@ -37,6 +41,10 @@ pub enum UserAction {
         source: CardSummary,
         target: CardSummary,
     },
+    StartFieldTest,
+    FieldTestResult {
+        works: bool,
+    },
     BackToScan,
     SoftReset,
     Disconnect,
@@ -66,6 +74,8 @@ impl UserAction {
             UserAction::MarkComplete { source, target } => {
                 WizardAction::MarkComplete { source, target }
             }
+            UserAction::StartFieldTest => WizardAction::StartFieldTest,
+            UserAction::FieldTestResult { works } => WizardAction::FieldTestResult { works },
             UserAction::BackToScan => WizardAction::BackToScan,
             UserAction::SoftReset => WizardAction::SoftReset,
             UserAction::Disconnect => WizardAction::Disconnect,
@@ -95,10 +105,123 @@ impl UserAction {
 pub fn wizard_action(
     action: UserAction,
     machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
 ) -> Result<WizardState, AppError> {
     let mut machine = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
     })?;
+    let ends_job = matches!(action, UserAction::Reset | UserAction::SoftReset);
     machine.transition(action.into_wizard_action())?;
+    if ends_job {
+        hf_state.end_session();
+    }
     Ok(machine.current.clone())
 }
+
+// ---------------------------------------------------------------------------
+// Session checkpoints
+// ---------------------------------------------------------------------------
+
+/// How often the session in progress is checkpointed to the database.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Checkpoint the wizard session every `CHECKPOINT_INTERVAL` for the life of
+/// the app, so `resume_session` can pick it up after a crash or close.
+/// Called once from `lib.rs` setup.
+pub fn spawn_checkpointer(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut saved: Option<String> = None;
+        loop {
+            tokio::time::sleep(CHECKPOINT_INTERVAL).await;
+            checkpoint_session(&app, &mut saved);
+        }
+    });
+}
+
+/// Save the session if it changed since `saved`. A session checkpointed
+/// earlier that has since finished, failed or been reset is cleared; one
+/// left by a previous run is kept until it's resumed or replaced.
+fn checkpoint_session(app: &AppHandle, saved: &mut Option<String>) {
+    let mut checkpoint = match app.state::<Mutex<WizardMachine>>().lock() {
+        Ok(machine) => machine.checkpoint(),
+        Err(_) => return,
+    };
+    if let Some(checkpoint) = &mut checkpoint {
+        hf_clone::checkpoint_hf_context(&app.state::<HfOperationState>(), checkpoint);
+    }
+    let db = app.state::<Database>();
+    match checkpoint {
+        Some(checkpoint) => {
+            let json = serde_json::to_string(&checkpoint).unwrap_or_default();
+            if saved.as_deref() == Some(json.as_str()) {
+                return;
+            }
+            match db.set_wizard_checkpoint(&checkpoint) {
+                Ok(()) => *saved = Some(json),
+                Err(e) => log::warn!("Could not checkpoint the session: {}", e),
+            }
+        }
+        None if saved.is_some() => match db.clear_wizard_checkpoint() {
+            Ok(()) => *saved = None,
+            Err(e) => log::warn!("Could not clear the session checkpoint: {}", e),
+        },
+        None => {}
+    }
+}
+
+/// A restored session: the state to continue from, plus the source card the
+/// write commands need (`WaitingForBlank` and later don't carry it).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumedSession {
+    pub state: WizardState,
+    pub card: Option<CheckpointCard>,
+}
+
+/// Restore the session checkpointed before the app last closed. The device
+/// is re-detected (its port may have changed) and an HF dump the session
+/// still needs must exist. `None` when there's nothing to resume.
+#[tauri::command]
+pub async fn resume_session(
+    app: AppHandle,
+    machine: State<'_, Mutex<WizardMachine>>,
+    hf_state: State<'_, HfOperationState>,
+    db: State<'_, Database>,
+) -> Result<Option<ResumedSession>, AppError> {
+    let Some(checkpoint) = db.get_wizard_checkpoint()? else {
+        return Ok(None);
+    };
+    {
+        let m = machine.lock().map_err(|e| {
+            AppError::CommandFailed(format!("State lock poisoned: {}", e))
+        })?;
+        if !matches!(m.current, WizardState::Idle | WizardState::DeviceConnected { .. }) {
+            return Err(AppError::InvalidTransition(
+                "Finish or reset the current session before resuming another".into(),
+            ));
+        }
+    }
+
+    if checkpoint.needs_dump() {
+        let dump = checkpoint.dump_path.as_deref().ok_or("no dump path recorded".to_string());
+        if let Err(detail) = dump.and_then(hf_clone::check_dump_file) {
+            log::warn!("Cannot resume session: {}", detail);
+            db.clear_wizard_checkpoint()?;
+            return Err(AppError::CommandFailed(
+                "The card dump for this session is gone. Scan the card again.".into(),
+            ));
+        }
+    }
+
+    let (port, model, firmware, fork) = connection::detect_device(&app).await?;
+    let mut m = machine.lock().map_err(|e| {
+        AppError::CommandFailed(format!("State lock poisoned: {}", e))
+    })?;
+    hf_clone::restore_hf_context(&hf_state, &checkpoint);
+    m.restore(checkpoint, port, model, firmware)?;
+    m.fork = Some(fork);
+    Ok(Some(ResumedSession {
+        state: m.current.clone(),
+        card: m.source_card().cloned(),
+    }))
+}
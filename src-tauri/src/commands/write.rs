@@ -1,19 +1,561 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::cards::types::{BlankType, CardType, RecoveryAction};
+use crate::db::Database;
 use crate::error::AppError;
-use crate::pm3::{command_builder, connection, output_parser};
+use crate::pm3::operation::{self, OperationResult};
+use crate::pm3::diagnosis::{self, FailureSignals, LikelyCause};
+use crate::pm3::dictionaries::{DictionaryKind, SelectedDictionaries};
+use crate::pm3::connection::ChainedCommand;
+use crate::pm3::output_parser::T5577Unlock;
+use crate::pm3::{blank_compat, clone_summary, command_builder, connection, output_parser, scan_cache};
 use crate::state::{WizardAction, WizardMachine, WizardState};
 
-/// Total progress steps for the T5577 write flow:
-/// detect -> check password -> wipe -> verify wipe -> clone -> done
-const T5577_TOTAL_STEPS: u16 = 6;
+/// Steps of an LF write flow, in order. On a T5577, wipe and verify-wipe
+/// only run for password-protected blanks; field assist only runs with
+/// `lf_field_assist`. An EM4305 has no password check and is always wiped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LfWriteStep {
+    Detect,
+    CheckPassword,
+    Wipe,
+    VerifyWipe,
+    /// The first clone write, sent in one PM3 run after `lf tune` and the
+    /// settle pause (see `lf_chain`).
+    FieldAssist,
+    Clone,
+    Done,
+}
+
+impl LfWriteStep {
+    fn progress(self) -> f32 {
+        match self {
+            LfWriteStep::Detect => 0.1,
+            LfWriteStep::CheckPassword => 0.2,
+            LfWriteStep::Wipe => 0.35,
+            LfWriteStep::VerifyWipe => 0.5,
+            LfWriteStep::FieldAssist => 0.6,
+            LfWriteStep::Clone => 0.7,
+            LfWriteStep::Done => 1.0,
+        }
+    }
+
+    /// Progress and step index of the EM4305 flow (see `EM4305_TOTAL_STEPS`).
+    fn em4305_progress(self) -> (f32, u16) {
+        match self {
+            LfWriteStep::Detect | LfWriteStep::CheckPassword => (0.1, 0),
+            LfWriteStep::Wipe => (0.3, 1),
+            LfWriteStep::VerifyWipe => (0.5, 2),
+            LfWriteStep::FieldAssist | LfWriteStep::Clone => (0.7, 3),
+            LfWriteStep::Done => (1.0, 4),
+        }
+    }
+
+    /// Label shown in the UI while the step runs.
+    fn name(self) -> &'static str {
+        match self {
+            LfWriteStep::Detect => "Detecting T5577...",
+            LfWriteStep::CheckPassword => "Checking password...",
+            LfWriteStep::Wipe => "Wiping blank...",
+            LfWriteStep::VerifyWipe => "Verifying wipe...",
+            LfWriteStep::FieldAssist => "Tuning field and writing clone...",
+            LfWriteStep::Clone => "Writing clone...",
+            LfWriteStep::Done => "Done",
+        }
+    }
+}
+
+/// Numbers the steps a T5577 write reports, 1 to N. N is how many steps
+/// the flow will report as far as it knows: all six (detect, check password,
+/// wipe, verify wipe, clone, done) until detect shows whether the blank gets
+/// wiped, and exact once the write plan is built.
+struct T5577Steps {
+    reported: u16,
+    total: u16,
+    last: Option<LfWriteStep>,
+}
+
+impl T5577Steps {
+    fn new() -> Self {
+        Self { reported: 0, total: 6, last: None }
+    }
+
+    fn expect_wipe(&mut self, wipe: bool) {
+        self.total = if wipe { 6 } else { 4 };
+    }
+
+    /// What has been reported, then the plan's steps, then done.
+    fn expect_plan(&mut self, plan: &LfWritePlan) {
+        let mut steps: Vec<LfWriteStep> = plan.steps.iter().map(|(step, _)| *step).collect();
+        steps.dedup();
+        self.total = self.reported + steps.len() as u16 + 1;
+    }
+
+    /// Number and total to report `step` with, or `None` when it repeats
+    /// the step just reported (a clone sent as several commands).
+    fn report(&mut self, step: LfWriteStep) -> Option<(u16, u16)> {
+        if self.last == Some(step) {
+            return None;
+        }
+        self.last = Some(step);
+        self.reported += 1;
+        Some((self.reported, self.total))
+    }
+}
 
 /// Total progress steps for the EM4305 write flow:
 /// detect -> wipe -> verify wipe -> clone -> done
 const EM4305_TOTAL_STEPS: u16 = 5;
 
+/// Pause after `lf tune` before an LF clone, so the field is stable when the
+/// write starts.
+const LF_FIELD_SETTLE_MS: u64 = 300;
+
+/// Write-flow preferences. Stored via `app.manage()` in `lib.rs`.
+pub struct WriteSettings {
+    auto_wipe_before_clone: AtomicBool,
+    lf_field_assist: AtomicBool,
+    always_dump_source: AtomicBool,
+    verify_in_place: AtomicBool,
+    /// `lf search` output captured in the same PM3 run as the last LF write
+    /// (`verify_in_place`), consumed by the next `verify_clone`.
+    in_place_readback: Mutex<Option<String>>,
+}
+
+impl WriteSettings {
+    pub fn new() -> Self {
+        Self {
+            auto_wipe_before_clone: AtomicBool::new(false),
+            lf_field_assist: AtomicBool::new(false),
+            always_dump_source: AtomicBool::new(false),
+            verify_in_place: AtomicBool::new(false),
+            in_place_readback: Mutex::new(None),
+        }
+    }
+
+    pub fn auto_wipe_before_clone(&self) -> bool {
+        self.auto_wipe_before_clone.load(Ordering::Relaxed)
+    }
+
+    pub fn lf_field_assist(&self) -> bool {
+        self.lf_field_assist.load(Ordering::Relaxed)
+    }
+
+    pub fn always_dump_source(&self) -> bool {
+        self.always_dump_source.load(Ordering::Relaxed)
+    }
+
+    pub fn set_always_dump_source(&self, enabled: bool) {
+        self.always_dump_source.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn verify_in_place(&self) -> bool {
+        self.verify_in_place.load(Ordering::Relaxed)
+    }
+
+    fn stash_readback(&self, readback: Option<String>) {
+        if let Ok(mut slot) = self.in_place_readback.lock() {
+            *slot = readback;
+        }
+    }
+
+    fn take_readback(&self) -> Option<String> {
+        self.in_place_readback.lock().ok()?.take()
+    }
+}
+
+fn lf_field_assist(app: &AppHandle) -> bool {
+    app.try_state::<WriteSettings>()
+        .is_some_and(|s| s.lf_field_assist())
+}
+
+/// Wipe every T5577 before cloning, not just password-protected ones, so no
+/// config from a previous clone (modulation, bit rate) survives. EM4305
+/// blanks are always wiped.
+#[tauri::command]
+pub fn set_auto_wipe_before_clone(
+    enabled: bool,
+    settings: State<'_, WriteSettings>,
+) -> Result<(), AppError> {
+    settings.auto_wipe_before_clone.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// For weak LF antennas (PM3 Easy): run `lf tune` and wait for the field to
+/// settle before each LF clone, and retry a failed readback once.
+#[tauri::command]
+pub fn set_lf_field_assist(
+    enabled: bool,
+    settings: State<'_, WriteSettings>,
+) -> Result<(), AppError> {
+    settings.lf_field_assist.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Write LF clones and read them back in one PM3 run, so the card can't
+/// shift between the write and the verification readback.
+#[tauri::command]
+pub fn set_verify_in_place(
+    enabled: bool,
+    settings: State<'_, WriteSettings>,
+) -> Result<(), AppError> {
+    settings.verify_in_place.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Before every HF write, archive the source card's dump as a bundle and a
+/// library entry, linked from the clone_log entry — a record of what was
+/// cloned that dump retention leaves alone. `clear_sensitive_state` deletes
+/// the bundles, since they hold the source's keys.
+#[tauri::command]
+pub fn set_always_dump_source(
+    enabled: bool,
+    settings: State<'_, WriteSettings>,
+) -> Result<(), AppError> {
+    settings.set_always_dump_source(enabled);
+    Ok(())
+}
+
+/// The PM3 run an LF command goes out as, with the index of `cmd` in it, or
+/// `None` when `cmd` runs alone. With `field_assist`, `lf tune` and the settle
+/// pause go first: the field only stays up within one client run. With
+/// `readback`, the `lf search` `verify_clone` would run follows.
+fn lf_chain(
+    cmd: &str,
+    field_assist: bool,
+    readback: bool,
+) -> Result<Option<(ChainedCommand, usize)>, AppError> {
+    let settle = command_builder::build_msleep(LF_FIELD_SETTLE_MS);
+    let mut parts = Vec::with_capacity(4);
+    if field_assist {
+        parts.extend([command_builder::build_lf_tune(), settle.as_str()]);
+    }
+    let index = parts.len();
+    parts.push(cmd);
+    if readback {
+        parts.push(command_builder::build_lf_search());
+    }
+    if parts.len() == 1 {
+        return Ok(None);
+    }
+    Ok(Some((ChainedCommand::new(&parts)?, index)))
+}
+
+/// What a plan entry sends: the clone write with field assist goes out as
+/// its `lf_chain`, everything else as is.
+fn plan_command(step: LfWriteStep, cmd: &str) -> Result<String, AppError> {
+    if step != LfWriteStep::FieldAssist {
+        return Ok(cmd.to_string());
+    }
+    Ok(match lf_chain(cmd, true, false)? {
+        Some((chain, _)) => chain.script(),
+        None => cmd.to_string(),
+    })
+}
+
+/// Run an LF clone command, after `lf tune` in the same PM3 run with
+/// `field_assist`. For the last write (`last`) with `verify_in_place` on,
+/// `lf search` runs in that invocation too and its output is kept for
+/// `verify_clone`. Returns the clone command's own output either way.
+async fn run_clone_step(
+    app: &AppHandle,
+    port: &str,
+    clone_cmd: &str,
+    field_assist: bool,
+    last: bool,
+) -> Result<String, AppError> {
+    let settings = app.try_state::<WriteSettings>();
+    if let (Some(settings), true) = (&settings, last) {
+        // A readback from an earlier clone must never verify this one
+        settings.stash_readback(None);
+    }
+    let readback = last && settings.as_ref().is_some_and(|s| s.verify_in_place());
+    let Some((chain, index)) = lf_chain(clone_cmd, field_assist, readback)? else {
+        return connection::run_command(app, port, clone_cmd).await;
+    };
+    let output = connection::run_chained(app, port, &chain).await?;
+    if let (Some(settings), true) = (&settings, readback) {
+        settings.stash_readback(chain.part_output(&output, index + 1));
+    }
+    Ok(chain.part_output(&output, index).unwrap_or(output))
+}
+
+/// How an LF clone is written, beyond what is written onto which blank.
+#[derive(Debug, Clone, Copy, Default)]
+struct LfWriteOptions<'a> {
+    /// Clone from the raw frame rather than FC/CN.
+    prefer_raw: bool,
+    /// The blank's current T5577 password, when it's locked.
+    password: Option<&'a str>,
+    /// Password to lock the finished clone with.
+    set_password: Option<&'a str>,
+    /// Wipe (and check) a T5577 even when it isn't locked.
+    auto_wipe: bool,
+    /// `lf tune` before the first clone write (see `lf_chain`).
+    field_assist: bool,
+}
+
+/// Everything an LF write sends once the blank is detected (and a T5577's
+/// password is known). The write flows run it and `preview_clone_commands`
+/// prints it, so a preview is exactly what a write sends.
+#[derive(Debug)]
+struct LfWritePlan {
+    /// Wipe, wipe check and clone writes, each with the step it reports as.
+    steps: Vec<(LfWriteStep, String)>,
+    /// Commands locking the clone with `set_password`; the last one is the
+    /// detect confirming the lock.
+    lock: Vec<String>,
+}
+
+/// The plan for writing `card_type` onto `blank`, or `None` when no clone
+/// command can be built for it.
+fn lf_write_plan(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    blank: &BlankType,
+    options: &LfWriteOptions,
+) -> Result<Option<LfWritePlan>, AppError> {
+    let steps = match blank {
+        BlankType::T5577 | BlankType::Q5 => {
+            let Some(clone_cmds) =
+                t55xx_clone_commands(card_type, uid, decoded, blank, options.prefer_raw)
+            else {
+                return Ok(None);
+            };
+            t5577_write_plan(
+                blank,
+                options.password,
+                options.auto_wipe,
+                options.field_assist,
+                &clone_cmds,
+            )?
+        }
+        BlankType::EM4305 => {
+            let Some(clone_cmd) = command_builder::build_clone_command_for_blank(
+                card_type, uid, decoded, blank, options.prefer_raw,
+            ) else {
+                return Ok(None);
+            };
+            let mut steps = vec![
+                (LfWriteStep::Wipe, command_builder::build_em4305_wipe().to_string()),
+                (LfWriteStep::VerifyWipe, command_builder::build_em4305_read_word(0)),
+            ];
+            steps.extend(clone_steps(options.field_assist, &[clone_cmd]));
+            steps
+        }
+        _ => return Ok(None),
+    };
+    let lock = match options.set_password {
+        Some(pw) => {
+            let mut cmds = t5577_lock_commands(card_type, blank, decoded, pw)
+                .map_err(AppError::CommandFailed)?;
+            cmds.push(
+                command_builder::build_t5577_detect_with_password(pw)
+                    .map_err(AppError::CommandFailed)?,
+            );
+            cmds
+        }
+        None => Vec::new(),
+    };
+    Ok(Some(LfWritePlan { steps, lock }))
+}
+
+/// Commands sent to a T5577 (or Q5) once its password (if any) is known, in
+/// order. Wipe + verify run for locked blanks, or for every blank with
+/// auto-wipe on. The clone itself is never sent with the old password: the
+/// wipe clears it.
+fn t5577_write_plan(
+    blank: &BlankType,
+    password: Option<&str>,
+    auto_wipe: bool,
+    field_assist: bool,
+    clone_cmds: &[String],
+) -> Result<Vec<(LfWriteStep, String)>, AppError> {
+    let mut plan = Vec::with_capacity(3 + clone_cmds.len());
+    if password.is_some() || auto_wipe {
+        let wipe_cmd = command_builder::build_wipe_command(blank, password)
+            .ok_or_else(|| AppError::CommandFailed("No wipe command for this blank type".into()))?;
+        plan.push((LfWriteStep::Wipe, wipe_cmd));
+        plan.push((LfWriteStep::VerifyWipe, command_builder::build_t5577_detect().to_string()));
+    }
+    plan.extend(clone_steps(field_assist, clone_cmds));
+    Ok(plan)
+}
+
+/// The clone writes of a plan. With `field_assist`, the first one is the
+/// `FieldAssist` step: `lf tune` and the settle pause go out with it.
+fn clone_steps(
+    field_assist: bool,
+    clone_cmds: &[String],
+) -> impl Iterator<Item = (LfWriteStep, String)> + '_ {
+    clone_cmds.iter().enumerate().map(move |(i, cmd)| {
+        let step = if field_assist && i == 0 {
+            LfWriteStep::FieldAssist
+        } else {
+            LfWriteStep::Clone
+        };
+        (step, cmd.clone())
+    })
+}
+
+/// Block writes copying a T5577-hosted source verbatim (see
+/// `scan::enrich_lf_data`), if the scan kept a usable dump of it.
+fn t55xx_raw_clone(decoded: &std::collections::HashMap<String, String>) -> Option<Vec<String>> {
+    let blocks = command_builder::t55xx_source_blocks(decoded)?;
+    command_builder::build_t55xx_clone_raw(&blocks)
+        .map_err(|e| log::warn!("Source T5577 blocks unusable, cloning by protocol: {}", e))
+        .ok()
+}
+
+/// Clone commands for a T55xx-family blank. A T5577-hosted source is copied
+/// block for block onto a T5577; a Q5's config block has another layout, so
+/// it always gets the protocol clone with `--q5`. `prefer_raw` picks the raw
+/// frame over FC/CN for the protocol clone.
+fn t55xx_clone_commands(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    blank: &BlankType,
+    prefer_raw: bool,
+) -> Option<Vec<String>> {
+    if *blank == BlankType::Q5 {
+        return command_builder::build_clone_command_for_blank(
+            card_type, uid, decoded, blank, prefer_raw,
+        )
+        .map(|cmd| vec![cmd]);
+    }
+    t55xx_raw_clone(decoded).or_else(|| {
+        command_builder::build_clone_command_with(card_type, uid, decoded, prefer_raw)
+            .map(|cmd| vec![cmd])
+    })
+}
+
+/// Commands locking a T5577 clone with `password` once it's written. Only
+/// EM4100 has a known config block to rewrite (or the source's own, for a
+/// verbatim copy of a T5577-hosted source).
+fn t5577_lock_commands(
+    card_type: &CardType,
+    blank: &BlankType,
+    decoded: &std::collections::HashMap<String, String>,
+    password: &str,
+) -> Result<Vec<String>, String> {
+    if *card_type != CardType::EM4100 || *blank != BlankType::T5577 {
+        return Err("A clone password can only be set on an EM4100 written to a T5577".into());
+    }
+    let config = t55xx_raw_clone(decoded)
+        .and_then(|_| command_builder::t55xx_source_blocks(decoded))
+        .and_then(|blocks| blocks.first().and_then(|c| u32::from_str_radix(c, 16).ok()))
+        .unwrap_or(command_builder::T55XX_EM4100_CONFIG);
+    command_builder::build_t55xx_set_password(config, password)
+}
+
+/// Commands `write_clone_with_data` would send to write `card_type` onto
+/// `blank`, after detection and the password check. Err says why no clone
+/// command can be built, in the same words the write flow would use.
+fn preview_clone_commands(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    blank: &BlankType,
+    options: &LfWriteOptions,
+) -> Result<Vec<String>, String> {
+    blank_compat::check_clone_target(card_type, blank)?;
+    if let Some(pw) = options.password {
+        command_builder::build_t5577_wipe_with_password(pw)
+            .map_err(|e| format!("Password validation failed: {}", e))?;
+    }
+    if let Some(pw) = options.set_password {
+        t5577_lock_commands(card_type, blank, decoded, pw)
+            .map_err(|e| format!("Password validation failed: {}", e))?;
+    }
+    if !matches!(blank, BlankType::T5577 | BlankType::Q5 | BlankType::EM4305) {
+        return Err(format!(
+            "{} is written from a dump, not a single clone command",
+            card_type.display_name()
+        ));
+    }
+
+    let raw_copy = match blank {
+        BlankType::T5577 => t55xx_raw_clone(decoded),
+        _ => None,
+    };
+    if raw_copy.is_none() {
+        command_builder::validate_clone_fields(card_type, uid, decoded)
+            .map_err(|e| format!("Cannot clone {}: {}", card_type.display_name(), e))?;
+    }
+
+    let plan = lf_write_plan(card_type, uid, decoded, blank, options)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No clone command for {}", card_type.display_name()))?;
+    let mut commands = plan
+        .steps
+        .iter()
+        .map(|(step, cmd)| plan_command(*step, cmd))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    commands.extend(plan.lock);
+    Ok(commands)
+}
+
+/// `preview_clone_command` result: the commands, or why there are none.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClonePreview {
+    pub blank_type: BlankType,
+    pub commands: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Show the exact PM3 commands a clone of saved card `card_id` onto
+/// `blank_type` (default: the card's recommended blank) would send, using the
+/// current write settings. `set_password` previews locking the clone. Never
+/// touches the device.
+#[tauri::command]
+pub fn preview_clone_command(
+    card_id: i64,
+    blank_type: Option<BlankType>,
+    prefer_raw: Option<bool>,
+    password: Option<String>,
+    set_password: Option<String>,
+    db: State<'_, Database>,
+    settings: State<'_, WriteSettings>,
+) -> Result<ClonePreview, AppError> {
+    let card = db
+        .get_saved_card(card_id)?
+        .ok_or_else(|| AppError::CommandFailed(format!("Saved card {} not found", card_id)))?;
+    let card_type: CardType =
+        serde_json::from_value(serde_json::Value::String(card.card_type.clone())).map_err(|_| {
+            AppError::CommandFailed(format!("Unknown card type '{}'", card.card_type))
+        })?;
+    let decoded: std::collections::HashMap<String, String> =
+        serde_json::from_str(&card.decoded).unwrap_or_default();
+    let blank = blank_type.unwrap_or_else(|| card_type.recommended_blank());
+
+    let options = LfWriteOptions {
+        prefer_raw: prefer_raw.unwrap_or(false),
+        password: password.as_deref(),
+        set_password: set_password.as_deref(),
+        auto_wipe: settings.auto_wipe_before_clone(),
+        field_assist: settings.lf_field_assist(),
+    };
+    let result = preview_clone_commands(&card_type, &card.uid, &decoded, &blank, &options);
+    Ok(match result {
+        Ok(commands) => ClonePreview { blank_type: blank, commands, error: None },
+        Err(e) => ClonePreview { blank_type: blank, commands: Vec::new(), error: Some(e) },
+    })
+}
+
+/// Rank the likely causes of a clone that failed verification from the
+/// signals the frontend collected (scan CRC, blank check, antenna tune,
+/// readback), each with a suggested fix.
+#[tauri::command]
+pub fn diagnose_clone_failure(signals: FailureSignals) -> Vec<LikelyCause> {
+    diagnosis::diagnose_clone_failure(&signals)
+}
+
 /// Stub that returns an error directing callers to write_clone_with_data.
 /// Kept registered so the frontend gets a clear message if it calls without params.
 #[tauri::command]
@@ -29,6 +571,7 @@ pub async fn write_clone(
 /// Write clone with explicit parameters from the frontend.
 /// This is the preferred entry point. Handles T5577 password safety and EM4305 blanks.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn write_clone_with_data(
     app: AppHandle,
     port: String,
@@ -36,6 +579,38 @@ pub async fn write_clone_with_data(
     uid: String,
     decoded: std::collections::HashMap<String, String>,
     blank_type: Option<BlankType>,
+    prefer_raw: Option<bool>,
+    password: Option<String>,
+    set_password: Option<String>,
+    machine: State<'_, Mutex<WizardMachine>>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    scan_cache::invalidate(&app);
+    operation::run_operation(run_write_clone_with_data(
+        app,
+        port,
+        card_type,
+        uid,
+        decoded,
+        blank_type,
+        prefer_raw.unwrap_or(false),
+        password,
+        set_password,
+        machine,
+    ))
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_write_clone_with_data(
+    app: AppHandle,
+    port: String,
+    card_type: CardType,
+    uid: String,
+    decoded: std::collections::HashMap<String, String>,
+    blank_type: Option<BlankType>,
+    prefer_raw: bool,
+    password: Option<String>,
+    set_password: Option<String>,
     machine: State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     log::debug!("write_clone_with_data: port={}, card_type={:?}, uid={}, blank_type={:?}", port, card_type, uid, blank_type);
@@ -71,14 +646,33 @@ pub async fn write_clone_with_data(
 
     let blank = blank_type.unwrap_or_else(|| card_type.recommended_blank());
 
-    // Guard: reject EM4305 blank for card types that don't support the --em flag.
-    // Only the original 11 LF types support EM4305. The newer types (Presco, Nedap,
-    // GProxII, Gallagher, PAC, Noralsy, Jablotron, SecuraKey, Visa2000, Motorola,
-    // IDTECK) will fail silently or error when --em is passed.
-    if blank == BlankType::EM4305 && !card_type.supports_em4305() {
+    // Guard: the blank must be able to carry this card type at all (right
+    // frequency, EM4305 only for types that take --em) before anything is sent.
+    blank_compat::check_clone_target(&card_type, &blank).map_err(AppError::CommandFailed)?;
+
+    // User-supplied T5577 password (after chk came up empty) must be well-formed
+    if let Some(pw) = &password {
+        command_builder::build_t5577_wipe_with_password(pw)
+            .map_err(|e| AppError::CommandFailed(format!("Password validation failed: {}", e)))?;
+    }
+    // Password to lock the finished clone with
+    if let Some(pw) = &set_password {
+        t5577_lock_commands(&card_type, &blank, &decoded, pw)
+            .map_err(|e| AppError::CommandFailed(format!("Password validation failed: {}", e)))?;
+    }
+
+    // Reject field values PM3 would refuse before touching the blank
+    // (a verbatim T5577 copy doesn't use them)
+    let raw_copy = blank == BlankType::T5577 && t55xx_raw_clone(&decoded).is_some();
+    if raw_copy {
+        log::debug!("Source is a T5577: copying its blocks verbatim");
+    } else if let Err(e) = command_builder::check_required_fields(&card_type, &uid, &decoded) {
+        return Err(AppError::CommandFailed(format!("Cannot clone: {}", e)));
+    } else if let Err(e) = command_builder::validate_clone_fields(&card_type, &uid, &decoded) {
         return Err(AppError::CommandFailed(format!(
-            "{} does not support EM4305 blanks. Please use a T5577 blank instead.",
-            card_type.display_name()
+            "Cannot clone {}: {}",
+            card_type.display_name(),
+            e
         )));
     }
 
@@ -90,12 +684,30 @@ pub async fn write_clone_with_data(
         m.transition(WizardAction::StartWrite)?;
     }
 
+    clone_summary::begin(&app, &card_type, &uid, &blank);
+    if decoded.get("crc_ok").is_some_and(|ok| ok == "false") {
+        clone_summary::warn(&app, "Source card failed its CRC check; the clone copies it as read");
+    }
+
     // Branch based on blank type.
     // Errors from the write flow are caught and reported as FSM Error state
     // to keep the backend FSM in sync with the frontend XState machine.
-    match blank {
-        BlankType::T5577 => {
-            match write_t5577_flow(&app, &port, &card_type, &uid, &decoded, &machine).await {
+    let result: Result<WizardState, AppError> = match blank {
+        BlankType::T5577 | BlankType::Q5 => {
+            match write_t5577_flow(
+                &app,
+                &port,
+                &card_type,
+                &uid,
+                &decoded,
+                &blank,
+                prefer_raw,
+                password.as_deref(),
+                set_password.as_deref(),
+                &machine,
+            )
+            .await
+            {
                 Ok(state) => Ok(state),
                 Err(e) => {
                     let err_detail = e.to_string();
@@ -120,7 +732,9 @@ pub async fn write_clone_with_data(
             }
         }
         BlankType::EM4305 => {
-            match write_em4305_flow(&app, &port, &card_type, &uid, &decoded, &machine).await {
+            match write_em4305_flow(&app, &port, &card_type, &uid, &decoded, prefer_raw, &machine)
+                .await
+            {
                 Ok(state) => Ok(state),
                 Err(e) => {
                     let err_detail = e.to_string();
@@ -155,23 +769,49 @@ pub async fn write_clone_with_data(
             })?;
             Ok(m.current.clone())
         }
-    }
+    };
+    // A write that failed or ended in Error never reaches verification
+    clone_summary::end_write(&app, &result);
+    result
 }
 
+/// Shown when a T5577 is password-locked and `lf t55xx chk` found nothing.
+pub(crate) const T5577_PASSWORD_UNKNOWN_HINT: &str =
+    "This T5577 is password-protected and none of the known passwords worked. \
+     Aftermarket blanks sometimes ship with a vendor password — if you have it \
+     (8 hex characters), enter it to unlock the card, or use a different blank.";
+
+/// Shown when the blank's config block is OTP-locked.
+const T5577_CONFIG_LOCKED_WARNING: &str =
+    "This T5577's config is locked — the clone will keep its current modulation.";
+
 /// T5577 write flow:
-/// - No password: detect -> clone (clone overwrites config + data blocks directly)
-/// - Password: detect -> find password -> wipe -> verify wipe -> clone
+/// - No password: detect -> clone (clone overwrites config + data blocks directly),
+///   or detect -> wipe -> verify wipe -> clone with auto-wipe on
+/// - Password: detect -> find password (or use `user_password`) -> wipe -> verify wipe -> clone
+///
+/// With `set_password`, the clone is then locked with it and the lock is
+/// confirmed by detecting the tag under that password.
+///
+/// A chip that detects as a T5555 is written as a Q5 (`--q5`) whatever
+/// `blank` said: a plain T5577 clone leaves a Q5 silently unreadable.
+#[allow(clippy::too_many_arguments)]
 async fn write_t5577_flow(
     app: &AppHandle,
     port: &str,
     card_type: &CardType,
     uid: &str,
     decoded: &std::collections::HashMap<String, String>,
+    blank: &BlankType,
+    prefer_raw: bool,
+    user_password: Option<&str>,
+    set_password: Option<&str>,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     // Step 1: Detect T5577
     log::debug!("T5577 flow: Step 1 detect");
-    update_progress(app, machine, 0.1, Some(0), Some(T5577_TOTAL_STEPS))?;
+    let mut steps = T5577Steps::new();
+    update_t5577_step(app, machine, &mut steps, LfWriteStep::Detect)?;
 
     let detect_out =
         connection::run_command(app, port, command_builder::build_t5577_detect()).await?;
@@ -187,29 +827,59 @@ async fn write_t5577_flow(
             Some(RecoveryAction::Retry),
         );
     }
+    // Data writes still land on a locked config, so only warn
+    if t5577_status.locked {
+        clone_summary::warn(app, T5577_CONFIG_LOCKED_WARNING);
+    }
+    let blank = match t5577_status.blank_type() {
+        BlankType::Q5 => BlankType::Q5,
+        _ => blank.clone(),
+    };
+    if blank == BlankType::Q5 {
+        log::debug!("T5577 flow: writing as Q5 (chip {})", t5577_status.chip_type);
+    }
+
+    // For clean T5577s the clone command overwrites config + data blocks directly,
+    // so the wipe only runs for locked blanks unless auto-wipe is on. Skipping it
+    // avoids an extra write cycle that can fail on weaker LF antennas (PM3 Easy)
+    // and eliminates two subprocess spawns (fewer serial port open/close).
+    let auto_wipe = app
+        .try_state::<WriteSettings>()
+        .is_some_and(|s| s.auto_wipe_before_clone());
+    steps.expect_wipe(t5577_status.password_set || auto_wipe);
 
     // Step 2: Check for password protection
-    update_progress(app, machine, 0.2, Some(1), Some(T5577_TOTAL_STEPS))?;
+    update_t5577_step(app, machine, &mut steps, LfWriteStep::CheckPassword)?;
 
-    let password: Option<String> = if t5577_status.password_set {
-        // Password detected -- run chk to find it
-        let chk_out = connection::run_command(app, port, command_builder::build_t5577_chk()).await;
+    let password: Option<String> = if !t5577_status.password_set {
+        None
+    } else if let Some(pw) = user_password {
+        // User supplied the password after an earlier chk came up empty --
+        // skip the dictionary run
+        Some(pw.to_uppercase())
+    } else {
+        // Password detected -- run chk to find it (with the user's dictionary, if picked)
+        let chk_cmd = match app
+            .try_state::<SelectedDictionaries>()
+            .and_then(|s| s.get(DictionaryKind::T55xx))
+        {
+            Some(dict) => command_builder::build_t5577_chk_with_dict(&dict),
+            None => command_builder::build_t5577_chk().to_string(),
+        };
+        let chk_out = connection::run_command(app, port, &chk_cmd).await;
         match chk_out {
-            Ok(output) => {
-                let found = output_parser::parse_t5577_chk(&output);
-                if found.is_none() {
-                    // Password set but could not be recovered
+            Ok(output) => match output_parser::resolve_t5577_unlock(&output) {
+                T5577Unlock::Password(found) => Some(found),
+                T5577Unlock::PasswordUnknown => {
                     return report_error(
                         machine,
                         "Card is password-locked, cannot recover password",
-                        "This T5577 is password-protected and the password could not be found. \
-                         Use a different blank card.",
+                        T5577_PASSWORD_UNKNOWN_HINT,
                         true,
-                        Some(RecoveryAction::Retry),
+                        Some(RecoveryAction::EnterPassword),
                     );
                 }
-                found
-            }
+            },
             Err(_) => {
                 return report_error(
                     machine,
@@ -220,94 +890,112 @@ async fn write_t5577_flow(
                 );
             }
         }
-    } else {
-        None
     };
 
-    // Step 3-4: Wipe + verify (ONLY when password-protected).
-    // For clean T5577s the clone command overwrites config + data blocks directly.
-    // Skipping wipe avoids an extra write cycle that can fail on weaker LF antennas
-    // (PM3 Easy) and eliminates two subprocess spawns (fewer serial port open/close).
-    if password.is_some() {
-        update_progress(app, machine, 0.35, Some(2), Some(T5577_TOTAL_STEPS))?;
-
-        let wipe_cmd =
-            command_builder::build_wipe_command(&BlankType::T5577, password.as_deref())
-                .ok_or_else(|| {
-                    AppError::CommandFailed("No wipe command for this blank type".into())
-                })?;
-        connection::run_command(app, port, &wipe_cmd).await?;
-
-        // Verify wipe — ensure T5577 is detected and no longer password-protected.
-        // PM3 can return exit code 0 even when a password-protected wipe fails silently.
-        update_progress(app, machine, 0.5, Some(3), Some(T5577_TOTAL_STEPS))?;
-
-        let verify_wipe_out =
-            connection::run_command(app, port, command_builder::build_t5577_detect()).await?;
-        let verify_status = output_parser::parse_t5577_detect(&verify_wipe_out);
-
-        if !verify_status.detected || verify_status.password_set {
-            return report_error(
-                machine,
-                "T5577 wipe verification failed — card may still be password-protected",
-                "Wipe verification failed. The card may still be password-protected. \
-                 Do not remove the card — try again or use a different blank.",
-                true,
-                Some(RecoveryAction::Retry),
-            );
-        }
-    }
-
-    // SAFETY: If password was set, it was cleared by wipe above.
-    // Shadow the variable to prevent accidental re-lock on clone command.
-    let password: Option<String> = None;
-
-    // Step 5: Clone
-    update_progress(app, machine, 0.7, Some(4), Some(T5577_TOTAL_STEPS))?;
+    // Step 3-5: Wipe + verify, then clone
+    let options = LfWriteOptions {
+        prefer_raw,
+        password: password.as_deref(),
+        set_password,
+        auto_wipe,
+        field_assist: lf_field_assist(app),
+    };
 
+    // Build the plan before touching the blank so an uncloneable card never
+    // gets wiped. A T5577-hosted source is copied block for block.
     log::debug!("Clone: uid={}, type={:?}, decoded={:?}", uid, card_type, decoded);
+    let Some(plan) = lf_write_plan(card_type, uid, decoded, &blank, &options)? else {
+        return report_error(
+            machine,
+            &format!("No clone command for {:?}", card_type),
+            "This card type cannot be cloned with the current method.",
+            false,
+            None,
+        );
+    };
+    steps.expect_plan(&plan);
 
-    let base_clone_cmd = command_builder::build_clone_command(card_type, uid, decoded);
+    // The plan's steps end with the clone; its last write can take the readback along
+    let last = plan.steps.len().saturating_sub(1);
+    for (i, (step, cmd)) in plan.steps.into_iter().enumerate() {
+        update_t5577_step(app, machine, &mut steps, step)?;
+        log::debug!("sending={}", cmd);
+        let write = matches!(step, LfWriteStep::FieldAssist | LfWriteStep::Clone);
+        let output = if write {
+            run_clone_step(app, port, &cmd, step == LfWriteStep::FieldAssist, i == last).await
+        } else {
+            connection::run_command(app, port, &cmd).await
+        };
+        if write {
+            log::debug!("clone_result={:?}", output.as_ref().map(|s| s.chars().take(500).collect::<String>()).map_err(|e| e.to_string()));
+        }
+        let output = output?;
 
-    log::debug!("clone_cmd={:?}", base_clone_cmd);
+        match step {
+            // Verify wipe — ensure T5577 is detected and no longer password-protected.
+            // PM3 can return exit code 0 even when a password-protected wipe fails silently.
+            LfWriteStep::VerifyWipe => {
+                let verify_status = output_parser::parse_t5577_detect(&output);
 
-    match base_clone_cmd {
-        Some(cmd) => {
-            let final_cmd = match &password {
-                Some(pw) => command_builder::build_clone_with_password(&cmd, pw)
-                    .map_err(|e| AppError::CommandFailed(format!("Password validation failed: {}", e)))?,
-                None => cmd,
-            };
-            log::debug!("sending={}", final_cmd);
-            let clone_output = connection::run_command(app, port, &final_cmd).await;
-            log::debug!("clone_result={:?}", clone_output.as_ref().map(|s| s.chars().take(500).collect::<String>()).map_err(|e| e.to_string()));
-            let clone_output = clone_output?;
+                if verify_status.detected && verify_status.password_set && user_password.is_some() {
+                    return report_error(
+                        machine,
+                        "T5577 still locked after wipe with user-supplied password",
+                        "The password you entered did not unlock this T5577. \
+                         Check it and try again, or use a different blank.",
+                        true,
+                        Some(RecoveryAction::EnterPassword),
+                    );
+                }
+                if !verify_status.detected || verify_status.password_set {
+                    return report_error(
+                        machine,
+                        "T5577 wipe verification failed — card may still be password-protected",
+                        "Wipe verification failed. The card may still be password-protected. \
+                         Do not remove the card — try again or use a different blank.",
+                        true,
+                        Some(RecoveryAction::Retry),
+                    );
+                }
+            }
             // Check for failure indicators in PM3 output
-            if clone_output.contains("[!!]")
-                || clone_output.to_lowercase().contains("fail")
+            LfWriteStep::FieldAssist | LfWriteStep::Clone
+                if output.contains("[!!]") || output.to_lowercase().contains("fail") =>
             {
                 return report_error(
                     machine,
-                    &format!("Clone command may have failed: {}", clone_output.chars().take(200).collect::<String>()),
+                    &format!("Clone command may have failed: {}", output.chars().take(200).collect::<String>()),
                     "Write may have failed. Do not remove the card — try again.",
                     true,
                     Some(RecoveryAction::Retry),
                 );
             }
+            _ => {}
         }
-        None => {
+    }
+
+    // Lock the clone. Password mode doesn't change what the tag transmits,
+    // so the readback in verify_clone still works without it.
+    let mut lock_detect = None;
+    for cmd in &plan.lock {
+        lock_detect = Some(connection::run_command(app, port, cmd).await?);
+    }
+    if let Some(detect_out) = lock_detect {
+        let lock_status = output_parser::parse_t5577_detect(&detect_out);
+        if !lock_status.detected || !lock_status.password_set {
             return report_error(
                 machine,
-                &format!("No clone command for {:?}", card_type),
-                "This card type cannot be cloned with the current method.",
-                false,
-                None,
+                "T5577 password lock not confirmed after write",
+                "The clone was written but the password did not take. \
+                 Do not remove the card — try again.",
+                true,
+                Some(RecoveryAction::Retry),
             );
         }
     }
 
     // Step 6: Done writing -> Verifying transition
-    update_progress(app, machine, 1.0, Some(5), Some(T5577_TOTAL_STEPS))?;
+    update_t5577_step(app, machine, &mut steps, LfWriteStep::Done)?;
     {
         let mut m = machine.lock().map_err(|e| {
             AppError::CommandFailed(format!("State lock poisoned: {}", e))
@@ -331,6 +1019,7 @@ async fn write_em4305_flow(
     card_type: &CardType,
     uid: &str,
     decoded: &std::collections::HashMap<String, String>,
+    prefer_raw: bool,
     machine: &State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     // Step 1: Detect EM4305 — verify the blank chip is present before wiping.
@@ -350,65 +1039,74 @@ async fn write_em4305_flow(
         );
     }
 
-    // Step 2: Wipe EM4305
-    update_progress(app, machine, 0.3, Some(1), Some(EM4305_TOTAL_STEPS))?;
-
-    connection::run_command(app, port, command_builder::build_em4305_wipe()).await?;
-
-    // Step 3: Verify wipe — read word 0 and check it's zeroed.
-    // PM3 can return exit code 0 even when wipe fails silently.
-    // Proceeding to clone without this check risks corrupted data on the card.
-    update_progress(app, machine, 0.5, Some(2), Some(EM4305_TOTAL_STEPS))?;
-
-    let verify_out =
-        connection::run_command(app, port, &command_builder::build_em4305_read_word(0)).await?;
-    if let Some(word0) = output_parser::parse_em4305_word0(&verify_out) {
-        if word0 != "00000000" {
-            return report_error(
-                machine,
-                &format!(
-                    "EM4305 wipe verification failed — word 0 is {} (expected 00000000)",
-                    word0
-                ),
-                "Wipe verification failed. The card may not have been wiped correctly. \
-                 Do not remove the card — try again or use a different blank.",
-                true,
-                Some(RecoveryAction::Retry),
-            );
-        }
-    }
-    // If parse_em4305_word0 returns None, we can't verify — proceed with caution.
-    // This is acceptable: the clone step will fail if the card is in a bad state.
+    // Build the plan before wiping so an uncloneable card never gets wiped
+    let options = LfWriteOptions {
+        prefer_raw,
+        field_assist: lf_field_assist(app),
+        ..LfWriteOptions::default()
+    };
+    let Some(plan) = lf_write_plan(card_type, uid, decoded, &BlankType::EM4305, &options)? else {
+        return report_error(
+            machine,
+            &format!("No clone command for {:?}", card_type),
+            "This card type cannot be cloned with the current method.",
+            false,
+            None,
+        );
+    };
 
-    // Step 4: Clone with --em flag
-    update_progress(app, machine, 0.7, Some(3), Some(EM4305_TOTAL_STEPS))?;
+    // Steps 2-4: wipe, verify wipe, clone with --em
+    let last = plan.steps.len().saturating_sub(1);
+    for (i, (step, cmd)) in plan.steps.into_iter().enumerate() {
+        let (progress, index) = step.em4305_progress();
+        update_progress(app, machine, progress, Some(index), Some(EM4305_TOTAL_STEPS))?;
+        let output = match step {
+            LfWriteStep::FieldAssist | LfWriteStep::Clone => {
+                run_clone_step(app, port, &cmd, step == LfWriteStep::FieldAssist, i == last)
+                    .await?
+            }
+            _ => connection::run_command(app, port, &cmd).await?,
+        };
 
-    let base_clone_cmd = command_builder::build_clone_command(card_type, uid, decoded);
-    match base_clone_cmd {
-        Some(cmd) => {
-            let em_cmd = command_builder::build_clone_for_em4305(&cmd);
-            let clone_output = connection::run_command(app, port, &em_cmd).await?;
+        match step {
+            // Verify wipe — word 0 must read back zeroed. PM3 can return exit
+            // code 0 even when wipe fails silently, and cloning onto a half-wiped
+            // card risks corrupted data. An unparseable read can't be verified:
+            // proceed, the clone step fails if the card is in a bad state.
+            LfWriteStep::VerifyWipe => {
+                if let Some(word0) = output_parser::parse_em4305_word0(&output) {
+                    if word0 != "00000000" {
+                        return report_error(
+                            machine,
+                            &format!(
+                                "EM4305 wipe verification failed — word 0 is {} \
+                                 (expected 00000000)",
+                                word0
+                            ),
+                            "Wipe verification failed. The card may not have been wiped correctly. \
+                             Do not remove the card — try again or use a different blank.",
+                            true,
+                            Some(RecoveryAction::Retry),
+                        );
+                    }
+                }
+            }
             // Check for failure indicators in PM3 output
-            if clone_output.contains("[!!]")
-                || clone_output.to_lowercase().contains("fail")
+            LfWriteStep::FieldAssist | LfWriteStep::Clone
+                if output.contains("[!!]") || output.to_lowercase().contains("fail") =>
             {
                 return report_error(
                     machine,
-                    &format!("EM4305 clone may have failed: {}", clone_output.chars().take(200).collect::<String>()),
+                    &format!(
+                        "EM4305 clone may have failed: {}",
+                        output.chars().take(200).collect::<String>()
+                    ),
                     "Write may have failed. Do not remove the card — try again.",
                     true,
                     Some(RecoveryAction::Retry),
                 );
             }
-        }
-        None => {
-            return report_error(
-                machine,
-                &format!("No clone command for {:?}", card_type),
-                "This card type cannot be cloned with the current method.",
-                false,
-                None,
-            );
+            _ => {}
         }
     }
 
@@ -443,6 +1141,27 @@ pub async fn verify_clone(
     source_decoded: Option<std::collections::HashMap<String, String>>,
     _blank_type: Option<BlankType>,
     machine: State<'_, Mutex<WizardMachine>>,
+) -> Result<OperationResult<WizardState>, AppError> {
+    operation::run_operation(run_verify_clone(
+        app,
+        port,
+        source_uid,
+        source_card_type,
+        source_decoded,
+        _blank_type,
+        machine,
+    ))
+    .await
+}
+
+async fn run_verify_clone(
+    app: AppHandle,
+    port: String,
+    source_uid: String,
+    source_card_type: CardType,
+    source_decoded: Option<std::collections::HashMap<String, String>>,
+    _blank_type: Option<BlankType>,
+    machine: State<'_, Mutex<WizardMachine>>,
 ) -> Result<WizardState, AppError> {
     // Guard: must be in Verifying state before running any hardware commands.
     // Without this check, a call from the wrong state would waste a PM3
@@ -462,17 +1181,57 @@ pub async fn verify_clone(
         }
     }
 
+    clone_summary::join(&app);
+
     // Use generic `lf search` for verification — parse_lf_search is designed to parse
     // its output format. Type-specific readers (lf hid reader, etc.) produce different
     // output that parse_lf_search can't handle, causing false verification failures.
-    let verify_output = connection::run_command(&app, &port, "lf search").await?;
-
-    // Use detailed verification if decoded fields are available
-    let (success, mismatched) = if let Some(ref decoded) = source_decoded {
-        output_parser::verify_match_detailed(&source_card_type, decoded, &verify_output)
-    } else {
-        output_parser::verify_match(&source_uid, &verify_output)
+    let check = |output: &str| match source_decoded {
+        Some(ref decoded) => output_parser::verify_match_detailed(&source_card_type, decoded, output),
+        None => output_parser::verify_match(&source_uid, output),
     };
+    // A readback taken in the same run as the write (verify_in_place) is used
+    // as is: the card hasn't moved since
+    let in_place = app.try_state::<WriteSettings>().and_then(|s| s.take_readback());
+    let mut verify_output = match in_place {
+        Some(readback) => readback,
+        None => connection::run_command(&app, &port, "lf search").await?,
+    };
+    let (mut success, mut mismatched) = check(&verify_output);
+
+    // Weak antennas often misread a good clone: reposition hint, settle, retry once
+    if !success && lf_field_assist(&app) {
+        connection::emit_output(
+            &app,
+            "[=] Readback didn't match. Keep the card centred on the antenna; retrying...",
+            false,
+        );
+        let (chain, index) = lf_chain(command_builder::build_lf_search(), true, false)?
+            .ok_or_else(|| AppError::CommandFailed("Field assist chain missing".into()))?;
+        let output = connection::run_chained(&app, &port, &chain).await?;
+        verify_output = chain.part_output(&output, index).unwrap_or(output);
+        (success, mismatched) = check(&verify_output);
+        if success {
+            clone_summary::warn(&app, "Readback matched only on the second try (field assist)");
+        }
+    }
+
+    let source_clock = source_decoded.as_ref().and_then(|d| d.get("clock"));
+    if let (Some(source), Some(clone)) = (source_clock, output_parser::parse_lf_clock(&verify_output)) {
+        if *source != clone.to_string() {
+            clone_summary::warn(
+                &app,
+                &format!("Clone runs at RF/{} but the source card used RF/{}", clone, source),
+            );
+        }
+    }
+    if !success {
+        clone_summary::warn(
+            &app,
+            &format!("Readback didn't match the source ({} field(s) differ)", mismatched.len()),
+        );
+    }
+    let summary = clone_summary::finish(&app, success, true);
 
     let mut m = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
@@ -480,6 +1239,7 @@ pub async fn verify_clone(
     m.transition(WizardAction::VerificationResult {
         success,
         mismatched_blocks: mismatched.clone(),
+        summary,
     })?;
 
     // Note: VerificationComplete stores success/failure. The FINISH/MarkComplete
@@ -506,6 +1266,48 @@ fn update_progress(
     progress: f32,
     current_block: Option<u16>,
     total_blocks: Option<u16>,
+) -> Result<(), AppError> {
+    emit_progress(app, machine, progress, current_block, total_blocks, None)
+}
+
+fn update_t5577_step(
+    app: &AppHandle,
+    machine: &State<'_, Mutex<WizardMachine>>,
+    steps: &mut T5577Steps,
+    step: LfWriteStep,
+) -> Result<(), AppError> {
+    let Some((number, total)) = steps.report(step) else {
+        return Ok(());
+    };
+    record_progress(machine, step.progress(), Some(number), Some(total))?;
+    emit_payload(app, t5577_step_payload(step, number, total));
+    Ok(())
+}
+
+/// `write-progress` payload of a T5577 write reaching `step`, step `number`
+/// of `total`.
+fn t5577_step_payload(step: LfWriteStep, number: u16, total: u16) -> serde_json::Value {
+    progress_payload(step.progress(), Some(number), Some(total), Some(step.name()))
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    machine: &State<'_, Mutex<WizardMachine>>,
+    progress: f32,
+    current_block: Option<u16>,
+    total_blocks: Option<u16>,
+    step_name: Option<&str>,
+) -> Result<(), AppError> {
+    record_progress(machine, progress, current_block, total_blocks)?;
+    emit_payload(app, progress_payload(progress, current_block, total_blocks, step_name));
+    Ok(())
+}
+
+fn record_progress(
+    machine: &State<'_, Mutex<WizardMachine>>,
+    progress: f32,
+    current_block: Option<u16>,
+    total_blocks: Option<u16>,
 ) -> Result<(), AppError> {
     let mut m = machine.lock().map_err(|e| {
         AppError::CommandFailed(format!("State lock poisoned: {}", e))
@@ -515,18 +1317,31 @@ fn update_progress(
         current_block,
         total_blocks,
     })?;
+    Ok(())
+}
+
+fn emit_payload(app: &AppHandle, payload: serde_json::Value) {
     // Emit event to frontend for real-time progress updates
-    if let Err(e) = app.emit(
-        "write-progress",
-        serde_json::json!({
-            "progress": progress,
-            "current_block": current_block,
-            "total_blocks": total_blocks,
-        }),
-    ) {
+    if let Err(e) = app.emit("write-progress", payload) {
         log::warn!("Failed to emit write-progress event: {}", e);
     }
-    Ok(())
+}
+
+/// `write-progress` event payload. `step_name` labels the current step when
+/// the flow has named steps (T5577).
+fn progress_payload(
+    progress: f32,
+    current_block: Option<u16>,
+    total_blocks: Option<u16>,
+    step_name: Option<&str>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "progress": progress,
+        "current_block": current_block,
+        "total_blocks": total_blocks,
+        "step_name": step_name,
+        "operation_id": operation::current_operation_id(),
+    })
 }
 
 fn report_error(
@@ -547,3 +1362,341 @@ fn report_error(
     })?;
     Ok(m.current.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_t5577_clone_reports_six_numbered_steps() {
+        let decoded = std::collections::HashMap::new();
+        // The steps write_t5577_flow reports for a blank that is (or isn't)
+        // locked, with field assist on or off
+        let events = |locked: bool, field_assist| {
+            let options = LfWriteOptions {
+                password: locked.then_some("51243648"),
+                field_assist,
+                ..Default::default()
+            };
+            let plan = lf_write_plan(
+                &CardType::EM4100,
+                "0F0368568B",
+                &decoded,
+                &BlankType::T5577,
+                &options,
+            )
+            .unwrap()
+            .unwrap();
+            let mut steps = T5577Steps::new();
+            let mut events = Vec::new();
+            let mut report = |steps: &mut T5577Steps, step| {
+                if let Some((number, total)) = steps.report(step) {
+                    events.push(t5577_step_payload(step, number, total));
+                }
+            };
+            report(&mut steps, LfWriteStep::Detect);
+            steps.expect_wipe(locked);
+            report(&mut steps, LfWriteStep::CheckPassword);
+            steps.expect_plan(&plan);
+            for (step, _) in &plan.steps {
+                report(&mut steps, *step);
+            }
+            report(&mut steps, LfWriteStep::Done);
+            events
+        };
+        let names = |events: &[serde_json::Value]| -> Vec<String> {
+            events.iter().map(|e| e["step_name"].as_str().unwrap().to_string()).collect()
+        };
+        let numbers = |events: &[serde_json::Value]| -> Vec<(u64, u64)> {
+            let number = |e: &serde_json::Value, key: &str| e[key].as_u64().unwrap();
+            events
+                .iter()
+                .map(|e| (number(e, "current_block"), number(e, "total_blocks")))
+                .collect()
+        };
+
+        let full = events(true, true);
+        assert_eq!(full.len(), 6);
+        assert_eq!(
+            names(&full),
+            vec![
+                "Detecting T5577...",
+                "Checking password...",
+                "Wiping blank...",
+                "Verifying wipe...",
+                "Tuning field and writing clone...",
+                "Done",
+            ]
+        );
+        assert_eq!(numbers(&full), (1..=6).map(|n| (n, 6)).collect::<Vec<_>>());
+        for pair in full.windows(2) {
+            assert!(pair[0]["progress"].as_f64() < pair[1]["progress"].as_f64());
+        }
+        assert_eq!(full[5]["progress"], 1.0);
+
+        // Without field assist the clone goes out as the plain clone step
+        assert_eq!(names(&events(true, false))[4], "Writing clone...");
+
+        // A clean blank skips the wipe: four steps, counted to 4/4
+        let clean = events(false, false);
+        assert_eq!(numbers(&clean)[1..], [(2, 4), (3, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn auto_wipe_runs_wipe_before_clone() {
+        let clone = "lf em 410x clone --id 0F0368568B";
+        let clone_cmds = vec![clone.to_string()];
+
+        let plan = t5577_write_plan(&BlankType::T5577, None, true, false, &clone_cmds).unwrap();
+        let cmds: Vec<&str> = plan.iter().map(|(_, cmd)| cmd.as_str()).collect();
+        assert_eq!(cmds, vec!["lf t55xx wipe", "lf t55xx detect", clone]);
+        assert_eq!(plan[0].0, LfWriteStep::Wipe);
+        assert_eq!(plan[2].0, LfWriteStep::Clone);
+
+        // Field assist: tune and settle in the same PM3 run as the clone
+        let plan = t5577_write_plan(&BlankType::T5577, None, true, true, &clone_cmds).unwrap();
+        assert_eq!(plan[2], (LfWriteStep::FieldAssist, clone.to_string()));
+        let sent: Vec<String> =
+            plan.iter().map(|(step, cmd)| plan_command(*step, cmd).unwrap()).collect();
+        assert_eq!(
+            sent,
+            vec![
+                "lf t55xx wipe",
+                "lf t55xx detect",
+                "lf tune --iter 1; msleep -t 300; lf em 410x clone --id 0F0368568B",
+            ]
+        );
+        let (chain, index) = lf_chain(clone, true, true).unwrap().unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(
+            chain.script(),
+            "lf tune --iter 1; msleep -t 300; lf em 410x clone --id 0F0368568B; lf search"
+        );
+        assert!(lf_chain(clone, false, false).unwrap().is_none());
+        // Only the first of several block writes carries the tune
+        let blocks = vec!["lf t55xx write -b 1 -d FF83C030".to_string(), clone.to_string()];
+        let plan = t5577_write_plan(&BlankType::T5577, None, false, true, &blocks).unwrap();
+        let steps: Vec<LfWriteStep> = plan.iter().map(|(step, _)| *step).collect();
+        assert_eq!(steps, vec![LfWriteStep::FieldAssist, LfWriteStep::Clone]);
+
+        // Off: a clean blank goes straight to the clone
+        let plan = t5577_write_plan(&BlankType::T5577, None, false, false, &clone_cmds).unwrap();
+        assert_eq!(plan, vec![(LfWriteStep::Clone, clone.to_string())]);
+
+        // Locked blank: the recovered password unlocks the wipe either way
+        let plan =
+            t5577_write_plan(&BlankType::T5577, Some("51243648"), true, false, &clone_cmds)
+                .unwrap();
+        assert_eq!(plan[0].1, "lf t55xx wipe -p 51243648");
+        assert_eq!(plan.last().unwrap().1, clone);
+    }
+
+    #[test]
+    fn em4100_clone_locked_after_write() {
+        let decoded = std::collections::HashMap::new();
+        assert_eq!(
+            t5577_lock_commands(&CardType::EM4100, &BlankType::T5577, &decoded, "51243648")
+                .unwrap(),
+            vec!["lf t55xx write -b 7 -d 51243648", "lf t55xx write -b 0 -d 00148050"]
+        );
+        assert!(t5577_lock_commands(&CardType::EM4100, &BlankType::T5577, &decoded, "5124")
+            .is_err());
+        assert!(
+            t5577_lock_commands(&CardType::EM4100, &BlankType::EM4305, &decoded, "51243648")
+                .is_err()
+        );
+        assert!(
+            t5577_lock_commands(&CardType::HIDProx, &BlankType::T5577, &decoded, "51243648")
+                .is_err()
+        );
+
+        // The preview shows the lock and its check, as the write sends them
+        let options =
+            LfWriteOptions { set_password: Some("51243648"), ..LfWriteOptions::default() };
+        let preview = |blank: &BlankType| {
+            preview_clone_commands(&CardType::EM4100, "0F0368568B", &decoded, blank, &options)
+        };
+        assert_eq!(
+            preview(&BlankType::T5577).unwrap(),
+            vec![
+                "lf em 410x clone --id 0F0368568B",
+                "lf t55xx write -b 7 -d 51243648",
+                "lf t55xx write -b 0 -d 00148050",
+                "lf t55xx detect -p 51243648",
+            ]
+        );
+        let err = preview(&BlankType::EM4305).unwrap_err();
+        assert!(err.starts_with("Password validation failed"), "{}", err);
+    }
+
+    #[test]
+    fn em4305_plan_matches_write_steps() {
+        let decoded = std::collections::HashMap::new();
+        let options = LfWriteOptions { field_assist: true, ..LfWriteOptions::default() };
+        let plan =
+            lf_write_plan(&CardType::EM4100, "0F0368568B", &decoded, &BlankType::EM4305, &options)
+                .unwrap()
+                .unwrap();
+        let steps: Vec<LfWriteStep> = plan.steps.iter().map(|(step, _)| *step).collect();
+        assert_eq!(
+            steps,
+            vec![LfWriteStep::Wipe, LfWriteStep::VerifyWipe, LfWriteStep::FieldAssist]
+        );
+        assert_eq!(plan.steps[2].1, "lf em 410x clone --id 0F0368568B --em");
+        assert!(plan.lock.is_empty());
+
+        // Nothing to clone with: no plan, nothing gets wiped
+        let plan = lf_write_plan(&CardType::COTAG, "1234", &decoded, &BlankType::EM4305, &options);
+        assert!(plan.unwrap().is_none());
+    }
+
+    #[test]
+    fn q5_blank_routes_to_q5_clone() {
+        // `lf t55xx detect` on a Q5 blank
+        let detect = "[=]  Chip type......... Q5/T5555\n\
+                      [=]  Modulation........ ASK\n\
+                      [=]  Bit rate.......... 5 - RF/64\n\
+                      [=]  Inverted.......... No\n\
+                      [=]  Offset............ 32\n\
+                      [=]  Seq. terminator... No\n\
+                      [=]  Block0............ 6001F004 (auto detect)\n\
+                      [=]  Downlink mode..... default/fixed bit length\n\
+                      [=]  Password set...... No";
+        let status = output_parser::parse_t5577_detect(detect);
+        assert_eq!(status.chip_type, "Q5/T5555");
+        assert_eq!(status.block0.as_deref(), Some("6001F004"));
+        let blank = status.blank_type();
+        assert_eq!(blank, BlankType::Q5);
+        // Older clients print the bare chip name
+        let bare = detect.replace("Q5/T5555", "T5555");
+        assert_eq!(output_parser::parse_t5577_detect(&bare).blank_type(), BlankType::Q5);
+
+        // Source T5577 blocks are ignored: a Q5 config block has another layout
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert(
+            command_builder::T55XX_BLOCKS_KEY.to_string(),
+            "00148040,FF83C030,0C6B1D8E".to_string(),
+        );
+        let clone_cmds =
+            t55xx_clone_commands(&CardType::EM4100, "0F0368568B", &decoded, &blank, false)
+                .unwrap();
+        assert_eq!(clone_cmds, vec!["lf em 410x clone --id 0F0368568B --q5"]);
+        let plan = t5577_write_plan(&blank, None, true, false, &clone_cmds).unwrap();
+        assert_eq!(plan[0].1, "lf t55xx wipe --q5");
+
+        let preview = preview_clone_commands(
+            &CardType::Motorola,
+            "A0000000A0000000",
+            &std::collections::HashMap::new(),
+            &BlankType::Q5,
+            &LfWriteOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(preview, vec!["lf motorola clone --raw A0000000A0000000 --q5"]);
+        assert!(t5577_lock_commands(&CardType::EM4100, &blank, &decoded, "51243648").is_err());
+    }
+
+    #[test]
+    fn t5577_source_copied_block_for_block() {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert(
+            command_builder::T55XX_BLOCKS_KEY.to_string(),
+            "00148040,FF83C030,0C6B1D8E,00000000".to_string(),
+        );
+        let clone_cmds = t55xx_raw_clone(&decoded).expect("usable source blocks");
+        let plan = t5577_write_plan(&BlankType::T5577, None, true, false, &clone_cmds).unwrap();
+        let cmds: Vec<&str> = plan.iter().map(|(_, cmd)| cmd.as_str()).collect();
+        assert_eq!(
+            cmds,
+            vec![
+                "lf t55xx wipe",
+                "lf t55xx detect",
+                "lf t55xx write -b 1 -d FF83C030",
+                "lf t55xx write -b 2 -d 0C6B1D8E",
+                "lf t55xx write -b 0 -d 00148040",
+            ]
+        );
+
+        // Password mode on the source: fall back to the protocol clone
+        decoded.insert(
+            command_builder::T55XX_BLOCKS_KEY.to_string(),
+            "00148050,FF83C030,0C6B1D8E".to_string(),
+        );
+        assert!(t55xx_raw_clone(&decoded).is_none());
+        assert!(t55xx_raw_clone(&std::collections::HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn clone_preview_for_saved_cards() {
+        // Saved cards keep `decoded` as a JSON object string
+        let decoded: std::collections::HashMap<String, String> =
+            serde_json::from_str("{}").unwrap();
+        let preview = |card_type: &CardType, uid: &str, blank: &BlankType, password, auto_wipe| {
+            let options = LfWriteOptions { password, auto_wipe, ..LfWriteOptions::default() };
+            preview_clone_commands(card_type, uid, &decoded, blank, &options)
+        };
+        let em = |blank: &BlankType, auto_wipe| {
+            preview(&CardType::EM4100, "0F0368568B", blank, None, auto_wipe)
+        };
+        assert_eq!(
+            em(&BlankType::T5577, false).unwrap(),
+            vec!["lf em 410x clone --id 0F0368568B"]
+        );
+        assert_eq!(
+            em(&BlankType::T5577, true).unwrap(),
+            vec!["lf t55xx wipe", "lf t55xx detect", "lf em 410x clone --id 0F0368568B"]
+        );
+        assert_eq!(
+            em(&BlankType::EM4305, false).unwrap(),
+            vec!["lf em 4x05 wipe", "lf em 4x05 read -a 0", "lf em 410x clone --id 0F0368568B --em"]
+        );
+        let locked = preview(
+            &CardType::EM4100,
+            "0F0368568B",
+            &BlankType::T5577,
+            Some("51243648"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(locked[0], "lf t55xx wipe -p 51243648");
+
+        // Not buildable: bad field, wrong blank, HF card
+        let keri: std::collections::HashMap<String, String> =
+            serde_json::from_str(r#"{"card_number":"12AB"}"#).unwrap();
+        let err = preview_clone_commands(
+            &CardType::Keri,
+            "12AB",
+            &keri,
+            &BlankType::T5577,
+            &LfWriteOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("Keri card number must be decimal"), "{}", err);
+        assert!(em(&BlankType::MagicMifareGen1a, false).is_err());
+        let err = preview(
+            &CardType::MifareClassic1K,
+            "7DE9254E",
+            &BlankType::MagicMifareGen1a,
+            None,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("written from a dump"), "{}", err);
+    }
+
+    #[test]
+    fn clone_preview_prefers_raw_only_when_asked() {
+        let raw = "0F55555695596A6A9999A59A";
+        let paradox: std::collections::HashMap<String, String> = serde_json::from_str(
+            &format!(r#"{{"facility_code":"96","card_number":"40426","raw":"{}"}}"#, raw),
+        )
+        .unwrap();
+        let preview = |prefer_raw| {
+            let options = LfWriteOptions { prefer_raw, ..LfWriteOptions::default() };
+            preview_clone_commands(&CardType::Paradox, raw, &paradox, &BlankType::T5577, &options)
+                .unwrap()
+        };
+        assert_eq!(preview(false), vec!["lf paradox clone --fc 96 --cn 40426"]);
+        assert_eq!(preview(true), vec![format!("lf paradox clone --raw {}", raw)]);
+    }
+}
@@ -1,53 +1,301 @@
 pub mod models;
 
-use rusqlite::Connection;
-use std::path::PathBuf;
+use rusqlite::{Connection, ErrorCode};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use crate::error::AppError;
-
 pub struct Database {
     pub conn: Mutex<Connection>,
+    /// `false` when running on the in-memory fallback: history works for
+    /// this session but is lost on exit.
+    pub persistent: bool,
+    /// Non-fatal problem hit while opening, for the UI to show once.
+    pub warning: Option<String>,
 }
 
-impl Database {
-    pub fn open(app_data_dir: PathBuf) -> Result<Self, AppError> {
-        std::fs::create_dir_all(&app_data_dir).map_err(|e| {
-            AppError::DatabaseError(format!("Cannot create data dir: {}", e))
-        })?;
-
-        let db_path = app_data_dir.join("phosphor.db");
-        let conn = Connection::open(&db_path)?;
-
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS clone_log (
-                id          INTEGER PRIMARY KEY AUTOINCREMENT,
-                source_type TEXT NOT NULL,
-                source_uid  TEXT NOT NULL,
-                target_type TEXT NOT NULL,
-                target_uid  TEXT NOT NULL,
-                port        TEXT NOT NULL,
-                success     INTEGER NOT NULL DEFAULT 0,
-                timestamp   TEXT NOT NULL,
-                notes       TEXT
+const DB_FILE: &str = "phosphor.db";
+
+/// Environment variable overriding the data directory (portable installs,
+/// separate profiles, CI). `--data-dir <path>` on the command line wins.
+pub const DATA_DIR_ENV: &str = "PHOSPHOR_DATA_DIR";
+
+/// Directory holding the database and project keyfile. Stored via
+/// `app.manage()` in `lib.rs` so everything agrees on the override.
+pub struct DataDir(pub PathBuf);
+
+/// `path` with symlinks and `..` resolved, if it exists and lies inside
+/// `data_dir`. Guards commands that take a file path from the frontend.
+pub fn path_in_data_dir(data_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let root = data_dir.canonicalize().ok()?;
+    let path = path.canonicalize().ok()?;
+    path.starts_with(&root).then_some(path)
+}
+
+/// The data dir override from `--data-dir <path>` / `--data-dir=<path>`,
+/// else `env_value` (from `PHOSPHOR_DATA_DIR`). Empty values are ignored.
+pub fn data_dir_override<I>(args: I, env_value: Option<String>) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--data-dir") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => continue,
+        };
+        if let Some(v) = value.filter(|v| !v.trim().is_empty()) {
+            return Some(PathBuf::from(v));
+        }
+    }
+    env_value.filter(|v| !v.trim().is_empty()).map(PathBuf::from)
+}
+
+/// Pick the data dir: the override if it exists or can be created, else
+/// `default` with a warning for the UI (the app still starts).
+pub fn resolve_data_dir(
+    override_dir: Option<PathBuf>,
+    default: PathBuf,
+) -> (PathBuf, Option<String>) {
+    let Some(dir) = override_dir else {
+        return (default, None);
+    };
+    match std::fs::create_dir_all(&dir) {
+        Ok(()) if dir.is_dir() => (dir, None),
+        result => {
+            let reason = result
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "not a directory".into());
+            let warning = format!(
+                "Custom data directory {} can't be used ({}); using {} instead.",
+                dir.display(),
+                reason,
+                default.display()
             );
+            log::warn!("{}", warning);
+            (default, Some(warning))
+        }
+    }
+}
 
-            CREATE TABLE IF NOT EXISTS saved_cards (
-                id                INTEGER PRIMARY KEY AUTOINCREMENT,
-                name              TEXT NOT NULL,
-                card_type         TEXT NOT NULL,
-                frequency         TEXT NOT NULL,
-                uid               TEXT NOT NULL,
-                raw               TEXT NOT NULL DEFAULT '',
-                decoded           TEXT NOT NULL DEFAULT '{}',
-                cloneable         INTEGER NOT NULL DEFAULT 1,
-                recommended_blank TEXT NOT NULL DEFAULT 'T5577',
-                created_at        TEXT NOT NULL
-            );",
-        )?;
-
-        Ok(Database {
+impl Database {
+    /// Open the history database for the app. Never fails: a corrupt file is
+    /// moved aside and recreated, and anything else that stops the file from
+    /// opening (read-only data dir, locked file) falls back to an in-memory
+    /// database so cloning still works without persistent history.
+    pub fn open_or_fallback(app_data_dir: PathBuf) -> Self {
+        let db_path = app_data_dir.join(DB_FILE);
+        let error = match std::fs::create_dir_all(&app_data_dir) {
+            Err(e) => format!("Cannot create data dir: {}", e),
+            Ok(()) => match open_file(&db_path) {
+                Ok(conn) => return Self::from_conn(conn, true, None),
+                Err(e) if is_corrupt(&e) => match recreate(&db_path) {
+                    Ok((conn, backup)) => {
+                        let warning = format!(
+                            "History database was corrupt and has been reset. The old file was kept as {}.",
+                            backup.display()
+                        );
+                        log::warn!("{}", warning);
+                        return Self::from_conn(conn, true, Some(warning));
+                    }
+                    Err(reset) => format!("{} (reset failed: {})", e, reset),
+                },
+                Err(e) => e.to_string(),
+            },
+        };
+
+        log::warn!("Cannot open {}: {}; using in-memory history", db_path.display(), error);
+        let conn = Connection::open_in_memory()
+            .and_then(|conn| init_schema(&conn).map(|_| conn))
+            .expect("failed to open in-memory database");
+        Self::from_conn(
+            conn,
+            false,
+            Some(format!(
+                "History can't be saved this session ({}). Cloning still works.",
+                error
+            )),
+        )
+    }
+
+    fn from_conn(conn: Connection, persistent: bool, warning: Option<String>) -> Self {
+        Database {
             conn: Mutex::new(conn),
-        })
+            persistent,
+            warning,
+        }
+    }
+}
+
+fn open_file(path: &Path) -> Result<Connection, rusqlite::Error> {
+    let conn = Connection::open(path)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS clone_log (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_type TEXT NOT NULL,
+            source_uid  TEXT NOT NULL,
+            target_type TEXT NOT NULL,
+            target_uid  TEXT NOT NULL,
+            port        TEXT NOT NULL,
+            success     INTEGER NOT NULL DEFAULT 0,
+            timestamp   TEXT NOT NULL,
+            notes       TEXT,
+            field_verified INTEGER,
+            source_dump TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS saved_cards (
+            id                INTEGER PRIMARY KEY AUTOINCREMENT,
+            name              TEXT NOT NULL,
+            card_type         TEXT NOT NULL,
+            frequency         TEXT NOT NULL,
+            uid               TEXT NOT NULL,
+            raw               TEXT NOT NULL DEFAULT '',
+            decoded           TEXT NOT NULL DEFAULT '{}',
+            cloneable         INTEGER NOT NULL DEFAULT 1,
+            recommended_blank TEXT NOT NULL DEFAULT 'T5577',
+            created_at        TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS wizard_checkpoint (
+            id         INTEGER PRIMARY KEY CHECK (id = 1),
+            checkpoint TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS last_operation (
+            id         INTEGER PRIMARY KEY CHECK (id = 1),
+            summary    TEXT NOT NULL,
+            finished   INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL
+        );",
+    )?;
+    migrate(conn)
+}
+
+/// SQLite's verdict that the file isn't a usable database.
+fn is_corrupt(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt) | Some(ErrorCode::NotADatabase)
+    )
+}
+
+/// Move a corrupt database aside (`phosphor.db.corrupt-<timestamp>`) and
+/// start a fresh one in its place.
+fn recreate(db_path: &Path) -> Result<(Connection, PathBuf), String> {
+    let backup = db_path.with_extension(format!(
+        "db.corrupt-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::rename(db_path, &backup).map_err(|e| e.to_string())?;
+    let conn = open_file(db_path).map_err(|e| e.to_string())?;
+    Ok((conn, backup))
+}
+
+/// Bring databases created by older versions up to the current schema.
+fn migrate(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // clone_log.field_verified: door-reader test outcome (NULL = not tested)
+    let has_field_verified = conn
+        .prepare("SELECT 1 FROM pragma_table_info('clone_log') WHERE name = 'field_verified'")?
+        .exists([])?;
+    if !has_field_verified {
+        conn.execute("ALTER TABLE clone_log ADD COLUMN field_verified INTEGER", [])?;
+    }
+    // clone_log.source_dump: archived source card bundle (NULL = none kept)
+    let has_source_dump = conn
+        .prepare("SELECT 1 FROM pragma_table_info('clone_log') WHERE name = 'source_dump'")?
+        .exists([])?;
+    if !has_source_dump {
+        conn.execute("ALTER TABLE clone_log ADD COLUMN source_dump TEXT", [])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{clone_record, TempDir};
+
+    #[test]
+    fn unopenable_dir_falls_back_to_memory() {
+        let dir = TempDir::new("blocked");
+        // A file where the data dir should be: create_dir_all fails
+        let blocker = dir.join("not-a-dir");
+        std::fs::write(&blocker, b"x").unwrap();
+
+        let db = Database::open_or_fallback(blocker.join("data"));
+        assert!(!db.persistent);
+        assert!(db.warning.as_deref().unwrap().contains("Cloning still works"));
+
+        db.insert_record(&clone_record()).unwrap();
+        assert_eq!(db.get_history(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn data_dir_override_from_args_then_env() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            data_dir_override(
+                args(&["phosphor", "--data-dir", "/usb/phosphor"]),
+                Some("/env".into())
+            ),
+            Some(PathBuf::from("/usb/phosphor"))
+        );
+        assert_eq!(
+            data_dir_override(args(&["phosphor", "--data-dir=/usb/p2"]), None),
+            Some(PathBuf::from("/usb/p2"))
+        );
+        assert_eq!(
+            data_dir_override(args(&["phosphor"]), Some("/env".into())),
+            Some(PathBuf::from("/env"))
+        );
+        assert_eq!(data_dir_override(args(&["phosphor"]), Some("  ".into())), None);
+    }
+
+    #[test]
+    fn data_dir_override_respected_or_falls_back() {
+        let dir = TempDir::new("override");
+        let default = dir.join("default");
+
+        let custom = dir.join("portable/data");
+        let (resolved, warning) = resolve_data_dir(Some(custom.clone()), default.clone());
+        assert_eq!(resolved, custom);
+        assert!(warning.is_none());
+        assert!(Database::open_or_fallback(resolved).persistent);
+        assert!(custom.join(DB_FILE).exists());
+
+        // A file in the way: fall back to the default dir, which still opens
+        let blocker = dir.join("file");
+        std::fs::write(&blocker, b"x").unwrap();
+        let (resolved, warning) = resolve_data_dir(Some(blocker.join("data")), default.clone());
+        assert_eq!(resolved, default);
+        assert!(warning.unwrap().contains("can't be used"));
+        assert!(Database::open_or_fallback(resolved).persistent);
+    }
+
+    #[test]
+    fn corrupt_file_backed_up_and_recreated() {
+        let dir = TempDir::new("corrupt");
+        std::fs::write(dir.join(DB_FILE), vec![0xA5; 4096]).unwrap();
+
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        assert!(db.persistent);
+        assert!(db.warning.as_deref().unwrap().contains("corrupt"));
+        db.insert_record(&clone_record()).unwrap();
+        assert_eq!(db.get_history(10).unwrap().len(), 1);
+
+        let backups = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
     }
 }
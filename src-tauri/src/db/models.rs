@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::db::Database;
 use crate::error::AppError;
+use crate::pm3::clone_summary::OperationSummary;
+use crate::state::WizardCheckpoint;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CloneRecord {
@@ -15,9 +17,15 @@ pub struct CloneRecord {
     pub success: bool,
     pub timestamp: String,
     pub notes: Option<String>,
+    /// Whether the clone opened the real reader; `None` until field-tested.
+    #[serde(default)]
+    pub field_verified: Option<bool>,
+    /// Archived bundle of the source card, kept when `always_dump_source` is on.
+    #[serde(default)]
+    pub source_dump: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SavedCard {
     pub id: Option<i64>,
@@ -32,14 +40,26 @@ pub struct SavedCard {
     pub created_at: String,
 }
 
+/// The most recent clone, kept across restarts so the UI can offer to pick
+/// up where the user left off. A single row, replaced by every clone.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastOperation {
+    pub summary: OperationSummary,
+    /// `false` while the clone is still running, or if the app closed
+    /// before it finished.
+    pub finished: bool,
+    pub updated_at: String,
+}
+
 impl Database {
     pub fn insert_record(&self, record: &CloneRecord) -> Result<i64, AppError> {
         let conn = self.conn.lock().map_err(|e| {
             AppError::DatabaseError(format!("Lock poisoned: {}", e))
         })?;
         conn.execute(
-            "INSERT INTO clone_log (source_type, source_uid, target_type, target_uid, port, success, timestamp, notes)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO clone_log (source_type, source_uid, target_type, target_uid, port, success, timestamp, notes, field_verified, source_dump)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 record.source_type,
                 record.source_uid,
@@ -49,6 +69,8 @@ impl Database {
                 record.success as i32,
                 record.timestamp,
                 record.notes,
+                record.field_verified,
+                record.source_dump,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -59,7 +81,7 @@ impl Database {
             AppError::DatabaseError(format!("Lock poisoned: {}", e))
         })?;
         let mut stmt = conn.prepare(
-            "SELECT id, source_type, source_uid, target_type, target_uid, port, success, timestamp, notes
+            "SELECT id, source_type, source_uid, target_type, target_uid, port, success, timestamp, notes, field_verified, source_dump
              FROM clone_log ORDER BY id DESC LIMIT ?1",
         )?;
         let rows = stmt.query_map(params![limit], |row| {
@@ -73,6 +95,8 @@ impl Database {
                 success: row.get::<_, i32>(6)? != 0,
                 timestamp: row.get(7)?,
                 notes: row.get(8)?,
+                field_verified: row.get(9)?,
+                source_dump: row.get(10)?,
             })
         })?;
 
@@ -83,6 +107,72 @@ impl Database {
         Ok(records)
     }
 
+    /// Earlier clones of a card, newest first. UIDs are compared ignoring
+    /// case and `:`/space separators.
+    pub fn find_clones_by_source_uid(&self, uid: &str) -> Result<Vec<CloneRecord>, AppError> {
+        let normalized: String = uid
+            .chars()
+            .filter(|c| *c != ':' && !c.is_whitespace())
+            .collect::<String>()
+            .to_uppercase();
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let mut stmt = conn.prepare(
+            "SELECT id, source_type, source_uid, target_type, target_uid, port, success, timestamp, notes, field_verified, source_dump
+             FROM clone_log
+             WHERE UPPER(REPLACE(REPLACE(source_uid, ':', ''), ' ', '')) = ?1
+             ORDER BY id DESC LIMIT 20",
+        )?;
+        let rows = stmt.query_map(params![normalized], |row| {
+            Ok(CloneRecord {
+                id: row.get(0)?,
+                source_type: row.get(1)?,
+                source_uid: row.get(2)?,
+                target_type: row.get(3)?,
+                target_uid: row.get(4)?,
+                port: row.get(5)?,
+                success: row.get::<_, i32>(6)? != 0,
+                timestamp: row.get(7)?,
+                notes: row.get(8)?,
+                field_verified: row.get(9)?,
+                source_dump: row.get(10)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(records)
+    }
+
+    /// Replace the note on an existing clone_log entry (`None` clears it).
+    /// Returns `false` when no entry has that id.
+    pub fn update_clone_note(&self, id: i64, note: Option<&str>) -> Result<bool, AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let updated = conn.execute(
+            "UPDATE clone_log SET notes = ?1 WHERE id = ?2",
+            params![note, id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Record the door-reader test outcome for a clone_log entry.
+    /// Returns `false` when no entry has that id.
+    pub fn set_field_verified(&self, id: i64, works: bool) -> Result<bool, AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let updated = conn.execute(
+            "UPDATE clone_log SET field_verified = ?1 WHERE id = ?2",
+            params![works, id],
+        )?;
+        Ok(updated > 0)
+    }
+
     pub fn insert_saved_card(&self, card: &SavedCard) -> Result<i64, AppError> {
         let conn = self.conn.lock().map_err(|e| {
             AppError::DatabaseError(format!("Lock poisoned: {}", e))
@@ -113,20 +203,7 @@ impl Database {
             "SELECT id, name, card_type, frequency, uid, raw, decoded, cloneable, recommended_blank, created_at
              FROM saved_cards ORDER BY created_at DESC",
         )?;
-        let rows = stmt.query_map([], |row| {
-            Ok(SavedCard {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                card_type: row.get(2)?,
-                frequency: row.get(3)?,
-                uid: row.get(4)?,
-                raw: row.get(5)?,
-                decoded: row.get(6)?,
-                cloneable: row.get::<_, i32>(7)? != 0,
-                recommended_blank: row.get(8)?,
-                created_at: row.get(9)?,
-            })
-        })?;
+        let rows = stmt.query_map([], saved_card_from_row)?;
 
         let mut cards = Vec::new();
         for row in rows {
@@ -135,6 +212,18 @@ impl Database {
         Ok(cards)
     }
 
+    pub fn get_saved_card(&self, id: i64) -> Result<Option<SavedCard>, AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, card_type, frequency, uid, raw, decoded, cloneable, recommended_blank, created_at
+             FROM saved_cards WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], saved_card_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
     pub fn delete_saved_card(&self, id: i64) -> Result<(), AppError> {
         let conn = self.conn.lock().map_err(|e| {
             AppError::DatabaseError(format!("Lock poisoned: {}", e))
@@ -142,4 +231,217 @@ impl Database {
         conn.execute("DELETE FROM saved_cards WHERE id = ?1", params![id])?;
         Ok(())
     }
+
+    pub fn set_last_operation(&self, op: &LastOperation) -> Result<(), AppError> {
+        let summary = serde_json::to_string(&op.summary)
+            .map_err(|e| AppError::DatabaseError(format!("Cannot serialize summary: {}", e)))?;
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        conn.execute(
+            "INSERT OR REPLACE INTO last_operation (id, summary, finished, updated_at)
+             VALUES (1, ?1, ?2, ?3)",
+            params![summary, op.finished, op.updated_at],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the saved wizard session checkpoint.
+    pub fn set_wizard_checkpoint(&self, checkpoint: &WizardCheckpoint) -> Result<(), AppError> {
+        let json = serde_json::to_string(checkpoint)
+            .map_err(|e| AppError::DatabaseError(format!("Cannot serialize checkpoint: {}", e)))?;
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        conn.execute(
+            "INSERT OR REPLACE INTO wizard_checkpoint (id, checkpoint, updated_at)
+             VALUES (1, ?1, ?2)",
+            params![json, chrono::Local::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// `None` when no session is checkpointed, or the checkpoint is from a
+    /// format this version can't read.
+    pub fn get_wizard_checkpoint(&self) -> Result<Option<WizardCheckpoint>, AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let mut stmt = conn.prepare("SELECT checkpoint FROM wizard_checkpoint WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows
+            .next()
+            .transpose()?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    pub fn clear_wizard_checkpoint(&self) -> Result<(), AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        conn.execute("DELETE FROM wizard_checkpoint", [])?;
+        Ok(())
+    }
+
+    /// `None` before the first clone, or when the stored summary is from a
+    /// format this version can't read.
+    pub fn get_last_operation(&self) -> Result<Option<LastOperation>, AppError> {
+        let conn = self.conn.lock().map_err(|e| {
+            AppError::DatabaseError(format!("Lock poisoned: {}", e))
+        })?;
+        let mut stmt =
+            conn.prepare("SELECT summary, finished, updated_at FROM last_operation WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, bool>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let Some((summary, finished, updated_at)) = rows.next().transpose()? else {
+            return Ok(None);
+        };
+        Ok(serde_json::from_str(&summary).ok().map(|summary| LastOperation {
+            summary,
+            finished,
+            updated_at,
+        }))
+    }
+}
+
+fn saved_card_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SavedCard> {
+    Ok(SavedCard {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        card_type: row.get(2)?,
+        frequency: row.get(3)?,
+        uid: row.get(4)?,
+        raw: row.get(5)?,
+        decoded: row.get(6)?,
+        cloneable: row.get::<_, i32>(7)? != 0,
+        recommended_blank: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{clone_record, TempDir};
+
+    fn open_temp_db(name: &str) -> (TempDir, Database) {
+        let dir = TempDir::new(name);
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        assert!(db.persistent, "{:?}", db.warning);
+        (dir, db)
+    }
+
+    #[test]
+    fn update_clone_note_round_trip() {
+        let (_dir, db) = open_temp_db("note");
+        let id = db.insert_record(&clone_record()).unwrap();
+
+        assert!(db.update_clone_note(id, Some("works on door 3, not door 5")).unwrap());
+        let history = db.get_history(10).unwrap();
+        assert_eq!(history[0].notes.as_deref(), Some("works on door 3, not door 5"));
+
+        assert!(db.update_clone_note(id, None).unwrap());
+        assert_eq!(db.get_history(10).unwrap()[0].notes, None);
+
+        assert!(!db.update_clone_note(id + 1, Some("missing")).unwrap());
+    }
+
+    #[test]
+    fn scanned_uid_finds_previous_clones() {
+        let (_dir, db) = open_temp_db("seen");
+        let mut seeded = clone_record();
+        seeded.source_uid = "0f:03:68:56:8b".into();
+        seeded.timestamp = "2024-03-01T09:00:00Z".into();
+        db.insert_record(&seeded).unwrap();
+        let mut other = clone_record();
+        other.source_uid = "1122334455".into();
+        db.insert_record(&other).unwrap();
+
+        let scan = "[+] EM 410x ID 0F0368568B\n[+] Valid EM410x ID found!";
+        let (_, card) = crate::pm3::output_parser::parse_lf_search(scan).unwrap();
+        let seen = db.find_clones_by_source_uid(&card.uid).unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].timestamp, "2024-03-01T09:00:00Z");
+        assert!(seen[0].success);
+
+        assert!(db.find_clones_by_source_uid("DEADBEEF").unwrap().is_empty());
+    }
+
+    #[test]
+    fn field_verified_round_trip() {
+        let (_dir, db) = open_temp_db("field");
+        let id = db.insert_record(&clone_record()).unwrap();
+        assert_eq!(db.get_history(10).unwrap()[0].field_verified, None);
+
+        assert!(db.set_field_verified(id, false).unwrap());
+        assert_eq!(db.get_history(10).unwrap()[0].field_verified, Some(false));
+        assert!(!db.set_field_verified(id + 1, true).unwrap());
+    }
+
+    #[test]
+    fn last_operation_survives_reopen() {
+        use crate::cards::types::{BlankType, CardType};
+
+        let (dir, db) = open_temp_db("lastop");
+        assert_eq!(db.get_last_operation().unwrap(), None);
+        let mut op = LastOperation {
+            summary: OperationSummary {
+                source_type: CardType::HIDProx,
+                source_uid: "2006EC0C86".into(),
+                blank_type: BlankType::T5577,
+                commands_run: vec!["lf hid clone -r 2006ec0c86".into()],
+                success: false,
+                verified: false,
+                duration_ms: 0,
+                warnings: Vec::new(),
+                source_dump: None,
+            },
+            finished: false,
+            updated_at: "2026-10-16T09:00:00+00:00".into(),
+        };
+        db.set_last_operation(&op).unwrap();
+        op.summary.success = true;
+        op.summary.verified = true;
+        op.finished = true;
+        db.set_last_operation(&op).unwrap();
+
+        // Simulated restart: a fresh Database on the same dir
+        drop(db);
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        assert_eq!(db.get_last_operation().unwrap(), Some(op));
+    }
+
+    #[test]
+    fn migration_adds_field_verified_to_old_db() {
+        let dir = TempDir::new("db-migrate");
+        // clone_log as created before field_verified existed
+        rusqlite::Connection::open(dir.join("phosphor.db"))
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE clone_log (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, source_type TEXT NOT NULL,
+                    source_uid TEXT NOT NULL, target_type TEXT NOT NULL,
+                    target_uid TEXT NOT NULL, port TEXT NOT NULL,
+                    success INTEGER NOT NULL DEFAULT 0, timestamp TEXT NOT NULL, notes TEXT
+                );
+                INSERT INTO clone_log (source_type, source_uid, target_type, target_uid, port, success, timestamp)
+                VALUES ('EM4100', '0F0368568B', 'T5577', '0F0368568B', 'COM3', 1, '2026-01-15T10:30:00Z');",
+            )
+            .unwrap();
+
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        assert!(db.persistent);
+        let history = db.get_history(10).unwrap();
+        assert_eq!(history[0].field_verified, None);
+        assert!(db.set_field_verified(history[0].id.unwrap(), true).unwrap());
+        // Re-opening an already migrated DB is a no-op
+        drop(db);
+        let db = Database::open_or_fallback(dir.to_path_buf());
+        assert_eq!(db.get_history(10).unwrap()[0].field_verified, Some(true));
+    }
 }
@@ -13,6 +13,40 @@ pub enum AppError {
     InvalidTransition(String),
     #[error("Timeout: {0}")]
     Timeout(String),
+    #[error("Permission denied opening {0}")]
+    PermissionDenied(String),
+    #[error("Port {0} is in use by another application")]
+    PortBusy(String),
+    /// A running PM3 process was killed through `cancel_hf_operation`.
+    #[error("Operation cancelled by user")]
+    Cancelled,
+}
+
+impl AppError {
+    /// Actionable guidance for errors the user can fix on their own machine.
+    /// `None` for everything else — callers fall back to their generic message.
+    pub fn user_message(&self) -> Option<String> {
+        match self {
+            AppError::PermissionDenied(port) => Some(if cfg!(target_os = "linux") {
+                format!(
+                    "No permission to open {}. Add your user to the dialout group \
+                     (sudo usermod -aG dialout $USER), then log out and back in.",
+                    port
+                )
+            } else {
+                format!(
+                    "No permission to open {}. Check the port permissions and try again.",
+                    port
+                )
+            }),
+            AppError::PortBusy(port) => Some(format!(
+                "{} is in use. Close any other app using the Proxmark3 \
+                 (another PM3 client, serial monitor, Arduino IDE) and try again.",
+                port
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl From<rusqlite::Error> for AppError {
@@ -3,12 +3,23 @@ mod commands;
 mod db;
 mod error;
 mod pm3;
+#[cfg(feature = "websocket")]
+mod remote;
 mod state;
+#[cfg(test)]
+mod test_util;
 
 use std::sync::Mutex;
 
 use commands::firmware::FlashState;
-use pm3::connection::HfOperationState;
+use commands::jobs::JobRegistry;
+use commands::sequence::SequenceState;
+use commands::write::WriteSettings;
+use pm3::clone_summary::CloneTracker;
+use pm3::connection::{HfOperationState, TerminalSettings};
+use pm3::dictionaries::SelectedDictionaries;
+use pm3::mock::MockMode;
+use pm3::scan_cache::ScanCache;
 use state::WizardMachine;
 use tauri::Manager;
 
@@ -18,43 +29,122 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
-            let data_dir = app
+            let default_dir = app
                 .path()
                 .app_data_dir()
                 .expect("failed to resolve app data dir");
-            let database =
-                db::Database::open(data_dir).expect("failed to open database");
+            let override_dir =
+                db::data_dir_override(std::env::args(), std::env::var(db::DATA_DIR_ENV).ok());
+            let (data_dir, dir_warning) = db::resolve_data_dir(override_dir, default_dir);
+            // Falls back to in-memory history rather than refusing to launch
+            let mut database = db::Database::open_or_fallback(data_dir.clone());
+            if database.warning.is_none() {
+                database.warning = dir_warning;
+            }
             app.manage(database);
+            app.manage(db::DataDir(data_dir));
             app.manage(Mutex::new(WizardMachine::new()));
             app.manage(FlashState::new());
             app.manage(HfOperationState::new());
+            app.manage(SequenceState::new());
+            app.manage(ScanCache::new());
+            app.manage(TerminalSettings::new());
+            app.manage(WriteSettings::new());
+            app.manage(SelectedDictionaries::new());
+            app.manage(CloneTracker::new());
+            app.manage(JobRegistry::new());
+
+            let mock = MockMode::from_env();
+            if mock.is_enabled() {
+                log::warn!("Offline mode: PM3 commands replay recorded output");
+            }
+            app.manage(mock);
+            commands::wizard::spawn_checkpointer(app.handle().clone());
+
+            #[cfg(feature = "websocket")]
+            remote::start(app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::wizard::get_wizard_state,
             commands::wizard::wizard_action,
+            commands::wizard::resume_session,
             commands::device::detect_device,
+            commands::device::get_capabilities,
+            commands::device::about_info,
+            commands::device::feature_availability,
+            commands::device::device_status,
+            commands::device::probe_command_support,
             commands::blank::detect_blank,
+            commands::blank::recommend_hf_blank,
+            commands::blank::mifare_write_test,
             commands::scan::scan_card,
+            commands::scan::scan_all,
+            commands::scan::watch_lf,
+            commands::scan::card_metadata,
             commands::write::write_clone,
             commands::write::write_clone_with_data,
+            commands::write::set_auto_wipe_before_clone,
+            commands::write::set_lf_field_assist,
+            commands::write::set_always_dump_source,
+            commands::write::set_verify_in_place,
             commands::write::verify_clone,
+            commands::write::preview_clone_command,
+            commands::write::diagnose_clone_failure,
+            commands::sequence::clone_sequence,
+            commands::sequence::continue_sequence,
+            commands::sequence::cancel_sequence,
+            commands::jobs::submit_job,
+            commands::jobs::get_job_status,
+            commands::jobs::cancel_job,
             commands::history::get_history,
             commands::history::save_clone_record,
+            commands::history::update_clone_note,
+            commands::history::record_field_test,
+            commands::history::database_status,
+            commands::history::get_last_operation,
             commands::firmware::check_firmware_version,
+            commands::firmware::verify_pm3_binary,
             commands::firmware::flash_firmware,
+            commands::firmware::recover_firmware,
             commands::firmware::cancel_flash,
             commands::erase::detect_chip,
             commands::erase::wipe_chip,
+            commands::erase::write_t55xx_config,
+            commands::erase::em4305_dump,
+            commands::em4x50::em4x50_dump,
+            commands::em4x50::em4x50_clone,
             commands::saved::save_card,
             commands::saved::get_saved_cards,
             commands::saved::delete_saved_card,
             commands::raw::run_raw_command,
+            commands::raw::debug_parse,
+            commands::raw::set_terminal_ansi,
+            commands::raw::set_pm3_verbosity,
             commands::hf_clone::hf_autopwn,
+            commands::hf_clone::resume_autopwn,
+            commands::hf_clone::dump_sectors,
             commands::hf_clone::hf_write_clone,
+            commands::hf_clone::clone_desfire_uid,
             commands::hf_clone::hf_dump,
             commands::hf_clone::hf_verify_clone,
+            commands::hf_clone::read_mf_block,
             commands::hf_clone::cancel_hf_operation,
+            commands::keys::add_keys_to_project,
+            commands::keys::get_project_keys,
+            commands::keys::clear_project_keys,
+            commands::keys::list_dictionaries,
+            commands::keys::select_dictionary,
+            commands::lf_buffer::capture_lf_buffer,
+            commands::lf_buffer::list_lf_captures,
+            commands::lf_buffer::demod_lf_capture,
+            commands::dump::export_dump_report,
+            commands::dump::clear_sensitive_state,
+            commands::dump::export_card_bundle,
+            commands::dump::import_card_bundle,
+            commands::wiegand::decode_raw,
+            commands::selfcheck::run_parser_selfcheck,
+            commands::selfcheck::benchmark_clone,
         ])
         .run(tauri::generate_context!())
         .expect("error running Phosphor");
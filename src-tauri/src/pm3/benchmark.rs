@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::cards::types::CardType;
+use crate::error::AppError;
+use crate::pm3::{command_builder, output_parser};
+
+// Timed clone cycle for performance tracking: the source card is identified
+// beforehand and only its decoded data is passed in, so the benchmark writes
+// the blank on the reader and verifies the blank. The PM3 runner is injected
+// so the same pipeline runs against hardware or the offline fixtures.
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    /// `"scan"`, `"detect"`, `"clone"` or `"verify"`.
+    pub phase: &'static str,
+    pub millis: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub card_type: CardType,
+    pub uid: String,
+    pub phases: Vec<PhaseTiming>,
+    pub total_millis: f64,
+    pub verified: bool,
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Clone the source (`card_type`/`uid`/`decoded`, from an earlier scan) onto
+/// the T5577 blank on the reader through `run` (command -> PM3 output),
+/// timing each phase. The reader must not already read as the source: that
+/// is the source card itself, or a blank holding this clone, and either way
+/// the verify would prove nothing. HF cards need a key recovery/dump first
+/// and aren't benchmarked.
+pub async fn run_benchmark<R, Fut>(
+    mut run: R,
+    card_type: CardType,
+    uid: String,
+    decoded: HashMap<String, String>,
+) -> Result<BenchmarkReport, AppError>
+where
+    R: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let clone_cmd =
+        command_builder::build_clone_command(&card_type, &uid, &decoded).ok_or_else(|| {
+            AppError::CommandFailed(format!(
+                "{} can't be cloned from the scanned data",
+                card_type.display_name()
+            ))
+        })?;
+    let started = Instant::now();
+    let mut phases = Vec::with_capacity(4);
+    let mut timed = |phase: &'static str, since: Instant| {
+        phases.push(PhaseTiming { phase, millis: millis(since.elapsed()) });
+    };
+
+    let t = Instant::now();
+    let before = run(command_builder::build_lf_search().to_string()).await?;
+    if output_parser::verify_match_detailed(&card_type, &decoded, &before).0 {
+        return Err(AppError::CommandFailed(format!(
+            "The reader already reads {} {}. Remove the source card and place a blank \
+             T5577 on the reader.",
+            card_type.display_name(),
+            uid
+        )));
+    }
+    timed("scan", t);
+
+    let t = Instant::now();
+    let detect = run(command_builder::build_t5577_detect().to_string()).await?;
+    if !output_parser::parse_t5577_detect(&detect).detected {
+        return Err(AppError::CommandFailed(
+            "No T5577 blank found. Place a blank on the reader.".into(),
+        ));
+    }
+    timed("detect", t);
+
+    let t = Instant::now();
+    run(clone_cmd).await?;
+    timed("clone", t);
+
+    let t = Instant::now();
+    let readback = run(command_builder::build_lf_search().to_string()).await?;
+    let (verified, _) = output_parser::verify_match_detailed(&card_type, &decoded, &readback);
+    timed("verify", t);
+
+    Ok(BenchmarkReport {
+        card_type,
+        uid,
+        phases,
+        total_millis: millis(started.elapsed()),
+        verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm3::mock::MockMode;
+
+    fn em4100(uid: &str) -> (CardType, String, HashMap<String, String>) {
+        let decoded = HashMap::from([("id".to_string(), uid.to_string())]);
+        (CardType::EM4100, uid.to_string(), decoded)
+    }
+
+    /// A T5577 on the reader that reads as nothing until the clone lands.
+    #[tokio::test]
+    async fn clone_verified_on_the_blank() {
+        let mock = MockMode::new(true, None);
+        let mut sent = Vec::new();
+        let mut cloned = false;
+        let (card_type, uid, decoded) = em4100("0F00112233");
+        let report = run_benchmark(
+            |cmd| {
+                sent.push(cmd.clone());
+                cloned |= cmd.starts_with("lf em 410x clone");
+                let output = if cmd == "lf search" && !cloned {
+                    Ok("[-] No known 125/134 kHz tags found!".to_string())
+                } else {
+                    mock.replay(&cmd)
+                };
+                async move { output }
+            },
+            card_type,
+            uid,
+            decoded,
+        )
+        .await
+        .unwrap();
+
+        assert!(report.verified);
+        let names: Vec<_> = report.phases.iter().map(|p| p.phase).collect();
+        assert_eq!(names, vec!["scan", "detect", "clone", "verify"]);
+        assert!(report.phases.iter().all(|p| p.millis >= 0.0));
+        assert!(report.total_millis >= report.phases.iter().map(|p| p.millis).sum::<f64>());
+        assert_eq!(sent[1], "lf t55xx detect");
+        assert!(sent[2].starts_with("lf em 410x clone --id 0F00112233"));
+    }
+
+    #[tokio::test]
+    async fn source_on_the_reader_refused() {
+        let mock = MockMode::new(true, None);
+        let mut sent = Vec::new();
+        // The mock reader holds EM4100 0F00112233, i.e. the source itself
+        let (card_type, uid, decoded) = em4100("0F00112233");
+        let err = run_benchmark(
+            |cmd| {
+                sent.push(cmd.clone());
+                let output = mock.replay(&cmd);
+                async move { output }
+            },
+            card_type,
+            uid,
+            decoded,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Remove the source card"), "{}", err);
+        assert_eq!(sent, vec!["lf search"]);
+
+        // A blank holding a different card is written, and verified against the source
+        let (card_type, uid, decoded) = em4100("0102030405");
+        let report = run_benchmark(
+            |cmd| {
+                let output = mock.replay(&cmd);
+                async move { output }
+            },
+            card_type,
+            uid,
+            decoded,
+        )
+        .await
+        .unwrap();
+        assert!(!report.verified);
+    }
+}
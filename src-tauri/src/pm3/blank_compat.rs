@@ -0,0 +1,446 @@
+use serde::Serialize;
+
+use crate::cards::types::{BlankType, CardType, Frequency, MagicGeneration};
+use crate::pm3::output_parser;
+
+// Can a given magic blank actually take a MIFARE Classic clone? Generation
+// alone isn't enough: Gen1a/Gen2 silicon has a fixed UID length and memory
+// size, so e.g. a 4-byte CUID will happily accept a 7-byte card's dump and
+// end up with the wrong UID.
+
+/// ISO 14443-A identity of the card on the reader (`hf mf info` header).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iso14aIdentity {
+    pub uid_len: usize,
+    /// As printed (`ATQA: 00 44` -> `0x0044`).
+    pub atqa: Option<u16>,
+    pub sak: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlankCompatibility {
+    pub compatible: bool,
+    pub generation: Option<MagicGeneration>,
+    /// Blank type to write with when compatible.
+    pub blank_type: Option<BlankType>,
+    pub reason: String,
+}
+
+/// UID size announced in ATQA bits 8-7 (ISO/IEC 14443-3, 6.4.2): single,
+/// double or triple size. `None` for the RFU value.
+fn atqa_uid_len(atqa: u16) -> Option<usize> {
+    match (atqa >> 6) & 0b11 {
+        0b00 => Some(4),
+        0b01 => Some(7),
+        0b10 => Some(10),
+        _ => None,
+    }
+}
+
+/// UID lengths (bytes) the blank on the reader can be programmed with.
+/// Gen1a/Gen2 UID length is fixed by the silicon: a double-size CUID
+/// (ATQA `00 44`) can't present a 4-byte UID whatever block 0 says, so the
+/// ATQA wins over the length of the UID currently reported.
+pub fn blank_uid_lengths(gen: &MagicGeneration, blank: &Iso14aIdentity) -> Vec<usize> {
+    match gen {
+        MagicGeneration::Gen1a | MagicGeneration::Gen2 => {
+            vec![blank.atqa.and_then(atqa_uid_len).unwrap_or(blank.uid_len)]
+        }
+        MagicGeneration::Gen3 => vec![4, 7],
+        MagicGeneration::Gen4GTU | MagicGeneration::Gen4GDM => vec![4, 7, 10],
+    }
+}
+
+/// Reject a source UID the blank can't present. Err holds the explanation.
+pub fn check_uid_length(
+    source_uid_len: usize,
+    gen: &MagicGeneration,
+    blank: &Iso14aIdentity,
+) -> Result<(), String> {
+    let uid_lengths = blank_uid_lengths(gen, blank);
+    if uid_lengths.contains(&source_uid_len) {
+        return Ok(());
+    }
+    let supported = uid_lengths
+        .iter()
+        .map(|n| format!("{}-byte", n))
+        .collect::<Vec<_>>()
+        .join("/");
+    Err(format!(
+        "{:?} blank only supports {} UIDs; the source has a {}-byte UID.",
+        gen, supported, source_uid_len
+    ))
+}
+
+/// Whether the blank has 4K of memory. Gen1a/Gen2 come in fixed 1K or 4K
+/// variants (SAK 0x18 = 4K); Gen3 is 1K only; Gen4 is configurable.
+fn supports_4k(gen: &MagicGeneration, blank_sak: Option<u8>) -> bool {
+    match gen {
+        MagicGeneration::Gen1a | MagicGeneration::Gen2 => blank_sak == Some(0x18),
+        MagicGeneration::Gen3 => false,
+        MagicGeneration::Gen4GTU | MagicGeneration::Gen4GDM => true,
+    }
+}
+
+fn blank_type_for(gen: &MagicGeneration) -> BlankType {
+    match gen {
+        MagicGeneration::Gen1a => BlankType::MagicMifareGen1a,
+        MagicGeneration::Gen2 => BlankType::MagicMifareGen2,
+        MagicGeneration::Gen3 => BlankType::MagicMifareGen3,
+        MagicGeneration::Gen4GTU => BlankType::MagicMifareGen4GTU,
+        MagicGeneration::Gen4GDM => BlankType::MagicMifareGen4GDM,
+    }
+}
+
+/// Check a blank against the source card's UID length and memory size.
+pub fn check_compatibility(
+    source_uid_len: usize,
+    source_is_4k: bool,
+    blank_gen: Option<MagicGeneration>,
+    blank: &Iso14aIdentity,
+) -> BlankCompatibility {
+    let Some(gen) = blank_gen else {
+        return BlankCompatibility {
+            compatible: false,
+            generation: None,
+            blank_type: None,
+            reason: "No magic capabilities detected — this card can't change its UID.".into(),
+        };
+    };
+
+    let incompatible = |reason: String| BlankCompatibility {
+        compatible: false,
+        generation: Some(gen.clone()),
+        blank_type: None,
+        reason,
+    };
+
+    if let Err(reason) = check_uid_length(source_uid_len, &gen, blank) {
+        return incompatible(reason);
+    }
+
+    if source_is_4k && !supports_4k(&gen, blank.sak) {
+        return incompatible(format!(
+            "{:?} blank is 1K; the source is a MIFARE Classic 4K.",
+            gen
+        ));
+    }
+
+    BlankCompatibility {
+        compatible: true,
+        blank_type: Some(blank_type_for(&gen)),
+        reason: format!(
+            "{:?} blank can take a {}-byte UID{}.",
+            gen,
+            source_uid_len,
+            if source_is_4k { " and 4K of data" } else { "" }
+        ),
+        generation: Some(gen),
+    }
+}
+
+/// Workflow to write `blank_type` with, given the blank's `hf mf info`
+/// output. ZUID / ONUID dual-magic cards sold as CUID take the Gen1a backdoor
+/// (`cload`) instead of the Gen2 config-force / block 0 sequence, which these
+/// cards don't implement reliably.
+pub fn write_workflow(blank_type: &BlankType, blank_info: &str) -> BlankType {
+    match blank_type {
+        BlankType::MagicMifareGen2 if output_parser::parse_gen1_wakeup(blank_info) => {
+            BlankType::MagicMifareGen1a
+        }
+        _ => blank_type.clone(),
+    }
+}
+
+// Gen2 write test: a CUID that `hf mf info` doesn't flag is only told apart
+// from a genuine card by writing block 0. Byte 15 (manufacturer data) is
+// flipped so UID, BCC, SAK and ATQA are never touched, then put back.
+
+/// Block 0 byte the write test changes.
+const WRITE_TEST_BYTE: usize = 15;
+
+/// Block 0 as written by the write test: `original` with byte 15 inverted.
+pub fn write_test_pattern(original: &[u8; 16]) -> [u8; 16] {
+    let mut pattern = *original;
+    pattern[WRITE_TEST_BYTE] ^= 0xFF;
+    pattern
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WriteTestVerdict {
+    /// Block 0 took the write and was restored: a direct-write (Gen2) card.
+    Gen2,
+    /// Block 0 read back unchanged: not a direct-write card.
+    NotWritable,
+    /// Block 0 couldn't be read back after the write, or read back something
+    /// other than the test pattern; restored either way.
+    Inconclusive,
+    /// The original block 0 could not be confirmed after the test.
+    RestoreFailed,
+}
+
+/// Verdict from the block 0 readbacks. `after_restore` is only consulted
+/// when `after_write` differs from `original` (that's when a restore runs).
+pub fn write_test_verdict(
+    original: &[u8; 16],
+    after_write: Option<[u8; 16]>,
+    after_restore: Option<[u8; 16]>,
+) -> WriteTestVerdict {
+    if after_write.as_ref() == Some(original) {
+        return WriteTestVerdict::NotWritable;
+    }
+    if after_restore.as_ref() != Some(original) {
+        return WriteTestVerdict::RestoreFailed;
+    }
+    if after_write == Some(write_test_pattern(original)) {
+        WriteTestVerdict::Gen2
+    } else {
+        WriteTestVerdict::Inconclusive
+    }
+}
+
+/// Carrier frequency a blank answers on. The magic MIFARE/Ultralight and
+/// iCLASS blanks are 13.56 MHz parts with no 125 kHz front end.
+fn blank_frequency(blank: &BlankType) -> Frequency {
+    match blank {
+        BlankType::T5577 | BlankType::Q5 | BlankType::EM4305 => Frequency::LF,
+        _ => Frequency::HF,
+    }
+}
+
+/// HID Prox on an iCLASS blank: same brand, unrelated technology. Gets its
+/// own explanation because the generic frequency message doesn't clear up
+/// why an "HID card" won't go onto an HID blank.
+const HID_PROX_ON_ICLASS: &str = "HID Prox is a 125 kHz proximity card; iCLASS is HID's \
+13.56 MHz smart card technology. They share a brand, not a chip, so an iCLASS blank can't \
+carry an HID Prox ID — and iCLASS SE credentials hold a signed SIO (Secure Identity Object) \
+that can't be cloned without HID's keys. Clone this card onto a T5577 blank instead.";
+
+/// Early warning for blank detection: why the detected magic blank can't
+/// present a `source_uid_len`-byte UID, if it can't. Stays quiet without a
+/// known source length or generation; the write-time check still runs.
+pub fn early_uid_length_warning(
+    source_uid_len: Option<usize>,
+    gen: Option<&MagicGeneration>,
+    blank: Option<&Iso14aIdentity>,
+) -> Option<String> {
+    check_uid_length(source_uid_len?, gen?, blank?).err()
+}
+
+/// Gate run before any write command: can this blank carry a clone of
+/// `card_type` at all? Err holds the explanation for the user.
+pub fn check_clone_target(card_type: &CardType, blank: &BlankType) -> Result<(), String> {
+    if *card_type == CardType::HIDProx && *blank == BlankType::IClassBlank {
+        return Err(HID_PROX_ON_ICLASS.to_string());
+    }
+    let card_freq = card_type.frequency();
+    if blank_frequency(blank) != card_freq {
+        return Err(match card_freq {
+            Frequency::LF => format!(
+                "{} is a 13.56 MHz blank and can't emulate {} at 125 kHz. Use a T5577{} blank.",
+                blank.display_name(),
+                card_type.display_name(),
+                if card_type.supports_em4305() { " or EM4305" } else { "" }
+            ),
+            Frequency::HF => format!(
+                "{} is a 125 kHz blank and can't carry a 13.56 MHz {} clone. Use a {} blank.",
+                blank.display_name(),
+                card_type.display_name(),
+                card_type.recommended_blank().display_name()
+            ),
+        });
+    }
+
+    // Only the original LF types take the --em flag; the rest fail silently
+    if *blank == BlankType::EM4305 && !card_type.supports_em4305() {
+        return Err(format!(
+            "{} does not support EM4305 blanks. Please use a T5577 blank instead.",
+            card_type.display_name()
+        ));
+    }
+    if *blank == BlankType::Q5 && !card_type.supports_q5() {
+        return Err(format!(
+            "{} can't be cloned onto a Q5 blank. Please use a T5577 blank instead.",
+            card_type.display_name()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank(uid_len: usize, sak: u8) -> Iso14aIdentity {
+        Iso14aIdentity { uid_len, atqa: None, sak: Some(sak) }
+    }
+
+    #[test]
+    fn seven_byte_source_on_four_byte_gen2() {
+        let result = check_compatibility(7, false, Some(MagicGeneration::Gen2), &blank(4, 0x08));
+        assert!(!result.compatible);
+        assert!(result.reason.contains("4-byte"), "{}", result.reason);
+        assert!(result.reason.contains("7-byte"), "{}", result.reason);
+        assert_eq!(result.blank_type, None);
+    }
+
+    #[test]
+    fn seven_byte_source_on_gen3_or_gen4() {
+        for gen in [MagicGeneration::Gen3, MagicGeneration::Gen4GTU] {
+            let result = check_compatibility(7, false, Some(gen.clone()), &blank(4, 0x08));
+            assert!(result.compatible, "{:?}", gen);
+        }
+    }
+
+    #[test]
+    fn seven_byte_source_warned_at_blank_detection() {
+        let gen1a = MagicGeneration::Gen1a;
+        let warning = early_uid_length_warning(Some(7), Some(&gen1a), Some(&blank(4, 0x08)))
+            .expect("4-byte Gen1a can't take a 7-byte UID");
+        assert!(warning.contains("4-byte") && warning.contains('7'), "{}", warning);
+        let gen4 = MagicGeneration::Gen4GTU;
+        assert!(early_uid_length_warning(Some(7), Some(&gen4), Some(&blank(4, 0x08))).is_none());
+        // No scan or no magic detected: nothing to compare yet
+        assert!(early_uid_length_warning(None, Some(&gen1a), Some(&blank(4, 0x08))).is_none());
+        assert!(early_uid_length_warning(Some(7), None, Some(&blank(4, 0x08))).is_none());
+    }
+
+    #[test]
+    fn four_byte_source_on_matching_gen1a() {
+        let result = check_compatibility(4, false, Some(MagicGeneration::Gen1a), &blank(4, 0x08));
+        assert!(result.compatible);
+        assert_eq!(result.blank_type, Some(BlankType::MagicMifareGen1a));
+    }
+
+    #[test]
+    fn classic_4k_needs_4k_blank() {
+        let result = check_compatibility(4, true, Some(MagicGeneration::Gen1a), &blank(4, 0x08));
+        assert!(!result.compatible);
+        assert!(result.reason.contains("4K"));
+        assert!(check_compatibility(4, true, Some(MagicGeneration::Gen1a), &blank(4, 0x18)).compatible);
+        assert!(!check_compatibility(4, true, Some(MagicGeneration::Gen3), &blank(4, 0x08)).compatible);
+    }
+
+    #[test]
+    fn em4100_on_lf_blank_allowed() {
+        assert!(check_clone_target(&CardType::EM4100, &BlankType::T5577).is_ok());
+        assert!(check_clone_target(&CardType::EM4100, &BlankType::EM4305).is_ok());
+        assert!(check_clone_target(&CardType::MifareClassic1K, &BlankType::MagicMifareGen2).is_ok());
+    }
+
+    #[test]
+    fn em4100_on_hf_blank_rejected() {
+        let err = check_clone_target(&CardType::EM4100, &BlankType::MagicUltralight).unwrap_err();
+        assert!(err.contains("can't emulate EM4100 at 125 kHz"), "{}", err);
+        assert!(err.contains("T5577 or EM4305"), "{}", err);
+
+        let err = check_clone_target(&CardType::MifareClassic1K, &BlankType::T5577).unwrap_err();
+        assert!(err.contains("125 kHz blank"), "{}", err);
+
+        // EM4305 only for types that take --em
+        assert!(check_clone_target(&CardType::Motorola, &BlankType::EM4305).is_err());
+    }
+
+    #[test]
+    fn hid_prox_on_iclass_blank_explained() {
+        let err = check_clone_target(&CardType::HIDProx, &BlankType::IClassBlank).unwrap_err();
+        assert!(err.contains("125 kHz") && err.contains("13.56 MHz"), "{}", err);
+        assert!(err.contains("SIO"), "{}", err);
+        assert!(err.contains("T5577"), "{}", err);
+        // Other HF blanks keep the generic frequency message
+        let err = check_clone_target(&CardType::HIDProx, &BlankType::MagicMifareGen1a).unwrap_err();
+        assert!(!err.contains("SIO"), "{}", err);
+    }
+
+    #[test]
+    fn atqa_decides_fixed_uid_length() {
+        let cuid = |atqa: u16, sak: u8| Iso14aIdentity {
+            uid_len: 4,
+            atqa: Some(atqa),
+            sak: Some(sak),
+        };
+        // 1K single-size, 4K single-size, 1K double-size, 4K double-size
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen2, &cuid(0x0004, 0x08)), vec![4]);
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen2, &cuid(0x0002, 0x18)), vec![4]);
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen2, &cuid(0x0044, 0x08)), vec![7]);
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen1a, &cuid(0x0042, 0x18)), vec![7]);
+        // Programmable generations ignore the current ATQA
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen3, &cuid(0x0044, 0x08)), vec![4, 7]);
+        // No ATQA in the output: fall back to the UID length on the reader
+        assert_eq!(blank_uid_lengths(&MagicGeneration::Gen2, &blank(7, 0x08)), vec![7]);
+    }
+
+    #[test]
+    fn four_byte_source_on_seven_byte_only_gen2() {
+        // Block 0 was rewritten to a 4-byte UID, but the silicon is double-size
+        let blank = Iso14aIdentity { uid_len: 4, atqa: Some(0x0044), sak: Some(0x08) };
+        let err = check_uid_length(4, &MagicGeneration::Gen2, &blank).unwrap_err();
+        assert!(err.contains("only supports 7-byte"), "{}", err);
+        assert!(!check_compatibility(4, false, Some(MagicGeneration::Gen2), &blank).compatible);
+        assert!(check_uid_length(7, &MagicGeneration::Gen2, &blank).is_ok());
+    }
+
+    #[test]
+    fn non_magic_blank_rejected() {
+        let result = check_compatibility(4, false, None, &blank(4, 0x08));
+        assert!(!result.compatible);
+        assert_eq!(result.generation, None);
+    }
+
+    #[test]
+    fn zuid_and_onuid_cuid_use_gen1a_workflow() {
+        let zuid = "[+] Magic capabilities... Gen 2 / CUID\n\
+                    [+] Magic capabilities... Gen 4 GDM / USCUID ( ZUID Gen1 Magic Wakeup )";
+        assert_eq!(
+            write_workflow(&BlankType::MagicMifareGen2, zuid),
+            BlankType::MagicMifareGen1a
+        );
+        let onuid = "[+]  UID: 7D E9 25 4E   ( ONUID, re-used )\n\
+                     [+] Magic capabilities... Gen 1a\n\
+                     [+] Magic capabilities... Gen 2 / CUID";
+        assert_eq!(
+            write_workflow(&BlankType::MagicMifareGen2, onuid),
+            BlankType::MagicMifareGen1a
+        );
+        // Plain CUID keeps the Gen2 sequence; other blanks are left alone
+        let cuid = "[+]  UID: 01 02 03 04\n[+] Magic capabilities... Gen 2 / CUID";
+        assert_eq!(write_workflow(&BlankType::MagicMifareGen2, cuid), BlankType::MagicMifareGen2);
+        assert_eq!(
+            write_workflow(&BlankType::MagicMifareGen4GDM, zuid),
+            BlankType::MagicMifareGen4GDM
+        );
+    }
+
+    #[test]
+    fn write_test_verdicts() {
+        let original = [1, 2, 3, 4, 4, 8, 4, 0, 0, 0, 0, 0, 0, 0, 0xBE, 0xEF];
+        let pattern = write_test_pattern(&original);
+        assert_eq!(pattern[15], 0x10);
+        assert_eq!(pattern[..15], original[..15]);
+
+        // Genuine card: the write bounced, nothing to restore
+        assert_eq!(
+            write_test_verdict(&original, Some(original), None),
+            WriteTestVerdict::NotWritable
+        );
+        assert_eq!(
+            write_test_verdict(&original, Some(pattern), Some(original)),
+            WriteTestVerdict::Gen2
+        );
+        // Readback lost after the write, but the restore was confirmed
+        assert_eq!(
+            write_test_verdict(&original, None, Some(original)),
+            WriteTestVerdict::Inconclusive
+        );
+        // Restore not confirmed, whatever the write did
+        assert_eq!(
+            write_test_verdict(&original, Some(pattern), Some(pattern)),
+            WriteTestVerdict::RestoreFailed
+        );
+        assert_eq!(
+            write_test_verdict(&original, Some(pattern), None),
+            WriteTestVerdict::RestoreFailed
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cards::types::{BlankType, CardType};
+use crate::db::models::LastOperation;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::pm3::operation;
+use crate::state::WizardState;
+
+// End-of-clone report shared by the LF and HF paths. The write command opens
+// it, the verify command joins it, and every PM3 command and warning from
+// those two operations is recorded; anything else running meanwhile (a scan
+// from another window, a raw terminal command) is not. Verification, or a
+// write that fails, closes it.
+
+/// What a finished clone did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSummary {
+    pub source_type: CardType,
+    pub source_uid: String,
+    pub blank_type: BlankType,
+    pub commands_run: Vec<String>,
+    /// Written without error and, where it was checked, read back matching.
+    pub success: bool,
+    /// A readback ran and produced a verdict.
+    pub verified: bool,
+    pub duration_ms: u64,
+    pub warnings: Vec<String>,
+    /// Bundle of the source card archived before the write, if one was kept.
+    #[serde(default)]
+    pub source_dump: Option<String>,
+}
+
+impl OperationSummary {
+    /// Warnings as a clone_log note, `None` when there were none.
+    pub fn notes(&self) -> Option<String> {
+        if self.warnings.is_empty() {
+            return None;
+        }
+        let mut notes = self.warnings.join("; ");
+        if notes.len() > MAX_NOTES_LEN {
+            let mut end = MAX_NOTES_LEN - 3;
+            while !notes.is_char_boundary(end) {
+                end -= 1;
+            }
+            notes.truncate(end);
+            notes.push_str("...");
+        }
+        Some(notes)
+    }
+}
+
+/// `save_clone_record` rejects longer notes.
+const MAX_NOTES_LEN: usize = 1000;
+
+struct Draft {
+    summary: OperationSummary,
+    started: Instant,
+    /// Operations (see `operation::run_operation`) recording into this draft.
+    operations: Vec<u64>,
+}
+
+impl Draft {
+    fn records(&self, operation_id: Option<u64>) -> bool {
+        operation_id.is_some_and(|id| self.operations.contains(&id))
+    }
+}
+
+/// Summary of the clone in progress plus the last finished one. Stored via
+/// `app.manage()` in `lib.rs`.
+pub struct CloneTracker {
+    current: Mutex<Option<Draft>>,
+    last: Mutex<Option<OperationSummary>>,
+}
+
+impl CloneTracker {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Start a summary for the write running as `operation_id`, dropping any
+    /// clone that never reached verification.
+    pub fn begin(
+        &self,
+        source_type: CardType,
+        source_uid: &str,
+        blank_type: BlankType,
+        operation_id: Option<u64>,
+    ) {
+        let draft = Draft {
+            summary: OperationSummary {
+                source_type,
+                source_uid: source_uid.to_string(),
+                blank_type,
+                commands_run: Vec::new(),
+                success: false,
+                verified: false,
+                duration_ms: 0,
+                warnings: Vec::new(),
+                source_dump: None,
+            },
+            started: Instant::now(),
+            operations: operation_id.into_iter().collect(),
+        };
+        if let Ok(mut current) = self.current.lock() {
+            *current = Some(draft);
+        }
+    }
+
+    /// Record into the open summary from `operation_id` too (the verify
+    /// command of the clone being summarised).
+    pub fn join(&self, operation_id: Option<u64>) {
+        if let Ok(mut current) = self.current.lock() {
+            if let (Some(draft), Some(id)) = (current.as_mut(), operation_id) {
+                if !draft.operations.contains(&id) {
+                    draft.operations.push(id);
+                }
+            }
+        }
+    }
+
+    /// No-op unless a clone is open and `operation_id` is one of its own.
+    pub fn record_command(&self, cmd: &str, operation_id: Option<u64>) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(draft) = current.as_mut().filter(|d| d.records(operation_id)) {
+                draft.summary.commands_run.push(cmd.to_string());
+            }
+        }
+    }
+
+    pub fn warn(&self, warning: &str, operation_id: Option<u64>) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(draft) = current.as_mut().filter(|d| d.records(operation_id)) {
+                if !draft.summary.warnings.iter().any(|w| w == warning) {
+                    draft.summary.warnings.push(warning.to_string());
+                }
+            }
+        }
+    }
+
+    /// Attach the archived source bundle to the open summary.
+    pub fn set_source_dump(&self, path: &str) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(draft) = current.as_mut() {
+                draft.summary.source_dump = Some(path.to_string());
+            }
+        }
+    }
+
+    /// Snapshot of the open summary.
+    pub fn current(&self) -> Option<OperationSummary> {
+        let current = self.current.lock().ok()?;
+        current.as_ref().map(|draft| draft.summary.clone())
+    }
+
+    /// Close the open summary. `None` if no clone was open.
+    pub fn finish(&self, success: bool, verified: bool) -> Option<OperationSummary> {
+        let draft = self.current.lock().ok()?.take()?;
+        let summary = OperationSummary {
+            success,
+            verified,
+            duration_ms: draft.started.elapsed().as_millis() as u64,
+            ..draft.summary
+        };
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(summary.clone());
+        }
+        Some(summary)
+    }
+
+    /// Close the open summary as failed unless the write left the clone
+    /// ready to verify: it returned an error or ended in the Error state.
+    pub fn end_write(
+        &self,
+        result: &Result<WizardState, AppError>,
+    ) -> Option<OperationSummary> {
+        let verifiable = result
+            .as_ref()
+            .is_ok_and(|state| !matches!(state, WizardState::Error { .. }));
+        if verifiable {
+            return None;
+        }
+        self.finish(false, false)
+    }
+
+    /// Most recently finished summary.
+    pub fn last(&self) -> Option<OperationSummary> {
+        self.last.lock().ok()?.clone()
+    }
+}
+
+/// Open a summary for the write running on this task.
+pub fn begin(app: &AppHandle, source_type: &CardType, source_uid: &str, blank_type: &BlankType) {
+    if let Some(tracker) = app.try_state::<CloneTracker>() {
+        tracker.begin(
+            source_type.clone(),
+            source_uid,
+            blank_type.clone(),
+            operation::current_operation_id(),
+        );
+        if let Some(summary) = tracker.current() {
+            persist(app, summary, false);
+        }
+    }
+}
+
+/// Keep `summary` as the last operation, so it survives a restart.
+fn persist(app: &AppHandle, summary: OperationSummary, finished: bool) {
+    let Some(db) = app.try_state::<Database>() else {
+        return;
+    };
+    let op = LastOperation {
+        summary,
+        finished,
+        updated_at: chrono::Local::now().to_rfc3339(),
+    };
+    if let Err(e) = db.set_last_operation(&op) {
+        log::warn!("Could not save the last operation: {}", e);
+    }
+}
+
+/// Let the verify command running on this task record into the open summary.
+pub fn join(app: &AppHandle) {
+    if let Some(tracker) = app.try_state::<CloneTracker>() {
+        tracker.join(operation::current_operation_id());
+    }
+}
+
+pub fn record_command(app: &AppHandle, cmd: &str) {
+    if let Some(tracker) = app.try_state::<CloneTracker>() {
+        tracker.record_command(cmd, operation::current_operation_id());
+    }
+}
+
+pub fn warn(app: &AppHandle, warning: &str) {
+    log::warn!("{}", warning);
+    if let Some(tracker) = app.try_state::<CloneTracker>() {
+        tracker.warn(warning, operation::current_operation_id());
+    }
+}
+
+pub fn set_source_dump(app: &AppHandle, path: &str) {
+    if let Some(tracker) = app.try_state::<CloneTracker>() {
+        tracker.set_source_dump(path);
+    }
+}
+
+/// Close the summary, log it and emit it as `operation-summary`.
+pub fn finish(app: &AppHandle, success: bool, verified: bool) -> Option<OperationSummary> {
+    let summary = app.try_state::<CloneTracker>()?.finish(success, verified)?;
+    Some(report(app, summary))
+}
+
+/// End of the write command: close the summary unless the clone goes on to
+/// verification (see `CloneTracker::end_write`).
+pub fn end_write(app: &AppHandle, result: &Result<WizardState, AppError>) {
+    if let Some(summary) = app.try_state::<CloneTracker>().and_then(|t| t.end_write(result)) {
+        report(app, summary);
+    }
+}
+
+fn report(app: &AppHandle, summary: OperationSummary) -> OperationSummary {
+    log::info!(
+        "Clone {:?} {} -> {:?}: success={} verified={} {}ms, {} commands, warnings: {:?}",
+        summary.source_type,
+        summary.source_uid,
+        summary.blank_type,
+        summary.success,
+        summary.verified,
+        summary.duration_ms,
+        summary.commands_run.len(),
+        summary.warnings
+    );
+    persist(app, summary.clone(), true);
+    let _ = app.emit(
+        "operation-summary",
+        serde_json::json!({
+            "summary": summary,
+            "operation_id": operation::current_operation_id(),
+        }),
+    );
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cards::types::RecoveryAction;
+
+    /// Run `f` as its own command invocation, with the ID the module-level
+    /// helpers would pass.
+    async fn in_operation(f: impl FnOnce(Option<u64>)) {
+        let run = async {
+            f(operation::current_operation_id());
+            Ok(())
+        };
+        operation::run_operation(run).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn records_only_the_clones_operations() {
+        let tracker = CloneTracker::new();
+        in_operation(|op| {
+            tracker.record_command("lf search", op);
+            tracker.warn("ignored", op);
+        })
+        .await;
+        assert_eq!(tracker.finish(true, true), None);
+
+        // Write
+        in_operation(|op| {
+            tracker.begin(CardType::EM4100, "0F00112233", BlankType::T5577, op);
+            tracker.record_command("lf t55xx detect", op);
+            tracker.warn("Source CRC check failed", op);
+            tracker.warn("Source CRC check failed", op);
+        })
+        .await;
+        // Another window scanning meanwhile, and a command outside any operation
+        in_operation(|op| {
+            tracker.record_command("hf search", op);
+            tracker.warn("Unrelated scan warning", op);
+        })
+        .await;
+        tracker.record_command("hw status", None);
+        // Verify
+        in_operation(|op| {
+            tracker.join(op);
+            tracker.record_command("lf search", op);
+        })
+        .await;
+
+        let summary = tracker.finish(false, false).unwrap();
+        assert_eq!(summary.commands_run, vec!["lf t55xx detect", "lf search"]);
+        assert_eq!(summary.warnings, vec!["Source CRC check failed"]);
+        assert_eq!(summary.notes().as_deref(), Some("Source CRC check failed"));
+        assert_eq!(tracker.last(), Some(summary));
+        assert_eq!(tracker.finish(true, true), None);
+    }
+
+    #[test]
+    fn failed_write_closes_the_summary() {
+        let tracker = CloneTracker::new();
+        tracker.begin(CardType::EM4100, "0F00112233", BlankType::T5577, Some(1));
+        assert_eq!(tracker.end_write(&Ok(WizardState::Verifying)), None);
+        assert!(tracker.current().is_some());
+
+        let failed = tracker.end_write(&Err(AppError::CommandFailed("port lost".into())));
+        let failed = failed.expect("an errored write closes the summary");
+        assert!(!failed.success && !failed.verified);
+        assert!(tracker.current().is_none());
+
+        tracker.begin(CardType::EM4100, "0F00112233", BlankType::T5577, Some(2));
+        let error = WizardState::Error {
+            message: "T5577 not detected".into(),
+            user_message: "No blank found.".into(),
+            recoverable: true,
+            recovery_action: Some(RecoveryAction::Retry),
+        };
+        assert!(tracker.end_write(&Ok(error)).is_some());
+        assert!(tracker.current().is_none());
+    }
+
+    #[test]
+    fn long_notes_truncated() {
+        let tracker = CloneTracker::new();
+        tracker.begin(CardType::EM4100, "0F00112233", BlankType::T5577, Some(1));
+        for i in 0..100 {
+            tracker.warn(&format!("warning number {} of many", i), Some(1));
+        }
+        let notes = tracker.finish(true, true).unwrap().notes().unwrap();
+        assert_eq!(notes.len(), MAX_NOTES_LEN);
+        assert!(notes.ends_with("..."));
+    }
+}
@@ -2,7 +2,9 @@
 /// All commands assume the Iceman fork with `-f` flag for subprocess piping.
 
 use crate::cards::types::{BlankType, CardType};
+use crate::pm3::dump_report;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
 /// Validates that a string contains only hex characters.
@@ -46,6 +48,298 @@ fn validate_hex_or_colon(value: &str, field_name: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Noralsy stores the card ID as 7 BCD digits.
+const NORALSY_MAX_CN: u32 = 9_999_999;
+/// Noralsy only keeps the last two year digits; PM3 expands them to 19xx/20xx.
+const NORALSY_YEAR_RANGE: std::ops::RangeInclusive<u32> = 1900..=2099;
+
+fn validate_noralsy(cn: &str, year: Option<&str>) -> Result<(u32, Option<u32>), String> {
+    let cn_n = cn
+        .parse::<u32>()
+        .ok()
+        .filter(|n| *n <= NORALSY_MAX_CN)
+        .ok_or_else(|| {
+            format!(
+                "Invalid Noralsy card number: must be 0-{}, got '{}'",
+                NORALSY_MAX_CN, cn
+            )
+        })?;
+    let year_n = match year {
+        Some(y) => Some(
+            y.parse::<u32>()
+                .ok()
+                .filter(|n| y.len() == 4 && NORALSY_YEAR_RANGE.contains(n))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid Noralsy year: must be {}-{}, got '{}'",
+                        NORALSY_YEAR_RANGE.start(),
+                        NORALSY_YEAR_RANGE.end(),
+                        y
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    Ok((cn_n, year_n))
+}
+
+/// AWID facility code / card number bit widths per Wiegand format, as packed
+/// by PM3's `lf awid clone`. Unknown formats are left for PM3 to reject.
+fn awid_field_bits(fmt: u32) -> Option<(u32, u32)> {
+    match fmt {
+        26 => Some((8, 16)),
+        34 => Some((8, 24)),
+        37 => Some((13, 18)),
+        50 => Some((16, 32)),
+        _ => None,
+    }
+}
+
+/// Card numbers are parsed as u64: the 50-bit format uses the full 32-bit CN
+/// range and must not be squeezed through narrower 26-bit assumptions.
+fn validate_awid(fc: &str, cn: &str, fmt: Option<u32>) -> Result<(u32, u64), String> {
+    let fc_n = fc
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid AWID facility code: '{}'", fc))?;
+    let cn_n = cn
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid AWID card number: '{}'", cn))?;
+    if let Some((fc_bits, cn_bits)) = fmt.and_then(awid_field_bits) {
+        let max_fc = (1u64 << fc_bits) - 1;
+        let max_cn = (1u64 << cn_bits) - 1;
+        if u64::from(fc_n) > max_fc {
+            return Err(format!(
+                "AWID {}-bit facility code must be 0-{}, got {}",
+                fmt.unwrap_or_default(), max_fc, fc_n
+            ));
+        }
+        if cn_n > max_cn {
+            return Err(format!(
+                "AWID {}-bit card number must be 0-{}, got {}",
+                fmt.unwrap_or_default(), max_cn, cn_n
+            ));
+        }
+    }
+    Ok((fc_n, cn_n))
+}
+
+/// Raw frame length in bits that `lf <type> clone --raw` expects. Paradox and
+/// G-Prox II are 96-bit frames; Pyramid carries its 26-bit Wiegand payload in a
+/// 128-bit frame; Motorola is 64 bits.
+fn expected_raw_bits(card_type: &CardType) -> Option<usize> {
+    match card_type {
+        CardType::Paradox | CardType::GProxII => Some(96),
+        CardType::Pyramid => Some(128),
+        CardType::Motorola => Some(64),
+        _ => None,
+    }
+}
+
+/// Reject a raw value of the wrong length for its type. A marginal read often
+/// drops or repeats nibbles, and PM3's own error for that is cryptic.
+fn validate_raw_length(card_type: &CardType, raw: &str) -> Result<(), String> {
+    let Some(bits) = expected_raw_bits(card_type) else {
+        return Ok(());
+    };
+    let expected = bits / 4;
+    let got = raw.chars().filter(|c| *c != ':').count();
+    let problem = match got.cmp(&expected) {
+        std::cmp::Ordering::Less => "too short",
+        std::cmp::Ordering::Greater => "too long",
+        std::cmp::Ordering::Equal => return Ok(()),
+    };
+    Err(format!(
+        "Raw {} for {}: expected {} bits ({} hex chars), got {} hex chars. Try re-reading the card.",
+        problem,
+        card_type.display_name(),
+        bits,
+        expected,
+        got
+    ))
+}
+
+/// FC and CN both present and numeric, so a structured clone is used over raw.
+fn has_numeric_fc_cn(decoded: &std::collections::HashMap<String, String>) -> bool {
+    matches!(
+        (decoded.get("facility_code"), decoded.get("card_number")),
+        (Some(fc), Some(cn)) if fc.parse::<u32>().is_ok() && cn.parse::<u32>().is_ok()
+    )
+}
+
+/// Check the decoded fields a clone command is built from, so callers can show
+/// why a card can't be cloned instead of a generic "not supported" error.
+/// Types without field-level rules always pass.
+pub fn validate_clone_fields(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+) -> Result<(), String> {
+    match card_type {
+        CardType::Noralsy => {
+            let cn = decoded.get("card_number").map(|s| s.as_str()).unwrap_or(uid);
+            let year = decoded.get("year").map(|s| s.as_str());
+            validate_noralsy(cn, year).map(|_| ())
+        }
+        CardType::AWID => match (decoded.get("facility_code"), decoded.get("card_number")) {
+            (Some(fc), Some(cn)) => {
+                let fmt = decoded.get("format").and_then(|f| f.parse::<u32>().ok());
+                validate_awid(fc, cn, fmt).map(|_| ())
+            }
+            _ => Ok(()),
+        },
+        // Raw length only matters when the clone falls back to `--raw`
+        CardType::Paradox if !has_numeric_fc_cn(decoded) => validate_raw_length(card_type, uid),
+        CardType::Pyramid if !has_numeric_fc_cn(decoded) => match decoded.get("raw") {
+            Some(raw) => validate_raw_length(card_type, raw),
+            None => Ok(()),
+        },
+        CardType::Motorola => {
+            let raw = decoded.get("raw").map(|s| s.as_str()).unwrap_or(uid);
+            validate_raw_length(card_type, raw)
+        }
+        CardType::GProxII if decoded.contains_key("raw_fallback") => {
+            validate_raw_length(card_type, uid)
+        }
+        CardType::Keri => {
+            let cn = decoded.get("card_number").map(|s| s.as_str()).unwrap_or(uid);
+            validate_keri_cn(cn).map(|_| ())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Why no clone command can be built from a card's decoded fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloneBuildError {
+    /// Fields the clone command needs that the card doesn't have.
+    Missing(Vec<&'static str>),
+    /// A field is present but its clone flag can't take the value.
+    Malformed {
+        field: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    /// A value PM3 would refuse or silently truncate (range, raw length).
+    Rejected(String),
+    /// The type has no single clone command.
+    Unsupported,
+}
+
+impl CloneBuildError {
+    /// The reason for the user, e.g. "missing region_code for Gallagher".
+    pub fn describe(&self, card_type: &CardType) -> String {
+        let name = card_type.display_name();
+        match self {
+            Self::Missing(fields) => format!("missing {} for {}", fields.join(", "), name),
+            Self::Malformed { field, value, expected } => {
+                format!("{} for {} must be {}, got '{}'", field, name, expected, value)
+            }
+            Self::Rejected(reason) => reason.clone(),
+            Self::Unsupported => format!("{} has no clone command", name),
+        }
+    }
+}
+
+/// A field set the clone command couldn't use, with how many of its fields
+/// the card had, to report against the set it got furthest with.
+struct FieldSetError {
+    error: CloneBuildError,
+    found: usize,
+}
+
+impl FieldSetError {
+    /// `self` (the preferred set) unless `other` had more of its fields.
+    fn closer(self, other: FieldSetError) -> FieldSetError {
+        if other.found > self.found {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl From<FieldSetError> for CloneBuildError {
+    fn from(e: FieldSetError) -> Self {
+        e.error
+    }
+}
+
+/// A single value standing in for a field set (UID fallbacks).
+fn rejected(reason: String) -> FieldSetError {
+    FieldSetError { error: CloneBuildError::Rejected(reason), found: 1 }
+}
+
+/// `fields` of `decoded`, or which of them are missing.
+fn present_fields<'a, const N: usize>(
+    decoded: &'a std::collections::HashMap<String, String>,
+    fields: [&'static str; N],
+) -> Result<[&'a str; N], FieldSetError> {
+    let missing: Vec<&'static str> =
+        fields.iter().copied().filter(|f| !decoded.contains_key(*f)).collect();
+    if !missing.is_empty() {
+        let found = N - missing.len();
+        return Err(FieldSetError { error: CloneBuildError::Missing(missing), found });
+    }
+    Ok(fields.map(|f| decoded[f].as_str()))
+}
+
+/// `fields` of `decoded` as decimal numbers.
+fn decimal_fields<const N: usize>(
+    decoded: &std::collections::HashMap<String, String>,
+    fields: [&'static str; N],
+) -> Result<[u32; N], FieldSetError> {
+    let values = present_fields(decoded, fields)?;
+    let mut numbers = [0u32; N];
+    for ((n, field), value) in numbers.iter_mut().zip(fields).zip(values) {
+        *n = value.parse().map_err(|_| FieldSetError {
+            error: CloneBuildError::Malformed {
+                field,
+                value: value.to_string(),
+                expected: "a decimal number",
+            },
+            found: N,
+        })?;
+    }
+    Ok(numbers)
+}
+
+/// `decoded["raw"]` if it's hex of the length `card_type` expects.
+fn raw_field<'a>(
+    card_type: &CardType,
+    decoded: &'a std::collections::HashMap<String, String>,
+) -> Result<&'a str, FieldSetError> {
+    let [raw] = present_fields(decoded, ["raw"])?;
+    if validate_hex(raw, "raw").is_err() {
+        let error =
+            CloneBuildError::Malformed { field: "raw", value: raw.to_string(), expected: "hex" };
+        return Err(FieldSetError { error, found: 1 });
+    }
+    validate_raw_length(card_type, raw).map_err(rejected)?;
+    Ok(raw)
+}
+
+fn validate_keri_cn(cn: &str) -> Result<u32, String> {
+    cn.parse::<u32>().map_err(|_| {
+        format!(
+            "Keri card number must be decimal, got '{}'. Re-read the card to get its ID.",
+            cn
+        )
+    })
+}
+
+/// Check `decoded` carries every field `build_clone_command` needs for
+/// `card_type`, naming the missing or malformed one ("missing region_code
+/// for Gallagher") so the write can be refused before the device is touched.
+pub fn check_required_fields(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+) -> Result<(), String> {
+    build_base_clone_command(card_type, uid, decoded, false)
+        .map(|_| ())
+        .map_err(|e| e.describe(card_type))
+}
+
 /// Allowed HID Wiegand format strings.
 const VALID_HID_FORMATS: &[&str] = &["H10301", "H10302", "H10304", "Corp1000"];
 
@@ -57,10 +351,64 @@ fn validate_hid_format(format: &str) -> bool {
 // Device / search commands
 // ---------------------------------------------------------------------------
 
+/// Client preferences; the header names the user data dir.
+pub fn build_prefs_show() -> &'static str {
+    "prefs show"
+}
+
 pub fn build_lf_search() -> &'static str {
     "lf search"
 }
 
+/// Report every HID Prox badge presented until cancelled.
+pub fn build_lf_hid_watch() -> &'static str {
+    "lf hid watch"
+}
+
+/// One antenna measurement: powers the LF field up before a write on a weak
+/// antenna. Without `--iter` it runs until the button is pressed.
+pub fn build_lf_tune() -> &'static str {
+    "lf tune --iter 1"
+}
+
+/// Client-side pause, for spacing commands run in one PM3 invocation.
+pub fn build_msleep(ms: u64) -> String {
+    format!("msleep -t {}", ms)
+}
+
+// ---------------------------------------------------------------------------
+// Sample buffer capture
+// ---------------------------------------------------------------------------
+
+/// Sample the LF field into the graph buffer without demodulating it.
+pub fn build_lf_read() -> &'static str {
+    "lf read"
+}
+
+/// Quoted `-f` argument for the `data` buffer commands.
+fn buffer_file_arg(path: &str) -> Result<String, String> {
+    if path.trim().is_empty() || path.chars().any(|c| matches!(c, '"' | ';' | '\n' | '\r')) {
+        return Err(format!("Invalid buffer file path '{}'", path));
+    }
+    Ok(format!("-f \"{}\"", path))
+}
+
+/// Save the graph buffer (the samples of the last `lf read`) to `path`.
+pub fn build_data_save(path: &str) -> Result<String, String> {
+    Ok(format!("data save {}", buffer_file_arg(path)?))
+}
+
+/// Load samples saved by `data save` back into the graph buffer.
+pub fn build_data_load(path: &str) -> Result<String, String> {
+    Ok(format!("data load {}", buffer_file_arg(path)?))
+}
+
+/// `lf search` over the graph buffer (e.g. a loaded capture) instead of
+/// sampling the antenna.
+pub fn build_lf_search_buffer() -> &'static str {
+    "lf search -1"
+}
+
 // ---------------------------------------------------------------------------
 // T5577 blank management
 // ---------------------------------------------------------------------------
@@ -73,6 +421,11 @@ pub fn build_t5577_chk() -> &'static str {
     "lf t55xx chk"
 }
 
+/// `lf t55xx chk` against a specific password dictionary.
+pub fn build_t5577_chk_with_dict(dictionary: &str) -> String {
+    format!("lf t55xx chk -f \"{}\"", dictionary)
+}
+
 pub fn build_t5577_wipe() -> &'static str {
     "lf t55xx wipe"
 }
@@ -83,6 +436,181 @@ pub fn build_t5577_wipe_with_password(password: &str) -> Result<String, String>
     Ok(format!("lf t55xx wipe -p {}", password))
 }
 
+/// Wipe a Q5 (T5555) blank back to its own default config.
+pub fn build_q5_wipe() -> &'static str {
+    "lf t55xx wipe --q5"
+}
+
+/// Read every block of a T55xx (page 0 config + data, page 1 traceability).
+pub fn build_t55xx_dump() -> &'static str {
+    "lf t55xx dump"
+}
+
+/// Write one page-0 block of a T55xx.
+pub fn build_t55xx_write_block(block: u8, data: &str) -> Result<String, String> {
+    if block > 7 {
+        return Err(format!("Invalid T55xx block {}: page 0 has blocks 0-7", block));
+    }
+    if data.len() != 8 {
+        return Err(format!("Invalid block data: must be 8 hex characters, got '{}'", data));
+    }
+    validate_hex(data, "block data")?;
+    Ok(format!("lf t55xx write -b {} -d {}", block, data.to_uppercase()))
+}
+
+/// Decoded key under which a T5577-hosted source's page-0 blocks are kept
+/// (comma-separated, block 0 first).
+pub const T55XX_BLOCKS_KEY: &str = "t55xx_blocks";
+
+/// Page-0 blocks of a source that is itself a T5577, if the scan dumped them.
+pub fn t55xx_source_blocks(decoded: &std::collections::HashMap<String, String>) -> Option<Vec<String>> {
+    let blocks = decoded.get(T55XX_BLOCKS_KEY)?;
+    Some(blocks.split(',').map(|b| b.trim().to_string()).collect())
+}
+
+/// Copy a T5577-hosted source verbatim: data blocks 1..=MAXBLK from its
+/// config block, then the config block itself, so the target behaves exactly
+/// like the source instead of being re-encoded by a protocol clone command.
+/// A source with password mode on is refused — its config would lock the
+/// target with a password that isn't part of the copy.
+pub fn build_t55xx_clone_raw(blocks: &[String]) -> Result<Vec<String>, String> {
+    let config = blocks.first().ok_or("No T55xx blocks to clone")?;
+    validate_hex(config, "config block")?;
+    let config_value = u32::from_str_radix(config, 16)
+        .map_err(|_| format!("Invalid config block '{}'", config))?;
+    if config_value & T55XX_PWD_BIT != 0 {
+        return Err("Source T5577 has password mode enabled; clone it by protocol instead".into());
+    }
+    let max_block = ((config_value >> 5) & 0x7) as usize;
+    if max_block == 0 || max_block >= blocks.len() {
+        return Err(format!(
+            "Config block {} uses blocks 1-{} but the dump has {} data blocks",
+            config,
+            max_block,
+            blocks.len() - 1
+        ));
+    }
+    let mut cmds = Vec::with_capacity(max_block + 1);
+    for (block, data) in blocks.iter().enumerate().take(max_block + 1).skip(1) {
+        cmds.push(build_t55xx_write_block(block as u8, data)?);
+    }
+    cmds.push(build_t55xx_write_block(0, config)?);
+    Ok(cmds)
+}
+
+/// Block 0 PWD bit: password mode on.
+pub const T55XX_PWD_BIT: u32 = 1 << 4;
+
+/// Block 0 `lf em 410x clone` writes: RF/64, Manchester, data in blocks 1-2.
+pub const T55XX_EM4100_CONFIG: u32 = 0x0014_8040;
+
+/// Lock a freshly cloned T5577 with `password`: the password into block 7,
+/// then `config` rewritten with password mode on. Refused when `config`
+/// transmits block 7, since the tag would then broadcast its own password.
+pub fn build_t55xx_set_password(config: u32, password: &str) -> Result<Vec<String>, String> {
+    validate_password(password)?;
+    let max_block = (config >> 5) & 0x7;
+    if max_block == 7 {
+        return Err(format!(
+            "Config block {:08X} transmits block 7, which would expose the password",
+            config
+        ));
+    }
+    Ok(vec![
+        build_t55xx_write_block(7, password)?,
+        build_t55xx_write_block(0, &format!("{:08X}", config | T55XX_PWD_BIT))?,
+    ])
+}
+
+/// Write `config` straight to block 0, for a modulation the protocol clone
+/// commands wouldn't choose. `password_mode` is whether the tag is in
+/// password mode now, with `password` its password. A `config` turning
+/// password mode on needs `password`: a tag already in password mode keeps
+/// its block 7, any other gets `password` written to block 7 first (see
+/// `build_t55xx_set_password`).
+pub fn build_t55xx_write_config(
+    config: &str,
+    password: Option<&str>,
+    password_mode: bool,
+) -> Result<Vec<String>, String> {
+    let cmd = build_t55xx_write_block(0, config)?;
+    let value = u32::from_str_radix(config, 16)
+        .map_err(|_| format!("Invalid config block '{}'", config))?;
+    if value & T55XX_PWD_BIT != 0 {
+        let Some(pw) = password else {
+            return Err(format!(
+                "Config block {} enables password mode; give the tag's password",
+                config.to_uppercase()
+            ));
+        };
+        if !password_mode {
+            return build_t55xx_set_password(value & !T55XX_PWD_BIT, pw);
+        }
+        if (value >> 5) & 0x7 == 7 {
+            return Err(format!(
+                "Config block {} transmits block 7, which would expose the password",
+                config.to_uppercase()
+            ));
+        }
+    }
+    match password.filter(|_| password_mode) {
+        Some(pw) => {
+            validate_password(pw)?;
+            Ok(vec![format!("{} -p {}", cmd, pw.to_uppercase())])
+        }
+        None => Ok(vec![cmd]),
+    }
+}
+
+/// `lf t55xx config` options for the bit rate and modulation `config` sets,
+/// so a read can demodulate a tag `lf t55xx detect` doesn't recognise. The
+/// client forgets them on exit: chain them with the read.
+pub fn build_t55xx_config_for_block0(config: u32) -> Result<String, String> {
+    const RATES: [u32; 8] = [8, 16, 32, 40, 50, 64, 100, 128];
+    let rate = RATES[((config >> 18) & 0x7) as usize];
+    let modulation = match (config >> 12) & 0x1F {
+        0x00 => "NRZ",
+        0x01 => "PSK1",
+        0x02 => "PSK2",
+        0x03 => "PSK3",
+        0x04 => "FSK1",
+        0x05 => "FSK2",
+        0x06 => "FSK1A",
+        0x07 => "FSK2A",
+        0x08 => "ASK",
+        0x10 => "BI",
+        0x18 => "BIA",
+        other => {
+            return Err(format!(
+                "Config block {:08X} has an unknown modulation ({:#04X})",
+                config, other
+            ))
+        }
+    };
+    Ok(format!("lf t55xx config --rate {} --{}", rate, modulation))
+}
+
+/// `lf t55xx read` of one page-0 block, with the tag's password if it's in
+/// password mode.
+pub fn build_t55xx_read_block(block: u8, password: Option<&str>) -> Result<String, String> {
+    if block > 7 {
+        return Err(format!("Invalid T55xx block {}: page 0 has blocks 0-7", block));
+    }
+    match password {
+        Some(pw) => {
+            validate_password(pw)?;
+            Ok(format!("lf t55xx read -b {} -p {}", block, pw.to_uppercase()))
+        }
+        None => Ok(format!("lf t55xx read -b {}", block)),
+    }
+}
+
+/// `lf t55xx detect` with a password, to confirm a lock took.
+pub fn build_t5577_detect_with_password(password: &str) -> Result<String, String> {
+    validate_password(password)?;
+    Ok(format!("lf t55xx detect -p {}", password.to_uppercase()))
+}
+
 // ---------------------------------------------------------------------------
 // EM4305 blank management
 // ---------------------------------------------------------------------------
@@ -108,24 +636,134 @@ pub fn build_clone_for_em4305(base_cmd: &str) -> String {
     format!("{} --em", base_cmd)
 }
 
-/// Append `-p {password}` to a base clone command for password-protected T5577.
-pub fn build_clone_with_password(base_cmd: &str, password: &str) -> Result<String, String> {
+/// Append `--q5` flag to a base clone command for Q5 (T5555) blanks.
+pub fn build_clone_for_q5(base_cmd: &str) -> String {
+    format!("{} --q5", base_cmd)
+}
+
+// ---------------------------------------------------------------------------
+// EM4x50 native clone
+// ---------------------------------------------------------------------------
+
+/// EM4x50 memory: words 0-33.
+pub const EM4X50_WORD_COUNT: usize = 34;
+/// Word 0 is the password, write-only.
+const EM4X50_PASSWORD_WORD: usize = 0;
+/// Word 1: first/last read-protected word, then first/last write-inhibited word.
+const EM4X50_PROTECTION_WORD: usize = 1;
+/// Word 2: first/last word sent in default read mode, then option flags.
+const EM4X50_CONTROL_WORD: usize = 2;
+/// User data words; 32 (serial) and 33 (device ID) are read-only.
+const EM4X50_DATA_WORDS: std::ops::RangeInclusive<usize> = 3..=31;
+/// Factory password of a blank EM4x50.
+pub const EM4X50_DEFAULT_PASSWORD: &str = "00000000";
+
+/// Read all EM4x50 words. Parse with `output_parser::parse_em4x50_dump()`.
+pub fn build_em4x50_dump() -> &'static str {
+    "lf em 4x50 dump"
+}
+
+/// Write one EM4x50 word. The password word (0) is set with `wrpwd`
+/// instead, and the serial/ID words (32, 33) are read-only.
+pub fn build_em4x50_write(word: u8, data: &str) -> Result<String, String> {
+    let word_idx = usize::from(word);
+    if word_idx == EM4X50_PASSWORD_WORD || word_idx > *EM4X50_DATA_WORDS.end() {
+        return Err(format!("Invalid EM4x50 word {}: only words 1-31 are writable", word));
+    }
+    if data.len() != 8 {
+        return Err(format!("Invalid word data: must be 8 hex characters, got '{}'", data));
+    }
+    validate_hex(data, "word data")?;
+    Ok(format!("lf em 4x50 wrbl -b {} -d {}", word, data.to_uppercase()))
+}
+
+/// `build_em4x50_write` after a login, for words the protection word
+/// inhibits without one.
+pub fn build_em4x50_write_with_password(
+    word: u8,
+    data: &str,
+    password: &str,
+) -> Result<String, String> {
     validate_password(password)?;
-    Ok(format!("{} -p {}", base_cmd, password))
+    Ok(format!("{} -p {}", build_em4x50_write(word, data)?, password.to_uppercase()))
+}
+
+/// Wipe an EM4x50 that still has the factory password.
+pub fn build_em4x50_wipe() -> String {
+    format!("lf em 4x50 wipe -p {}", EM4X50_DEFAULT_PASSWORD)
+}
+
+/// Words `[first, last]` named by bytes `at` and `at + 1` of a protection
+/// or control word (MSB-first hex, as `lf em 4x50 dump` prints it).
+fn em4x50_word_range(word: &str, at: usize) -> Option<std::ops::RangeInclusive<usize>> {
+    let byte = |i: usize| {
+        word.get(i * 2..i * 2 + 2)
+            .and_then(|b| usize::from_str_radix(b, 16).ok())
+    };
+    Some(byte(at)?..=byte(at + 1)?)
+}
+
+/// Words the protection word lets `build_em4x50_clone` copy: user data
+/// words outside the source's read-protected range (those read back as
+/// zeros without the source's password).
+pub fn em4x50_clonable_words(words: &[String]) -> Result<Vec<usize>, String> {
+    if words.len() != EM4X50_WORD_COUNT {
+        return Err(format!(
+            "EM4x50 dump has {} words, expected {}",
+            words.len(),
+            EM4X50_WORD_COUNT
+        ));
+    }
+    let protection = &words[EM4X50_PROTECTION_WORD];
+    let read_protected = em4x50_word_range(protection, 0)
+        .ok_or_else(|| format!("Invalid protection word '{}'", protection))?;
+    Ok(EM4X50_DATA_WORDS
+        .filter(|w| !read_protected.contains(w))
+        .collect())
+}
+
+/// Commands that clone a dumped EM4x50 (`words`, 0-33) onto an EM4x50 with
+/// the factory password: wipe, write the readable user data words, then
+/// the control word so the clone sends the same words in default read mode.
+/// The password and protection words are never copied — the source's would
+/// lock or write-inhibit the target with values that aren't in the dump.
+pub fn build_em4x50_clone(words: &[String]) -> Result<Vec<String>, String> {
+    let data_words = em4x50_clonable_words(words)?;
+    if data_words.is_empty() {
+        return Err("Every EM4x50 data word is read-protected; nothing to clone".into());
+    }
+    let mut cmds = Vec::with_capacity(data_words.len() + 2);
+    cmds.push(build_em4x50_wipe());
+    for w in data_words {
+        cmds.push(build_em4x50_write(w as u8, &words[w])?);
+    }
+    cmds.push(build_em4x50_write_with_password(
+        EM4X50_CONTROL_WORD as u8,
+        &words[EM4X50_CONTROL_WORD],
+        EM4X50_DEFAULT_PASSWORD,
+    )?);
+    Ok(cmds)
 }
 
 // ---------------------------------------------------------------------------
 // LF clone commands — original 11 types (improved)
 // ---------------------------------------------------------------------------
 
+/// Clocks `lf em 410x clone --clk` accepts.
+const EM410X_CLONE_CLOCKS: [u16; 4] = [16, 32, 40, 64];
+
 pub fn build_em4100_clone(id: &str) -> String {
     format!("lf em 410x clone --id {}", id)
 }
 
 /// HID clone using detected Wiegand format (defaults to H10301 / 26-bit).
-pub fn build_hid_clone(fc: u32, cn: u32, format: Option<&str>) -> String {
+/// `fc` is `None` for formats without a facility code (H10302).
+pub fn build_hid_clone(fc: Option<u32>, cn: u32, format: Option<&str>) -> String {
     let wiegand = format.unwrap_or("H10301");
-    format!("lf hid clone -w {} --fc {} --cn {}", wiegand, fc, cn)
+    match fc {
+        Some(fc) => format!("lf hid clone -w {} --fc {} --cn {}", wiegand, fc, cn),
+        None => format!("lf hid clone -w {} --cn {}", wiegand, cn),
+    }
 }
 
 pub fn build_hid_clone_raw(raw: &str) -> String {
@@ -136,6 +774,11 @@ pub fn build_indala_clone(raw: &str) -> String {
     format!("lf indala clone --raw {}", raw)
 }
 
+/// Indala 26-bit clone from FC/CN (PM3 re-encodes the 64-bit frame).
+pub fn build_indala_clone_fc_cn(fc: u32, cn: u32) -> String {
+    format!("lf indala clone --fc {} --cn {}", fc, cn)
+}
+
 /// IO Prox clone with version number support.
 pub fn build_ioprox_clone(fc: u32, cn: u32, vn: u32) -> String {
     format!("lf io clone --vn {} --fc {} --cn {}", vn, fc, cn)
@@ -146,7 +789,7 @@ pub fn build_ioprox_clone_raw(raw: &str) -> String {
 }
 
 /// AWID clone with format support (26/34/37/50 bit).
-pub fn build_awid_clone(fc: u32, cn: u32, fmt: Option<u32>) -> String {
+pub fn build_awid_clone(fc: u32, cn: u64, fmt: Option<u32>) -> String {
     match fmt {
         Some(f) => format!("lf awid clone --fmt {} --fc {} --cn {}", f, fc, cn),
         None => format!("lf awid clone --fc {} --cn {}", fc, cn),
@@ -165,7 +808,8 @@ pub fn build_fdxb_clone_raw(raw: &str) -> String {
     format!("lf fdxb clone --raw {}", raw)
 }
 
-/// Paradox clone with FC/CN (preferred over raw).
+/// Paradox clone with FC/CN (preferred over raw unless the caller asks for
+/// raw, see `build_clone_command_with`).
 pub fn build_paradox_clone(fc: u32, cn: u32) -> String {
     format!("lf paradox clone --fc {} --cn {}", fc, cn)
 }
@@ -238,6 +882,11 @@ pub fn build_gproxii_clone(xor: u32, fmt: u32, fc: u32, cn: u32) -> String {
     )
 }
 
+/// GProxII clone from the raw 96-bit frame, for reads where FC/CN didn't decode.
+pub fn build_gproxii_clone_raw(raw: &str) -> String {
+    format!("lf gproxii clone --raw {}", raw)
+}
+
 /// Gallagher clone with region, facility, card number, issue level.
 pub fn build_gallagher_clone(rc: u32, fc: u32, cn: u32, il: u32) -> String {
     format!(
@@ -257,7 +906,7 @@ pub fn build_pac_clone_raw(raw: &str) -> String {
 
 /// Noralsy clone with card number and optional year.
 /// PM3 `lf noralsy clone` uses --cn (card ID, decimal) and -y (year, optional).
-pub fn build_noralsy_clone(cn: &str, year: Option<&str>) -> String {
+pub fn build_noralsy_clone(cn: u32, year: Option<u32>) -> String {
     match year {
         Some(y) => format!("lf noralsy clone --cn {} -y {}", cn, y),
         None => format!("lf noralsy clone --cn {}", cn),
@@ -300,71 +949,134 @@ pub fn build_clone_command(
     uid: &str,
     decoded: &std::collections::HashMap<String, String>,
 ) -> Option<String> {
+    build_clone_command_with(card_type, uid, decoded, false)
+}
+
+/// `build_clone_command`, optionally cloning from the full raw frame even when
+/// FC/CN decoded. For Paradox the raw reproduces the card exactly; FC/CN can
+/// decode ambiguously. Types without a raw-first path ignore `prefer_raw`.
+pub fn build_clone_command_with(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    prefer_raw: bool,
+) -> Option<String> {
+    let cmd = build_base_clone_command(card_type, uid, decoded, prefer_raw).ok()?;
+    Some(match clone_clock_flag(card_type, decoded) {
+        Some(flag) => format!("{} {}", cmd, flag),
+        None => cmd,
+    })
+}
+
+/// Default bit rate and accepted `--clk` values for the types whose clone
+/// command takes `--clk`. The others always write their standard rate.
+fn clone_clocks(card_type: &CardType) -> Option<(u16, &'static [u16])> {
+    match card_type {
+        CardType::EM4100 => Some((64, &EM410X_CLONE_CLOCKS)),
+        _ => None,
+    }
+}
+
+/// `--clk N` when the source card runs at a non-default bit rate (decoded
+/// `clock`, see `parse_lf_clock`) and the clone command can reproduce it.
+pub fn clone_clock_flag(
+    card_type: &CardType,
+    decoded: &std::collections::HashMap<String, String>,
+) -> Option<String> {
+    let (default, accepted) = clone_clocks(card_type)?;
+    let clock = decoded.get("clock")?.parse::<u16>().ok()?;
+    (clock != default && accepted.contains(&clock)).then(|| format!("--clk {}", clock))
+}
+
+/// Clone command for writing onto `blank`. EM4305 blanks get `--em` and Q5
+/// blanks `--q5`; types whose clone command lacks the flag (see
+/// `CardType::supports_em4305`) get no command rather than one PM3 would run
+/// against a T5577 default.
+pub fn build_clone_command_for_blank(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    blank: &BlankType,
+    prefer_raw: bool,
+) -> Option<String> {
+    let cmd = build_clone_command_with(card_type, uid, decoded, prefer_raw)?;
+    match blank {
+        BlankType::EM4305 if card_type.supports_em4305() => Some(build_clone_for_em4305(&cmd)),
+        BlankType::EM4305 => None,
+        BlankType::Q5 if card_type.supports_q5() => Some(build_clone_for_q5(&cmd)),
+        BlankType::Q5 => None,
+        _ => Some(cmd),
+    }
+}
+
+fn build_base_clone_command(
+    card_type: &CardType,
+    uid: &str,
+    decoded: &std::collections::HashMap<String, String>,
+    prefer_raw: bool,
+) -> Result<String, CloneBuildError> {
     // Validate uid: must be hex with optional colons (no spaces, semicolons, or other injection vectors)
     if !HEX_COLON_RE.is_match(uid) {
-        return None;
+        return Err(CloneBuildError::Malformed {
+            field: "uid",
+            value: uid.to_string(),
+            expected: "hex with optional colons",
+        });
     }
 
     match card_type {
-        CardType::EM4100 => Some(build_em4100_clone(uid)),
+        CardType::EM4100 => Ok(build_em4100_clone(uid)),
 
         CardType::HIDProx => {
             // Prefer raw clone — exact bit copy, no re-encoding.
             // Structured clone (Wiegand format) can fail on PM3 Easy (weaker antenna).
-            if let Some(raw) = decoded
-                .get("raw")
-                .filter(|raw| validate_hex(raw, "raw").is_ok())
-            {
-                return Some(build_hid_clone_raw(raw));
-            }
+            let raw = match raw_field(card_type, decoded) {
+                Ok(raw) => return Ok(build_hid_clone_raw(raw)),
+                Err(e) => e,
+            };
             // Fallback to structured clone when raw not available
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    let fmt = decoded
-                        .get("format")
-                        .map(|s| s.as_str())
-                        .filter(|f| validate_hid_format(f));
-                    return Some(build_hid_clone(fc_n, cn_n, fmt));
-                }
+            let fmt = decoded
+                .get("format")
+                .map(|s| s.as_str())
+                .filter(|f| validate_hid_format(f));
+            if !needs_facility_code(card_type, decoded) {
+                let [cn] = decimal_fields(decoded, ["card_number"]).map_err(|e| raw.closer(e))?;
+                return Ok(build_hid_clone(None, cn, fmt));
             }
-            None
+            let [fc, cn] = decimal_fields(decoded, ["facility_code", "card_number"])
+                .map_err(|e| raw.closer(e))?;
+            Ok(build_hid_clone(Some(fc), cn, fmt))
         }
 
         CardType::Indala => {
+            // Decoded FC/CN (incl. descrambled Heden/FlexSecur) beats raw
+            if let Ok([fc, cn]) = decimal_fields(decoded, ["facility_code", "card_number"]) {
+                return Ok(build_indala_clone_fc_cn(fc, cn));
+            }
             // Prefer raw hex from parser (avoids using decimal UID as --raw)
             let raw = decoded.get("raw").map(|s| s.as_str()).unwrap_or(uid);
-            Some(build_indala_clone(raw))
+            Ok(build_indala_clone(raw))
         }
 
         CardType::IOProx => {
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
+            if let Ok([fc, cn]) = decimal_fields(decoded, ["facility_code", "card_number"]) {
                 let vn = decoded
                     .get("version")
                     .and_then(|v| v.parse::<u32>().ok())
                     .unwrap_or(0);
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    return Some(build_ioprox_clone(fc_n, cn_n, vn));
-                }
+                return Ok(build_ioprox_clone(fc, cn, vn));
             }
             // uid already validated at top
-            Some(build_ioprox_clone_raw(uid))
+            Ok(build_ioprox_clone_raw(uid))
         }
 
         CardType::AWID => {
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    let fmt = decoded.get("format").and_then(|f| f.parse::<u32>().ok());
-                    return Some(build_awid_clone(fc_n, cn_n, fmt));
-                }
-            }
             // No raw fallback — awid clone requires --fc and --cn flags
-            None
+            let [fc, cn] = present_fields(decoded, ["facility_code", "card_number"])?;
+            let fmt = decoded.get("format").and_then(|f| f.parse::<u32>().ok());
+            // Out-of-range FC/CN would be silently truncated by PM3
+            let (fc_n, cn_n) = validate_awid(fc, cn, fmt).map_err(CloneBuildError::Rejected)?;
+            Ok(build_awid_clone(fc_n, cn_n, fmt))
         }
 
         CardType::FDX_B => {
@@ -372,7 +1084,7 @@ pub fn build_clone_command(
                 (decoded.get("country"), decoded.get("national_id"))
             {
                 if let (Ok(cc), Ok(nid)) = (country.parse::<u32>(), national.parse::<u64>()) {
-                    return Some(build_fdxb_clone(cc, nid));
+                    return Ok(build_fdxb_clone(cc, nid));
                 }
             }
             // Fallback to raw (validated) then uid (already validated at top)
@@ -380,39 +1092,38 @@ pub fn build_clone_command(
                 .get("raw")
                 .filter(|r| validate_hex(r, "raw").is_ok())
             {
-                Some(build_fdxb_clone_raw(raw))
+                Ok(build_fdxb_clone_raw(raw))
             } else {
-                Some(build_fdxb_clone_raw(uid))
+                Ok(build_fdxb_clone_raw(uid))
             }
         }
 
         CardType::Paradox => {
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    return Some(build_paradox_clone(fc_n, cn_n));
-                }
+            let raw = raw_field(card_type, decoded).ok();
+            if let Some(raw) = raw.filter(|_| prefer_raw) {
+                return Ok(build_paradox_clone_raw(raw));
             }
+            let fc_cn = match decimal_fields(decoded, ["facility_code", "card_number"]) {
+                Ok([fc, cn]) => return Ok(build_paradox_clone(fc, cn)),
+                Err(e) => e,
+            };
             // uid already validated at top
-            Some(build_paradox_clone_raw(uid))
+            let raw = raw.unwrap_or(uid);
+            validate_raw_length(card_type, raw)
+                .map_err(|e| fc_cn.closer(rejected(e)))?;
+            Ok(build_paradox_clone_raw(raw))
         }
 
-        CardType::Viking => Some(build_viking_clone(uid)),
+        CardType::Viking => Ok(build_viking_clone(uid)),
 
         CardType::Pyramid => {
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    return Some(build_pyramid_clone(fc_n, cn_n));
-                }
-            }
+            let fc_cn = match decimal_fields(decoded, ["facility_code", "card_number"]) {
+                Ok([fc, cn]) => return Ok(build_pyramid_clone(fc, cn)),
+                Err(e) => e,
+            };
             // Raw fallback — parser stores raw hex in decoded["raw"]
-            decoded
-                .get("raw")
-                .filter(|r| validate_hex(r, "raw").is_ok())
-                .map(|raw| build_pyramid_clone_raw(raw))
+            let raw = raw_field(card_type, decoded).map_err(|e| fc_cn.closer(e))?;
+            Ok(build_pyramid_clone_raw(raw))
         }
 
         CardType::Keri => {
@@ -421,15 +1132,17 @@ pub fn build_clone_command(
                 .get("card_number")
                 .map(|s| s.as_str())
                 .unwrap_or(uid);
+            // PM3 only takes a decimal --cn; a raw hex fallback can't be cloned
+            validate_keri_cn(cn).map_err(CloneBuildError::Rejected)?;
             let fc = decoded.get("facility_code").map(|s| s.as_str());
             let keri_type = decoded
                 .get("keri_type")
                 .map(|s| s.as_str())
                 .filter(|t| *t == "i" || *t == "m");
-            Some(build_keri_clone(cn, fc, keri_type))
+            Ok(build_keri_clone(cn, fc, keri_type))
         }
 
-        CardType::NexWatch => Some(build_nexwatch_clone(uid)),
+        CardType::NexWatch => Ok(build_nexwatch_clone(uid)),
 
         // --- New 11 types ---
 
@@ -438,67 +1151,48 @@ pub fn build_clone_command(
                 (decoded.get("site_code"), decoded.get("user_code"))
             {
                 if let (Ok(sc_n), Ok(uc_n)) = (sc.parse::<u32>(), uc.parse::<u32>()) {
-                    return Some(build_presco_clone(sc_n, uc_n));
+                    return Ok(build_presco_clone(sc_n, uc_n));
                 }
             }
             // uid already validated at top (hex with optional colons)
-            Some(build_presco_clone_hex(uid))
+            Ok(build_presco_clone_hex(uid))
         }
 
         CardType::Nedap => {
-            if let (Some(st), Some(cc), Some(id)) = (
-                decoded.get("subtype"),
-                decoded.get("customer_code"),
-                decoded.get("card_number"),
-            ) {
-                if let (Ok(st_n), Ok(cc_n), Ok(id_n)) =
-                    (st.parse::<u32>(), cc.parse::<u32>(), id.parse::<u32>())
-                {
-                    return Some(build_nedap_clone(st_n, cc_n, id_n));
-                }
-            }
             // No raw fallback — nedap clone requires --st, --cc and --id flags
-            None
+            let [st, cc, id] =
+                decimal_fields(decoded, ["subtype", "customer_code", "card_number"])?;
+            Ok(build_nedap_clone(st, cc, id))
         }
 
         CardType::GProxII => {
-            if let (Some(fc), Some(cn)) =
-                (decoded.get("facility_code"), decoded.get("card_number"))
-            {
-                let xor = decoded
-                    .get("xor")
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .unwrap_or(0);
-                let fmt = decoded
-                    .get("format")
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .unwrap_or(26);
-                if let (Ok(fc_n), Ok(cn_n)) = (fc.parse::<u32>(), cn.parse::<u32>()) {
-                    return Some(build_gproxii_clone(xor, fmt, fc_n, cn_n));
+            let fc_cn = match decimal_fields(decoded, ["facility_code", "card_number"]) {
+                Ok([fc, cn]) => {
+                    let xor = decoded
+                        .get("xor")
+                        .and_then(|v| v.parse::<u32>().ok())
+                        .unwrap_or(0);
+                    let fmt = decoded
+                        .get("format")
+                        .and_then(|v| v.parse::<u32>().ok())
+                        .unwrap_or(26);
+                    return Ok(build_gproxii_clone(xor, fmt, fc, cn));
                 }
-            }
-            // No raw fallback — gproxii clone requires --xor, --fmt, --fc and --cn flags
-            None
+                Err(e) => e,
+            };
+            // Raw fallback — parser keeps the frame hex as the uid when fields didn't decode
+            present_fields(decoded, ["raw_fallback"]).map_err(|e| fc_cn.closer(e))?;
+            validate_raw_length(card_type, uid).map_err(CloneBuildError::Rejected)?;
+            Ok(build_gproxii_clone_raw(uid))
         }
 
         CardType::Gallagher => {
-            if let (Some(rc), Some(fc), Some(cn), Some(il)) = (
-                decoded.get("region_code"),
-                decoded.get("facility_code"),
-                decoded.get("card_number"),
-                decoded.get("issue_level"),
-            ) {
-                if let (Ok(rc_n), Ok(fc_n), Ok(cn_n), Ok(il_n)) = (
-                    rc.parse::<u32>(),
-                    fc.parse::<u32>(),
-                    cn.parse::<u32>(),
-                    il.parse::<u32>(),
-                ) {
-                    return Some(build_gallagher_clone(rc_n, fc_n, cn_n, il_n));
-                }
-            }
             // No raw fallback — gallagher clone requires --rc, --fc, --cn, --il flags
-            None
+            let [rc, fc, cn, il] = decimal_fields(
+                decoded,
+                ["region_code", "facility_code", "card_number", "issue_level"],
+            )?;
+            Ok(build_gallagher_clone(rc, fc, cn, il))
         }
 
         CardType::PAC => {
@@ -507,12 +1201,12 @@ pub fn build_clone_command(
                 .get("raw")
                 .filter(|r| validate_hex(r, "raw").is_ok())
             {
-                return Some(build_pac_clone_raw(raw));
+                return Ok(build_pac_clone_raw(raw));
             }
             if let Some(cn) = decoded.get("card_number") {
-                return Some(build_pac_clone(cn));
+                return Ok(build_pac_clone(cn));
             }
-            Some(build_pac_clone(uid))
+            Ok(build_pac_clone(uid))
         }
 
         CardType::Noralsy => {
@@ -521,17 +1215,19 @@ pub fn build_clone_command(
                 .map(|s| s.as_str())
                 .unwrap_or(uid);
             let year = decoded.get("year").map(|s| s.as_str());
-            Some(build_noralsy_clone(cn, year))
+            // PM3 rejects out-of-range IDs; see validate_clone_fields for the reason
+            let (cn_n, year_n) = validate_noralsy(cn, year).map_err(CloneBuildError::Rejected)?;
+            Ok(build_noralsy_clone(cn_n, year_n))
         }
 
         CardType::Jablotron => {
             if let Some(cn) = decoded.get("card_number") {
                 // card_number for Jablotron is hex
                 if validate_hex(cn, "card_number").is_ok() {
-                    return Some(build_jablotron_clone(cn));
+                    return Ok(build_jablotron_clone(cn));
                 }
             }
-            Some(build_jablotron_clone(uid))
+            Ok(build_jablotron_clone(uid))
         }
 
         CardType::SecuraKey => {
@@ -539,31 +1235,26 @@ pub fn build_clone_command(
                 .get("raw")
                 .filter(|r| validate_hex(r, "raw").is_ok())
             {
-                Some(build_securakey_clone(raw))
+                Ok(build_securakey_clone(raw))
             } else {
-                Some(build_securakey_clone(uid))
+                Ok(build_securakey_clone(uid))
             }
         }
 
         CardType::Visa2000 => {
-            if let Some(cn) = decoded.get("card_number") {
-                if let Ok(cn_n) = cn.parse::<u32>() {
-                    return Some(build_visa2000_clone(cn_n));
-                }
-            }
             // No raw fallback — visa2000 clone requires numeric --cn flag
-            None
+            let [cn] = decimal_fields(decoded, ["card_number"])?;
+            Ok(build_visa2000_clone(cn))
         }
 
         CardType::Motorola => {
-            if let Some(raw) = decoded
+            let raw = decoded
                 .get("raw")
                 .filter(|r| validate_hex(r, "raw").is_ok())
-            {
-                Some(build_motorola_clone(raw))
-            } else {
-                Some(build_motorola_clone(uid))
-            }
+                .map(|r| r.as_str())
+                .unwrap_or(uid);
+            validate_raw_length(card_type, raw).map_err(CloneBuildError::Rejected)?;
+            Ok(build_motorola_clone(raw))
         }
 
         CardType::IDTECK => {
@@ -571,23 +1262,126 @@ pub fn build_clone_command(
                 .get("raw")
                 .filter(|r| validate_hex(r, "raw").is_ok())
             {
-                Some(build_idteck_clone(raw))
+                Ok(build_idteck_clone(raw))
             } else {
-                Some(build_idteck_clone(uid))
+                Ok(build_idteck_clone(uid))
             }
         }
 
         // Non-cloneable LF types
-        CardType::COTAG | CardType::EM4x50 | CardType::Hitag => None,
+        CardType::COTAG | CardType::EM4x50 | CardType::Hitag => Err(CloneBuildError::Unsupported),
 
         // HF cloning not yet implemented in this module
-        CardType::MifareClassic1K
+        CardType::MifareMini
+        | CardType::MifareClassic1K
         | CardType::MifareClassic4K
         | CardType::MifareUltralight
         | CardType::NTAG
         | CardType::DESFire
-        | CardType::IClass => None,
+        | CardType::IClass => Err(CloneBuildError::Unsupported),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Card-number sequences (enrollment testing)
+// ---------------------------------------------------------------------------
+
+/// Longest sequence accepted in one call.
+pub const MAX_SEQUENCE_LEN: u32 = 100;
+
+/// Largest card number the clone command can encode for this type/format.
+/// `None` for types without an FC/CN clone path.
+fn max_card_number(
+    card_type: &CardType,
+    decoded: &std::collections::HashMap<String, String>,
+) -> Option<u64> {
+    let max = match card_type {
+        CardType::HIDProx => match decoded.get("format").map(|s| s.as_str()) {
+            Some("H10302") => (1u64 << 35) - 1,
+            Some("H10304") => (1u64 << 19) - 1,
+            Some("Corp1000") => (1u64 << 20) - 1,
+            _ => u64::from(u16::MAX),
+        },
+        CardType::AWID => {
+            let fmt = decoded.get("format").and_then(|f| f.parse::<u32>().ok()).unwrap_or(26);
+            let (_, cn_bits) = awid_field_bits(fmt)?;
+            (1u64 << cn_bits) - 1
+        }
+        CardType::IOProx | CardType::Indala => u64::from(u16::MAX),
+        _ => return None,
+    };
+    // The FC/CN builders other than AWID take a u32 card number
+    if *card_type == CardType::AWID {
+        Some(max)
+    } else {
+        Some(max.min(u64::from(u32::MAX)))
+    }
+}
+
+/// Whether an FC/CN clone of this card needs a facility code: every type
+/// does, except HID formats the Wiegand table lists without one (H10302).
+fn needs_facility_code(
+    card_type: &CardType,
+    decoded: &std::collections::HashMap<String, String>,
+) -> bool {
+    *card_type != CardType::HIDProx
+        || decoded
+            .get("format")
+            .and_then(|f| crate::cards::wiegand::has_facility_code(f))
+            .unwrap_or(true)
+}
+
+/// Fields card `card_number` of a sequence is cloned from and should read
+/// back with: the source's, without raw data, with the new card number.
+pub fn sequence_card_fields(
+    decoded: &std::collections::HashMap<String, String>,
+    card_number: u64,
+) -> std::collections::HashMap<String, String> {
+    let mut fields = decoded.clone();
+    fields.remove("raw");
+    fields.insert("card_number".to_string(), card_number.to_string());
+    fields
+}
+
+/// Clone commands for `count` consecutive card numbers starting at the
+/// decoded `card_number`, same facility code. Returns `(card_number, command)`
+/// pairs. Raw data is dropped so every command is built from FC/CN.
+pub fn build_clone_sequence(
+    card_type: &CardType,
+    decoded: &std::collections::HashMap<String, String>,
+    count: u32,
+) -> Result<Vec<(u64, String)>, String> {
+    if count == 0 || count > MAX_SEQUENCE_LEN {
+        return Err(format!("Sequence length must be 1-{}, got {}", MAX_SEQUENCE_LEN, count));
+    }
+    let max_cn = max_card_number(card_type, decoded)
+        .ok_or_else(|| format!("{:?} has no facility code / card number clone", card_type))?;
+    if needs_facility_code(card_type, decoded) && !decoded.contains_key("facility_code") {
+        return Err("Missing facility code".into());
+    }
+    let base = decoded
+        .get("card_number")
+        .and_then(|cn| cn.parse::<u64>().ok())
+        .ok_or_else(|| "Missing or non-numeric card number".to_string())?;
+    let last = base
+        .checked_add(u64::from(count) - 1)
+        .ok_or_else(|| format!("Card number range from {} (+{} cards) overflows", base, count))?;
+    if last > max_cn {
+        return Err(format!(
+            "Card number {} exceeds the format maximum {} (base {} + {} cards)",
+            last, max_cn, base, count
+        ));
     }
+
+    (base..=last)
+        .map(|cn| {
+            let fields = sequence_card_fields(decoded, cn);
+            validate_clone_fields(card_type, "", &fields)?;
+            build_clone_command(card_type, "0", &fields)
+                .map(|cmd| (cn, cmd))
+                .ok_or_else(|| format!("Cannot build clone command for card number {}", cn))
+        })
+        .collect()
 }
 
 // ---------------------------------------------------------------------------
@@ -619,24 +1413,324 @@ pub fn build_hf_mfdes_info() -> &'static str {
 }
 
 // ---------------------------------------------------------------------------
-// HF autopwn (MIFARE Classic key recovery + dump)
+// Output verbosity
 // ---------------------------------------------------------------------------
 
-/// Build `hf mf autopwn` command. Uses `--4k` flag for 4K cards.
-pub fn build_hf_autopwn(card_type: &CardType) -> String {
+/// How much PM3 prints. `Verbose` adds `-v` to commands that take it;
+/// `Debug` also raises the client debug level (`-d 1`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    #[default]
+    Normal,
+    Verbose,
+    Debug,
+}
+
+/// Commands with a `-v` / `--verbose` switch. Anything else ignores the
+/// verbosity rather than failing on an unknown flag.
+const VERBOSE_COMMANDS: &[&str] = &["hf search", "hf 14a info", "hf mf info", "hf mf autopwn"];
+
+/// `cmd` with `-v` appended when `verbosity` asks for it and the command
+/// supports it. Already-verbose commands are returned unchanged.
+pub fn with_verbosity(cmd: &str, verbosity: Verbosity) -> String {
+    if verbosity == Verbosity::Normal {
+        return cmd.to_string();
+    }
+    let supported = VERBOSE_COMMANDS.iter().any(|prefix| {
+        cmd.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    });
+    let tokens: Vec<&str> = cmd.split_whitespace().collect();
+    if !supported || tokens.contains(&"-v") || tokens.contains(&"--verbose") {
+        return cmd.to_string();
+    }
+    format!("{} -v", cmd)
+}
+
+/// Client arguments placed before `-c`: `-d 1` at `Debug`, nothing otherwise.
+pub fn client_debug_args(verbosity: Verbosity) -> &'static [&'static str] {
+    match verbosity {
+        Verbosity::Debug => &["-d", "1"],
+        _ => &[],
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HF autopwn (MIFARE Classic key recovery + dump)
+// ---------------------------------------------------------------------------
+
+/// Build `hf mf autopwn` command. Uses `--4k` / `--mini` for those sizes.
+pub fn build_hf_autopwn(card_type: &CardType) -> String {
     match card_type {
         CardType::MifareClassic4K => "hf mf autopwn --4k".to_string(),
+        CardType::MifareMini => "hf mf autopwn --mini".to_string(),
         _ => "hf mf autopwn".to_string(),
     }
 }
 
+/// ` --mini` for MIFARE Mini on commands that otherwise assume 1K
+/// (`cload`, `restore`, `gload`, `dump`, `cview`).
+fn mini_flag(card_type: &CardType) -> &'static str {
+    match card_type {
+        CardType::MifareMini => " --mini",
+        _ => "",
+    }
+}
+
+/// `hf mf autopwn` seeded with a key dictionary (e.g. the project keyfile).
+pub fn build_hf_autopwn_with_keys(card_type: &CardType, keyfile: &str) -> String {
+    format!("{} -f \"{}\"", build_hf_autopwn(card_type), keyfile)
+}
+
+/// Autopwn strategy picked from the scan's decoded fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutopwnPlan {
+    /// Key dictionaries for `-f`, in the order their keys should be tried.
+    /// Empty keeps PM3's built-in default; PM3 takes one file, so several are
+    /// merged first (see `keyfile::merge_keyfiles`).
+    pub dictionaries: Vec<String>,
+    /// Short reason shown in the terminal so the user knows why.
+    pub reason: &'static str,
+    /// The dictionary the user selected. It is always in `dictionaries`, so
+    /// it replaces PM3's built-in default even where `reason` would keep it.
+    pub selected: Option<String>,
+}
+
+impl AutopwnPlan {
+    /// Terminal lines explaining the plan, the selected dictionary included.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![self.reason.to_string()];
+        if let Some(dict) = &self.selected {
+            lines.push(format!(
+                "Using the selected dictionary {} instead of PM3's default",
+                dict
+            ));
+        }
+        lines
+    }
+}
+
+/// Pick the autopwn dictionaries from `hf search` results (`prng`, `sak`,
+/// `magic`), the dictionary the user selected and the project keyfile.
+///
+/// | Scan result                      | Dictionary      | Why                                       |
+/// |----------------------------------|-----------------|-------------------------------------------|
+/// | magic Gen1a/Gen1b                | PM3 default     | backdoor read, keys barely matter         |
+/// | PRNG WEAK                        | PM3 default     | darkside + nested recover keys in seconds |
+/// | PRNG STATIC                      | PM3 default     | autopwn switches to static nested itself  |
+/// | PRNG HARD/unknown + project keys | project keyfile | hardnested is slow; try known keys first  |
+/// | PRNG HARD/unknown, no keys       | PM3 default     | dictionary, then hardnested               |
+///
+/// With no PRNG reported, SAK 88 (Infineon Classic) counts as weak and SAK
+/// 28/38 (SmartMX emulating Classic) as hardened. A selected `dictionary`
+/// is always used, after the project keys when those apply. `-f` replaces
+/// PM3's default dictionary, so the project keyfile is only worth it when
+/// the fallback attack is expensive.
+pub fn select_autopwn_plan(
+    decoded: &std::collections::HashMap<String, String>,
+    dictionary: Option<&str>,
+    project_keyfile: Option<&str>,
+) -> AutopwnPlan {
+    let sak = decoded.get("sak").map(|s| s.trim().trim_start_matches("0x").to_uppercase());
+    let prng = decoded.get("prng").map(|p| p.to_uppercase()).or_else(|| {
+        match sak.as_deref() {
+            Some("88") => Some("WEAK".to_string()),
+            _ => None,
+        }
+    });
+    let magic = decoded
+        .get("magic")
+        .map(|m| m.to_lowercase().replace(' ', ""))
+        .unwrap_or_default();
+
+    let (project_keyfile, reason) = if magic.starts_with("gen1") {
+        (None, "Gen1 magic card: backdoor access, standard autopwn")
+    } else {
+        match prng.as_deref() {
+            Some("WEAK") => (None, "Weak PRNG: darkside/nested attack"),
+            Some("STATIC") => (None, "Static nonce: static nested attack"),
+            _ => match project_keyfile {
+                Some(keys) => (Some(keys), "Hardened PRNG: trying project keys before hardnested"),
+                None => (None, "Hardened PRNG: default dictionary, then hardnested"),
+            },
+        }
+    };
+    AutopwnPlan {
+        dictionaries: project_keyfile.into_iter().chain(dictionary).map(String::from).collect(),
+        reason,
+        selected: dictionary.map(String::from),
+    }
+}
+
+/// PM3's card-size flag for commands that take one explicitly.
+fn mf_size_flag(card_type: &CardType) -> &'static str {
+    match card_type {
+        CardType::MifareClassic4K => "--4k",
+        CardType::MifareMini => "--mini",
+        _ => "--1k",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Partial autopwn resume (per-sector nested / hardnested)
+// ---------------------------------------------------------------------------
+
+/// A recovered key used to authenticate a nested attack on another sector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownKey {
+    pub sector: u16,
+    pub key_b: bool,
+    pub key: String,
+}
+
+/// One missing key and the command that recovers it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumeStep {
+    pub sector: u16,
+    pub key_b: bool,
+    pub cmd: String,
+}
+
+fn sector_trailer(sector: u16) -> usize {
+    let (first, count) = dump_report::sector_blocks(usize::from(sector));
+    first + count - 1
+}
+
+fn nested_args(sector: u16, target_key_b: bool, known: &KnownKey) -> String {
+    format!(
+        "--blk {} -{} -k {} --tblk {} --t{}",
+        sector_trailer(known.sector),
+        if known.key_b { 'b' } else { 'a' },
+        known.key,
+        sector_trailer(sector),
+        if target_key_b { 'b' } else { 'a' },
+    )
+}
+
+/// `hf mf nested` for one key of one sector (weak PRNG cards).
+pub fn build_mf_nested_sector(sector: u16, target_key_b: bool, known: &KnownKey) -> String {
+    format!("hf mf nested {}", nested_args(sector, target_key_b, known))
+}
+
+/// `hf mf hardnested` for one key of one sector (hardened PRNG cards).
+pub fn build_mf_hardnested_sector(sector: u16, target_key_b: bool, known: &KnownKey) -> String {
+    format!("hf mf hardnested {}", nested_args(sector, target_key_b, known))
+}
+
+/// Commands recovering only the keys a partial autopwn left missing, using
+/// the first recovered key as the known key. Nested for a weak PRNG,
+/// hardnested otherwise (static-nonce cards are left to autopwn).
+pub fn build_resume_plan(
+    sectors: &[dump_report::SectorKeys],
+    prng: Option<&str>,
+) -> Result<Vec<ResumeStep>, String> {
+    let prng = prng.map(str::to_uppercase);
+    if prng.as_deref() == Some("STATIC") {
+        return Err("Static-nonce cards need a full autopwn run (static nested)".into());
+    }
+    let known = sectors
+        .iter()
+        .enumerate()
+        .find_map(|(sector, keys)| {
+            let (key, key_b) = match (&keys.key_a, &keys.key_b) {
+                (Some(a), _) => (a, false),
+                (None, Some(b)) => (b, true),
+                (None, None) => return None,
+            };
+            Some(KnownKey { sector: sector as u16, key_b, key: key.clone() })
+        })
+        .ok_or("No recovered key to start from; run a full autopwn")?;
+
+    let build = if prng.as_deref() == Some("WEAK") {
+        build_mf_nested_sector
+    } else {
+        build_mf_hardnested_sector
+    };
+    let mut steps = Vec::new();
+    for (sector, keys) in sectors.iter().enumerate() {
+        let sector = sector as u16;
+        for (missing, key_b) in [(keys.key_a.is_none(), false), (keys.key_b.is_none(), true)] {
+            if missing {
+                steps.push(ResumeStep { sector, key_b, cmd: build(sector, key_b, &known) });
+            }
+        }
+    }
+    Ok(steps)
+}
+
+/// `hf mf dump` authenticating with a specific key file.
+pub fn build_mf_dump_with_keys(card_type: &CardType, keyfile: &str) -> String {
+    format!("hf mf dump {} --keys \"{}\"", mf_size_flag(card_type), keyfile)
+}
+
+/// Sectors on a Classic card: Mini 5, 1K 16, 4K 40 (32 small + 8 large).
+fn mf_sector_count(card_type: &CardType) -> u16 {
+    match card_type {
+        CardType::MifareClassic4K => 40,
+        CardType::MifareMini => 5,
+        _ => 16,
+    }
+}
+
+/// Check a selective-dump range against the card's sector layout.
+pub fn validate_sector_range(
+    card_type: &CardType,
+    first_sector: u16,
+    last_sector: u16,
+) -> Result<(), String> {
+    let count = mf_sector_count(card_type);
+    if first_sector > last_sector {
+        return Err(format!(
+            "First sector {} is after last sector {}",
+            first_sector, last_sector
+        ));
+    }
+    if last_sector >= count {
+        return Err(format!(
+            "Sector {} out of range: card has sectors 0-{}",
+            last_sector,
+            count - 1
+        ));
+    }
+    Ok(())
+}
+
+/// Selective dump of `first_sector..=last_sector`, one `hf mf rdsc` per
+/// sector. `hf mf dump` always reads the whole card, which on a 4K is slow
+/// when only a few sectors matter. `keys` is the parsed key file; key A is
+/// used where known, else key B.
+pub fn build_mf_dump_range(
+    card_type: &CardType,
+    first_sector: u16,
+    last_sector: u16,
+    keys: &[dump_report::SectorKeys],
+) -> Result<Vec<String>, String> {
+    validate_sector_range(card_type, first_sector, last_sector)?;
+    (first_sector..=last_sector)
+        .map(|sector| {
+            let sector_keys = keys.get(usize::from(sector));
+            let (flag, key) = match sector_keys.map(|k| (&k.key_a, &k.key_b)) {
+                Some((Some(a), _)) => ('a', a),
+                Some((None, Some(b))) => ('b', b),
+                _ => return Err(format!("No key recovered for sector {}", sector)),
+            };
+            validate_hex(key, "Key")?;
+            if key.len() != 12 {
+                return Err(format!("Key for sector {} must be 12 hex characters", sector));
+            }
+            Ok(format!("hf mf rdsc -s {} -{} -k {}", sector, flag, key))
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // HF clone write commands
 // ---------------------------------------------------------------------------
 
 /// Gen1a: load full dump via magic wakeup (40/43) backdoor.
-pub fn build_mf_cload(dump_path: &str) -> String {
-    format!("hf mf cload -f {}", dump_path)
+pub fn build_mf_cload(card_type: &CardType, dump_path: &str) -> String {
+    format!("hf mf cload{} -f {}", mini_flag(card_type), dump_path)
 }
 
 /// Gen2/CUID: force 14a config to allow block 0 write.
@@ -657,13 +1751,23 @@ pub fn build_mf_wrbl0(key: &str, data: &str) -> String {
 }
 
 /// Gen2/Gen3: restore all blocks from a binary dump file.
-pub fn build_mf_restore(dump_path: &str) -> String {
-    format!("hf mf restore -f {}", dump_path)
+pub fn build_mf_restore(card_type: &CardType, dump_path: &str) -> String {
+    format!("hf mf restore{} -f {}", mini_flag(card_type), dump_path)
 }
 
 /// Gen3: set UID via APDU command. `uid`: 8 or 14 hex chars (no spaces).
-pub fn build_mf_gen3uid(uid: &str) -> String {
-    format!("hf mf gen3uid --uid {}", uid)
+/// The length sets the card's UID size, so a Gen3 last used as 4-byte is
+/// switched to 7-byte (and back) by the same command. Any other length is
+/// rejected rather than left for PM3 to pad or truncate.
+pub fn build_mf_gen3uid(uid: &str) -> Result<String, String> {
+    validate_hex(uid, "UID")?;
+    match uid.len() {
+        8 | 14 => Ok(format!("hf mf gen3uid --uid {}", uid)),
+        n => Err(format!(
+            "Gen3 UID must be 4 or 7 bytes (8 or 14 hex chars), got {} hex chars",
+            n
+        )),
+    }
 }
 
 /// Gen3: write block 0 via APDU command. `block0`: 32 hex chars.
@@ -672,8 +1776,21 @@ pub fn build_mf_gen3blk(block0: &str) -> String {
 }
 
 /// Gen4 GTU/UMC: load full dump via gload.
-pub fn build_mf_gload(dump_path: &str) -> String {
-    format!("hf mf gload -f {}", dump_path)
+pub fn build_mf_gload(card_type: &CardType, dump_path: &str) -> String {
+    format!("hf mf gload{} -f {}", mini_flag(card_type), dump_path)
+}
+
+/// Gen4 GTU/UMC: set the anticollision UID length (4, 7 or 10 bytes) with
+/// the raw `CF <pwd> 68` config command, default password 00000000. The
+/// card keeps answering with its old UID length until this is changed.
+pub fn build_mf_gen4_uid_len(uid_bytes: usize) -> Result<String, String> {
+    let mode = match uid_bytes {
+        4 => "00",
+        7 => "01",
+        10 => "02",
+        n => return Err(format!("Gen4 UID length must be 4, 7 or 10 bytes, got {}", n)),
+    };
+    Ok(format!("hf 14a raw -s -c -t 1000 CF0000000068{}", mode))
 }
 
 /// Gen4 GDM: write a single block. `blk`: 0-255, `data`: 32 hex chars.
@@ -681,17 +1798,89 @@ pub fn build_mf_gdm_setblk(blk: u16, data: &str) -> String {
     format!("hf mf gdmsetblk --blk {} -d {}", blk, data)
 }
 
+/// Gen4 GDM without the Gen1a backdoor: one `gdmsetblk` per block of a
+/// Classic dump, as (block, command) in block order. The dump must be a whole
+/// Mini/1K/2K/4K image.
+pub fn build_mf_gdm_setblk_all(dump: &[u8]) -> Result<Vec<(u16, String)>, String> {
+    if dump_report::sector_count(dump.len()).is_none() {
+        return Err(format!("Dump is {} bytes, not a MIFARE Classic image", dump.len()));
+    }
+    Ok(dump
+        .chunks(16)
+        .enumerate()
+        .map(|(blk, data)| {
+            let blk = blk as u16;
+            (blk, build_mf_gdm_setblk(blk, &dump_report::hex(data)))
+        })
+        .collect())
+}
+
 /// UL/NTAG: restore dump from file. `-s` = special pages, `-e` = engineering mode.
 pub fn build_mfu_restore(dump_path: &str) -> String {
     format!("hf mfu restore -f {} -s -e", dump_path)
 }
 
+/// UL/NTAG: read a single 4-byte page.
+pub fn build_mfu_rdbl(page: u8) -> String {
+    format!("hf mfu rdbl -b {}", page)
+}
+
 /// iCLASS: restore dump from file using default key (key index 0).
 /// Writes blocks 6-18 (application data, skips header and config blocks).
 pub fn build_iclass_restore(dump_path: &str) -> String {
     format!("hf iclass restore -f {} --first 6 --last 18 --ki 0", dump_path)
 }
 
+// ---------------------------------------------------------------------------
+// DESFire UID-only clone
+// ---------------------------------------------------------------------------
+
+/// DESFire EV1/EV2 anticollision answer (ATQA 0x0344, SAK 0x20). Block 0
+/// stores ATQA low byte first.
+const DESFIRE_SAK: &str = "20";
+const DESFIRE_ATQA_BLOCK0: &str = "4403";
+
+/// Commands that put a DESFire card's 7-byte UID (and, where the blank allows,
+/// its ATQA/SAK) on a magic blank. Application data is encrypted and NOT
+/// copied — this only fools readers that check the UID alone.
+pub fn build_desfire_uid_clone(uid: &str, blank: &BlankType) -> Result<Vec<String>, String> {
+    let uid: String = uid
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect::<String>()
+        .to_uppercase();
+    if !uid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("UID must be hex, got '{}'", uid));
+    }
+    if uid.len() != 14 {
+        return Err(format!(
+            "DESFire UID-only clone needs the card's 7-byte UID, got {} bytes. \
+             A 4-byte UID starting 08 is a random ID that changes on every read.",
+            uid.len() / 2
+        ));
+    }
+
+    // UID | SAK | ATQA | manufacturer bytes (zeroed)
+    let block0 = format!("{}{}{}000000000000", uid, DESFIRE_SAK, DESFIRE_ATQA_BLOCK0);
+    match blank {
+        BlankType::MagicMifareGen3 => Ok(vec![
+            build_mf_gen3uid(&uid)?,
+            build_mf_gen3blk(&block0),
+        ]),
+        // Switch to 7-byte anticollision first; gsetblk alone keeps a 4-byte UID
+        BlankType::MagicMifareGen4GTU => Ok(vec![
+            build_mf_gen4_uid_len(7)?,
+            format!("hf mf gsetblk --blk 0 -d {}", block0),
+        ]),
+        // UL anticollision is fixed (ATQA 0044, SAK 00): UID only
+        BlankType::MagicUltralight => Ok(vec![format!("hf mfu setuid --uid {}", uid)]),
+        _ => Err(format!(
+            "{} can't take a 7-byte UID-only clone. Use a Gen3, Gen4 GTU or magic Ultralight blank.",
+            blank.display_name()
+        )),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // HF dump commands (no key recovery needed)
 // ---------------------------------------------------------------------------
@@ -706,6 +1895,12 @@ pub fn build_iclass_dump() -> &'static str {
     "hf iclass dump --ki 0"
 }
 
+/// iCLASS: decrypt a legacy dump with the transport key so the PACS credential
+/// in block 7 can be decoded. Writes a `*-decrypted.bin` next to the input.
+pub fn build_iclass_decrypt(dump_path: &str) -> String {
+    format!("hf iclass decrypt -f {}", dump_path)
+}
+
 // ---------------------------------------------------------------------------
 // HF data check commands (blank detection — existing data check)
 // ---------------------------------------------------------------------------
@@ -716,10 +1911,28 @@ pub fn build_mf_cgetblk(blk: u16) -> String {
     format!("hf mf cgetblk --blk {}", blk)
 }
 
+/// Which MIFARE Classic sector key authenticates a read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KeyType {
+    #[default]
+    A,
+    B,
+}
+
+impl KeyType {
+    fn flag(self) -> &'static str {
+        match self {
+            KeyType::A => "-a",
+            KeyType::B => "-b",
+        }
+    }
+}
+
 /// Read single block with specified key. Returns hex data if key is valid.
-/// `blk`: block number, `key`: 12 hex chars (e.g., "FFFFFFFFFFFF").
-pub fn build_mf_rdbl(blk: u16, key: &str) -> String {
-    format!("hf mf rdbl --blk {} -k {}", blk, key)
+/// `blk`: block number, `key`: 12 hex chars (e.g., "FFFFFFFFFFFF"). Sectors
+/// set up for key-B-only reads need `KeyType::B`.
+pub fn build_mf_rdbl(blk: u16, key: &str, key_type: KeyType) -> String {
+    format!("hf mf rdbl --blk {} -k {} {}", blk, key, key_type.flag())
 }
 
 // ---------------------------------------------------------------------------
@@ -727,14 +1940,14 @@ pub fn build_mf_rdbl(blk: u16, key: &str) -> String {
 // ---------------------------------------------------------------------------
 
 /// Gen1a: read all blocks via magic wakeup (40/43) backdoor. No keys needed.
-pub fn build_mf_cview() -> &'static str {
-    "hf mf cview"
+pub fn build_mf_cview(card_type: &CardType) -> String {
+    format!("hf mf cview{}", mini_flag(card_type))
 }
 
 /// MIFARE Classic: dump all blocks using recovered keys.
 /// Auto-discovers `hf-mf-<UID>-key.bin` in working directory.
-pub fn build_mf_dump() -> &'static str {
-    "hf mf dump"
+pub fn build_mf_dump(card_type: &CardType) -> String {
+    format!("hf mf dump{}", mini_flag(card_type))
 }
 
 // ---------------------------------------------------------------------------
@@ -750,11 +1963,35 @@ pub fn build_wipe_command(blank_type: &BlankType, password: Option<&str>) -> Opt
             Some(pw) => Some(build_t5577_wipe_with_password(pw).ok()?),
             None => Some(build_t5577_wipe().to_string()),
         },
+        BlankType::Q5 => match password {
+            Some(pw) => Some(format!("{} --q5", build_t5577_wipe_with_password(pw).ok()?)),
+            None => Some(build_q5_wipe().to_string()),
+        },
         // Other blank types don't have a wipe command in this module
         _ => None,
     }
 }
 
+// ---------------------------------------------------------------------------
+// Firmware flashing (flasher mode arguments, not `-c` commands)
+// ---------------------------------------------------------------------------
+
+/// Arguments for `proxmark3 <port> --flash --image <elf>... -w`.
+/// `unlock_bootloader` is required whenever a bootrom image is written, which
+/// is how a device stuck in bootloader mode gets recovered.
+pub fn build_flash_args(port: &str, images: &[&str], unlock_bootloader: bool) -> Vec<String> {
+    let mut args = vec![port.to_string(), "--flash".to_string()];
+    if unlock_bootloader {
+        args.push("--unlock-bootloader".to_string());
+    }
+    for image in images {
+        args.push("--image".to_string());
+        args.push(image.to_string());
+    }
+    args.push("-w".to_string());
+    args
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -770,6 +2007,28 @@ mod tests {
         assert_eq!(build_hf_search(), "hf search");
     }
 
+    #[test]
+    fn verbosity_appends_flag_where_supported() {
+        assert_eq!(with_verbosity("hf mf info", Verbosity::Normal), "hf mf info");
+        assert_eq!(with_verbosity("hf mf info", Verbosity::Verbose), "hf mf info -v");
+        assert_eq!(with_verbosity("hf 14a info", Verbosity::Debug), "hf 14a info -v");
+        assert_eq!(
+            with_verbosity("hf mf autopwn --4k -f keys.dic", Verbosity::Verbose),
+            "hf mf autopwn --4k -f keys.dic -v"
+        );
+        // Already verbose, unsupported, or only sharing a prefix
+        assert_eq!(with_verbosity("hf search -v", Verbosity::Verbose), "hf search -v");
+        assert_eq!(with_verbosity("lf t55xx detect", Verbosity::Verbose), "lf t55xx detect");
+        assert_eq!(with_verbosity("hf mf infox", Verbosity::Verbose), "hf mf infox");
+    }
+
+    #[test]
+    fn client_debug_args_only_at_debug() {
+        assert!(client_debug_args(Verbosity::Normal).is_empty());
+        assert!(client_debug_args(Verbosity::Verbose).is_empty());
+        assert_eq!(client_debug_args(Verbosity::Debug), ["-d", "1"]);
+    }
+
     #[test]
     fn hf_14a_info_cmd() {
         assert_eq!(build_hf_14a_info(), "hf 14a info");
@@ -795,6 +2054,182 @@ mod tests {
         assert_eq!(build_hf_mfdes_info(), "hf mfdes info");
     }
 
+    // -- T55xx raw clone --
+
+    #[test]
+    fn em4x50_write_cmd() {
+        assert_eq!(
+            build_em4x50_write(3, "deadbeef").unwrap(),
+            "lf em 4x50 wrbl -b 3 -d DEADBEEF"
+        );
+        assert_eq!(
+            build_em4x50_write_with_password(2, "0320000A", "00000000").unwrap(),
+            "lf em 4x50 wrbl -b 2 -d 0320000A -p 00000000"
+        );
+        assert!(build_em4x50_write(0, "00000000").is_err());
+        assert!(build_em4x50_write(32, "00000000").is_err());
+        assert!(build_em4x50_write(3, "0000000").is_err());
+        assert!(build_em4x50_write(3, "0000000G").is_err());
+        assert_eq!(build_em4x50_wipe(), "lf em 4x50 wipe -p 00000000");
+    }
+
+    #[test]
+    fn em4x50_clone_sequence_from_word_table() {
+        let mut words: Vec<String> = (0..EM4X50_WORD_COUNT).map(|w| format!("{:08X}", w)).collect();
+        // Words 0-4 read-protected; default read sends words 3-8
+        words[1] = "00040000".into();
+        words[2] = "03080000".into();
+        let cmds = build_em4x50_clone(&words).unwrap();
+        assert_eq!(cmds[0], "lf em 4x50 wipe -p 00000000");
+        assert_eq!(cmds[1], "lf em 4x50 wrbl -b 5 -d 00000005");
+        assert_eq!(cmds[cmds.len() - 2], "lf em 4x50 wrbl -b 31 -d 0000001F");
+        assert_eq!(cmds[cmds.len() - 1], "lf em 4x50 wrbl -b 2 -d 03080000 -p 00000000");
+        // Wipe + words 5-31 + control; password, protection and serial/ID untouched
+        assert_eq!(cmds.len(), 1 + 27 + 1);
+        assert!(!cmds.iter().any(|c| c.contains("-b 0 ") || c.contains("-b 1 ")));
+        assert!(!cmds.iter().any(|c| c.contains("-b 32") || c.contains("-b 33")));
+
+        // Everything read-protected
+        words[1] = "001F0000".into();
+        assert!(build_em4x50_clone(&words).is_err());
+        assert!(build_em4x50_clone(&words[..33]).is_err());
+    }
+
+    #[test]
+    fn t55xx_write_block_cmd() {
+        assert_eq!(
+            build_t55xx_write_block(1, "ff83c030").unwrap(),
+            "lf t55xx write -b 1 -d FF83C030"
+        );
+        assert!(build_t55xx_write_block(8, "00000000").is_err());
+        assert!(build_t55xx_write_block(1, "0000000").is_err());
+        assert!(build_t55xx_write_block(1, "0000000G").is_err());
+    }
+
+    #[test]
+    fn t55xx_raw_clone_round_trip() {
+        // EM4100 hosted on a T5577: config 00148040 (MAXBLK 2) + two data blocks.
+        // Blocks past MAXBLK are left alone, config goes last.
+        let blocks: Vec<String> = ["00148040", "FF83C030", "0C6B1D8E", "00000000", "DEADBEEF"]
+            .iter()
+            .map(|b| b.to_string())
+            .collect();
+        assert_eq!(
+            build_t55xx_clone_raw(&blocks).unwrap(),
+            vec![
+                "lf t55xx write -b 1 -d FF83C030",
+                "lf t55xx write -b 2 -d 0C6B1D8E",
+                "lf t55xx write -b 0 -d 00148040",
+            ]
+        );
+
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert(T55XX_BLOCKS_KEY.to_string(), blocks.join(","));
+        assert_eq!(t55xx_source_blocks(&decoded).unwrap(), blocks);
+    }
+
+    #[test]
+    fn t55xx_raw_clone_rejects_bad_config() {
+        let blocks = |b: &[&str]| b.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        // PWD bit set
+        assert!(build_t55xx_clone_raw(&blocks(&["00148050", "FF83C030", "0C6B1D8E"])).is_err());
+        // MAXBLK 3 but only two data blocks dumped
+        assert!(build_t55xx_clone_raw(&blocks(&["00107060", "1D555955", "5A6A6AA5"])).is_err());
+        assert!(build_t55xx_clone_raw(&[]).is_err());
+    }
+
+    #[test]
+    fn t55xx_set_password_sequence() {
+        assert_eq!(
+            build_t55xx_set_password(T55XX_EM4100_CONFIG, "a1b2c3d4").unwrap(),
+            vec![
+                "lf t55xx write -b 7 -d A1B2C3D4",
+                "lf t55xx write -b 0 -d 00148050",
+            ]
+        );
+        assert_eq!(
+            build_t5577_detect_with_password("a1b2c3d4").unwrap(),
+            "lf t55xx detect -p A1B2C3D4"
+        );
+        // Password must be exactly 8 hex characters
+        assert!(build_t55xx_set_password(T55XX_EM4100_CONFIG, "1234567").is_err());
+        assert!(build_t55xx_set_password(T55XX_EM4100_CONFIG, "12345678; hw").is_err());
+        assert!(build_t55xx_set_password(T55XX_EM4100_CONFIG, "GGGGGGGG").is_err());
+        assert!(build_t5577_detect_with_password("").is_err());
+        // MAXBLK 7 would transmit the password block
+        assert!(build_t55xx_set_password(0x000880E0, "A1B2C3D4").is_err());
+    }
+
+    #[test]
+    fn data_buffer_save_load_cmds() {
+        let path = "/home/user/phosphor/lf-captures/unknown-20261016-101500.pm3";
+        assert_eq!(
+            build_data_save(path).unwrap(),
+            format!("data save -f \"{}\"", path)
+        );
+        assert_eq!(
+            build_data_load("C:\\Phosphor Data\\lf-captures\\lf.pm3").unwrap(),
+            "data load -f \"C:\\Phosphor Data\\lf-captures\\lf.pm3\""
+        );
+        assert_eq!(build_lf_search_buffer(), "lf search -1");
+        assert!(build_data_save("").is_err());
+        assert!(build_data_save("a.pm3\" ; hw reset").is_err());
+        assert!(build_data_load("a.pm3\nhw reset").is_err());
+    }
+
+    #[test]
+    fn t55xx_config_write_cmd() {
+        // FSK2a RF/50, blocks 1-3
+        assert_eq!(
+            build_t55xx_write_config("00107060", None, false).unwrap(),
+            vec!["lf t55xx write -b 0 -d 00107060"]
+        );
+        assert_eq!(
+            build_t55xx_write_config("00107060", Some("51243648"), true).unwrap(),
+            vec!["lf t55xx write -b 0 -d 00107060 -p 51243648"]
+        );
+        assert_eq!(
+            build_t55xx_write_config("00148050", Some("a1b2c3d4"), true).unwrap(),
+            vec!["lf t55xx write -b 0 -d 00148050 -p A1B2C3D4"]
+        );
+        // Turning password mode on: the password goes into block 7 first
+        assert_eq!(
+            build_t55xx_write_config("00148050", Some("a1b2c3d4"), false).unwrap(),
+            vec!["lf t55xx write -b 7 -d A1B2C3D4", "lf t55xx write -b 0 -d 00148050"]
+        );
+        assert!(build_t55xx_write_config("0010706", None, false).is_err());
+        assert!(build_t55xx_write_config("0010706Z", None, false).is_err());
+        assert!(build_t55xx_write_config("00107060; hw", None, false).is_err());
+        assert!(build_t55xx_write_config("00107060", Some("1234"), true).is_err());
+        // Password mode needs the password, and must not transmit block 7
+        assert!(build_t55xx_write_config("00148050", None, true).is_err());
+        assert!(build_t55xx_write_config("000880F0", Some("51243648"), true).is_err());
+        assert!(build_t55xx_write_config("000880F0", Some("51243648"), false).is_err());
+    }
+
+    #[test]
+    fn t55xx_forced_config_read() {
+        assert_eq!(
+            build_t55xx_config_for_block0(0x0010_7060).unwrap(),
+            "lf t55xx config --rate 50 --FSK2A"
+        );
+        assert_eq!(
+            build_t55xx_config_for_block0(T55XX_EM4100_CONFIG).unwrap(),
+            "lf t55xx config --rate 64 --ASK"
+        );
+        assert_eq!(
+            build_t55xx_config_for_block0(0x0008_1040).unwrap(),
+            "lf t55xx config --rate 32 --PSK1"
+        );
+        assert!(build_t55xx_config_for_block0(0x0001_F000).is_err());
+        assert_eq!(build_t55xx_read_block(0, None).unwrap(), "lf t55xx read -b 0");
+        assert_eq!(
+            build_t55xx_read_block(0, Some("a1b2c3d4")).unwrap(),
+            "lf t55xx read -b 0 -p A1B2C3D4"
+        );
+        assert!(build_t55xx_read_block(8, None).is_err());
+    }
+
     // -- HF autopwn --
 
     #[test]
@@ -816,11 +2251,187 @@ mod tests {
         assert_eq!(cmd, "hf mf autopwn");
     }
 
+    fn scan_fields(prng: Option<&str>, magic: Option<&str>) -> std::collections::HashMap<String, String> {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("sak".to_string(), "08".to_string());
+        if let Some(p) = prng {
+            decoded.insert("prng".to_string(), p.to_string());
+        }
+        if let Some(m) = magic {
+            decoded.insert("magic".to_string(), m.to_string());
+        }
+        decoded
+    }
+
+    #[test]
+    fn autopwn_plan_weak_prng_uses_standard() {
+        let plan = select_autopwn_plan(&scan_fields(Some("WEAK"), None), None, Some("keys.dic"));
+        assert!(plan.dictionaries.is_empty());
+    }
+
+    #[test]
+    fn autopwn_plan_static_prng_uses_standard() {
+        let plan = select_autopwn_plan(&scan_fields(Some("STATIC"), None), None, Some("keys.dic"));
+        assert!(plan.dictionaries.is_empty());
+    }
+
+    #[test]
+    fn autopwn_plan_hard_prng_prefers_project_keys() {
+        let plan = select_autopwn_plan(&scan_fields(Some("HARD"), None), None, Some("keys.dic"));
+        assert_eq!(plan.dictionaries, vec!["keys.dic"]);
+
+        // No project keys yet -> PM3 default dictionary + hardnested
+        let plan = select_autopwn_plan(&scan_fields(Some("HARD"), None), None, None);
+        assert!(plan.dictionaries.is_empty());
+
+        // PRNG not reported is treated as hardened
+        let plan = select_autopwn_plan(&scan_fields(None, None), None, Some("keys.dic"));
+        assert_eq!(plan.dictionaries, vec!["keys.dic"]);
+    }
+
+    #[test]
+    fn autopwn_plan_keeps_keyfile_with_selected_dictionary() {
+        let hard = scan_fields(Some("HARD"), None);
+        let plan = select_autopwn_plan(&hard, Some("user.dic"), Some("keys.dic"));
+        assert_eq!(plan.dictionaries, vec!["keys.dic", "user.dic"]);
+        // A weak card skips the project keys, not the user's choice
+        let weak = scan_fields(Some("WEAK"), None);
+        let plan = select_autopwn_plan(&weak, Some("user.dic"), Some("keys.dic"));
+        assert_eq!(plan.dictionaries, vec!["user.dic"]);
+        // ...and says it replaces PM3's default the weak PRNG would use
+        assert_eq!(plan.selected.as_deref(), Some("user.dic"));
+        assert_eq!(
+            plan.describe(),
+            vec![
+                "Weak PRNG: darkside/nested attack",
+                "Using the selected dictionary user.dic instead of PM3's default",
+            ]
+        );
+        let plan = select_autopwn_plan(&weak, None, Some("keys.dic"));
+        assert_eq!(plan.selected, None);
+        assert_eq!(plan.describe(), vec!["Weak PRNG: darkside/nested attack"]);
+    }
+
+    #[test]
+    fn autopwn_plan_prng_from_sak() {
+        let mut decoded = scan_fields(None, None);
+        decoded.insert("sak".to_string(), "88".to_string());
+        let plan = select_autopwn_plan(&decoded, None, Some("keys.dic"));
+        assert!(plan.dictionaries.is_empty());
+        assert_eq!(plan.reason, "Weak PRNG: darkside/nested attack");
+
+        decoded.insert("sak".to_string(), "28".to_string());
+        let plan = select_autopwn_plan(&decoded, None, Some("keys.dic"));
+        assert_eq!(plan.dictionaries, vec!["keys.dic"]);
+        // A reported PRNG wins over the SAK
+        decoded.insert("prng".to_string(), "weak".to_string());
+        assert!(select_autopwn_plan(&decoded, None, Some("keys.dic")).dictionaries.is_empty());
+    }
+
+    #[test]
+    fn autopwn_plan_gen1_magic_uses_standard() {
+        let plan =
+            select_autopwn_plan(&scan_fields(Some("HARD"), Some("Gen 1a")), None, Some("keys.dic"));
+        assert!(plan.dictionaries.is_empty());
+    }
+
+    #[test]
+    fn hf_autopwn_with_keys() {
+        let cmd = build_hf_autopwn_with_keys(&CardType::MifareClassic4K, "/data/project_keys.dic");
+        assert_eq!(cmd, "hf mf autopwn --4k -f \"/data/project_keys.dic\"");
+    }
+
+    // -- Partial autopwn resume --
+
+    #[test]
+    fn mf_nested_sector_cmd() {
+        let known = KnownKey { sector: 0, key_b: false, key: "FFFFFFFFFFFF".into() };
+        assert_eq!(
+            build_mf_nested_sector(5, true, &known),
+            "hf mf nested --blk 3 -a -k FFFFFFFFFFFF --tblk 23 --tb"
+        );
+        // 4K sectors 32+ are 16 blocks long
+        assert_eq!(
+            build_mf_hardnested_sector(39, false, &known),
+            "hf mf hardnested --blk 3 -a -k FFFFFFFFFFFF --tblk 255 --ta"
+        );
+    }
+
+    #[test]
+    fn resume_plan_targets_missing_keys_only() {
+        use dump_report::SectorKeys;
+        let found = |k: &str| Some(k.to_string());
+        let sectors = vec![
+            SectorKeys { key_a: None, key_b: found("B0B1B2B3B4B5") },
+            SectorKeys { key_a: found("A0A1A2A3A4A5"), key_b: found("B0B1B2B3B4B5") },
+            SectorKeys { key_a: None, key_b: None },
+            SectorKeys { key_a: found("A0A1A2A3A4A5"), key_b: None },
+        ];
+
+        let plan = build_resume_plan(&sectors, Some("weak")).unwrap();
+        let targets: Vec<(u16, bool)> = plan.iter().map(|s| (s.sector, s.key_b)).collect();
+        assert_eq!(targets, vec![(0, false), (2, false), (2, true), (3, true)]);
+        // Sector 0 key B is the first recovered key
+        assert_eq!(plan[0].cmd, "hf mf nested --blk 3 -b -k B0B1B2B3B4B5 --tblk 3 --ta");
+        assert_eq!(plan[3].cmd, "hf mf nested --blk 3 -b -k B0B1B2B3B4B5 --tblk 15 --tb");
+
+        let hard = build_resume_plan(&sectors, Some("HARD")).unwrap();
+        assert!(hard.iter().all(|s| s.cmd.starts_with("hf mf hardnested ")));
+        assert_eq!(hard.len(), 4);
+
+        assert!(build_resume_plan(&sectors, Some("STATIC")).is_err());
+        assert!(build_resume_plan(&vec![SectorKeys::default(); 16], Some("WEAK")).is_err());
+        assert_eq!(
+            build_mf_dump_with_keys(&CardType::MifareClassic4K, "hf-mf-01020304-key.bin"),
+            "hf mf dump --4k --keys \"hf-mf-01020304-key.bin\""
+        );
+    }
+
+    #[test]
+    fn mf_dump_range_cmds() {
+        use dump_report::SectorKeys;
+        let mut keys = vec![
+            SectorKeys {
+                key_a: Some("A0A1A2A3A4A5".into()),
+                key_b: Some("B0B1B2B3B4B5".into()),
+            };
+            40
+        ];
+        keys[31].key_a = None;
+
+        let cmds = build_mf_dump_range(&CardType::MifareClassic4K, 30, 33, &keys).unwrap();
+        assert_eq!(
+            cmds,
+            vec![
+                "hf mf rdsc -s 30 -a -k A0A1A2A3A4A5",
+                "hf mf rdsc -s 31 -b -k B0B1B2B3B4B5",
+                "hf mf rdsc -s 32 -a -k A0A1A2A3A4A5",
+                "hf mf rdsc -s 33 -a -k A0A1A2A3A4A5",
+            ]
+        );
+        keys[5] = SectorKeys::default();
+        assert!(build_mf_dump_range(&CardType::MifareClassic4K, 4, 6, &keys).is_err());
+        assert!(build_mf_dump_range(&CardType::MifareClassic1K, 0, 3, &keys[..2]).is_err());
+    }
+
+    #[test]
+    fn sector_range_follows_card_layout() {
+        // 1K-like region, and the 8 large 16-block sectors that end a 4K
+        assert!(validate_sector_range(&CardType::MifareClassic1K, 0, 15).is_ok());
+        assert!(validate_sector_range(&CardType::MifareClassic1K, 3, 16).is_err());
+        assert!(validate_sector_range(&CardType::MifareClassic4K, 0, 31).is_ok());
+        assert!(validate_sector_range(&CardType::MifareClassic4K, 32, 39).is_ok());
+        assert!(validate_sector_range(&CardType::MifareClassic4K, 39, 40).is_err());
+        assert!(validate_sector_range(&CardType::MifareMini, 0, 4).is_ok());
+        assert!(validate_sector_range(&CardType::MifareMini, 5, 5).is_err());
+        assert!(validate_sector_range(&CardType::MifareClassic4K, 10, 9).is_err());
+    }
+
     // -- Gen1a clone --
 
     #[test]
     fn mf_cload_cmd() {
-        let cmd = build_mf_cload("hf-mf-01020304-dump.bin");
+        let cmd = build_mf_cload(&CardType::MifareClassic1K, "hf-mf-01020304-dump.bin");
         assert_eq!(cmd, "hf mf cload -f hf-mf-01020304-dump.bin");
     }
 
@@ -850,7 +2461,7 @@ mod tests {
 
     #[test]
     fn mf_restore_cmd() {
-        let cmd = build_mf_restore("hf-mf-AABBCCDD-dump.bin");
+        let cmd = build_mf_restore(&CardType::MifareClassic1K, "hf-mf-AABBCCDD-dump.bin");
         assert_eq!(cmd, "hf mf restore -f hf-mf-AABBCCDD-dump.bin");
     }
 
@@ -858,16 +2469,23 @@ mod tests {
 
     #[test]
     fn mf_gen3uid_4byte() {
-        let cmd = build_mf_gen3uid("01020304");
+        let cmd = build_mf_gen3uid("01020304").unwrap();
         assert_eq!(cmd, "hf mf gen3uid --uid 01020304");
     }
 
     #[test]
     fn mf_gen3uid_7byte() {
-        let cmd = build_mf_gen3uid("01020304050607");
+        let cmd = build_mf_gen3uid("01020304050607").unwrap();
         assert_eq!(cmd, "hf mf gen3uid --uid 01020304050607");
     }
 
+    #[test]
+    fn mf_gen3uid_rejects_ambiguous_lengths() {
+        for uid in ["0102030405", "010203", "0102030405060708", "", "0102030G"] {
+            assert!(build_mf_gen3uid(uid).is_err(), "{}", uid);
+        }
+    }
+
     #[test]
     fn mf_gen3blk_cmd() {
         let cmd = build_mf_gen3blk("0102030404080400000000000000BEEF");
@@ -878,7 +2496,7 @@ mod tests {
 
     #[test]
     fn mf_gload_cmd() {
-        let cmd = build_mf_gload("hf-mf-01020304-dump.bin");
+        let cmd = build_mf_gload(&CardType::MifareClassic1K, "hf-mf-01020304-dump.bin");
         assert_eq!(cmd, "hf mf gload -f hf-mf-01020304-dump.bin");
     }
 
@@ -902,6 +2520,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mf_gdm_setblk_all_from_dump() {
+        let mut dump = vec![0u8; 1024];
+        dump[..16].copy_from_slice(&[
+            0x01, 0x02, 0x03, 0x04, 0x04, 0x08, 0x04, 0x00, 0, 0, 0, 0, 0, 0, 0xBE, 0xEF,
+        ]);
+        dump[48..64].copy_from_slice(&[
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x80, 0x69, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF,
+        ]);
+        let cmds = build_mf_gdm_setblk_all(&dump).unwrap();
+        assert_eq!(cmds.len(), 64);
+        assert_eq!(cmds[0], (0, build_mf_gdm_setblk(0, "0102030404080400000000000000BEEF")));
+        assert_eq!(
+            cmds[3].1,
+            "hf mf gdmsetblk --blk 3 -d FFFFFFFFFFFFFF078069FFFFFFFFFFFF"
+        );
+        assert_eq!(cmds[63].0, 63);
+
+        assert_eq!(build_mf_gdm_setblk_all(&[0u8; 4096]).unwrap().len(), 256);
+        assert_eq!(build_mf_gdm_setblk_all(&[0u8; 320]).unwrap().len(), 20);
+        assert!(build_mf_gdm_setblk_all(&[0u8; 1000]).is_err());
+        assert!(build_mf_gdm_setblk_all(&[]).is_err());
+    }
+
     #[test]
     fn mf_gdm_setblk_4k_block255() {
         let cmd = build_mf_gdm_setblk(255, "DEADBEEF" .repeat(4).as_str());
@@ -937,20 +2580,334 @@ mod tests {
         assert_eq!(build_mfu_dump(), "hf mfu dump");
     }
 
+    #[test]
+    fn mfu_rdbl_cmd() {
+        assert_eq!(build_mfu_rdbl(0x82), "hf mfu rdbl -b 130");
+    }
+
     #[test]
     fn iclass_dump_cmd() {
         assert_eq!(build_iclass_dump(), "hf iclass dump --ki 0");
     }
 
+    #[test]
+    fn iclass_decrypt_cmd() {
+        assert_eq!(
+            build_iclass_decrypt("hf-iclass-0102030405060708-dump.bin"),
+            "hf iclass decrypt -f hf-iclass-0102030405060708-dump.bin"
+        );
+    }
+
     // -- Verification commands --
 
     #[test]
     fn mf_cview_cmd() {
-        assert_eq!(build_mf_cview(), "hf mf cview");
+        assert_eq!(build_mf_cview(&CardType::MifareClassic1K), "hf mf cview");
+    }
+
+    #[test]
+    fn mf_rdbl_key_type() {
+        assert_eq!(
+            build_mf_rdbl(4, "FFFFFFFFFFFF", KeyType::A),
+            "hf mf rdbl --blk 4 -k FFFFFFFFFFFF -a"
+        );
+        assert_eq!(
+            build_mf_rdbl(6, "A0A1A2A3A4A5", KeyType::B),
+            "hf mf rdbl --blk 6 -k A0A1A2A3A4A5 -b"
+        );
     }
 
     #[test]
     fn mf_dump_cmd() {
-        assert_eq!(build_mf_dump(), "hf mf dump");
+        assert_eq!(build_mf_dump(&CardType::MifareClassic1K), "hf mf dump");
+    }
+
+    #[test]
+    fn mifare_mini_size_flags() {
+        let mini = CardType::MifareMini;
+        assert_eq!(build_hf_autopwn(&mini), "hf mf autopwn --mini");
+        assert_eq!(build_mf_dump(&mini), "hf mf dump --mini");
+        assert_eq!(build_mf_cview(&mini), "hf mf cview --mini");
+        assert_eq!(
+            build_mf_restore(&mini, "hf-mf-01020304-dump.bin"),
+            "hf mf restore --mini -f hf-mf-01020304-dump.bin"
+        );
+        assert_eq!(
+            build_mf_cload(&mini, "hf-mf-01020304-dump.bin"),
+            "hf mf cload --mini -f hf-mf-01020304-dump.bin"
+        );
+    }
+
+    // -- Firmware flashing --
+
+    #[test]
+    fn flash_args_fullimage() {
+        let args = build_flash_args("COM3", &["fw/fullimage.elf"], false);
+        assert_eq!(args.join(" "), "COM3 --flash --image fw/fullimage.elf -w");
+    }
+
+    #[test]
+    fn flash_args_recovery() {
+        let args = build_flash_args(
+            "/dev/ttyACM0",
+            &["fw/bootrom.elf", "fw/fullimage.elf"],
+            true,
+        );
+        assert_eq!(
+            args.join(" "),
+            "/dev/ttyACM0 --flash --unlock-bootloader --image fw/bootrom.elf --image fw/fullimage.elf -w"
+        );
+    }
+
+    // -- LF clone field validation --
+
+    fn noralsy_fields(cn: &str, year: Option<&str>) -> std::collections::HashMap<String, String> {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("card_number".to_string(), cn.to_string());
+        if let Some(y) = year {
+            decoded.insert("year".to_string(), y.to_string());
+        }
+        decoded
+    }
+
+    #[test]
+    fn required_fields_named_when_missing() {
+        let full = |pairs: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        type Pairs = &'static [(&'static str, &'static str)];
+        let cases: &[(CardType, Pairs, &str)] = &[
+            (CardType::HIDProx, &[("facility_code", "65")], "card_number"),
+            (CardType::AWID, &[("card_number", "1234")], "facility_code"),
+            (CardType::Pyramid, &[("card_number", "1234")], "facility_code"),
+            (CardType::GProxII, &[("facility_code", "12")], "card_number"),
+            (
+                CardType::Nedap,
+                &[("subtype", "5"), ("card_number", "1234")],
+                "customer_code",
+            ),
+            (
+                CardType::Gallagher,
+                &[("facility_code", "1"), ("card_number", "2"), ("issue_level", "3")],
+                "region_code",
+            ),
+            (CardType::Visa2000, &[], "card_number"),
+        ];
+        for (card_type, pairs, missing) in cases {
+            let decoded = full(pairs);
+            let err = check_required_fields(card_type, "0A0B", &decoded).unwrap_err();
+            let expected = format!("missing {} for {}", missing, card_type.display_name());
+            assert!(err.contains(&expected), "{:?}: {}", card_type, err);
+            assert!(build_clone_command(card_type, "0A0B", &decoded).is_none());
+
+            let mut complete = decoded.clone();
+            complete.insert(missing.to_string(), "7".into());
+            assert!(check_required_fields(card_type, "0A0B", &complete).is_ok(), "{:?}", card_type);
+        }
+
+        // Present but unusable, and alternatives that stand in for FC/CN
+        let check = |card_type: &CardType, pairs: &[(&str, &str)]| {
+            check_required_fields(card_type, "0A0B", &full(pairs))
+        };
+        let err = check(&CardType::Visa2000, &[("card_number", "A1")]).unwrap_err();
+        assert!(err.contains("card_number for Visa2000 must be a decimal number"), "{}", err);
+        assert!(check(&CardType::HIDProx, &[("raw", "2006ec0c86")]).is_ok());
+        assert!(check(&CardType::EM4100, &[]).is_ok());
+
+        // A wrong-length Pyramid raw is named, not a generic failure
+        let err = check(&CardType::Pyramid, &[("raw", "0F0A")]).unwrap_err();
+        assert!(err.contains("Raw too short for Pyramid"), "{}", err);
+        // Types whose card number can come from the UID
+        let err = check(&CardType::Keri, &[("card_number", "12AB")]).unwrap_err();
+        assert!(err.contains("Keri card number must be decimal"), "{}", err);
+        let err = check(&CardType::Noralsy, &[("card_number", "99999999")]).unwrap_err();
+        assert!(err.contains("Invalid Noralsy card number"), "{}", err);
+        let err = check(&CardType::Paradox, &[("facility_code", "96")]).unwrap_err();
+        assert_eq!(err, "missing card_number for Paradox");
+        let err = check(&CardType::Paradox, &[]).unwrap_err();
+        assert!(err.contains("Raw too short for Paradox"), "{}", err);
+        assert_eq!(
+            check(&CardType::COTAG, &[]).unwrap_err(),
+            "COTAG has no clone command"
+        );
+    }
+
+    #[test]
+    fn noralsy_valid_card() {
+        let decoded = noralsy_fields("112233", Some("2000"));
+        assert!(validate_clone_fields(&CardType::Noralsy, "112233", &decoded).is_ok());
+        assert_eq!(
+            build_clone_command(&CardType::Noralsy, "112233", &decoded).unwrap(),
+            "lf noralsy clone --cn 112233 -y 2000"
+        );
+    }
+
+    #[test]
+    fn noralsy_non_numeric_card_rejected() {
+        let decoded = noralsy_fields("11AB33", None);
+        let err = validate_clone_fields(&CardType::Noralsy, "11AB33", &decoded).unwrap_err();
+        assert!(err.contains("card number"));
+        assert!(build_clone_command(&CardType::Noralsy, "11AB33", &decoded).is_none());
+
+        let decoded = noralsy_fields("10000000", None);
+        assert!(validate_clone_fields(&CardType::Noralsy, "10000000", &decoded).is_err());
+    }
+
+    #[test]
+    fn noralsy_implausible_year_rejected() {
+        for year in ["23", "3023", "20x3"] {
+            let decoded = noralsy_fields("112233", Some(year));
+            let err = validate_clone_fields(&CardType::Noralsy, "112233", &decoded).unwrap_err();
+            assert!(err.contains("year"), "{}: {}", year, err);
+            assert!(build_clone_command(&CardType::Noralsy, "112233", &decoded).is_none());
+        }
+    }
+
+    #[test]
+    fn awid_fields_checked_against_format_width() {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("format".to_string(), "26".to_string());
+        decoded.insert("facility_code".to_string(), "50".to_string());
+        decoded.insert("card_number".to_string(), "70000".to_string());
+        let err = validate_clone_fields(&CardType::AWID, "", &decoded).unwrap_err();
+        assert!(err.contains("0-65535"), "{}", err);
+        assert!(build_clone_command(&CardType::AWID, "", &decoded).is_none());
+
+        // Same CN fits the 37-bit layout (18-bit CN) but its 13-bit FC caps at 8191
+        decoded.insert("format".to_string(), "37".to_string());
+        assert!(validate_clone_fields(&CardType::AWID, "", &decoded).is_ok());
+        decoded.insert("facility_code".to_string(), "8192".to_string());
+        assert!(validate_clone_fields(&CardType::AWID, "", &decoded).is_err());
+    }
+
+    #[test]
+    fn paradox_raw_length_checked() {
+        let decoded = std::collections::HashMap::new();
+        let raw = "0F55555695596A6A9999A59A"; // 96 bits
+        assert!(validate_clone_fields(&CardType::Paradox, raw, &decoded).is_ok());
+        assert_eq!(
+            build_clone_command(&CardType::Paradox, raw, &decoded).unwrap(),
+            "lf paradox clone --raw 0F55555695596A6A9999A59A"
+        );
+
+        let err = validate_clone_fields(&CardType::Paradox, &raw[..20], &decoded).unwrap_err();
+        assert!(err.contains("too short") && err.contains("96 bits"), "{}", err);
+        assert!(build_clone_command(&CardType::Paradox, &raw[..20], &decoded).is_none());
+        let long = format!("{}00", raw);
+        assert!(validate_clone_fields(&CardType::Paradox, &long, &decoded)
+            .unwrap_err()
+            .contains("too long"));
+
+        // FC/CN clone doesn't depend on the raw at all
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("facility_code".to_string(), "42".to_string());
+        decoded.insert("card_number".to_string(), "1337".to_string());
+        assert!(validate_clone_fields(&CardType::Paradox, "AB", &decoded).is_ok());
+    }
+
+    #[test]
+    fn pyramid_raw_length_checked() {
+        let mut decoded = std::collections::HashMap::new();
+        let raw = "0001010101010101010440013223921B"; // 128 bits
+        decoded.insert("raw".to_string(), raw.to_string());
+        assert!(validate_clone_fields(&CardType::Pyramid, raw, &decoded).is_ok());
+        assert_eq!(
+            build_clone_command(&CardType::Pyramid, raw, &decoded).unwrap(),
+            format!("lf pyramid clone --raw {}", raw)
+        );
+
+        decoded.insert("raw".to_string(), "0001010101010101".to_string());
+        let err = validate_clone_fields(&CardType::Pyramid, raw, &decoded).unwrap_err();
+        assert!(err.contains("too short") && err.contains("Pyramid"), "{}", err);
+        assert!(build_clone_command(&CardType::Pyramid, raw, &decoded).is_none());
+
+        decoded.insert("raw".to_string(), format!("{}FF", raw));
+        assert!(validate_clone_fields(&CardType::Pyramid, raw, &decoded)
+            .unwrap_err()
+            .contains("too long"));
+    }
+
+    #[test]
+    fn clone_sequence_increments_card_number() {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("facility_code".to_string(), "65".to_string());
+        decoded.insert("card_number".to_string(), "29334".to_string());
+        decoded.insert("format".to_string(), "H10301".to_string());
+        decoded.insert("raw".to_string(), "200078BE5E1E".to_string());
+
+        let seq = build_clone_sequence(&CardType::HIDProx, &decoded, 3).unwrap();
+        let cns: Vec<u64> = seq.iter().map(|(cn, _)| *cn).collect();
+        assert_eq!(cns, vec![29334, 29335, 29336]);
+        assert_eq!(seq[0].1, "lf hid clone -w H10301 --fc 65 --cn 29334");
+        assert_eq!(seq[2].1, "lf hid clone -w H10301 --fc 65 --cn 29336");
+
+        // H10302 has no facility code field
+        decoded.remove("facility_code");
+        assert!(build_clone_sequence(&CardType::HIDProx, &decoded, 2).is_err());
+        decoded.insert("format".to_string(), "H10302".to_string());
+        let seq = build_clone_sequence(&CardType::HIDProx, &decoded, 2).unwrap();
+        assert_eq!(seq[1].1, "lf hid clone -w H10302 --cn 29335");
+    }
+
+    #[test]
+    fn clone_sequence_range_checked() {
+        let mut decoded = std::collections::HashMap::new();
+        decoded.insert("facility_code".to_string(), "50".to_string());
+        decoded.insert("card_number".to_string(), "65534".to_string());
+        decoded.insert("format".to_string(), "26".to_string());
+        // 65534, 65535 fit a 26-bit AWID; a third card would overflow 16 bits
+        assert_eq!(build_clone_sequence(&CardType::AWID, &decoded, 2).unwrap().len(), 2);
+        let err = build_clone_sequence(&CardType::AWID, &decoded, 3).unwrap_err();
+        assert!(err.contains("65536"), "{}", err);
+
+        assert!(build_clone_sequence(&CardType::EM4100, &decoded, 2).is_err());
+        assert!(build_clone_sequence(&CardType::AWID, &decoded, 0).is_err());
+
+        decoded.insert("card_number".to_string(), u64::MAX.to_string());
+        let err = build_clone_sequence(&CardType::AWID, &decoded, 2).unwrap_err();
+        assert!(err.contains("overflows"), "{}", err);
+    }
+
+    #[test]
+    fn desfire_uid_clone_commands() {
+        let gen3 = build_desfire_uid_clone("04:A2:3B:1C:5D:80:00", &BlankType::MagicMifareGen3).unwrap();
+        assert_eq!(
+            gen3,
+            vec![
+                "hf mf gen3uid --uid 04A23B1C5D8000".to_string(),
+                "hf mf gen3blk 04A23B1C5D8000204403000000000000".to_string(),
+            ]
+        );
+
+        let gtu = build_desfire_uid_clone("04a23b1c5d8000", &BlankType::MagicMifareGen4GTU).unwrap();
+        assert_eq!(
+            gtu,
+            vec![
+                "hf 14a raw -s -c -t 1000 CF000000006801".to_string(),
+                "hf mf gsetblk --blk 0 -d 04A23B1C5D8000204403000000000000".to_string(),
+            ]
+        );
+
+        let ul = build_desfire_uid_clone("04A23B1C5D8000", &BlankType::MagicUltralight).unwrap();
+        assert_eq!(ul, vec!["hf mfu setuid --uid 04A23B1C5D8000"]);
+    }
+
+    #[test]
+    fn gen4_uid_len_config() {
+        assert_eq!(build_mf_gen4_uid_len(4).unwrap(), "hf 14a raw -s -c -t 1000 CF000000006800");
+        assert_eq!(build_mf_gen4_uid_len(7).unwrap(), "hf 14a raw -s -c -t 1000 CF000000006801");
+        assert_eq!(build_mf_gen4_uid_len(10).unwrap(), "hf 14a raw -s -c -t 1000 CF000000006802");
+        assert!(build_mf_gen4_uid_len(5).is_err());
+    }
+
+    #[test]
+    fn desfire_uid_clone_rejections() {
+        // Random 4-byte ID
+        let err = build_desfire_uid_clone("08123456", &BlankType::MagicMifareGen3).unwrap_err();
+        assert!(err.contains("7-byte"), "{}", err);
+        // Gen2 has a fixed UID length and no UID-only path
+        let err = build_desfire_uid_clone("04A23B1C5D8000", &BlankType::MagicMifareGen2).unwrap_err();
+        assert!(err.contains("Gen3"), "{}", err);
+        assert!(build_desfire_uid_clone("04A23B1C5D80ZZ", &BlankType::MagicMifareGen3).is_err());
     }
 }
@@ -1,15 +1,22 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{LazyLock, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use regex::Regex;
 use serde::Serialize;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tauri_plugin_shell::ShellExt;
 use tokio::time::timeout;
 
 use crate::error::AppError;
+use crate::pm3::clone_summary;
+use crate::pm3::command_builder::{self, Verbosity};
+use crate::pm3::dump_report::SectorKeys;
+use crate::pm3::mock::MockMode;
+use crate::pm3::operation::current_operation_id;
 use crate::pm3::output_parser::strip_ansi;
+use crate::pm3::version::{self, FirmwareFork};
 
 /// Payload emitted as `pm3-output` events for the live terminal panel.
 #[derive(Debug, Clone, Serialize)]
@@ -17,25 +24,176 @@ use crate::pm3::output_parser::strip_ansi;
 pub struct Pm3OutputPayload {
     pub text: String,
     pub is_error: bool,
+    /// Operation that produced this line; `None` for one-shot commands,
+    /// which don't run as an operation (see `operation`).
+    pub operation_id: Option<u64>,
+    /// The line with PM3's ANSI colour codes intact, when colour preservation
+    /// is on and the line had any. `text` is always the stripped version.
+    pub ansi: Option<String>,
 }
 
-/// Emit raw PM3 output to the frontend terminal panel.
+impl Pm3OutputPayload {
+    pub fn new(text: &str, is_error: bool) -> Self {
+        Self {
+            text: text.to_string(),
+            is_error,
+            operation_id: current_operation_id(),
+            ansi: None,
+        }
+    }
+}
+
+/// Terminal panel and PM3 output preferences. Stored via `app.manage()` in
+/// `lib.rs`.
+pub struct TerminalSettings {
+    preserve_ansi: AtomicBool,
+    verbosity: Mutex<Verbosity>,
+}
+
+impl TerminalSettings {
+    pub fn new() -> Self {
+        Self {
+            preserve_ansi: AtomicBool::new(false),
+            verbosity: Mutex::new(Verbosity::Normal),
+        }
+    }
+
+    /// Default verbosity for the PM3 commands the app builds. Commands typed
+    /// into the terminal run exactly as typed.
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity.lock().map(|v| *v).unwrap_or_default()
+    }
+
+    pub fn set_verbosity(&self, verbosity: Verbosity) {
+        if let Ok(mut current) = self.verbosity.lock() {
+            *current = verbosity;
+        }
+    }
+
+    pub fn preserve_ansi(&self) -> bool {
+        self.preserve_ansi.load(Ordering::Relaxed)
+    }
+
+    pub fn set_preserve_ansi(&self, enabled: bool) {
+        self.preserve_ansi.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// One payload per non-empty line of `text`. ANSI codes are always stripped
+/// from `text`; with `preserve_ansi` the coloured original rides along.
+fn output_payloads(text: &str, is_error: bool, preserve_ansi: bool) -> Vec<Pm3OutputPayload> {
+    text.lines()
+        .filter_map(|line| {
+            let cleaned = strip_ansi(line);
+            let trimmed = cleaned.trim();
+            if trimmed.is_empty() {
+                return None;
+            }
+            let mut payload = Pm3OutputPayload::new(trimmed, is_error);
+            if preserve_ansi && line.contains('\x1b') {
+                payload.ansi = Some(line.trim().to_string());
+            }
+            Some(payload)
+        })
+        .collect()
+}
+
+/// Emit PM3 output (raw or already cleaned) to the frontend terminal panel.
 pub fn emit_output(app: &AppHandle, text: &str, is_error: bool) {
-    for line in text.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let _ = app.emit(
-            "pm3-output",
-            Pm3OutputPayload {
-                text: trimmed.to_string(),
-                is_error,
-            },
-        );
+    let preserve_ansi = app
+        .try_state::<TerminalSettings>()
+        .is_some_and(|s| s.preserve_ansi());
+    for payload in output_payloads(text, is_error, preserve_ansi) {
+        let _ = app.emit("pm3-output", payload);
     }
 }
 
+// ---------------------------------------------------------------------------
+// Progress throttling
+// ---------------------------------------------------------------------------
+
+/// Minimum gap between two progress events of the same stream.
+pub const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Coalesces high-frequency progress payloads (hardnested can print hundreds
+/// of lines a second). `offer` passes a payload to `emit` when the last one
+/// went out at least `interval` ago; anything held back is replaced by newer
+/// updates and emitted when the throttle is dropped, so the final state
+/// always reaches the frontend. Terminal lines don't go through this -- they
+/// stay one event per line.
+pub struct ProgressThrottle<T, F: Fn(T)> {
+    interval: Duration,
+    emit: F,
+    state: Mutex<ThrottleState<T>>,
+}
+
+struct ThrottleState<T> {
+    last_emit: Option<Instant>,
+    pending: Option<T>,
+}
+
+impl<T, F: Fn(T)> ProgressThrottle<T, F> {
+    pub fn new(interval: Duration, emit: F) -> Self {
+        Self {
+            interval,
+            emit,
+            state: Mutex::new(ThrottleState {
+                last_emit: None,
+                pending: None,
+            }),
+        }
+    }
+
+    /// Emit `payload` now, or hold it until the next update is due.
+    pub fn offer(&self, payload: T) {
+        self.offer_at(payload, Instant::now())
+    }
+
+    fn offer_at(&self, payload: T, now: Instant) {
+        let Ok(mut state) = self.state.lock() else {
+            return (self.emit)(payload);
+        };
+        let due = state
+            .last_emit
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+        if due {
+            state.last_emit = Some(now);
+            state.pending = None;
+            drop(state);
+            (self.emit)(payload);
+        } else {
+            state.pending = Some(payload);
+        }
+    }
+}
+
+impl<T, F: Fn(T)> Drop for ProgressThrottle<T, F> {
+    /// Trailing edge: the latest held-back update goes out when the stream ends.
+    fn drop(&mut self) {
+        let pending = self.state.get_mut().ok().and_then(|state| state.pending.take());
+        if let Some(payload) = pending {
+            (self.emit)(payload);
+        }
+    }
+}
+
+/// The verbosity set for app-built commands (see `TerminalSettings`).
+fn app_verbosity(app: &AppHandle) -> Verbosity {
+    app.try_state::<TerminalSettings>()
+        .map(|s| s.verbosity())
+        .unwrap_or_default()
+}
+
+/// Client arguments for one `-c` command at `verbosity`. Parsers see the
+/// same stripped text either way; verbose output only adds lines they skip.
+fn pm3_args(port: &str, cmd: &str, verbosity: Verbosity) -> Vec<String> {
+    let mut args = vec!["-p".to_string(), port.to_string(), "-f".to_string()];
+    args.extend(command_builder::client_debug_args(verbosity).iter().map(|a| a.to_string()));
+    args.push("-c".to_string());
+    args.push(command_builder::with_verbosity(cmd, verbosity));
+    args
+}
+
 /// Maximum time to wait for a PM3 subprocess to complete (30 seconds).
 const PM3_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -64,43 +222,184 @@ fn pm3_scope_names() -> Vec<&'static str> {
 
 /// Validates that a port string matches expected serial port patterns.
 /// Accepts COM1-COM256+ (Windows), /dev/ttyACM0-99, /dev/ttyUSB0-99 (Linux),
-/// and /dev/tty.usbmodem* (macOS).
+/// and /dev/cu.usbmodem* or /dev/tty.usbmodem* (macOS).
 static PORT_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^(COM[1-9]\d*|/dev/tty(ACM|USB)\d{1,2}|/dev/tty\.usbmodem\w+)$")
+    Regex::new(r"^(COM[1-9]\d*|/dev/tty(ACM|USB)\d{1,2}|/dev/(cu|tty)\.usbmodem\w+)$")
         .expect("bad port regex")
 });
 
-/// Internal PM3 execution that does NOT emit to the frontend.
-/// Handles: port validation, command sanitization, sidecar fallback, PATH lookup,
-/// process spawn, output collection, ANSI stripping, and timeout.
-/// Returns the cleaned output string on success.
-async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, AppError> {
-    // Validate port format to prevent command injection via subprocess args
+/// Reject a port the frontend passed that isn't a PM3 serial port. Commands
+/// call this before touching the device or any state.
+pub fn check_port(port: &str) -> Result<(), AppError> {
     if !PORT_RE.is_match(port) {
-        return Err(AppError::CommandFailed(format!(
-            "Invalid port: {}",
-            port
-        )));
+        return Err(AppError::CommandFailed(format!("Invalid port: {}", port)));
     }
+    Ok(())
+}
+
+/// Recognize OS-level "can't open the serial port" failures in PM3 output.
+/// Linux/macOS report missing permissions as EACCES ("Permission denied");
+/// a port held by another process shows up as EBUSY ("Device or resource
+/// busy") or, on Windows, as ERROR_ACCESS_DENIED ("Access is denied") since
+/// COM ports have no per-user permissions there.
+pub fn classify_port_error(port: &str, detail: &str) -> Option<AppError> {
+    let lower = detail.to_lowercase();
 
+    if lower.contains("resource busy")
+        || lower.contains("port busy")
+        || lower.contains("already in use")
+        || lower.contains("in use by another")
+        || lower.contains("access is denied")
+        || lower.contains("error_access_denied")
+        || lower.contains("cannot lock")
+    {
+        return Some(AppError::PortBusy(port.to_string()));
+    }
+
+    if lower.contains("permission denied") || lower.contains("eacces") {
+        return Some(AppError::PermissionDenied(port.to_string()));
+    }
+
+    None
+}
+
+/// Build the error for a non-zero PM3 exit, preferring a structured
+/// port-access error over the generic `CommandFailed`.
+fn exit_error(port: &str, code: i32, detail: &str) -> AppError {
+    classify_port_error(port, detail).unwrap_or_else(|| {
+        AppError::CommandFailed(format!("Exit code {}: {}", code, detail))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Transient comms retry
+// ---------------------------------------------------------------------------
+
+/// Extra attempts after a transient comms failure on an open connection.
+const COMMS_RETRIES: usize = 2;
+
+/// Pause before retrying, so a USB hiccup can clear.
+const COMMS_RETRY_DELAY: Duration = Duration::from_millis(300);
+
+/// Client messages for a garbled or dropped exchange with the device, as
+/// opposed to the command itself failing (no tag, rejected arguments).
+const TRANSIENT_COMMS_SIGNATURES: &[&str] = &[
+    "communicating with proxmark3 device failed",
+    "sending bytes to proxmark3 failed",
+    "timeout while waiting for proxmark3 reply",
+    "received packet frame with incompatible length",
+    "received packet frame without start magic",
+    "received packet frame with wrong crc",
+];
+
+fn is_transient_comms_error(err: &AppError) -> bool {
+    let AppError::CommandFailed(msg) = err else {
+        return false;
+    };
+    let lower = msg.to_lowercase();
+    TRANSIENT_COMMS_SIGNATURES.iter().any(|sig| lower.contains(sig))
+}
+
+/// Verbs that only read from the card or device.
+const READ_ONLY_VERBS: &[&str] = &[
+    "search", "info", "rdbl", "rdsc", "dump", "detect", "read", "version", "status",
+];
+
+/// Whether `cmd` can simply be sent again: only read-only commands, whose
+/// verb (the last word before the options) is in `READ_ONLY_VERBS`. Anything
+/// else may have partly completed before the link dropped.
+fn is_retry_safe(cmd: &str) -> bool {
+    cmd.split_whitespace()
+        .take_while(|word| !word.starts_with('-'))
+        .last()
+        .is_some_and(|verb| READ_ONLY_VERBS.contains(&verb.to_lowercase().as_str()))
+}
+
+/// Run `attempt` for `cmd`, retrying up to `COMMS_RETRIES` times when it
+/// fails with a transient comms error and `cmd` is safe to repeat. Other
+/// errors (no card, rejected command, timeouts) are returned straight away.
+async fn with_comms_retry<F, Fut>(cmd: &str, mut attempt: F) -> Result<String, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<String, AppError>>,
+{
+    let mut retries = 0;
+    loop {
+        match attempt().await {
+            Err(e) if retries < COMMS_RETRIES
+                && is_transient_comms_error(&e)
+                && is_retry_safe(cmd) =>
+            {
+                retries += 1;
+                log::warn!("Transient comms error on '{}', retry {}: {}", cmd, retries, e);
+                tokio::time::sleep(COMMS_RETRY_DELAY).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Internal PM3 execution that does NOT emit to the frontend.
+/// Returns the cleaned (ANSI-stripped) output string on success.
+async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, AppError> {
+    let verbosity = app_verbosity(app);
+    with_comms_retry(cmd, || execute_pm3_raw(app, port, cmd, verbosity))
+        .await
+        .map(|raw| strip_ansi(&raw))
+}
+
+/// PM3's `-c` command separator, or a line break: a string holding any of
+/// these would run more than one command.
+fn has_command_separator(s: &str) -> bool {
+    s.contains(';') || s.contains('\n') || s.contains('\r')
+}
+
+/// Handles: port validation, command sanitization, sidecar fallback, PATH lookup,
+/// process spawn, output collection, and timeout.
+/// Returns stdout with PM3's ANSI colour codes intact on success.
+async fn execute_pm3_raw(
+    app: &AppHandle,
+    port: &str,
+    cmd: &str,
+    verbosity: Verbosity,
+) -> Result<String, AppError> {
     // Reject command strings containing PM3 command separators or newlines.
     // The PM3 CLI's `-c` flag treats `;` as a delimiter, so a crafted value
     // like "AA;lf t55xx wipe" would execute two commands. Block this at the
     // chokepoint so no caller can accidentally pass through unsanitised input.
-    if cmd.contains(';') || cmd.contains('\n') || cmd.contains('\r') {
+    // (`ChainedCommand` is the one way to run several, each part checked.)
+    if has_command_separator(cmd) {
         return Err(AppError::CommandFailed(
             "Invalid characters in command".into(),
         ));
     }
-    if port.contains(';') || port.contains('\n') || port.contains('\r') {
+    execute_pm3_script(app, port, cmd, verbosity).await
+}
+
+/// `execute_pm3_raw` without the separator check on `cmd`: only for
+/// `ChainedCommand` scripts, whose parts were checked one by one.
+async fn execute_pm3_script(
+    app: &AppHandle,
+    port: &str,
+    cmd: &str,
+    verbosity: Verbosity,
+) -> Result<String, AppError> {
+    // Validate port format to prevent command injection via subprocess args
+    check_port(port)?;
+    if has_command_separator(port) {
         return Err(AppError::CommandFailed(
             "Invalid characters in command".into(),
         ));
     }
 
+    // Offline mode: replay recorded output instead of touching hardware
+    if let Some(output) = replay_mock(app, cmd) {
+        return output;
+    }
+
     // 1) Try bundled sidecar binary first (available in production builds).
     //    In dev mode the sidecar won't exist, so this silently falls through.
-    match try_sidecar_silent(app, port, cmd).await {
+    match try_sidecar_silent(app, port, cmd, verbosity).await {
         Ok(output) => return Ok(output),
         Err(_) => { /* sidecar not available -- fall through to PATH/scope lookup */ }
     }
@@ -114,7 +413,7 @@ async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, A
         let output_future = app
             .shell()
             .command(scope_name)
-            .args(["-p", port, "-f", "-c", cmd])
+            .args(pm3_args(port, cmd, verbosity))
             .output();
 
         // Note: When the timeout fires and the future is dropped, Tauri's shell plugin
@@ -149,10 +448,7 @@ async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, A
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
         return match code {
-            0 => {
-                let cleaned = strip_ansi(&stdout);
-                Ok(cleaned)
-            }
+            0 => Ok(stdout),
             -5 | 251 => Err(AppError::Timeout(format!(
                 "PM3 timed out running: {}",
                 cmd
@@ -163,10 +459,7 @@ async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, A
                 } else {
                     strip_ansi(&stderr)
                 };
-                Err(AppError::CommandFailed(format!(
-                    "Exit code {}: {}",
-                    code, detail
-                )))
+                Err(exit_error(port, code, &detail))
             }
         };
     }
@@ -197,11 +490,129 @@ async fn execute_pm3(app: &AppHandle, port: &str, cmd: &str) -> Result<String, A
 /// - When the Tauri future is dropped (timeout or app shutdown), the shell plugin
 ///   cleans up the child process.
 pub async fn run_command(app: &AppHandle, port: &str, cmd: &str) -> Result<String, AppError> {
+    run_command_at(app, port, cmd, app_verbosity(app)).await
+}
+
+/// `run_command` for a command the user typed into the terminal: it runs as
+/// typed, without the verbosity set for app-built commands.
+pub async fn run_terminal_command(
+    app: &AppHandle,
+    port: &str,
+    cmd: &str,
+) -> Result<String, AppError> {
+    run_command_at(app, port, cmd, Verbosity::Normal).await
+}
+
+async fn run_command_at(
+    app: &AppHandle,
+    port: &str,
+    cmd: &str,
+    verbosity: Verbosity,
+) -> Result<String, AppError> {
     emit_output(app, &format!("pm3 --> {}", cmd), false);
-    match execute_pm3(app, port, cmd).await {
-        Ok(output) => {
-            emit_output(app, &output, false);
-            Ok(output)
+    clone_summary::record_command(app, cmd);
+    match with_comms_retry(cmd, || execute_pm3_raw(app, port, cmd, verbosity)).await {
+        Ok(raw) => {
+            // Terminal gets the colours (if enabled); parsers get clean text
+            emit_output(app, &raw, false);
+            Ok(strip_ansi(&raw))
+        }
+        Err(e) => {
+            emit_output(app, &e.to_string(), true);
+            Err(e)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Chained commands — several commands in one PM3 invocation
+// ---------------------------------------------------------------------------
+
+/// Commands run back to back in a single PM3 client (`-c "a; b"`), so the
+/// card never leaves the field between them (write, then read back). Built
+/// only from commands the app constructs; each part is checked for
+/// separators on its own, so a value inside one can't smuggle in another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainedCommand {
+    parts: Vec<String>,
+}
+
+impl ChainedCommand {
+    pub fn new(parts: &[&str]) -> Result<Self, AppError> {
+        if parts.len() < 2 {
+            return Err(AppError::CommandFailed(
+                "A chained command needs at least two commands".into(),
+            ));
+        }
+        if parts.iter().any(|p| p.trim().is_empty() || has_command_separator(p)) {
+            return Err(AppError::CommandFailed(
+                "Invalid characters in command".into(),
+            ));
+        }
+        Ok(Self {
+            parts: parts.iter().map(|p| p.trim().to_string()).collect(),
+        })
+    }
+
+    /// The `-c` argument: parts joined with PM3's separator.
+    pub fn script(&self) -> String {
+        self.parts.join("; ")
+    }
+
+    /// Output of part `index` from a run of the whole chain, cut at the
+    /// `pm3 --> <cmd>` line the client echoes before each part. `None` if
+    /// that echo isn't in the output (the client stopped early).
+    pub fn part_output(&self, output: &str, index: usize) -> Option<String> {
+        let mut current = None;
+        let mut next = 0;
+        let mut lines = Vec::new();
+        for line in output.lines() {
+            if self
+                .parts
+                .get(next)
+                .is_some_and(|part| line.trim_end().ends_with(&format!("pm3 --> {}", part)))
+            {
+                current = Some(next);
+                next += 1;
+                continue;
+            }
+            if current == Some(index) {
+                lines.push(line);
+            }
+        }
+        (next > index).then(|| lines.join("\n"))
+    }
+}
+
+/// Run a `ChainedCommand` once (it usually writes, so it is never retried)
+/// and return the whole cleaned output; split it with `part_output`.
+pub async fn run_chained(
+    app: &AppHandle,
+    port: &str,
+    chain: &ChainedCommand,
+) -> Result<String, AppError> {
+    let script = chain.script();
+    emit_output(app, &format!("pm3 --> {}", script), false);
+    for part in &chain.parts {
+        clone_summary::record_command(app, part);
+    }
+    let result = match app.try_state::<MockMode>().filter(|m| m.is_enabled()) {
+        // Offline: replay each part, echoed the way the client does
+        Some(mock) => chain
+            .parts
+            .iter()
+            .map(|part| {
+                mock.replay(part)
+                    .map(|out| format!("[usb|script] pm3 --> {}\n{}", part, out))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|outputs| outputs.join("\n")),
+        None => execute_pm3_script(app, port, &script, app_verbosity(app)).await,
+    };
+    match result {
+        Ok(raw) => {
+            emit_output(app, &raw, false);
+            Ok(strip_ansi(&raw))
         }
         Err(e) => {
             emit_output(app, &e.to_string(), true);
@@ -221,6 +632,18 @@ pub struct HfOperationState {
     pub child: Mutex<Option<CommandChild>>,
     /// Dump file path set by autopwn after completion (e.g. "hf-mf-01020304-dump.bin").
     pub dump_path: Mutex<Option<String>>,
+    /// Key file PM3 wrote next to the dump (e.g. "hf-mf-01020304-key.bin").
+    pub key_path: Mutex<Option<String>>,
+    /// Per-sector keys from the last autopwn's key table, for resuming a
+    /// partial recovery (index = sector).
+    pub sector_keys: Mutex<Option<Vec<SectorKeys>>>,
+    /// When the current HF job started (autopwn or dump), carried through
+    /// write and verify so progress can show time spent on the whole job.
+    /// Cleared when the wizard is reset.
+    session_start: Mutex<Option<Instant>>,
+    /// Set by `cancel_hf_operation` when it kills the running child, so the
+    /// streaming reader can report a cancellation instead of partial success.
+    pub cancelled: AtomicBool,
 }
 
 impl HfOperationState {
@@ -228,8 +651,53 @@ impl HfOperationState {
         Self {
             child: Mutex::new(None),
             dump_path: Mutex::new(None),
+            key_path: Mutex::new(None),
+            sector_keys: Mutex::new(None),
+            session_start: Mutex::new(None),
+            cancelled: AtomicBool::new(false),
         }
     }
+
+    /// Start timing a new HF job. Called when autopwn / dump begins.
+    pub fn start_session(&self) {
+        self.start_session_at(Instant::now());
+    }
+
+    fn start_session_at(&self, now: Instant) {
+        if let Ok(mut start) = self.session_start.lock() {
+            *start = Some(now);
+        }
+    }
+
+    /// Stop timing: the job was abandoned (wizard Reset / SoftReset).
+    pub fn end_session(&self) {
+        if let Ok(mut start) = self.session_start.lock() {
+            *start = None;
+        }
+    }
+
+    /// Time since `start_session`, or zero when no job has started.
+    pub fn session_elapsed(&self) -> Duration {
+        self.session_elapsed_at(Instant::now())
+    }
+
+    fn session_elapsed_at(&self, now: Instant) -> Duration {
+        self.session_start
+            .lock()
+            .ok()
+            .and_then(|start| start.map(|s| now.saturating_duration_since(s)))
+            .unwrap_or_default()
+    }
+
+    /// `session_elapsed` in whole seconds, as sent in progress payloads.
+    pub fn total_elapsed_secs(&self) -> u64 {
+        self.session_elapsed().as_secs()
+    }
+}
+
+/// Whether an error from `run_command_streaming` is a user cancellation.
+pub fn is_cancelled(err: &AppError) -> bool {
+    matches!(err, AppError::Cancelled)
 }
 
 // ---------------------------------------------------------------------------
@@ -245,7 +713,8 @@ impl HfOperationState {
 ///   progress events). The callback receives the cleaned line text.
 /// - The child process is stored in `hf_state.child` so `cancel_hf_operation`
 ///   can kill it mid-run.
-/// - Returns the accumulated cleaned output on success.
+/// - Returns the accumulated cleaned output on success, or a cancellation error
+///   (see `is_cancelled`) if the child was killed mid-run.
 pub async fn run_command_streaming<F>(
     app: &AppHandle,
     port: &str,
@@ -258,18 +727,33 @@ where
     F: FnMut(&str),
 {
     // Validate port
-    if !PORT_RE.is_match(port) {
-        return Err(AppError::CommandFailed(format!("Invalid port: {}", port)));
-    }
+    check_port(port)?;
 
     // Reject command separators
-    if cmd.contains(';') || cmd.contains('\n') || cmd.contains('\r') {
+    if has_command_separator(cmd) {
         return Err(AppError::CommandFailed(
             "Invalid characters in command".into(),
         ));
     }
 
     emit_output(app, &format!("pm3 --> {}", cmd), false);
+    clone_summary::record_command(app, cmd);
+
+    if let Some(result) = replay_mock(app, cmd) {
+        return match result {
+            Ok(output) => {
+                for line in output.lines() {
+                    emit_output(app, line, false);
+                    on_line(line);
+                }
+                Ok(output)
+            }
+            Err(e) => {
+                emit_output(app, &e.to_string(), true);
+                Err(e)
+            }
+        };
+    }
 
     // Try sidecar first, then scope names — same strategy as execute_pm3()
     let (rx, child) = spawn_pm3(app, port, cmd)?;
@@ -279,6 +763,7 @@ where
         let mut lock = hf_state.child.lock().map_err(|e| {
             AppError::CommandFailed(format!("HF state lock poisoned: {}", e))
         })?;
+        hf_state.cancelled.store(false, Ordering::SeqCst);
         *lock = Some(child);
     }
 
@@ -291,6 +776,13 @@ where
         *lock = None;
     }
 
+    // A killed child just closes its stream, which would otherwise look like a
+    // normal (partial) exit
+    if hf_state.cancelled.swap(false, Ordering::SeqCst) {
+        emit_output(app, &AppError::Cancelled.to_string(), true);
+        return Err(AppError::Cancelled);
+    }
+
     match result {
         Ok(output) => Ok(output),
         Err(e) => {
@@ -300,13 +792,22 @@ where
     }
 }
 
+/// Recorded output for `cmd` when offline mode is enabled, `None` otherwise.
+fn replay_mock(app: &AppHandle, cmd: &str) -> Option<Result<String, AppError>> {
+    let mock = app.try_state::<MockMode>()?;
+    if !mock.is_enabled() {
+        return None;
+    }
+    Some(mock.replay(cmd))
+}
+
 /// Spawn PM3 via sidecar or scope names, returning the event receiver + child.
 fn spawn_pm3(
     app: &AppHandle,
     port: &str,
     cmd: &str,
 ) -> Result<(tauri::async_runtime::Receiver<CommandEvent>, CommandChild), AppError> {
-    let args = ["-p", port, "-f", "-c", cmd];
+    let args = pm3_args(port, cmd, app_verbosity(app));
 
     // Try sidecar first
     if let Ok(sidecar_cmd) = app.shell().sidecar("binaries/proxmark3") {
@@ -370,7 +871,7 @@ where
                     let cleaned = strip_ansi(&line);
                     let trimmed = cleaned.trim();
                     if !trimmed.is_empty() {
-                        emit_output(app, trimmed, false);
+                        emit_output(app, &line, false);
                         on_line(trimmed);
                         accumulated.push_str(trimmed);
                         accumulated.push('\n');
@@ -381,7 +882,7 @@ where
                     let cleaned = strip_ansi(&line);
                     let trimmed = cleaned.trim();
                     if !trimmed.is_empty() {
-                        emit_output(app, trimmed, true);
+                        emit_output(app, &line, true);
                         on_line(trimmed);
                         accumulated.push_str(trimmed);
                         accumulated.push('\n');
@@ -415,11 +916,14 @@ where
 }
 
 /// Scan common COM/serial ports trying `hw version` to find a connected PM3.
-/// Returns (port, model, firmware) on success.
+/// Returns (port, model, firmware, fork) on success. An official-firmware
+/// device is still accepted, with a warning that most features need Iceman.
 ///
 /// Uses friendly, hacker-casual terminal output. All probe messages are green
 /// (non-error) except the final "not found" message.
-pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String), AppError> {
+pub async fn detect_device(
+    app: &AppHandle,
+) -> Result<(String, String, String, FirmwareFork), AppError> {
     let candidates = build_port_candidates();
 
     // Pick a random init message for personality
@@ -437,6 +941,10 @@ pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String),
         % init_msgs.len();
     emit_output(app, init_msgs[idx], false);
 
+    // A port we couldn't open (permissions / held by another app) is the most
+    // useful thing to report if no other port turns up a PM3.
+    let mut port_access_error: Option<AppError> = None;
+
     for port in &candidates {
         emit_output(app, &format!("[=] Knocking on {}...", port), false);
 
@@ -445,7 +953,11 @@ pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String),
                 if let Some((model, firmware)) = parse_hw_version(&output) {
                     emit_output(app, &format!("[+] Target acquired: {} on {}", model, port), false);
                     emit_output(app, &format!("[+] Firmware: {}", firmware), false);
-                    return Ok((port.clone(), model, firmware));
+                    let fork = version::device_fork(&output);
+                    if fork == FirmwareFork::Official {
+                        emit_output(app, &format!("[!!] {}", version::OFFICIAL_FORK_WARNING), true);
+                    }
+                    return Ok((port.clone(), model, firmware, fork));
                 }
                 // Got output but couldn't parse hw version -- wrong device
                 emit_output(app, &format!("[-] {} -- wrong device", port), false);
@@ -462,6 +974,7 @@ pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String),
                         port.clone(),
                         "Proxmark3".to_string(),
                         "mismatched".to_string(),
+                        FirmwareFork::Unknown,
                     ));
                 }
 
@@ -473,11 +986,23 @@ pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String),
                     return Err(e);
                 }
 
+                if matches!(e, AppError::PermissionDenied(_) | AppError::PortBusy(_)) {
+                    emit_output(app, &format!("[!!] {}", e), true);
+                    if port_access_error.is_none() {
+                        port_access_error = Some(e);
+                    }
+                    continue;
+                }
+
                 emit_output(app, &format!("[-] {} -- no response", port), false);
             }
         }
     }
 
+    if let Some(e) = port_access_error {
+        return Err(e);
+    }
+
     emit_output(app, "[!!] No Proxmark3 found.", true);
     emit_output(app, "[=] Try a different USB cable (some are charge-only)", false);
     emit_output(app, "[=] Check Device Manager for a COM port", false);
@@ -485,6 +1010,24 @@ pub async fn detect_device(app: &AppHandle) -> Result<(String, String, String),
     Err(AppError::DeviceNotFound)
 }
 
+/// Common PM3 suffixes of the macOS `usbmodem` device.
+const MACOS_USBMODEM_SUFFIXES: &[&str] = &["iceman1", "14101", "14201", "14301", "1", "2", "3"];
+
+/// macOS lists each USB modem twice. The callout device (`/dev/cu.*`) opens
+/// immediately; the dial-in one (`/dev/tty.*`) can block waiting for carrier
+/// detect, which shows up as a detection timeout. So every `cu.` port is
+/// tried before any `tty.` one.
+fn macos_port_candidates() -> Vec<String> {
+    ["cu", "tty"]
+        .iter()
+        .flat_map(|prefix| {
+            MACOS_USBMODEM_SUFFIXES
+                .iter()
+                .map(move |suffix| format!("/dev/{}.usbmodem{}", prefix, suffix))
+        })
+        .collect()
+}
+
 fn build_port_candidates() -> Vec<String> {
     let mut ports = Vec::new();
 
@@ -494,18 +1037,7 @@ fn build_port_candidates() -> Vec<String> {
             ports.push(format!("COM{}", i));
         }
     } else if cfg!(target_os = "macos") {
-        // macOS: /dev/tty.usbmodem* -- cover common PM3 suffixes
-        for suffix in &[
-            "iceman1",
-            "14101",
-            "14201",
-            "14301",
-            "1",
-            "2",
-            "3",
-        ] {
-            ports.push(format!("/dev/tty.usbmodem{}", suffix));
-        }
+        ports = macos_port_candidates();
     } else {
         // Linux: /dev/ttyACM* and /dev/ttyUSB*
         for i in 0..=5 {
@@ -517,6 +1049,46 @@ fn build_port_candidates() -> Vec<String> {
     ports
 }
 
+/// Time allowed for the client-only `proxmark3 --version`.
+const CLIENT_VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run `proxmark3 --version` against the binary `execute_pm3_raw` would use
+/// (sidecar first, then the scope names), without opening a port. Returns
+/// where the binary was found (`"bundled"` or the scope name) and its output.
+pub async fn run_client_version(app: &AppHandle) -> Result<(String, String), AppError> {
+    let mut candidates = Vec::new();
+    if let Ok(sidecar) = app.shell().sidecar("binaries/proxmark3") {
+        candidates.push(("bundled", sidecar));
+    }
+    for scope_name in pm3_scope_names() {
+        candidates.push((scope_name, app.shell().command(scope_name)));
+    }
+
+    for (source, command) in candidates {
+        let version_future = command.arg("--version").output();
+        let output = match timeout(CLIENT_VERSION_TIMEOUT, version_future).await {
+            Err(_) => {
+                return Err(AppError::Timeout(format!(
+                    "proxmark3 --version timed out after {}s",
+                    CLIENT_VERSION_TIMEOUT.as_secs()
+                )));
+            }
+            // Not installed here -- try the next location
+            Ok(Err(_)) => continue,
+            Ok(Ok(output)) => output,
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let text = if stdout.trim().is_empty() {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        } else {
+            stdout
+        };
+        return Ok((source.to_string(), strip_ansi(&text)));
+    }
+
+    Err(AppError::CommandFailed("Failed to spawn proxmark3: binary not found".into()))
+}
+
 /// Attempt to run a PM3 command via the bundled sidecar binary (silent -- no emit).
 /// Returns Ok(stdout) on success, Err on any failure (sidecar not found, spawn
 /// error, non-zero exit code). Callers should fall through to PATH-based lookup
@@ -525,14 +1097,19 @@ fn build_port_candidates() -> Vec<String> {
 /// The sidecar binary depends on DLLs (Qt5, ICU, etc.) bundled in the same
 /// directory via `bundle.resources`. The Windows DLL loader finds them
 /// automatically since they share the sidecar's directory.
-async fn try_sidecar_silent(app: &AppHandle, port: &str, cmd: &str) -> Result<String, AppError> {
+async fn try_sidecar_silent(
+    app: &AppHandle,
+    port: &str,
+    cmd: &str,
+    verbosity: Verbosity,
+) -> Result<String, AppError> {
     let sidecar = app
         .shell()
         .sidecar("binaries/proxmark3")
         .map_err(|e| AppError::CommandFailed(format!("Sidecar not available: {}", e)))?;
 
     let output_future = sidecar
-        .args(["-p", port, "-f", "-c", cmd])
+        .args(pm3_args(port, cmd, verbosity))
         .output();
 
     let output = match timeout(PM3_COMMAND_TIMEOUT, output_future).await {
@@ -557,10 +1134,7 @@ async fn try_sidecar_silent(app: &AppHandle, port: &str, cmd: &str) -> Result<St
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
     match code {
-        0 => {
-            let cleaned = strip_ansi(&stdout);
-            Ok(cleaned)
-        }
+        0 => Ok(stdout),
         -5 | 251 => Err(AppError::Timeout(format!(
             "PM3 timed out running: {}",
             cmd
@@ -571,10 +1145,7 @@ async fn try_sidecar_silent(app: &AppHandle, port: &str, cmd: &str) -> Result<St
             } else {
                 strip_ansi(&stderr)
             };
-            Err(AppError::CommandFailed(format!(
-                "Exit code {}: {}",
-                code, detail
-            )))
+            Err(exit_error(port, code, &detail))
         }
     }
 }
@@ -624,3 +1195,259 @@ fn extract_short_version(version_str: &str) -> String {
         version_str.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Fails with `err` `failures` times, then succeeds; counts attempts.
+    async fn flaky(
+        cmd: &str,
+        failures: usize,
+        err: fn() -> AppError,
+    ) -> (Result<String, AppError>, usize) {
+        let attempts = AtomicUsize::new(0);
+        let result = with_comms_retry(cmd, || {
+            let n = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n < failures {
+                    Err(err())
+                } else {
+                    Ok("ok".to_string())
+                }
+            }
+        })
+        .await;
+        (result, attempts.load(Ordering::SeqCst))
+    }
+
+    fn glitch() -> AppError {
+        AppError::CommandFailed(
+            "Exit code 1: [!!] Communicating with Proxmark3 device failed".into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn transient_error_retried_only_for_reads() {
+        for cmd in ["lf search", "hf mf rdbl --blk 0 -k FFFFFFFFFFFF", "lf t55xx read -b 0"] {
+            let (result, attempts) = flaky(cmd, 1, glitch).await;
+            assert_eq!(result.unwrap(), "ok", "{}", cmd);
+            assert_eq!(attempts, 2, "{}", cmd);
+        }
+        // Gives up after the retries run out
+        let (result, attempts) = flaky("hf 14a info", 5, glitch).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1 + COMMS_RETRIES);
+
+        // Destructive commands may have half-run: never resent
+        for cmd in [
+            "lf t55xx wipe -p 51243648",
+            "hf mf wrbl --blk 0 -k FFFFFFFFFFFF -d 00",
+            "lf em 410x clone --id 0F0368568B",
+            "hf mf cload -f hf-mf-01020304-dump.bin",
+            // Not known to be read-only: raw frames, config, protection
+            "hf 14a raw -sc 3000",
+            "hf mf gdmsetcfg -d 850000000000000000005A5A00000008",
+            "lf em 4x05 protect -d 00000000",
+        ] {
+            let (result, attempts) = flaky(cmd, 1, glitch).await;
+            assert!(result.is_err(), "{}", cmd);
+            assert_eq!(attempts, 1, "{}", cmd);
+        }
+
+        // Not a comms glitch: no retry
+        let no_tag = || AppError::CommandFailed("No known/supported 13.56 MHz tags found".into());
+        let (result, attempts) = flaky("hf search", 1, no_tag).await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn verbosity_only_in_args_it_is_asked_for() {
+        let args = |verbosity| pm3_args("/dev/ttyACM0", "hf mf info", verbosity);
+        // Terminal commands run at Normal: exactly as typed
+        assert_eq!(args(Verbosity::Normal), ["-p", "/dev/ttyACM0", "-f", "-c", "hf mf info"]);
+        assert_eq!(
+            args(Verbosity::Debug),
+            ["-p", "/dev/ttyACM0", "-f", "-d", "1", "-c", "hf mf info -v"]
+        );
+    }
+
+    #[test]
+    fn progress_burst_is_throttled() {
+        let emitted = std::cell::RefCell::new(Vec::new());
+        let throttle =
+            ProgressThrottle::new(PROGRESS_EMIT_INTERVAL, |i: u32| emitted.borrow_mut().push(i));
+        let start = Instant::now();
+        // 1000 updates over one second
+        for i in 0..1000u32 {
+            throttle.offer_at(i, start + Duration::from_millis(u64::from(i)));
+        }
+        assert_eq!(emitted.borrow().len(), 10);
+        assert_eq!(emitted.borrow()[0], 0);
+        assert_ne!(emitted.borrow().last(), Some(&999));
+
+        // The final update goes out when the stream ends
+        drop(throttle);
+        assert_eq!(emitted.borrow().len(), 11);
+        assert_eq!(emitted.borrow().last(), Some(&999));
+    }
+
+    #[test]
+    fn throttle_emits_nothing_more_after_emitted_update() {
+        let emitted = std::cell::RefCell::new(Vec::new());
+        let throttle =
+            ProgressThrottle::new(PROGRESS_EMIT_INTERVAL, |s: &str| emitted.borrow_mut().push(s));
+        throttle.offer("only");
+        drop(throttle);
+        assert_eq!(*emitted.borrow(), vec!["only"]);
+    }
+
+    #[test]
+    fn clone_chained_with_readback() {
+        let clone = command_builder::build_em4100_clone("0F00112233");
+        let chain = ChainedCommand::new(&[&clone, "lf search"]).unwrap();
+        assert_eq!(chain.script(), format!("{}; lf search", clone));
+
+        let output = format!(
+            "[usb|script] pm3 --> {}\n[+] Done!\n[usb|script] pm3 --> lf search\n\
+             [+] EM 410x ID 0F00112233\n[+] Valid EM410x ID found!",
+            clone
+        );
+        assert_eq!(chain.part_output(&output, 0).as_deref(), Some("[+] Done!"));
+        let readback = chain.part_output(&output, 1).unwrap();
+        assert!(readback.starts_with("[+] EM 410x ID 0F00112233"));
+        // Client died after the write: no readback to trust
+        assert_eq!(chain.part_output(&output[..output.find("[+] Done!").unwrap()], 1), None);
+
+        // A separator smuggled into one part is still refused
+        let injected = "lf em 410x clone --id 0F00112233;lf t55xx wipe";
+        assert!(ChainedCommand::new(&[injected, "lf search"]).is_err());
+        assert!(ChainedCommand::new(&[&clone, "lf search\nhw reset"]).is_err());
+        assert!(ChainedCommand::new(&[&clone]).is_err());
+        assert!(ChainedCommand::new(&[&clone, "  "]).is_err());
+    }
+
+    #[test]
+    fn hf_session_timer_spans_phases() {
+        let hf_state = HfOperationState::new();
+        assert_eq!(hf_state.session_elapsed(), Duration::ZERO);
+
+        // Autopwn starts the clock; write and verify only read it
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        hf_state.start_session_at(start);
+        assert_eq!(hf_state.session_elapsed_at(at(1500)), Duration::from_secs(1500));
+        *hf_state.dump_path.lock().unwrap() = Some("hf-mf-01020304-dump.bin".into());
+        assert_eq!(hf_state.session_elapsed_at(at(2520)), Duration::from_secs(2520));
+
+        // The next card's job starts from zero again
+        hf_state.start_session_at(at(3000));
+        assert_eq!(hf_state.session_elapsed_at(at(3010)), Duration::from_secs(10));
+
+        // Resetting the wizard stops the clock
+        hf_state.end_session();
+        assert_eq!(hf_state.session_elapsed_at(at(4000)), Duration::ZERO);
+    }
+
+    #[test]
+    fn ansi_forwarded_to_terminal_but_not_parsers() {
+        let raw = "[\x1b[32m+\x1b[0m] EM 410x ID \x1b[32m0F0368568B\x1b[0m\n\n[=] plain line";
+        let payloads = output_payloads(raw, false, true);
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].text, "[+] EM 410x ID 0F0368568B");
+        assert_eq!(
+            payloads[0].ansi.as_deref(),
+            Some("[\x1b[32m+\x1b[0m] EM 410x ID \x1b[32m0F0368568B\x1b[0m")
+        );
+        // Uncoloured lines carry no duplicate
+        assert_eq!(payloads[1].ansi, None);
+
+        // Setting off: stripped text only
+        assert!(output_payloads(raw, false, false).iter().all(|p| p.ansi.is_none()));
+
+        // What run_command returns for parsing has no escape codes
+        let (_, data) = crate::pm3::output_parser::parse_lf_search(&strip_ansi(raw)).unwrap();
+        assert_eq!(data.uid, "0F0368568B");
+    }
+
+    #[test]
+    fn macos_callout_ports_preferred() {
+        for port in ["/dev/cu.usbmodem14101", "/dev/tty.usbmodemiceman1", "COM3", "/dev/ttyACM0"] {
+            assert!(PORT_RE.is_match(port), "{}", port);
+        }
+        assert!(!PORT_RE.is_match("/dev/cu.Bluetooth-Incoming-Port"));
+        assert!(!PORT_RE.is_match("/dev/cu.usbmodem1;ls"));
+        assert!(check_port("").is_err() && check_port("COM3").is_ok());
+
+        let ports = macos_port_candidates();
+        assert!(ports.contains(&"/dev/cu.usbmodem14101".to_string()));
+        assert!(ports.contains(&"/dev/tty.usbmodem14101".to_string()));
+        let first_tty = ports.iter().position(|p| p.starts_with("/dev/tty.")).unwrap();
+        assert!(ports[..first_tty].iter().all(|p| p.starts_with("/dev/cu.")));
+        assert!(ports.iter().all(|p| PORT_RE.is_match(p)));
+    }
+
+    #[test]
+    fn port_error_linux_permission_denied() {
+        let stderr = "[!!] ERROR: cannot open port /dev/ttyACM0: Permission denied";
+        assert!(matches!(
+            classify_port_error("/dev/ttyACM0", stderr),
+            Some(AppError::PermissionDenied(p)) if p == "/dev/ttyACM0"
+        ));
+    }
+
+    #[test]
+    fn port_error_linux_busy() {
+        let stderr = "[!!] ERROR: cannot open /dev/ttyACM0: Device or resource busy";
+        assert!(matches!(
+            classify_port_error("/dev/ttyACM0", stderr),
+            Some(AppError::PortBusy(_))
+        ));
+    }
+
+    #[test]
+    fn port_error_macos_busy() {
+        let stderr = "open(/dev/tty.usbmodem14101) failed: Resource busy";
+        assert!(matches!(
+            classify_port_error("/dev/tty.usbmodem14101", stderr),
+            Some(AppError::PortBusy(_))
+        ));
+    }
+
+    #[test]
+    fn port_error_windows_access_denied_is_busy() {
+        let stderr = "[!!] ERROR: invalid serial port COM3\nCreateFile failed: Access is denied.";
+        assert!(matches!(
+            classify_port_error("COM3", stderr),
+            Some(AppError::PortBusy(p)) if p == "COM3"
+        ));
+    }
+
+    #[test]
+    fn port_error_unrelated_failure() {
+        let stderr = "[!!] ERROR: cannot communicate with the Proxmark";
+        assert!(classify_port_error("COM3", stderr).is_none());
+    }
+
+    #[test]
+    fn exit_error_falls_back_to_command_failed() {
+        match exit_error("COM3", 1, "[!] no tag found") {
+            AppError::CommandFailed(msg) => assert_eq!(msg, "Exit code 1: [!] no tag found"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn port_errors_have_user_guidance() {
+        assert!(AppError::PortBusy("COM3".into())
+            .user_message()
+            .unwrap()
+            .contains("Close any other app"));
+        assert!(AppError::PermissionDenied("/dev/ttyACM0".into())
+            .user_message()
+            .is_some());
+        assert!(AppError::DeviceNotFound.user_message().is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+// Best-guess explanation for a clone that failed verification. The signals
+// are gathered in different places (scan, blank detection, `lf tune`, dump,
+// readback); this ranks the causes they point to so the UI can lead with the
+// most likely fix instead of a bare "mismatch".
+
+/// LF antenna voltage under which writes and readbacks get unreliable.
+pub const WEAK_LF_ANTENNA_VOLTS: f32 = 20.0;
+
+/// Evidence collected around a failed clone. Unknown signals stay `None`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FailureSignals {
+    /// Source card's CRC/parity check (decoded `crc_ok`).
+    pub source_crc_ok: Option<bool>,
+    /// LF antenna voltage from `lf tune` / `hw tune`.
+    pub antenna_volts: Option<f32>,
+    /// PM3 Easy or other generic hardware with the smaller antenna.
+    pub generic_hardware: bool,
+    /// `check_clone_target` verdict for the blank.
+    pub blank_compatible: Option<bool>,
+    /// Readback decoded as a different type or modulation than the source.
+    pub readback_type_mismatch: bool,
+    /// Readback found no tag at all.
+    pub readback_empty: bool,
+    /// Blank UID length differs from the source (fixed 4-byte blank, 7-byte source).
+    pub uid_length_mismatch: bool,
+    /// Key recovery left sectors unread, so the dump written was incomplete.
+    pub partial_dump: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FailureCause {
+    BlankIncompatible,
+    UidLengthMismatch,
+    PartialDump,
+    WrongModulation,
+    CorruptSource,
+    WeakAntenna,
+    CardPlacement,
+}
+
+/// One probable cause, with how strongly the signals point to it (0-100).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LikelyCause {
+    pub cause: FailureCause,
+    pub score: u8,
+    pub explanation: String,
+    pub fix: String,
+}
+
+fn cause(cause: FailureCause, score: u8, explanation: &str, fix: &str) -> LikelyCause {
+    LikelyCause {
+        cause,
+        score,
+        explanation: explanation.to_string(),
+        fix: fix.to_string(),
+    }
+}
+
+/// Rank the causes `signals` point to, most likely first. Never empty: with
+/// no stronger evidence, card placement is the answer.
+pub fn diagnose_clone_failure(signals: &FailureSignals) -> Vec<LikelyCause> {
+    let mut causes = Vec::new();
+
+    if signals.blank_compatible == Some(false) {
+        causes.push(cause(
+            FailureCause::BlankIncompatible,
+            95,
+            "The blank can't carry this card type.",
+            "Use the recommended blank for this card.",
+        ));
+    }
+    if signals.uid_length_mismatch {
+        causes.push(cause(
+            FailureCause::UidLengthMismatch,
+            90,
+            "The blank's UID length doesn't match the source card.",
+            "Use a blank that supports the source's UID length (e.g. a 7-byte magic card).",
+        ));
+    }
+    if signals.partial_dump {
+        causes.push(cause(
+            FailureCause::PartialDump,
+            80,
+            "Some sectors were never read, so the clone is missing data.",
+            "Resume key recovery for the missing sectors, then write again.",
+        ));
+    }
+    if signals.readback_type_mismatch {
+        causes.push(cause(
+            FailureCause::WrongModulation,
+            75,
+            "The clone reads back as a different type or modulation than the source.",
+            "Wipe the blank and write again; check the blank's config block.",
+        ));
+    }
+    if signals.source_crc_ok == Some(false) {
+        causes.push(cause(
+            FailureCause::CorruptSource,
+            70,
+            "The source card failed its CRC check, so the data copied may be wrong.",
+            "Rescan the source card until it reads with a valid CRC.",
+        ));
+    }
+
+    let weak_volts = signals
+        .antenna_volts
+        .is_some_and(|v| v < WEAK_LF_ANTENNA_VOLTS);
+    if weak_volts || (signals.generic_hardware && signals.readback_empty) {
+        let score = match (weak_volts, signals.generic_hardware) {
+            (true, true) => 65,
+            (true, false) => 60,
+            _ => 50,
+        };
+        causes.push(cause(
+            FailureCause::WeakAntenna,
+            score,
+            "The LF field is too weak to write or read the blank reliably.",
+            "Turn on LF field assist, centre the card on the antenna and retry.",
+        ));
+    }
+
+    let placement_score = if signals.readback_empty { 40 } else { 20 };
+    causes.push(cause(
+        FailureCause::CardPlacement,
+        placement_score,
+        "The card may have moved or sat off-centre during the write or readback.",
+        "Hold the card still, centred on the antenna, and verify again.",
+    ));
+
+    causes.sort_by_key(|c| std::cmp::Reverse(c.score));
+    causes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn top(signals: FailureSignals) -> FailureCause {
+        diagnose_clone_failure(&signals)[0].cause
+    }
+
+    #[test]
+    fn strongest_signal_ranks_first() {
+        assert_eq!(top(FailureSignals::default()), FailureCause::CardPlacement);
+        assert_eq!(
+            top(FailureSignals {
+                antenna_volts: Some(12.5),
+                generic_hardware: true,
+                readback_empty: true,
+                ..Default::default()
+            }),
+            FailureCause::WeakAntenna
+        );
+        assert_eq!(
+            top(FailureSignals {
+                antenna_volts: Some(12.5),
+                readback_type_mismatch: true,
+                ..Default::default()
+            }),
+            FailureCause::WrongModulation
+        );
+        assert_eq!(
+            top(FailureSignals {
+                source_crc_ok: Some(false),
+                antenna_volts: Some(40.0),
+                ..Default::default()
+            }),
+            FailureCause::CorruptSource
+        );
+        assert_eq!(
+            top(FailureSignals {
+                blank_compatible: Some(false),
+                readback_type_mismatch: true,
+                ..Default::default()
+            }),
+            FailureCause::BlankIncompatible
+        );
+        assert_eq!(
+            top(FailureSignals {
+                uid_length_mismatch: true,
+                partial_dump: true,
+                ..Default::default()
+            }),
+            FailureCause::UidLengthMismatch
+        );
+    }
+
+    #[test]
+    fn causes_are_ordered_and_end_with_placement() {
+        let causes = diagnose_clone_failure(&FailureSignals {
+            partial_dump: true,
+            source_crc_ok: Some(false),
+            generic_hardware: true,
+            readback_empty: true,
+            ..Default::default()
+        });
+        let order: Vec<FailureCause> = causes.iter().map(|c| c.cause).collect();
+        assert_eq!(
+            order,
+            vec![
+                FailureCause::PartialDump,
+                FailureCause::CorruptSource,
+                FailureCause::WeakAntenna,
+                FailureCause::CardPlacement,
+            ]
+        );
+        // A healthy antenna on generic hardware isn't blamed
+        let causes = diagnose_clone_failure(&FailureSignals {
+            antenna_volts: Some(35.0),
+            generic_hardware: true,
+            ..Default::default()
+        });
+        assert!(causes.iter().all(|c| c.cause != FailureCause::WeakAntenna));
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::pm3::output_parser::strip_ansi;
+
+// Key/password dictionaries shipped with the PM3 client. Installs put them in
+// `<prefix>/share/proxmark3/dictionaries`, Windows/source builds next to the
+// binary in `dictionaries/`, and users add their own under
+// `~/.proxmark3/dictionaries`. The client searches the same directories for
+// `-f`, user dir first, so a name found there shadows the shipped one.
+
+/// What a dictionary holds, from PM3's file naming (`mfc_*`, `t55xx_*`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DictionaryKind {
+    /// MIFARE Classic keys (`hf mf autopwn` / `hf mf chk`).
+    MifareClassic,
+    /// T55xx passwords (`lf t55xx chk`).
+    T55xx,
+    IClass,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dictionary {
+    /// File name, e.g. `mfc_default_keys.dic`.
+    pub name: String,
+    pub path: String,
+    pub kind: DictionaryKind,
+}
+
+/// Dictionaries picked by the user. Stored via `app.manage()` in `lib.rs`;
+/// `None` keeps the app's own choice (PM3 default or the project keyfile).
+pub struct SelectedDictionaries {
+    mifare: Mutex<Option<String>>,
+    t55xx: Mutex<Option<String>>,
+}
+
+impl SelectedDictionaries {
+    pub fn new() -> Self {
+        Self {
+            mifare: Mutex::new(None),
+            t55xx: Mutex::new(None),
+        }
+    }
+
+    fn slot(&self, kind: DictionaryKind) -> Result<&Mutex<Option<String>>, AppError> {
+        match kind {
+            DictionaryKind::MifareClassic => Ok(&self.mifare),
+            DictionaryKind::T55xx => Ok(&self.t55xx),
+            other => Err(AppError::CommandFailed(format!(
+                "{:?} dictionaries can't be selected for key recovery",
+                other
+            ))),
+        }
+    }
+
+    pub fn get(&self, kind: DictionaryKind) -> Option<String> {
+        self.slot(kind).ok()?.lock().ok()?.clone()
+    }
+
+    pub fn set(&self, kind: DictionaryKind, path: Option<String>) -> Result<(), AppError> {
+        let mut slot = self.slot(kind)?.lock().map_err(|e| {
+            AppError::CommandFailed(format!("State lock poisoned: {}", e))
+        })?;
+        *slot = path;
+        Ok(())
+    }
+}
+
+/// `prefs show`: `[=]  Using "/home/user/.proxmark3/preferences.json"`
+static PREFS_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)using\s+"([^"]+?preferences\.json)""#).expect("bad prefs path regex")
+});
+
+/// The client's user data directory (`~/.proxmark3`), from `prefs show`.
+pub fn parse_user_dir(prefs_output: &str) -> Option<PathBuf> {
+    let clean = strip_ansi(prefs_output);
+    let caps = PREFS_PATH_RE.captures(&clean)?;
+    Path::new(&caps[1]).parent().map(Path::to_path_buf)
+}
+
+pub fn dictionary_kind(file_name: &str) -> DictionaryKind {
+    let lower = file_name.to_lowercase();
+    if lower.starts_with("mfc_") || lower.starts_with("mfc-") {
+        DictionaryKind::MifareClassic
+    } else if lower.starts_with("t55xx") {
+        DictionaryKind::T55xx
+    } else if lower.starts_with("iclass") {
+        DictionaryKind::IClass
+    } else {
+        DictionaryKind::Other
+    }
+}
+
+/// Directories the client searches, in its lookup order. `client_dir` is
+/// where the PM3 binary lives (the bundled sidecar sits next to the app).
+pub fn search_dirs(user_dir: Option<&Path>, client_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(user) = user_dir {
+        dirs.push(user.join("dictionaries"));
+    }
+    if let Some(client) = client_dir {
+        dirs.push(client.join("dictionaries"));
+        dirs.push(client.join("../share/proxmark3/dictionaries"));
+    }
+    let installs: &[&str] = if cfg!(target_os = "windows") {
+        &["C:\\proxmark3\\client\\dictionaries", "C:\\Program Files\\proxmark3\\dictionaries"]
+    } else if cfg!(target_os = "macos") {
+        &["/usr/local/share/proxmark3/dictionaries", "/opt/homebrew/share/proxmark3/dictionaries"]
+    } else {
+        &["/usr/local/share/proxmark3/dictionaries", "/usr/share/proxmark3/dictionaries"]
+    };
+    dirs.extend(installs.iter().map(PathBuf::from));
+    dirs
+}
+
+/// `.dic` files in `dirs`. Missing directories are skipped; a name already
+/// found in an earlier directory is skipped too, matching the client.
+pub fn list_dictionaries(dirs: &[PathBuf]) -> Vec<Dictionary> {
+    let mut found: Vec<Dictionary> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut in_dir: Vec<Dictionary> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if !path.is_file() || path.extension()? != "dic" {
+                    return None;
+                }
+                let name = path.file_name()?.to_str()?.to_string();
+                Some(Dictionary {
+                    kind: dictionary_kind(&name),
+                    path: path.to_string_lossy().to_string(),
+                    name,
+                })
+            })
+            .filter(|d| !found.iter().any(|f| f.name == d.name))
+            .collect();
+        found.append(&mut in_dir);
+    }
+    found.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name)));
+    found
+}
+
+/// Check a user-selected dictionary before it's put on a PM3 command line.
+/// It goes in quotes, but PM3 splits `-c` on `;` before any quoting, so
+/// command separators are refused along with quotes and line breaks.
+pub fn validate_dictionary_path(path: &str) -> Result<(), AppError> {
+    if path.contains(['"', ';', '\n', '\r']) || !path.to_lowercase().ends_with(".dic") {
+        return Err(AppError::CommandFailed(format!("Invalid dictionary path: '{}'", path)));
+    }
+    if !Path::new(path).is_file() {
+        return Err(AppError::CommandFailed(format!("Dictionary not found: {}", path)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const PREFS_SHOW: &str = "\
+[=] --- preferences ---
+[=]  Using \"/home/alice/.proxmark3/preferences.json\"
+[=]  Emoji................... ALIAS
+[=]  Color................... ANSI
+[=]  Debug level............. off
+[=]  Output file path........ /home/alice/.proxmark3/dumps";
+
+    #[test]
+    fn user_dir_from_prefs() {
+        assert_eq!(parse_user_dir(PREFS_SHOW), Some(PathBuf::from("/home/alice/.proxmark3")));
+        let coloured = PREFS_SHOW.replace("Using", "\x1b[33mUsing\x1b[0m");
+        assert!(parse_user_dir(&coloured).is_some());
+        assert_eq!(parse_user_dir("[!] Unknown command"), None);
+    }
+
+    #[test]
+    fn kinds_from_file_names() {
+        assert_eq!(dictionary_kind("mfc_default_keys.dic"), DictionaryKind::MifareClassic);
+        assert_eq!(dictionary_kind("t55xx_default_pwds.dic"), DictionaryKind::T55xx);
+        assert_eq!(dictionary_kind("iclass_default_keys.dic"), DictionaryKind::IClass);
+        assert_eq!(dictionary_kind("mfulc_default_keys.dic"), DictionaryKind::Other);
+    }
+
+    #[test]
+    fn lists_dic_files_user_dir_first() {
+        let root = TempDir::new("dicts");
+        let user = root.join("user");
+        let shipped = root.join("shipped");
+        std::fs::create_dir_all(&user).unwrap();
+        std::fs::create_dir_all(&shipped).unwrap();
+        std::fs::write(user.join("mfc_default_keys.dic"), "FFFFFFFFFFFF\n").unwrap();
+        for name in ["mfc_default_keys.dic", "t55xx_default_pwds.dic", "README.txt"] {
+            std::fs::write(shipped.join(name), "").unwrap();
+        }
+
+        let dicts = list_dictionaries(&[user.clone(), root.join("missing"), shipped]);
+        let names: Vec<&str> = dicts.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["mfc_default_keys.dic", "t55xx_default_pwds.dic"]);
+        assert!(dicts[0].path.starts_with(user.to_str().unwrap()));
+        assert_eq!(dicts[1].kind, DictionaryKind::T55xx);
+
+        assert!(validate_dictionary_path(&dicts[1].path).is_ok());
+        assert!(validate_dictionary_path("/tmp/x\" -c hw reset.dic").is_err());
+        // An existing file is still refused if its name would split the command
+        let smuggled = root.join("keys;hw reset.dic");
+        std::fs::write(&smuggled, "FFFFFFFFFFFF\n").unwrap();
+        let err = validate_dictionary_path(smuggled.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Invalid dictionary path"), "{}", err);
+    }
+
+    #[test]
+    fn only_recovery_kinds_selectable() {
+        let selected = SelectedDictionaries::new();
+        selected
+            .set(DictionaryKind::T55xx, Some("/d/t55xx_default_pwds.dic".into()))
+            .unwrap();
+        assert_eq!(
+            selected.get(DictionaryKind::T55xx).as_deref(),
+            Some("/d/t55xx_default_pwds.dic")
+        );
+        assert_eq!(selected.get(DictionaryKind::MifareClassic), None);
+        assert!(selected.set(DictionaryKind::IClass, None).is_err());
+    }
+}
@@ -0,0 +1,527 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::pm3::uid_safety;
+
+// Human-readable report for MIFARE Classic dumps: UID, per-sector keys,
+// decoded access bits and value blocks. Pure functions over the dump bytes —
+// the `export_dump_report` command only handles file I/O.
+
+const BLOCK_SIZE: usize = 16;
+const KEY_SIZE: usize = 6;
+
+// ---------------------------------------------------------------------------
+// Layout
+// ---------------------------------------------------------------------------
+
+/// Number of sectors for a Classic dump of `len` bytes (Mini / 1K / 2K / 4K).
+pub fn sector_count(len: usize) -> Option<usize> {
+    match len {
+        320 => Some(5),
+        1024 => Some(16),
+        2048 => Some(32),
+        4096 => Some(40),
+        _ => None,
+    }
+}
+
+/// First block and block count of a sector. Sectors 32+ (4K only) are 16 blocks.
+pub fn sector_blocks(sector: usize) -> (usize, usize) {
+    if sector < 32 {
+        (sector * 4, 4)
+    } else {
+        (128 + (sector - 32) * 16, 16)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Keys
+// ---------------------------------------------------------------------------
+
+/// Key A / key B for one sector, uppercase hex. `None` = not recovered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectorKeys {
+    pub key_a: Option<String>,
+    pub key_b: Option<String>,
+}
+
+/// Parse a PM3 binary key file (`hf-mf-<UID>-key.bin`): all key A values in
+/// sector order followed by all key B values, 6 bytes each.
+pub fn parse_key_file(data: &[u8]) -> Option<Vec<SectorKeys>> {
+    if data.is_empty() || !data.len().is_multiple_of(KEY_SIZE * 2) {
+        return None;
+    }
+    let sectors = data.len() / (KEY_SIZE * 2);
+    let (a, b) = data.split_at(sectors * KEY_SIZE);
+    Some(
+        a.chunks(KEY_SIZE)
+            .zip(b.chunks(KEY_SIZE))
+            .map(|(ka, kb)| SectorKeys {
+                key_a: Some(hex(ka)),
+                key_b: Some(hex(kb)),
+            })
+            .collect(),
+    )
+}
+
+/// Encode keys in the PM3 binary key file layout (see `parse_key_file`).
+/// Keys not recovered are written as `FFFFFFFFFFFF`, as PM3 does.
+pub fn encode_key_file(keys: &[SectorKeys]) -> Vec<u8> {
+    let key_bytes = |key: &Option<String>| -> Vec<u8> {
+        key.as_deref()
+            .filter(|k| k.len() == KEY_SIZE * 2)
+            .and_then(|k| {
+                (0..KEY_SIZE)
+                    .map(|i| u8::from_str_radix(&k[i * 2..i * 2 + 2], 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+            })
+            .unwrap_or_else(|| vec![0xFF; KEY_SIZE])
+    };
+    let mut out = Vec::with_capacity(keys.len() * KEY_SIZE * 2);
+    out.extend(keys.iter().flat_map(|k| key_bytes(&k.key_a)));
+    out.extend(keys.iter().flat_map(|k| key_bytes(&k.key_b)));
+    out
+}
+
+/// Keys read back from the sector trailers themselves. PM3 writes recovered
+/// keys into the trailers of its dumps, so this is the fallback when no key
+/// file is available.
+fn keys_from_trailer(trailer: &[u8]) -> SectorKeys {
+    SectorKeys {
+        key_a: Some(hex(&trailer[0..6])),
+        key_b: Some(hex(&trailer[10..16])),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Access bits
+// ---------------------------------------------------------------------------
+
+/// Access bits (C1 C2 C3) for the four block groups of a sector, decoded from
+/// trailer bytes 6..9. `valid` is false when the inverted copies disagree —
+/// such a trailer bricks the sector on a real card.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessBits {
+    pub groups: [(u8, u8, u8); 4],
+    pub valid: bool,
+}
+
+pub fn decode_access_bits(bytes: [u8; 3]) -> AccessBits {
+    let c1 = bytes[1] >> 4;
+    let c2 = bytes[2] & 0x0F;
+    let c3 = bytes[2] >> 4;
+    let nc1 = bytes[0] & 0x0F;
+    let nc2 = bytes[0] >> 4;
+    let nc3 = bytes[1] & 0x0F;
+    let valid = c1 == (!nc1 & 0x0F) && c2 == (!nc2 & 0x0F) && c3 == (!nc3 & 0x0F);
+
+    let mut groups = [(0, 0, 0); 4];
+    for (i, group) in groups.iter_mut().enumerate() {
+        *group = ((c1 >> i) & 1, (c2 >> i) & 1, (c3 >> i) & 1);
+    }
+    AccessBits { groups, valid }
+}
+
+/// Data block permissions for a C1 C2 C3 triple (NXP MF1S50 table 8).
+pub fn describe_data_access(bits: (u8, u8, u8)) -> &'static str {
+    match bits {
+        (0, 0, 0) => "read A|B, write A|B, value ops A|B (transport)",
+        (0, 1, 0) => "read A|B, no write",
+        (1, 0, 0) => "read A|B, write B",
+        (1, 1, 0) => "read A|B, write B, increment B, decrement A|B (value)",
+        (0, 0, 1) => "read A|B, decrement A|B only (value)",
+        (0, 1, 1) => "read B, write B",
+        (1, 0, 1) => "read B, no write",
+        _ => "no access",
+    }
+}
+
+/// Sector trailer permissions for a C1 C2 C3 triple (NXP MF1S50 table 7).
+pub fn describe_trailer_access(bits: (u8, u8, u8)) -> &'static str {
+    match bits {
+        (0, 0, 0) => "key A write A; access bits read A; key B read/write A",
+        (0, 1, 0) => "access bits read A; key B read A; nothing writable",
+        (1, 0, 0) => "key A write B; access bits read A|B; key B write B",
+        (1, 1, 0) => "access bits read A|B; nothing writable",
+        (0, 0, 1) => "key A write A; access bits read/write A; key B read/write A (transport)",
+        (0, 1, 1) => "key A write B; access bits read A|B, write B; key B write B",
+        (1, 0, 1) => "access bits read A|B, write B; keys not writable",
+        _ => "access bits read A|B; nothing writable",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Value blocks
+// ---------------------------------------------------------------------------
+
+/// Decode a value block: value, !value, value (LE i32) then addr, !addr,
+/// addr, !addr. Returns `(value, address)`.
+pub fn parse_value_block(block: &[u8]) -> Option<(i32, u8)> {
+    if block.len() != BLOCK_SIZE {
+        return None;
+    }
+    let v = u32::from_le_bytes(block[0..4].try_into().ok()?);
+    let nv = u32::from_le_bytes(block[4..8].try_into().ok()?);
+    let v2 = u32::from_le_bytes(block[8..12].try_into().ok()?);
+    let (a, na) = (block[12], block[13]);
+    if v != !nv || v != v2 || a != !na || block[14] != a || block[15] != na {
+        return None;
+    }
+    Some((v as i32, a))
+}
+
+// ---------------------------------------------------------------------------
+// Dump loading
+// ---------------------------------------------------------------------------
+
+/// Load dump bytes from `.bin` (raw) or `.eml` (one hex block per line).
+pub fn load_dump(path: &Path) -> Result<Vec<u8>, AppError> {
+    let is_eml = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("eml"));
+    if !is_eml {
+        return std::fs::read(path).map_err(|e| {
+            AppError::CommandFailed(format!("Failed to read dump file '{}': {}", path.display(), e))
+        });
+    }
+
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to read dump file '{}': {}", path.display(), e))
+    })?;
+    let mut data = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.len() != BLOCK_SIZE * 2 {
+            return Err(AppError::CommandFailed(format!("Malformed .eml line: '{}'", line)));
+        }
+        for i in (0..line.len()).step_by(2) {
+            let byte = u8::from_str_radix(&line[i..i + 2], 16).map_err(|_| {
+                AppError::CommandFailed(format!("Malformed .eml line: '{}'", line))
+            })?;
+            data.push(byte);
+        }
+    }
+    Ok(data)
+}
+
+// ---------------------------------------------------------------------------
+// Manufacturer block
+// ---------------------------------------------------------------------------
+
+/// Block 0 broken into its fields. Hex strings, uppercase; ATQA as PM3 prints
+/// it (`0004`), i.e. byte-swapped from how block 0 stores it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManufacturerBlock {
+    pub uid: String,
+    /// `None` for 7-byte UIDs, which carry no BCC in block 0.
+    pub bcc: Option<String>,
+    /// BCC is the XOR of the UID bytes (always true for 7-byte UIDs).
+    pub bcc_valid: bool,
+    pub sak: String,
+    pub atqa: String,
+    pub manufacturer_data: String,
+}
+
+impl ManufacturerBlock {
+    /// One line for dump results and logs.
+    pub fn describe(&self) -> String {
+        let bcc = match (&self.bcc, self.bcc_valid) {
+            (Some(bcc), true) => format!(", BCC {} (ok)", bcc),
+            (Some(bcc), false) => format!(", BCC {} (INVALID)", bcc),
+            (None, _) => String::new(),
+        };
+        format!("UID {}{}, SAK {}, ATQA {}", self.uid, bcc, self.sak, self.atqa)
+    }
+}
+
+/// Decode a Classic block 0. 4-byte UID layout: UID, BCC, SAK, ATQA (LSB
+/// first), manufacturer data. 7-byte UID layout: UID, SAK, ATQA, data.
+/// A block whose BCC doesn't match is still read as 4-byte unless only the
+/// 7-byte layout has a plausible ATQA there, one saying "double-size UID".
+pub fn decode_manufacturer_block(bytes: [u8; 16]) -> ManufacturerBlock {
+    let bcc_valid = uid_safety::compute_bcc(&bytes[0..4]) == bytes[4];
+    // ATQA bits 7-6: UID size (00 single, 01 double); the high byte is 00
+    let single_atqa = bytes[7] == 0x00 && bytes[6] & 0xC0 == 0x00;
+    let double_atqa = bytes[9] == 0x00 && bytes[8] & 0xC0 == 0x40;
+    let double_size = !bcc_valid && double_atqa && !single_atqa;
+    if double_size {
+        ManufacturerBlock {
+            uid: hex(&bytes[0..7]),
+            bcc: None,
+            bcc_valid: true,
+            sak: hex(&bytes[7..8]),
+            atqa: hex(&[bytes[9], bytes[8]]),
+            manufacturer_data: hex(&bytes[10..16]),
+        }
+    } else {
+        ManufacturerBlock {
+            uid: hex(&bytes[0..4]),
+            bcc: Some(hex(&bytes[4..5])),
+            bcc_valid,
+            sak: hex(&bytes[5..6]),
+            atqa: hex(&[bytes[7], bytes[6]]),
+            manufacturer_data: hex(&bytes[8..16]),
+        }
+    }
+}
+
+/// Decode block 0 of a Classic dump, `None` if the dump is shorter than a block.
+pub fn dump_manufacturer_block(dump: &[u8]) -> Option<ManufacturerBlock> {
+    let block0: [u8; 16] = dump.get(..BLOCK_SIZE)?.try_into().ok()?;
+    Some(decode_manufacturer_block(block0))
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+/// Render a markdown report for a Classic dump. Keys come from `keys` when
+/// given (PM3 key file), otherwise from the dump's sector trailers.
+pub fn build_report(dump: &[u8], keys: Option<&[SectorKeys]>) -> Result<String, AppError> {
+    let sectors = sector_count(dump.len()).ok_or_else(|| {
+        AppError::CommandFailed(format!(
+            "Not a MIFARE Classic dump ({} bytes; expected 320, 1024, 2048 or 4096)",
+            dump.len()
+        ))
+    })?;
+    let block = |n: usize| &dump[n * BLOCK_SIZE..(n + 1) * BLOCK_SIZE];
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# MIFARE Classic dump report\n");
+    let block0 = dump_manufacturer_block(dump).ok_or_else(|| {
+        AppError::CommandFailed("Dump has no block 0".into())
+    })?;
+    let _ = writeln!(out, "- UID: {}", block0.uid);
+    let _ = writeln!(out, "- Size: {} bytes, {} sectors", dump.len(), sectors);
+    let _ = writeln!(out, "- Block 0: {}", hex(block(0)));
+    if let Some(bcc) = &block0.bcc {
+        let _ = writeln!(
+            out,
+            "- BCC: {}{}",
+            bcc,
+            if block0.bcc_valid { "" } else { " (INVALID - readers won't anticollide)" }
+        );
+    }
+    let _ = writeln!(out, "- SAK: {}, ATQA: {}", block0.sak, block0.atqa);
+    let _ = writeln!(out, "- Manufacturer data: {}", block0.manufacturer_data);
+    let _ = writeln!(
+        out,
+        "- Key source: {}",
+        if keys.is_some() { "key file" } else { "sector trailers" }
+    );
+
+    let mut value_blocks = Vec::new();
+    for sector in 0..sectors {
+        let (first, count) = sector_blocks(sector);
+        let trailer_no = first + count - 1;
+        let trailer = block(trailer_no);
+        let sector_keys = keys
+            .and_then(|k| k.get(sector).cloned())
+            .unwrap_or_else(|| keys_from_trailer(trailer));
+        let access = decode_access_bits([trailer[6], trailer[7], trailer[8]]);
+
+        let _ = writeln!(out, "\n## Sector {}\n", sector);
+        let _ = writeln!(
+            out,
+            "- Key A: {}",
+            sector_keys.key_a.as_deref().unwrap_or("unknown")
+        );
+        let _ = writeln!(
+            out,
+            "- Key B: {}",
+            sector_keys.key_b.as_deref().unwrap_or("unknown")
+        );
+        let _ = writeln!(
+            out,
+            "- Access bits: {}{}",
+            hex(&trailer[6..9]),
+            if access.valid { "" } else { " (INVALID - inverted bits do not match)" }
+        );
+
+        for n in first..=trailer_no {
+            // Blocks within a 16-block sector share access groups in runs of 5
+            let group = if count == 4 { n - first } else { ((n - first) / 5).min(3) };
+            let bits = access.groups[if n == trailer_no { 3 } else { group }];
+            let perms = if n == trailer_no {
+                describe_trailer_access(bits)
+            } else {
+                describe_data_access(bits)
+            };
+            let _ = writeln!(
+                out,
+                "- Block {:>3}: {}  C{}{}{} {}",
+                n,
+                hex(block(n)),
+                bits.0,
+                bits.1,
+                bits.2,
+                perms
+            );
+            if n != 0 && n != trailer_no {
+                if let Some((value, addr)) = parse_value_block(block(n)) {
+                    value_blocks.push((n, value, addr));
+                }
+            }
+        }
+    }
+
+    let _ = writeln!(out, "\n## Value blocks\n");
+    if value_blocks.is_empty() {
+        let _ = writeln!(out, "None detected.");
+    }
+    for (n, value, addr) in value_blocks {
+        let _ = writeln!(out, "- Block {}: value {} (addr {})", n, value, addr);
+    }
+    Ok(out)
+}
+
+/// Uppercase hex, no separators.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSPORT_TRAILER: [u8; 16] = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x80, 0x69, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF,
+    ];
+
+    fn synthetic_1k() -> Vec<u8> {
+        let mut dump = vec![0u8; 1024];
+        // UID 01020304, BCC 04
+        dump[..8].copy_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x04, 0x08, 0x04, 0x00]);
+        for sector in 0..16 {
+            let t = (sector * 4 + 3) * BLOCK_SIZE;
+            dump[t..t + BLOCK_SIZE].copy_from_slice(&TRANSPORT_TRAILER);
+        }
+        // Sector 1 block 4: value 100 at address 4
+        dump[64..80].copy_from_slice(&[
+            0x64, 0, 0, 0, 0x9B, 0xFF, 0xFF, 0xFF, 0x64, 0, 0, 0, 0x04, 0xFB, 0x04, 0xFB,
+        ]);
+        dump
+    }
+
+    #[test]
+    fn access_bits_transport_config() {
+        let bits = decode_access_bits([0xFF, 0x07, 0x80]);
+        assert!(bits.valid);
+        assert_eq!(bits.groups, [(0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 1)]);
+        assert!(!decode_access_bits([0xFF, 0x07, 0x00]).valid);
+    }
+
+    #[test]
+    fn value_block_detection() {
+        let dump = synthetic_1k();
+        assert_eq!(parse_value_block(&dump[64..80]), Some((100, 4)));
+        assert_eq!(parse_value_block(&dump[80..96]), None); // all zeros
+    }
+
+    #[test]
+    fn key_file_layout() {
+        let mut data = vec![0xAAu8; 16 * 6];
+        data.extend(vec![0xBBu8; 16 * 6]);
+        let keys = parse_key_file(&data).unwrap();
+        assert_eq!(keys.len(), 16);
+        assert_eq!(keys[0].key_a.as_deref(), Some("AAAAAAAAAAAA"));
+        assert_eq!(keys[15].key_b.as_deref(), Some("BBBBBBBBBBBB"));
+        assert!(parse_key_file(&data[..7]).is_none());
+
+        // Round trip; an unrecovered key is written as FFFFFFFFFFFF
+        let mut keys = keys[..5].to_vec();
+        keys[2].key_b = None;
+        let encoded = encode_key_file(&keys);
+        assert_eq!(encoded.len(), 5 * 12);
+        let decoded = parse_key_file(&encoded).unwrap();
+        assert_eq!(decoded[1], keys[1]);
+        assert_eq!(decoded[2].key_b.as_deref(), Some("FFFFFFFFFFFF"));
+    }
+
+    #[test]
+    fn report_from_dump_and_keyfile() {
+        let dump = synthetic_1k();
+        let mut keyfile = vec![0xA0u8; 16 * 6];
+        keyfile.extend(vec![0xB0u8; 16 * 6]);
+        let keys = parse_key_file(&keyfile).unwrap();
+
+        let report = build_report(&dump, Some(&keys)).unwrap();
+        assert!(report.starts_with("# MIFARE Classic dump report"));
+        assert!(report.contains("- UID: 01020304"));
+        assert!(report.contains("- BCC: 04\n"));
+        assert!(report.contains("- SAK: 08, ATQA: 0004"));
+        assert!(report.contains("- Key source: key file"));
+        assert!(report.contains("## Sector 0"));
+        assert!(report.contains("## Sector 15"));
+        assert!(report.contains("- Key A: A0A0A0A0A0A0"));
+        assert!(report.contains("- Key B: B0B0B0B0B0B0"));
+        assert!(report.contains("- Access bits: FF0780"));
+        assert!(report.contains("(transport)"));
+        assert!(report.contains("## Value blocks"));
+        assert!(report.contains("- Block 4: value 100 (addr 4)"));
+    }
+
+    #[test]
+    fn manufacturer_block_valid_bcc() {
+        let block0 = decode_manufacturer_block([
+            0xDE, 0xAD, 0xBE, 0xEF, 0x22, 0x08, 0x04, 0x00, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67,
+            0x68, 0x69,
+        ]);
+        assert_eq!(block0.uid, "DEADBEEF");
+        assert_eq!(block0.bcc.as_deref(), Some("22"));
+        assert!(block0.bcc_valid);
+        assert_eq!(block0.sak, "08");
+        assert_eq!(block0.atqa, "0004");
+        assert_eq!(block0.manufacturer_data, "6263646566676869");
+        assert_eq!(block0.describe(), "UID DEADBEEF, BCC 22 (ok), SAK 08, ATQA 0004");
+    }
+
+    #[test]
+    fn manufacturer_block_invalid_bcc() {
+        let block0 = decode_manufacturer_block([
+            0xDE, 0xAD, 0xBE, 0xEF, 0x23, 0x08, 0x04, 0x00, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67,
+            0x68, 0x69,
+        ]);
+        assert_eq!(block0.uid, "DEADBEEF");
+        assert_eq!(block0.bcc.as_deref(), Some("23"));
+        assert!(!block0.bcc_valid);
+        assert!(block0.describe().contains("BCC 23 (INVALID)"));
+
+        let mut dump = synthetic_1k();
+        dump[4] = 0x05;
+        let report = build_report(&dump, None).unwrap();
+        assert!(report.contains("- BCC: 05 (INVALID"));
+    }
+
+    #[test]
+    fn manufacturer_block_seven_byte_uid() {
+        // 04 11 22 33 44 55 66, SAK 08, ATQA 0044
+        let block0 = decode_manufacturer_block([
+            0x04, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x08, 0x44, 0x00, 0x62, 0x63, 0x64, 0x65,
+            0x66, 0x67,
+        ]);
+        assert_eq!(block0.uid, "04112233445566");
+        assert_eq!(block0.bcc, None);
+        assert!(block0.bcc_valid);
+        assert_eq!(block0.sak, "08");
+        assert_eq!(block0.atqa, "0044");
+        assert_eq!(block0.manufacturer_data, "626364656667");
+        assert_eq!(dump_manufacturer_block(&[0u8; 8]), None);
+    }
+
+    #[test]
+    fn report_falls_back_to_trailer_keys() {
+        let report = build_report(&synthetic_1k(), None).unwrap();
+        assert!(report.contains("- Key source: sector trailers"));
+        assert!(report.contains("- Key A: FFFFFFFFFFFF"));
+    }
+
+    #[test]
+    fn report_rejects_non_classic_size() {
+        assert!(build_report(&[0u8; 540], None).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::pm3::output_parser::strip_ansi;
+
+// Device housekeeping from `hw status`: memory, flash, standalone mode and,
+// on variants that report it, uptime and battery. Every field is optional —
+// the sections present depend on firmware build and hardware.
+
+/// A key dictionary loaded into SPI flash (`Mifare... 2385 keys - spiffs file ...`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlashDictionary {
+    pub name: String,
+    pub keys: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceStatus {
+    /// As printed, e.g. `"2h 15m 3s"`.
+    pub uptime: Option<String>,
+    /// Free BigBuf memory in bytes.
+    pub available_memory: Option<u32>,
+    /// SPI flash size as printed, e.g. `"1 mb"`. `None` without external flash.
+    pub flash_size: Option<String>,
+    pub dictionaries: Vec<FlashDictionary>,
+    /// Standalone mode compiled into the firmware.
+    pub standalone_mode: Option<String>,
+    pub battery_mv: Option<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// Regexes for parsing `hw status` output
+// ---------------------------------------------------------------------------
+
+/// `Uptime.................. 2h 15m 3s`
+static UPTIME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*uptime[ \t.:]+(\S.*?)\s*$").expect("bad uptime regex")
+});
+
+/// `Available memory........ 38808`
+static AVAILABLE_MEMORY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*available memory[ \t.:]+(\d+)").expect("bad available memory regex")
+});
+
+/// `Memory size............. 1 mb (16 pages * 64k)`
+static FLASH_SIZE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*memory size[ \t.:]+(\d+\s*[km]b)").expect("bad flash size regex")
+});
+
+/// `Mifare... 2385 keys - spiffs file `mfc_default_keys.bin``
+static DICTIONARY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*(\w+)\.{2,}\s*(\d+)\s+keys").expect("bad dictionary regex")
+});
+
+/// `Battery................. 3.92 V` or `Battery voltage: 3920 mV`
+static BATTERY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*batt(?:ery)?[a-z ]*[ \t.:]+(\d+(?:\.\d+)?)\s*(m?v)\b")
+        .expect("bad battery regex")
+});
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// Parse `hw status` output. Missing sections leave their fields empty.
+pub fn parse_hw_status(output: &str) -> DeviceStatus {
+    let clean: String = strip_ansi(output)
+        .lines()
+        .map(|l| l.trim_start().trim_start_matches("[#]"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let capture = |re: &Regex| re.captures(&clean).map(|c| c[1].trim().to_string());
+
+    let battery_mv = BATTERY_RE.captures(&clean).and_then(|c| {
+        let value: f64 = c[1].parse().ok()?;
+        let mv = if c[2].eq_ignore_ascii_case("mv") { value } else { value * 1000.0 };
+        Some(mv.round() as u32)
+    });
+
+    DeviceStatus {
+        uptime: capture(&UPTIME_RE),
+        available_memory: capture(&AVAILABLE_MEMORY_RE).and_then(|m| m.parse().ok()),
+        flash_size: capture(&FLASH_SIZE_RE),
+        dictionaries: DICTIONARY_RE
+            .captures_iter(&clean)
+            .filter_map(|c| {
+                Some(FlashDictionary {
+                    name: c[1].to_string(),
+                    keys: c[2].parse().ok()?,
+                })
+            })
+            .collect(),
+        standalone_mode: parse_standalone_mode(&clean),
+        battery_mv,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// First line under the `Installed StandAlone Mode` header.
+fn parse_standalone_mode(clean: &str) -> Option<String> {
+    let mut lines = clean.lines();
+    lines.find(|l| l.to_lowercase().contains("installed standalone mode"))?;
+    lines
+        .next()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.to_lowercase().contains("no standalone"))
+        .map(str::to_string)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RDV4_HW_STATUS: &str = "\
+[#] Memory
+[#]   BigBuf_size............. 40000
+[#]   Available memory........ 38808
+[#] Tracing
+[#]   tracing ................ 1
+[#]   traceLen ............... 0
+[#] Current FPGA image
+[#]   mode.................... fpga_pm3_lf.ncd image 2s30vq100 2023-08-28 08:29:53
+[#] Flash memory
+[#]   Baudrate................ 24 MHz
+[#]   Init.................... OK
+[#]   Memory size............. 1 mb (16 pages * 64k)
+[#]   Unique ID (be).......... 0xD5670C27A3C43E28
+[#] Smart card module (ISO 7816)
+[#]   version................. v4.13
+[#] Various
+[#]   Max stack usage......... 4032 / 8480 bytes
+[#]   Slow clock.............. 29869 Hz
+[#]   Uptime.................. 2h 15m 3s
+[#]   Battery................. 3.92 V
+[#] Installed StandAlone Mode
+[#]   LF HID26 standalone - aka SamyRun (Samy Kamkar)
+[#] Flash memory dictionary loaded
+[#]   Mifare... 2385 keys - spiffs file `mfc_default_keys.bin`
+[#]   T55xx.... 118 keys - spiffs file `t55xx_default_pwds.bin`
+[#]   iClass... 36 keys - spiffs file `iclass_default_keys.bin`";
+
+    const EASY_HW_STATUS: &str = "\
+[#] Memory
+[#]   BigBuf_size............. 40000
+[#]   Available memory........ 40000
+[#] Various
+[#]   Max stack usage......... 4032 / 8480 bytes
+[#] Installed StandAlone Mode
+[#]   No standalone mode installed";
+
+    #[test]
+    fn rdv4_status_fields() {
+        let status = parse_hw_status(RDV4_HW_STATUS);
+        assert_eq!(status.uptime.as_deref(), Some("2h 15m 3s"));
+        assert_eq!(status.available_memory, Some(38808));
+        assert_eq!(status.flash_size.as_deref(), Some("1 mb"));
+        assert_eq!(status.battery_mv, Some(3920));
+        assert_eq!(
+            status.standalone_mode.as_deref(),
+            Some("LF HID26 standalone - aka SamyRun (Samy Kamkar)")
+        );
+        assert_eq!(status.dictionaries.len(), 3);
+        assert_eq!(
+            status.dictionaries[0],
+            FlashDictionary { name: "Mifare".into(), keys: 2385 }
+        );
+    }
+
+    #[test]
+    fn missing_sections_left_empty() {
+        let status = parse_hw_status(EASY_HW_STATUS);
+        assert_eq!(status.available_memory, Some(40000));
+        assert_eq!(status.uptime, None);
+        assert_eq!(status.flash_size, None);
+        assert_eq!(status.battery_mv, None);
+        assert_eq!(status.standalone_mode, None);
+        assert!(status.dictionaries.is_empty());
+    }
+
+    #[test]
+    fn battery_in_millivolts() {
+        let status = parse_hw_status("[#]   Battery voltage: 3710 mV");
+        assert_eq!(status.battery_mv, Some(3710));
+    }
+}
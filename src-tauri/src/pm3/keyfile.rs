@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+// Persistent MIFARE Classic key dictionary kept in the app data dir. Keys
+// recovered on one engagement are fed back into `hf mf autopwn` (`-f`)
+// so repeat cards fall to the dictionary step instead of a full attack.
+
+/// File name of the project keyfile inside the app data dir. Uses the `.dic`
+/// format PM3 reads for `-f`: one 12-hex-char key per line.
+pub const PROJECT_KEYFILE_NAME: &str = "project_keys.dic";
+
+/// Upper bound on keys accepted per call (prevents oversized IPC payloads).
+pub const MAX_KEYS_PER_CALL: usize = 1000;
+
+pub fn project_keyfile_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(PROJECT_KEYFILE_NAME)
+}
+
+/// Validate a MIFARE Classic key and normalise it to uppercase hex.
+pub fn normalize_key(key: &str) -> Result<String, AppError> {
+    let key = key.trim();
+    if key.len() != 12 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::CommandFailed(format!(
+            "Invalid key: must be exactly 12 hex characters, got '{}'",
+            key
+        )));
+    }
+    Ok(key.to_ascii_uppercase())
+}
+
+/// Parse keyfile contents. Blank lines and `#` comments are skipped, as are
+/// malformed lines (the file may have been edited by hand). Duplicates are
+/// dropped, keeping first-seen order.
+pub fn parse_keys(contents: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Ok(key) = normalize_key(line) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+/// Read the keyfile. A missing file is an empty keyfile.
+pub fn read_keys(path: &Path) -> Result<Vec<String>, AppError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(parse_keys(&contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(AppError::CommandFailed(format!(
+            "Failed to read keyfile {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// Merge `new_keys` into the keyfile and return the full, deduplicated list.
+/// The whole batch is rejected if any key is invalid so a typo never half-applies.
+pub fn add_keys(path: &Path, new_keys: &[String]) -> Result<Vec<String>, AppError> {
+    if new_keys.len() > MAX_KEYS_PER_CALL {
+        return Err(AppError::CommandFailed(format!(
+            "Too many keys: at most {} per call",
+            MAX_KEYS_PER_CALL
+        )));
+    }
+    let normalized = new_keys
+        .iter()
+        .map(|k| normalize_key(k))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut keys = read_keys(path)?;
+    for key in normalized {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    write_keys(path, &keys)?;
+    Ok(keys)
+}
+
+/// File name, in the app data dir, of the dictionary `merge_keyfiles` writes
+/// when autopwn gets several.
+pub const AUTOPWN_KEYFILE_NAME: &str = "autopwn_keys.dic";
+
+/// Write the keys of every dictionary in `paths`, in order and without
+/// duplicates, to `out`, for PM3 commands that take a single `-f` file.
+pub fn merge_keyfiles(paths: &[String], out: &Path) -> Result<(), AppError> {
+    let mut keys: Vec<String> = Vec::new();
+    for path in paths {
+        for key in read_keys(Path::new(path))? {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    write_keys(out, &keys)
+}
+
+/// Remove every key from the keyfile.
+pub fn clear_keys(path: &Path) -> Result<(), AppError> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::CommandFailed(format!(
+            "Failed to clear keyfile {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+fn write_keys(path: &Path, keys: &[String]) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            AppError::CommandFailed(format!("Cannot create data dir: {}", e))
+        })?;
+    }
+    let mut contents = String::from("# Phosphor project keys\n");
+    for key in keys {
+        contents.push_str(key);
+        contents.push('\n');
+    }
+    std::fs::write(path, contents).map_err(|e| {
+        AppError::CommandFailed(format!("Failed to write keyfile {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn key_validation() {
+        assert_eq!(normalize_key(" a0a1a2a3a4a5 ").unwrap(), "A0A1A2A3A4A5");
+        assert!(normalize_key("FFFFFFFFFFF").is_err()); // 11 chars
+        assert!(normalize_key("FFFFFFFFFFFFF").is_err()); // 13 chars
+        assert!(normalize_key("FFFFFFFFFFFG").is_err());
+        assert!(normalize_key("").is_err());
+    }
+
+    #[test]
+    fn parse_skips_comments_and_dedups() {
+        let keys = parse_keys("# header\nFFFFFFFFFFFF\n\nffffffffffff\nnot-a-key\nA0A1A2A3A4A5\n");
+        assert_eq!(keys, vec!["FFFFFFFFFFFF", "A0A1A2A3A4A5"]);
+    }
+
+    #[test]
+    fn merged_keyfiles_keep_every_key_in_order() {
+        let dir = TempDir::new("keys-merge");
+        let project = project_keyfile_path(&dir);
+        add_keys(&project, &["A0A1A2A3A4A5".into(), "FFFFFFFFFFFF".into()]).unwrap();
+        let user = dir.join("user.dic");
+        std::fs::write(&user, "# user dictionary\nffffffffffff\nB0B1B2B3B4B5\n").unwrap();
+        let out = dir.join(AUTOPWN_KEYFILE_NAME);
+
+        let paths = [project.to_string_lossy().into_owned(), user.to_string_lossy().into_owned()];
+        merge_keyfiles(&paths, &out).unwrap();
+        assert_eq!(read_keys(&out).unwrap(), vec!["A0A1A2A3A4A5", "FFFFFFFFFFFF", "B0B1B2B3B4B5"]);
+    }
+
+    #[test]
+    fn add_keys_round_trip() {
+        let dir = TempDir::new("keys-roundtrip");
+        let path = project_keyfile_path(&dir);
+        assert!(read_keys(&path).unwrap().is_empty());
+
+        let keys = add_keys(&path, &["ffffffffffff".into(), "A0A1A2A3A4A5".into()]).unwrap();
+        assert_eq!(keys, vec!["FFFFFFFFFFFF", "A0A1A2A3A4A5"]);
+
+        // Second batch overlaps the first — only the new key is appended
+        let keys = add_keys(&path, &["A0A1A2A3A4A5".into(), "D3F7D3F7D3F7".into()]).unwrap();
+        assert_eq!(keys, vec!["FFFFFFFFFFFF", "A0A1A2A3A4A5", "D3F7D3F7D3F7"]);
+        assert_eq!(read_keys(&path).unwrap(), keys);
+
+        clear_keys(&path).unwrap();
+        assert!(read_keys(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn add_keys_rejects_whole_batch_on_invalid_key() {
+        let dir = TempDir::new("keys-invalid");
+        let path = project_keyfile_path(&dir);
+        add_keys(&path, &["FFFFFFFFFFFF".into()]).unwrap();
+        assert!(add_keys(&path, &["A0A1A2A3A4A5".into(), "xyz".into()]).is_err());
+        assert_eq!(read_keys(&path).unwrap(), vec!["FFFFFFFFFFFF"]);
+    }
+}
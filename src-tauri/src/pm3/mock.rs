@@ -0,0 +1,309 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::AppError;
+use crate::pm3::parser_corpus;
+
+/// Managed state for offline/mock mode. When enabled, `execute_pm3` and
+/// `run_command_streaming` return recorded PM3 output instead of spawning the
+/// proxmark3 binary, so the wizard can be exercised without hardware.
+///
+/// Enabled by the `--offline` CLI flag or `PHOSPHOR_MOCK=1`. Fixtures are read
+/// from `PHOSPHOR_MOCK_FIXTURES` (one `<command>.txt` file per command, see
+/// `fixture_file_name`) and fall back to the built-in samples below.
+pub struct MockMode {
+    enabled: AtomicBool,
+    fixtures_dir: Option<PathBuf>,
+}
+
+impl MockMode {
+    pub fn new(enabled: bool, fixtures_dir: Option<PathBuf>) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            fixtures_dir,
+        }
+    }
+
+    /// Build from process arguments and environment.
+    pub fn from_env() -> Self {
+        let flag = std::env::args().any(|a| a == "--offline");
+        let env = std::env::var("PHOSPHOR_MOCK")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let fixtures_dir = std::env::var_os("PHOSPHOR_MOCK_FIXTURES").map(PathBuf::from);
+        Self::new(flag || env, fixtures_dir)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Recorded output for `cmd`: a file in the fixtures directory wins over
+    /// the built-in samples. Unknown commands fail like a real PM3 error would.
+    pub fn replay(&self, cmd: &str) -> Result<String, AppError> {
+        if let Some(dir) = &self.fixtures_dir {
+            if let Some(output) = read_fixture(dir, cmd) {
+                return Ok(output);
+            }
+        }
+
+        builtin_fixture(cmd).ok_or_else(|| {
+            AppError::CommandFailed(format!("No mock fixture for command: {}", cmd))
+        })
+    }
+}
+
+/// Fixture file name for a command: lowercase, every run of non-alphanumeric
+/// characters collapsed to `_`. `lf em 410x clone --id 0F00112233` ->
+/// `lf_em_410x_clone_id_0f00112233.txt`.
+pub fn fixture_file_name(cmd: &str) -> String {
+    let mut name = String::with_capacity(cmd.len() + 4);
+    let mut pending_sep = false;
+    for c in cmd.chars() {
+        if c.is_ascii_alphanumeric() {
+            if pending_sep && !name.is_empty() {
+                name.push('_');
+            }
+            pending_sep = false;
+            name.push(c.to_ascii_lowercase());
+        } else {
+            pending_sep = true;
+        }
+    }
+    name.push_str(".txt");
+    name
+}
+
+fn read_fixture(dir: &Path, cmd: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(fixture_file_name(cmd))).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Built-in fixtures — an EM4100 fob cloned onto a clean T5577
+// ---------------------------------------------------------------------------
+
+const MOCK_HW_VERSION: &str = "\
+[ Proxmark3 ]
+[ Client ]
+Iceman/master/v4.20728-358-ga2ba91043-suspect 2026-02-09 00:22:45 c0679a575
+[ ARM ]
+Bootrom.... Iceman/master/v4.20728-358-ga2ba91043-suspect 2026-02-09 00:22:17 c0679a575
+OS......... Iceman/master/v4.20728-358-ga2ba91043-suspect 2026-02-09 00:22:17 c0679a575
+[ Hardware ]
+--= uC: AT91SAM7S512 Rev B";
+
+const MOCK_T5577_DETECT: &str = "\
+[=] Chip type......... T55x7\n\
+[=] Modulation........ ASK/Manchester\n\
+[=] Bit Rate.......... RF/64\n\
+[=] Block0............ 00148040\n\
+[=] Password set...... No";
+
+const MOCK_LF_TUNE: &str = "\
+[=] Measuring LF antenna at 125.00 kHz\n\
+[+] LF antenna: 43.61 V - 125.00 kHz";
+
+const MOCK_EM410X_CLONE: &str = "\
+[+] Preparing to clone EM4102 to T55x7 tag with EM Tag ID 0F00112233 (RF/64)\n\
+[#] Clock rate: 64\n\
+[+] Done!";
+
+const MOCK_MF_CLOAD: &str = "\
+[=] Loaded 1024 bytes from binary file `hf-mf-7DE9254E-dump.bin`\n\
+[=] Copying to emulator memory\n\
+[+] Card loaded 64 blocks from file\n\
+[=] Done!";
+
+const MOCK_MF_CVIEW: &str = "\
+[=] View magic Gen1a card in MIFARE Classic 1k\n\
+[=]   # | sector 00 / 0x00                                | ascii\n\
+[=] ----+-------------------------------------------------+-----------------\n\
+[=]   0 | 7D E9 25 4E 0F 08 04 00 62 63 64 65 66 67 68 69 | }.%N....bcdefghi";
+
+/// Built-in fixtures matched by command prefix, first match wins.
+const BUILTIN_FIXTURES: &[(&str, &str)] = &[
+    ("hw version", MOCK_HW_VERSION),
+    ("lf search", parser_corpus::LF_EM4100),
+    ("lf tune", MOCK_LF_TUNE),
+    ("msleep", ""),
+    ("lf t55xx detect", MOCK_T5577_DETECT),
+    ("lf em 410x clone", MOCK_EM410X_CLONE),
+    ("hf search", parser_corpus::HF_CLASSIC_1K_MAGIC),
+    ("hf mf cload", MOCK_MF_CLOAD),
+    ("hf mf cview", MOCK_MF_CVIEW),
+];
+
+fn builtin_fixture(cmd: &str) -> Option<String> {
+    BUILTIN_FIXTURES
+        .iter()
+        .find(|(prefix, _)| cmd.starts_with(prefix))
+        .map(|(_, output)| output.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::types::{BlankType, CardType};
+    use crate::pm3::clone_summary::CloneTracker;
+    use crate::pm3::{command_builder, output_parser, version};
+    use crate::state::{WizardAction, WizardMachine, WizardState};
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn fixture_file_names() {
+        assert_eq!(fixture_file_name("lf search"), "lf_search.txt");
+        assert_eq!(
+            fixture_file_name("lf em 410x clone --id 0F00112233"),
+            "lf_em_410x_clone_id_0f00112233.txt"
+        );
+        assert_eq!(fixture_file_name("hf mf autopwn --4k"), "hf_mf_autopwn_4k.txt");
+    }
+
+    #[test]
+    fn unknown_command_fails() {
+        let mock = MockMode::new(true, None);
+        assert!(mock.replay("hf mfdes info").is_err());
+    }
+
+    #[test]
+    fn fixture_dir_overrides_builtin() {
+        let dir = TempDir::new("mock");
+        std::fs::write(dir.join("lf_search.txt"), "[+] Indala (len 64)  Raw: A0000000A0000000").unwrap();
+
+        let mock = MockMode::new(true, Some(dir.to_path_buf()));
+        let (card_type, _) = output_parser::parse_lf_search(&mock.replay("lf search").unwrap())
+            .expect("fixture should parse");
+        assert_eq!(card_type, CardType::Indala);
+        // Commands without a file still fall back to the built-ins
+        assert!(mock.replay("lf t55xx detect").is_ok());
+    }
+
+    /// Drive the full LF clone flow (detect -> scan -> blank -> write -> verify)
+    /// against the built-in fixtures, mirroring what the commands do.
+    #[test]
+    fn mock_clone_end_to_end() {
+        let mock = MockMode::new(true, None);
+        let mut m = WizardMachine::new();
+
+        // Detect device
+        m.transition(WizardAction::StartDetection).unwrap();
+        let hw = version::parse_detailed_hw_version(&mock.replay("hw version").unwrap());
+        assert!(hw.versions_match);
+        m.transition(WizardAction::DeviceFound {
+            port: "COM3".into(),
+            model: hw.model,
+            firmware: "v4.20728".into(),
+        })
+        .unwrap();
+
+        // Scan + identify
+        m.transition(WizardAction::StartScan).unwrap();
+        let scan = mock.replay(command_builder::build_lf_search()).unwrap();
+        let (card_type, card_data) = output_parser::parse_lf_search(&scan).unwrap();
+        assert_eq!(card_type, CardType::EM4100);
+        m.transition(WizardAction::CardFound {
+            frequency: card_type.frequency(),
+            card_type: card_type.clone(),
+            card_data: card_data.clone(),
+            cloneable: card_type.is_cloneable(),
+            recommended_blank: card_type.recommended_blank(),
+            previous_clones: Vec::new(),
+        })
+        .unwrap();
+
+        // Blank detection
+        m.transition(WizardAction::ProceedToWrite {
+            blank_type: BlankType::T5577,
+        })
+        .unwrap();
+        let detect = mock.replay(command_builder::build_t5577_detect()).unwrap();
+        let status = output_parser::parse_t5577_detect(&detect);
+        assert!(status.detected && !status.password_set);
+        m.transition(WizardAction::BlankReady {
+            blank_type: BlankType::T5577,
+            existing_data_type: None,
+            uid_length_warning: None,
+        })
+        .unwrap();
+
+        // Write
+        m.transition(WizardAction::StartWrite).unwrap();
+        let tracker = CloneTracker::new();
+        // Write and verify run as separate commands; the verify joins the summary
+        let (write, verify) = (Some(1), Some(2));
+        tracker.begin(card_type.clone(), &card_data.uid, BlankType::T5577, write);
+        let replay = |cmd: &str, op| {
+            tracker.record_command(cmd, op);
+            mock.replay(cmd).unwrap()
+        };
+        let clone_cmd =
+            command_builder::build_clone_command(&card_type, &card_data.uid, &card_data.decoded)
+                .unwrap();
+        let clone_out = replay(&clone_cmd, write);
+        assert!(!clone_out.contains("[!!]") && !clone_out.to_lowercase().contains("fail"));
+        m.transition(WizardAction::WriteFinished).unwrap();
+
+        // Verify
+        tracker.join(verify);
+        let readback = replay(command_builder::build_lf_search(), verify);
+        let (success, mismatched_blocks) =
+            output_parser::verify_match_detailed(&card_type, &card_data.decoded, &readback);
+        m.transition(WizardAction::VerificationResult {
+            success,
+            mismatched_blocks,
+            summary: tracker.finish(success, true),
+        })
+        .unwrap();
+
+        let WizardState::VerificationComplete { success: true, summary: Some(summary), .. } =
+            &m.current
+        else {
+            panic!("expected a successful verification with a summary, got {:?}", m.current);
+        };
+        assert_eq!(summary.source_type, CardType::EM4100);
+        assert_eq!(summary.source_uid, card_data.uid);
+        assert_eq!(summary.blank_type, BlankType::T5577);
+        assert_eq!(summary.commands_run, vec![clone_cmd.as_str(), "lf search"]);
+        assert!(summary.success && summary.verified);
+        assert!(summary.warnings.is_empty());
+    }
+
+    /// HF counterpart: a dumped MIFARE Classic 1K written to a Gen1a blank
+    /// and verified through the backdoor, as `hf_write_clone` /
+    /// `hf_verify_clone` do.
+    #[test]
+    fn mock_hf_gen1a_clone_summary() {
+        let mock = MockMode::new(true, None);
+        let tracker = CloneTracker::new();
+        let (card_type, card_data) =
+            output_parser::parse_hf_search(&mock.replay(command_builder::build_hf_search()).unwrap())
+                .unwrap();
+        assert_eq!(card_type, CardType::MifareClassic1K);
+
+        let (write, verify) = (Some(1), Some(2));
+        tracker.begin(card_type.clone(), &card_data.uid, BlankType::MagicMifareGen1a, write);
+        let replay = |cmd: &str, op| {
+            tracker.record_command(cmd, op);
+            mock.replay(cmd).unwrap()
+        };
+        let cload = command_builder::build_mf_cload(&card_type, "hf-mf-7DE9254E-dump.bin");
+        assert!(!replay(&cload, write).contains("[!!]"));
+
+        tracker.join(verify);
+        let search = replay(command_builder::build_hf_search(), verify);
+        let (_, clone_data) = output_parser::parse_hf_search(&search).unwrap();
+        assert_eq!(clone_data.uid, card_data.uid);
+        let cview = command_builder::build_mf_cview(&card_type);
+        assert!(!replay(&cview, verify).contains("[!!]"));
+        // No readback file to compare: UID-only, flagged as partial
+        tracker.warn("Readback failed; the clone was verified by UID only", verify);
+
+        let summary = tracker.finish(true, true).unwrap();
+        assert_eq!(summary.source_type, CardType::MifareClassic1K);
+        assert_eq!(summary.blank_type, BlankType::MagicMifareGen1a);
+        assert_eq!(summary.commands_run, vec![cload.as_str(), "hf search", cview.as_str()]);
+        assert!(summary.success && summary.verified);
+        assert_eq!(summary.warnings.len(), 1);
+        assert_eq!(tracker.last(), Some(summary));
+    }
+}
@@ -1,4 +1,19 @@
+pub mod benchmark;
+pub mod blank_compat;
+pub mod capabilities;
+pub mod clone_summary;
 pub mod command_builder;
 pub mod connection;
+pub mod diagnosis;
+pub mod dictionaries;
+pub mod dump_report;
+pub mod hw_status;
+pub mod keyfile;
+pub mod mock;
+pub mod ntag_lock;
+pub mod operation;
 pub mod output_parser;
+pub mod parser_corpus;
+pub mod scan_cache;
+pub mod uid_safety;
 pub mod version;
@@ -0,0 +1,202 @@
+use serde::Serialize;
+
+// NTAG21x lock-byte decoding (NXP NTAG213/215/216 datasheet, 8.5). Lock bits
+// are one-way: a locked page on the blank can never take the clone's data, so
+// `hf mfu restore` would leave a silently incomplete copy.
+
+/// Page holding the static lock bytes (bytes 2-3).
+pub const STATIC_LOCK_PAGE: u8 = 2;
+/// Capability container page; byte 2 is the data area size and identifies the variant.
+pub const CC_PAGE: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum NtagVariant {
+    Ntag213,
+    Ntag215,
+    Ntag216,
+}
+
+impl NtagVariant {
+    /// Variant from the CC data-area size byte (page 3, byte 2).
+    pub fn from_cc_size(size: u8) -> Option<Self> {
+        match size {
+            0x12 => Some(Self::Ntag213),
+            0x3E => Some(Self::Ntag215),
+            0x6D => Some(Self::Ntag216),
+            _ => None,
+        }
+    }
+
+    /// Last user memory page.
+    pub fn last_user_page(self) -> u16 {
+        match self {
+            Self::Ntag213 => 39,
+            Self::Ntag215 => 129,
+            Self::Ntag216 => 225,
+        }
+    }
+
+    /// Page holding the dynamic lock bytes (bytes 0-2).
+    pub fn dynamic_lock_page(self) -> u8 {
+        (self.last_user_page() + 1) as u8
+    }
+
+    /// CFG1 page; byte 0 is the ACCESS byte carrying CFGLCK.
+    pub fn cfg1_page(self) -> u8 {
+        (self.last_user_page() + 3) as u8
+    }
+
+    /// Pages covered by one dynamic lock bit.
+    fn dynamic_lock_granularity(self) -> u16 {
+        match self {
+            Self::Ntag213 => 2,
+            Self::Ntag215 | Self::Ntag216 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NtagLockStatus {
+    pub variant: NtagVariant,
+    /// Pages that can no longer be written, ascending.
+    pub locked_pages: Vec<u16>,
+    /// CFGLCK set — configuration pages are permanently read-only.
+    pub config_locked: bool,
+}
+
+impl NtagLockStatus {
+    /// Locked pages the clone's data would need: CC and user memory.
+    pub fn has_critical_locks(&self) -> bool {
+        !self.locked_pages.is_empty()
+    }
+}
+
+/// Pages locked by the static lock bytes (page 2, bytes 2 and 3).
+/// Byte 2: bit 3 = L-CC, bits 4-7 = pages 4-7. Byte 3: bits 0-7 = pages 8-15.
+/// Bits 0-2 of byte 2 are block-locks (they freeze the lock bits themselves).
+pub fn decode_static_lock(page2: [u8; 4]) -> Vec<u16> {
+    let bits = u16::from(page2[2]) | (u16::from(page2[3]) << 8);
+    (3..16).filter(|page| bits & (1 << page) != 0).collect()
+}
+
+/// Pages locked by the dynamic lock bytes (bytes 0-1 of the dynamic lock page).
+/// Each bit locks a group of pages starting at page 16; byte 2 is block-locks.
+pub fn decode_dynamic_lock(variant: NtagVariant, lock: [u8; 4]) -> Vec<u16> {
+    let bits = u16::from(lock[0]) | (u16::from(lock[1]) << 8);
+    let group = variant.dynamic_lock_granularity();
+    let last = variant.last_user_page();
+    let mut pages = Vec::new();
+    for bit in 0..16u16 {
+        let start = 16 + bit * group;
+        if start > last {
+            break;
+        }
+        if bits & (1 << bit) != 0 {
+            pages.extend(start..=(start + group - 1).min(last));
+        }
+    }
+    pages
+}
+
+/// Combine static, dynamic and CFGLCK bits into one status.
+pub fn decode_locks(
+    variant: NtagVariant,
+    page2: [u8; 4],
+    dynamic_lock: [u8; 4],
+    cfg1: [u8; 4],
+) -> NtagLockStatus {
+    let mut locked_pages = decode_static_lock(page2);
+    locked_pages.extend(decode_dynamic_lock(variant, dynamic_lock));
+    NtagLockStatus {
+        variant,
+        locked_pages,
+        config_locked: cfg1[0] & 0x40 != 0,
+    }
+}
+
+/// Compact page list for user messages: `4-7, 16-31`.
+pub fn format_page_ranges(pages: &[u16]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut iter = pages.iter().copied().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+        ranges.push(if start == end {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, end)
+        });
+    }
+    ranges.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNLOCKED: [u8; 4] = [0x00, 0x00, 0x00, 0x00];
+    // Serial bytes + internal byte are irrelevant to the lock decode
+    const PAGE2_UNLOCKED: [u8; 4] = [0x8B, 0x48, 0x00, 0x00];
+
+    #[test]
+    fn variant_from_cc() {
+        assert_eq!(NtagVariant::from_cc_size(0x12), Some(NtagVariant::Ntag213));
+        assert_eq!(NtagVariant::from_cc_size(0x3E), Some(NtagVariant::Ntag215));
+        assert_eq!(NtagVariant::from_cc_size(0x6D), Some(NtagVariant::Ntag216));
+        assert_eq!(NtagVariant::from_cc_size(0x06), None); // Ultralight
+        assert_eq!(NtagVariant::Ntag213.dynamic_lock_page(), 0x28);
+        assert_eq!(NtagVariant::Ntag215.dynamic_lock_page(), 0x82);
+        assert_eq!(NtagVariant::Ntag216.cfg1_page(), 0xE4);
+    }
+
+    #[test]
+    fn blank_ntag_has_no_locks() {
+        for variant in [NtagVariant::Ntag213, NtagVariant::Ntag215, NtagVariant::Ntag216] {
+            let status = decode_locks(variant, PAGE2_UNLOCKED, UNLOCKED, UNLOCKED);
+            assert!(!status.has_critical_locks(), "{:?}", variant);
+            assert!(!status.config_locked);
+        }
+    }
+
+    #[test]
+    fn ntag213_static_and_dynamic_locks() {
+        // L-CC + pages 4-5, page 15
+        let page2 = [0x8B, 0x48, 0x38, 0x80];
+        assert_eq!(decode_static_lock(page2), vec![3, 4, 5, 15]);
+        // byte0 bit0 = pages 16-17, byte1 bit3 = pages 38-39
+        let dynamic = decode_dynamic_lock(NtagVariant::Ntag213, [0x01, 0x08, 0x00, 0x00]);
+        assert_eq!(dynamic, vec![16, 17, 38, 39]);
+        // Bits past the last user page are ignored
+        assert!(decode_dynamic_lock(NtagVariant::Ntag213, [0x00, 0xF0, 0x00, 0x00]).is_empty());
+    }
+
+    #[test]
+    fn ntag215_dynamic_lock_groups() {
+        let pages = decode_dynamic_lock(NtagVariant::Ntag215, [0x80, 0x00, 0x00, 0x00]);
+        assert_eq!(pages, vec![128, 129]); // last group is truncated at page 129
+        let pages = decode_dynamic_lock(NtagVariant::Ntag215, [0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(pages, (16..=31).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ntag216_dynamic_lock_and_cfglck() {
+        let status = decode_locks(
+            NtagVariant::Ntag216,
+            PAGE2_UNLOCKED,
+            [0x00, 0x20, 0x00, 0x00],
+            [0x40, 0x05, 0x00, 0x00],
+        );
+        assert_eq!(status.locked_pages, vec![224, 225]);
+        assert!(status.config_locked);
+        assert!(status.has_critical_locks());
+    }
+
+    #[test]
+    fn page_ranges() {
+        assert_eq!(format_page_ranges(&[3, 4, 5, 15, 16, 17, 40]), "3-5, 15-17, 40");
+        assert_eq!(format_page_ranges(&[]), "");
+    }
+}
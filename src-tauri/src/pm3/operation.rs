@@ -0,0 +1,109 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+// Operations are the multi-step jobs whose events the UI has to tell apart:
+// the HF autopwn/dump/write/verify flows, LF write and verify, clone
+// sequences and the clone benchmark. Only those run under `run_operation`
+// and return an `OperationResult`. One-shot commands (scans, chip tools,
+// raw terminal commands, captures) are not operations; their `pm3-output`
+// lines carry no operation ID.
+
+/// Monotonic source of operation IDs. Starts at 1 so `0` never appears on the wire.
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+
+tokio::task_local! {
+    /// Operation ID of the command invocation running on the current task.
+    static CURRENT_OPERATION_ID: u64;
+}
+
+/// Allocate a fresh operation ID, unique for the lifetime of the process.
+pub fn next_operation_id() -> u64 {
+    NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Operation ID of the command currently running on this task, if any.
+/// Event emitters call this so every payload can be tied back to the
+/// invocation that produced it without threading the ID through every helper.
+pub fn current_operation_id() -> Option<u64> {
+    CURRENT_OPERATION_ID.try_with(|id| *id).ok()
+}
+
+/// Command result tagged with the operation ID its events were emitted under.
+/// The inner value is flattened so existing consumers (e.g. `WizardState`'s
+/// `step`/`data` shape) keep working unchanged.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationResult<T> {
+    pub operation_id: u64,
+    #[serde(flatten)]
+    pub result: T,
+}
+
+/// Run an operation's body under a freshly allocated operation ID. Every
+/// `pm3-output`, `hf-progress` and `write-progress` event emitted while `fut`
+/// runs carries the same ID, which is echoed back in the returned result.
+pub async fn run_operation<T, F>(fut: F) -> Result<OperationResult<T>, AppError>
+where
+    F: Future<Output = Result<T, AppError>>,
+{
+    let operation_id = next_operation_id();
+    let result = CURRENT_OPERATION_ID.scope(operation_id, fut).await?;
+    Ok(OperationResult {
+        operation_id,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm3::connection::Pm3OutputPayload;
+
+    #[tokio::test]
+    async fn payloads_share_operation_id() {
+        let op = run_operation(async {
+            let first = Pm3OutputPayload::new("pm3 --> lf search", false);
+            tokio::task::yield_now().await;
+            let second = Pm3OutputPayload::new("[+] EM 410x ID 0F0368568B", false);
+            Ok(vec![first, second])
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(op.result.len(), 2);
+        for payload in &op.result {
+            assert_eq!(payload.operation_id, Some(op.operation_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn separate_operations_get_distinct_ids() {
+        let a = run_operation(async { Ok(current_operation_id()) }).await.unwrap();
+        let b = run_operation(async { Ok(current_operation_id()) }).await.unwrap();
+        assert_eq!(a.result, Some(a.operation_id));
+        assert_eq!(b.result, Some(b.operation_id));
+        assert_ne!(a.operation_id, b.operation_id);
+    }
+
+    #[test]
+    fn no_operation_id_outside_scope() {
+        assert_eq!(current_operation_id(), None);
+        let payload = Pm3OutputPayload::new("hello", false);
+        assert_eq!(payload.operation_id, None);
+    }
+
+    #[test]
+    fn operation_result_flattens_inner_value() {
+        let wrapped = OperationResult {
+            operation_id: 7,
+            result: serde_json::json!({ "step": "Idle" }),
+        };
+        let json = serde_json::to_value(&wrapped).unwrap();
+        assert_eq!(json["operationId"], 7);
+        assert_eq!(json["step"], "Idle");
+    }
+}
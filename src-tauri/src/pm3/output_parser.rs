@@ -1,8 +1,13 @@
 use regex::Regex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
-use crate::cards::types::{AutopwnEvent, CardData, CardType, MagicGeneration, T5577Status};
+use crate::cards::types::{AutopwnEvent, CardData, CardType, Frequency, MagicGeneration, T5577Status};
+use crate::cards::wiegand;
+use crate::pm3::blank_compat::Iso14aIdentity;
+use crate::pm3::command_builder::EM4X50_WORD_COUNT;
+use crate::pm3::dump_report::SectorKeys;
 
 // ---------------------------------------------------------------------------
 // ANSI stripping
@@ -48,6 +53,18 @@ static INDALA_UID_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Indala.*?ID[:/\s]*([0-9A-Fa-f]+)").expect("bad indala uid regex")
 });
 
+/// 26-bit Wiegand PM3 recovered from a (possibly Heden/FlexSecur scrambled)
+/// Indala card: `Fmt 26 FC: 133 Card: 9000 Parity: ok`.
+static INDALA_FC_CN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Fmt\s*26\s+FC[:/\s]*(\d+)\s+Card[:/\s]*(\d+)")
+        .expect("bad indala fc/cn regex")
+});
+
+/// Heden-2L descramble candidate: `Heden-2L    | 888`.
+static INDALA_HEDEN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Heden-2L\s*\|\s*(\d+)").expect("bad indala heden regex")
+});
+
 static IOPROX_FC_CN_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)IO\s*Prox.*?(?:VN[:/\s]*(\d+))?.*?FC[:/\s]*(\d+).*?CN[:/\s]*(\d+)")
         .expect("bad ioprox fc/cn regex")
@@ -102,6 +119,25 @@ static PARADOX_RAW_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Paradox.*?Raw[:/\s]*([0-9A-Fa-f]+)").expect("bad paradox raw regex")
 });
 
+/// `Paradox - ID: 258b9f7a3 FC: 96 Card: 40426, Checksum: 05, Raw: ...`
+static PARADOX_ID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Paradox.*?\bID[:\s]*([0-9A-Fa-f]+)\b").expect("bad paradox id regex")
+});
+
+static PARADOX_CHECKSUM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Checksum[:\s]*([0-9A-Fa-f]{2})\b").expect("bad paradox checksum regex")
+});
+
+/// Format number / manufacturer code, on builds that print them.
+static PARADOX_FMT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:fmt|format)[:\s]+(\w+)").expect("bad paradox fmt regex")
+});
+
+static PARADOX_MFR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:manufacturer|mfr|mfg)(?:\s*code)?[:\s]+(\w+)")
+        .expect("bad paradox manufacturer regex")
+});
+
 static KERI_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Keri.*?(?:Internal|MS|Raw)[:/\s]*([0-9A-Fa-f]+)")
         .expect("bad keri regex")
@@ -181,6 +217,12 @@ static GALLAGHER_IL_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Issue\s+Level[:/\s]*(\d+)").expect("bad gallagher il regex")
 });
 
+// Real PM3 output: "CRC: 20 - 20 (ok)" (stored CRC - computed CRC)
+static GALLAGHER_CRC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)CRC[:\s]*([0-9A-Fa-f]{1,2})\s*-\s*([0-9A-Fa-f]{1,2})(?:\s*\((ok|fail)\w*\))?")
+        .expect("bad gallagher crc regex")
+});
+
 static PAC_DETECT_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)\[\+\].*\b(?:PAC|Stanley)\b").expect("bad pac detect regex")
 });
@@ -210,6 +252,23 @@ static SECURAKEY_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Secura\s*[Kk]ey.*?Raw[:/\s]+([0-9A-Fa-f]+)").expect("bad securakey regex")
 });
 
+// "Securakey - len: 26 FC: 0x35 Card: 64169, Raw: ..." (FC printed in hex)
+static SECURAKEY_FC_CN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Secura\s*key.*?FC[:/\s]*(0x[0-9A-Fa-f]+|\d+)\s*,?\s*Card[:/\s]*(\d+)")
+        .expect("bad securakey fc/cn regex")
+});
+
+// Full Wiegand message printed by several LF demods (AWID, Pyramid, SecuraKey):
+// "Wiegand: 26409a4" or "[+] Wiegand: 006BF553 parity ( ok )"
+static WIEGAND_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bWiegand:\s*([0-9A-Fa-f]+)\b").expect("bad wiegand regex")
+});
+
+// Bit length on the demod line: "Pyramid - len: 26, ..."
+static LEN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\blen[:/\s]+(\d+)").expect("bad len regex")
+});
+
 static VISA2000_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Visa2000.*?Card[:/\s]*(\d+)").expect("bad visa2000 regex")
 });
@@ -218,6 +277,12 @@ static MOTOROLA_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Motorola.*?Raw[:/\s]*([0-9A-Fa-f]+)").expect("bad motorola regex")
 });
 
+// Real PM3: "Motorola - fmt: 26 FC: 258 Card: 2, Raw: A0000000A0002021"
+static MOTOROLA_FC_CN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Motorola.*?(?:fmt[:\s]*(\d+).*?)?FC[:\s]*(\d+).*?Card[:\s]*(\d+)")
+        .expect("bad motorola fc/cn regex")
+});
+
 static IDTECK_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)IDTECK.*?Raw[:/\s]*([0-9A-Fa-f]+)").expect("bad idteck regex")
 });
@@ -295,6 +360,14 @@ static HF_PRNG_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("bad hf prng regex")
 });
 
+// Dual-magic markers: "( ZUID Gen1 Magic Wakeup )" after a capability line,
+// "( ONUID, re-used )" after the UID
+static HF_ZUID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bZUID\b|Gen\s*1\s*Magic\s*Wakeup").expect("bad hf zuid regex")
+});
+static HF_ONUID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bONUID\b").expect("bad hf onuid regex"));
+
 // Magic capabilities from hf mf info output
 static HF_MAGIC_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)(?:Magic|Gen(?:eration)?)\s*(?:capabilities)?[\s.:]*(?::[\s.]*)?(Gen\s*1[ab]?|CUID|USCUID|Gen\s*2|Gen\s*3|APDU|UFUID|GDM|Gen\s*4\s*(?:GTU|GDM)?|[Uu]ltimate)")
@@ -324,6 +397,23 @@ static HF_NTAG_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)NTAG\s*(\d{3})").expect("bad hf ntag type regex")
 });
 
+// `hf mfu rdbl` data row: "[=]   2/0x02 | 8B 48 00 00 | .H.."
+static MFU_RDBL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d+)/0x[0-9A-Fa-f]+\s*\|\s*([0-9A-Fa-f]{2}) ([0-9A-Fa-f]{2}) ([0-9A-Fa-f]{2}) ([0-9A-Fa-f]{2})")
+        .expect("bad mfu rdbl regex")
+});
+
+// `hf mfu info` GET_VERSION response: "[=]       Raw bytes: 00 04 04 02 01 00 11 03"
+static MFU_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Raw bytes:\s*((?:[0-9A-F]{2}\s*){8})").expect("bad mfu version regex")
+});
+
+// `hf mf rdbl` data row: "[=]   0 | 01 02 03 04 04 08 04 00 00 00 00 00 00 00 BE EF | ........"
+static MF_RDBL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[.\]\s+(\d+)\s*\|\s*((?:[0-9A-Fa-f]{2}\s){15}[0-9A-Fa-f]{2})")
+        .expect("bad mf rdbl regex")
+});
+
 // Ultralight type: "Ultralight EV1" / "Ultralight C" / "Ultralight Nano"
 static HF_MFU_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)(?:MIFARE\s+)?Ultralight(?:\s+(EV1|C|Nano|AES))?")
@@ -345,6 +435,14 @@ static AUTOPWN_KEY_FOUND_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("bad autopwn key found regex")
 });
 
+// Final key table row: "[+]  001 | 007 | FFFFFFFFFFFF | D | ------------ | 0"
+static MF_KEY_TABLE_ROW_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[\+\]\s*(\d{3})\s*\|\s*\d{3}\s*\|\s*([0-9A-Fa-f]{12}|-{12})\s*\|\s*(\S)\s*\|\s*([0-9A-Fa-f]{12}|-{12})\s*\|\s*(\S)",
+    )
+    .expect("bad mf key table regex")
+});
+
 // "Succeeded in dumping all blocks" — full dump
 static AUTOPWN_DUMP_OK_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)Succeeded\s+in\s+dumping\s+all\s+blocks")
@@ -375,6 +473,12 @@ static AUTOPWN_TIME_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("bad autopwn time regex")
 });
 
+// "[+] Writing block 12" / "[=] Writing to block  12: ..." / "[=] block  12: ..."
+static RESTORE_BLOCK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\[[=+#]\]\s*(?:writing\s+(?:to\s+)?)?block\s*(\d+)")
+        .expect("bad restore block regex")
+});
+
 // ---------------------------------------------------------------------------
 // Valid tag fallback
 // ---------------------------------------------------------------------------
@@ -388,7 +492,13 @@ static VALID_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
 // ---------------------------------------------------------------------------
 
 static T5577_CHIP_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)Chip\s*(?:type)?\.+\s*(T55x7|T5555|T5577)").expect("bad t5577 chip regex")
+    Regex::new(r"(?i)Chip\s*(?:type)?\.+\s*(T55x7|(?:Q5/)?T5555|T5577)")
+        .expect("bad t5577 chip regex")
+});
+
+/// `lf search`'s chipset line, e.g. "[+] Chipset detection: T55xx".
+static LF_CHIPSET_T55XX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)Chipset\s+detection\s*[.:]+\s*T55").expect("bad lf chipset regex")
 });
 
 static T5577_PASSWORD_RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -408,11 +518,43 @@ static T5577_PASSWORD_FOUND_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("bad t5577 password found regex")
 });
 
+/// `lf t55xx dump` row: `[+]  01 | FF83C030 | 1111...0000 | ...`
+static T55XX_DUMP_ROW_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[.\]\s+(\d{2})\s*\|\s*([0-9A-Fa-f]{8})\s*\|").expect("bad t55xx dump row regex")
+});
+
+/// `RF/64`, `Clock: 50`, `clock rate: 32`
+static LF_CLOCK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:\bRF/|\bclock(?:\s+rate)?\s*[:.]+\s*)(\d{1,3})\b")
+        .expect("bad lf clock regex")
+});
+/// Data bit rates a T5577 can be configured for.
+const LF_CLOCKS: [u16; 8] = [8, 16, 32, 40, 50, 64, 100, 128];
+
 // ---------------------------------------------------------------------------
 // Main parse_lf_search
 // ---------------------------------------------------------------------------
 
 pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
+    let (card_type, mut data) = parse_lf_tag(output)?;
+    if let Some(clock) = parse_lf_clock(output) {
+        data.decoded.insert("clock".to_string(), clock.to_string());
+    }
+    Some((card_type, data))
+}
+
+/// Bit rate the demodulator locked onto, from `EM410x ( RF/64 )`,
+/// `Clock: 50` or `Auto-detected clock rate: 32`. Only rates a T5577 can
+/// reproduce are kept.
+pub fn parse_lf_clock(output: &str) -> Option<u16> {
+    let clean = strip_ansi(output);
+    LF_CLOCK_RE
+        .captures_iter(&clean)
+        .filter_map(|caps| caps[1].parse::<u16>().ok())
+        .find(|clk| LF_CLOCKS.contains(clk))
+}
+
+fn parse_lf_tag(output: &str) -> Option<(CardType, CardData)> {
     let clean = strip_ansi(output);
 
     // Check for no-card condition first
@@ -457,6 +599,16 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             decoded.insert("type".to_string(), "Indala".to_string());
             decoded.insert("raw".to_string(), raw.clone());
             decoded.insert("id".to_string(), uid.clone());
+            // Keep FC/CN when PM3 managed to descramble them; the dispatcher
+            // prefers a structured clone over raw when these are present.
+            if let Some(caps) = INDALA_FC_CN_RE.captures(&clean) {
+                decoded.insert("format".to_string(), "26".to_string());
+                decoded.insert("facility_code".to_string(), caps[1].to_string());
+                decoded.insert("card_number".to_string(), caps[2].to_string());
+            }
+            if let Some(caps) = INDALA_HEDEN_RE.captures(&clean) {
+                decoded.insert("heden".to_string(), caps[1].to_string());
+            }
             return Some((
                 CardType::Indala,
                 CardData {
@@ -513,8 +665,9 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
 
     // --- New card types (check before generic fallback) ---
 
-    // Gallagher
-    if clean.contains("Gallagher") || clean.contains("GALLAGHER") {
+    // Gallagher (older systems and firmware call it Cardax)
+    if clean.contains("Gallagher") || clean.contains("GALLAGHER") || clean.contains("Cardax") {
+        let crc_ok = gallagher_crc_ok(&clean);
         // Fast path: single-line regex with all 4 fields
         if let Some(caps) = GALLAGHER_RE.captures(&clean) {
             let rc = caps[1].to_string();
@@ -528,6 +681,7 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             decoded.insert("facility_code".to_string(), fc);
             decoded.insert("card_number".to_string(), cn);
             decoded.insert("issue_level".to_string(), il);
+            insert_crc_ok(&mut decoded, crc_ok);
             return Some((
                 CardType::Gallagher,
                 CardData {
@@ -550,6 +704,7 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             decoded.insert("facility_code".to_string(), fc);
             decoded.insert("card_number".to_string(), cn);
             decoded.insert("issue_level".to_string(), il);
+            insert_crc_ok(&mut decoded, crc_ok);
             return Some((
                 CardType::Gallagher,
                 CardData {
@@ -565,6 +720,7 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             let mut decoded = HashMap::new();
             decoded.insert("type".to_string(), "Gallagher".to_string());
             decoded.insert("raw_fallback".to_string(), "true".to_string());
+            insert_crc_ok(&mut decoded, crc_ok);
             return Some((
                 CardType::Gallagher,
                 CardData {
@@ -702,6 +858,18 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             let mut decoded = HashMap::new();
             decoded.insert("type".to_string(), "SecuraKey".to_string());
             decoded.insert("raw".to_string(), raw.clone());
+            if let Some(fc_caps) = SECURAKEY_FC_CN_RE.captures(&clean) {
+                let fc = match fc_caps[1].strip_prefix("0x").or(fc_caps[1].strip_prefix("0X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => fc_caps[1].parse().ok(),
+                };
+                if let Some(fc) = fc {
+                    decoded.insert("facility_code".to_string(), fc.to_string());
+                    decoded.insert("card_number".to_string(), fc_caps[2].to_string());
+                }
+            }
+            let bit_len = LEN_RE.captures(&clean).and_then(|c| c[1].parse().ok());
+            insert_wiegand(&mut decoded, &clean, bit_len);
             return Some((
                 CardType::SecuraKey,
                 CardData {
@@ -738,6 +906,14 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
             let mut decoded = HashMap::new();
             decoded.insert("type".to_string(), "Motorola".to_string());
             decoded.insert("raw".to_string(), raw.clone());
+            // FC/CN are informational: PM3 only clones Motorola from raw
+            if let Some(fc_caps) = MOTOROLA_FC_CN_RE.captures(&clean) {
+                if let Some(fmt) = fc_caps.get(1) {
+                    decoded.insert("format".to_string(), fmt.as_str().to_string());
+                }
+                decoded.insert("facility_code".to_string(), fc_caps[2].to_string());
+                decoded.insert("card_number".to_string(), fc_caps[3].to_string());
+            }
             return Some((
                 CardType::Motorola,
                 CardData {
@@ -918,6 +1094,67 @@ pub fn parse_lf_search(output: &str) -> Option<(CardType, CardData)> {
     None
 }
 
+// ---------------------------------------------------------------------------
+// Continuous LF reads (lf hid watch, repeated lf search)
+// ---------------------------------------------------------------------------
+
+/// How long `lf hid watch` may go without a read before the badge counts as
+/// taken away. The watch prints nothing while the antenna is empty, so a
+/// quiet spell is the only sign a badge was removed.
+pub const LF_WATCH_GAP: Duration = Duration::from_secs(2);
+
+/// Turns continuous LF reads into one report per badge presented. A badge
+/// left on the antenna is reported once, until a different badge is read or
+/// the antenna is seen empty.
+#[derive(Debug, Default)]
+pub struct LfWatchParser {
+    read: String,
+    last: Option<(CardType, String)>,
+    last_seen: Option<Instant>,
+}
+
+impl LfWatchParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One `lf hid watch` line, received at `now`. A line that decodes on
+    /// its own starts a new read; lines after it (raw, checksum) are added to
+    /// it. Returns the card the first time a new badge's read decodes.
+    pub fn feed(&mut self, line: &str, now: Instant) -> Option<(CardType, CardData)> {
+        if self.last_seen.is_some_and(|seen| now.duration_since(seen) > LF_WATCH_GAP) {
+            self.last = None;
+        }
+        if parse_lf_search(line).is_some() {
+            self.read.clear();
+        }
+        self.read.push_str(line);
+        self.read.push('\n');
+
+        let read = parse_lf_search(&self.read);
+        if read.is_some() {
+            self.last_seen = Some(now);
+        }
+        self.observe(read)
+    }
+
+    /// One whole read, e.g. an `lf search` pass; `None` (nothing on the
+    /// antenna) lets the next badge count again. Returns the card when it
+    /// isn't the badge already reported.
+    pub fn observe(&mut self, read: Option<(CardType, CardData)>) -> Option<(CardType, CardData)> {
+        let Some((card_type, card_data)) = read else {
+            self.last = None;
+            return None;
+        };
+        let id = (card_type.clone(), card_data.uid.clone());
+        if self.last.as_ref() == Some(&id) {
+            return None;
+        }
+        self.last = Some(id);
+        Some((card_type, card_data))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // HF search parser
 // ---------------------------------------------------------------------------
@@ -1067,8 +1304,20 @@ pub fn parse_hf_search(output: &str) -> Option<(CardType, CardData)> {
     // --- SAK-based MIFARE Classic determination ---
     if let Some(sak_val) = sak {
         match sak_val {
-            // Classic 1K: SAK 0x08, 0x88, 0x09, 0x89
-            0x08 | 0x88 | 0x09 | 0x89 => {
+            // Mini: SAK 0x09 (5 sectors; PM3 lists "MIFARE Mini 0.3K")
+            0x09 => {
+                decoded.insert("type".to_string(), "MifareMini".to_string());
+                return Some((
+                    CardType::MifareMini,
+                    CardData {
+                        uid: uid.clone(),
+                        raw: String::new(),
+                        decoded,
+                    },
+                ));
+            }
+            // Classic 1K: SAK 0x08, 0x88, 0x89
+            0x08 | 0x88 | 0x89 => {
                 decoded.insert("type".to_string(), "MifareClassic1K".to_string());
                 return Some((
                     CardType::MifareClassic1K,
@@ -1113,6 +1362,17 @@ pub fn parse_hf_search(output: &str) -> Option<(CardType, CardData)> {
     }
 
     // --- Text-based fallback for MIFARE Classic ---
+    if clean.contains("MIFARE Mini") {
+        decoded.insert("type".to_string(), "MifareMini".to_string());
+        return Some((
+            CardType::MifareMini,
+            CardData {
+                uid: uid.clone(),
+                raw: String::new(),
+                decoded,
+            },
+        ));
+    }
     if clean.contains("MIFARE Classic 4K") || clean.contains("Classic 4K") {
         decoded.insert("type".to_string(), "MifareClassic4K".to_string());
         return Some((
@@ -1224,6 +1484,49 @@ pub fn parse_autopwn_line(line: &str) -> Option<AutopwnEvent> {
     None
 }
 
+/// Per-sector keys from the key table `hf mf autopwn` / `hf mf chk` print at
+/// the end (index = sector). A key shown as dashes or with result `0` was not
+/// recovered. Empty if no table was printed.
+pub fn parse_mf_key_table(output: &str) -> Vec<SectorKeys> {
+    let clean = strip_ansi(output);
+    let mut sectors: Vec<SectorKeys> = Vec::new();
+    for line in clean.lines() {
+        let Some(caps) = MF_KEY_TABLE_ROW_RE.captures(line.trim()) else {
+            continue;
+        };
+        let Ok(sector) = caps[1].parse::<usize>() else { continue };
+        let key = |key: &str, res: &str| {
+            (!key.starts_with('-') && res != "0").then(|| key.to_uppercase())
+        };
+        if sector >= sectors.len() {
+            sectors.resize(sector + 1, SectorKeys::default());
+        }
+        sectors[sector] = SectorKeys {
+            key_a: key(&caps[2], &caps[3]),
+            key_b: key(&caps[4], &caps[5]),
+        };
+    }
+    sectors
+}
+
+/// Key recovered by a single `hf mf nested` / `hf mf hardnested` run.
+pub fn parse_found_key(output: &str) -> Option<String> {
+    let clean = strip_ansi(output);
+    AUTOPWN_KEY_FOUND_RE
+        .captures(&clean)
+        .map(|c| c[1].to_uppercase())
+}
+
+/// Parse a single line from `hf mf restore` streaming output.
+/// Returns the block number being written, or `None` for anything else
+/// (header, summary and error lines).
+pub fn parse_restore_line(line: &str) -> Option<u16> {
+    let clean = strip_ansi(line);
+    RESTORE_BLOCK_RE
+        .captures(clean.trim())
+        .and_then(|caps| caps[1].parse().ok())
+}
+
 // ---------------------------------------------------------------------------
 // Magic card generation detection (from `hf mf info` output)
 // ---------------------------------------------------------------------------
@@ -1262,6 +1565,51 @@ pub fn parse_magic_detection(output: &str) -> Option<MagicGeneration> {
     None
 }
 
+/// ZUID / ONUID dual-magic cards: cheap blanks that report a Gen2/GDM
+/// generation but also answer the Gen1a backdoor wakeup. True for a ZUID
+/// marker, or an ONUID (re-used UID) alongside a Gen 1a capability line.
+pub fn parse_gen1_wakeup(output: &str) -> bool {
+    let clean = strip_ansi(output);
+    if HF_ZUID_RE.is_match(&clean) {
+        return true;
+    }
+    HF_ONUID_RE.is_match(&clean)
+        && HF_MAGIC_RE.captures_iter(&clean).any(|caps| {
+            let gen = caps[1].to_lowercase().replace(char::is_whitespace, "");
+            gen.starts_with("gen1")
+        })
+}
+
+/// True CUID blank: a Gen2 card whose magic line names CUID. These take a
+/// direct `wrbl --force` on block 0; other Gen2 variants may need the 14a
+/// config forced first.
+pub fn parse_cuid(output: &str) -> bool {
+    if parse_magic_detection(output) != Some(MagicGeneration::Gen2) {
+        return false;
+    }
+    strip_ansi(output).lines().any(|line| {
+        HF_MAGIC_RE.is_match(line)
+            && line
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|word| word.eq_ignore_ascii_case("cuid"))
+    })
+}
+
+/// UID length, ATQA and SAK from the ISO 14443-A header of `hf 14a info` /
+/// `hf mf info` output. `None` if no UID line is present.
+pub fn parse_14a_identity(output: &str) -> Option<Iso14aIdentity> {
+    let clean = strip_ansi(output);
+    let uid = HF_UID_RE.captures(&clean)?;
+    let uid_len = uid[1].chars().filter(|c| c.is_ascii_hexdigit()).count() / 2;
+    let atqa = HF_ATQA_RE
+        .captures(&clean)
+        .and_then(|caps| u16::from_str_radix(&caps[1].replace(char::is_whitespace, ""), 16).ok());
+    let sak = HF_SAK_RE
+        .captures(&clean)
+        .and_then(|caps| u8::from_str_radix(&caps[1], 16).ok());
+    Some(Iso14aIdentity { uid_len, atqa, sak })
+}
+
 /// Check if `hf 14a info` output indicates an ISO 14443-A card is present.
 /// Returns true if UID, ATQA, or SAK lines are found.
 pub fn is_hf_card_present(output: &str) -> bool {
@@ -1270,6 +1618,79 @@ pub fn is_hf_card_present(output: &str) -> bool {
     lower.contains("uid") && (lower.contains("atqa") || lower.contains("sak"))
 }
 
+/// Whether `lf search` / `hf search` output shows a card on that frequency.
+/// For HF a bare ISO 14443-A answer counts even if no parser recognises it.
+pub fn card_on_frequency(frequency: &Frequency, search_output: &str) -> bool {
+    match frequency {
+        Frequency::LF => parse_lf_search(search_output).is_some(),
+        Frequency::HF => {
+            parse_hf_search(search_output).is_some() || is_hf_card_present(search_output)
+        }
+    }
+}
+
+/// Random ID (ISO 14443-3 RID): a 4-byte UID starting `08`, new on every
+/// power-up.
+fn is_random_uid(uid: &str) -> bool {
+    let hex: String = uid.chars().filter(char::is_ascii_hexdigit).collect();
+    hex.len() == 8 && hex.starts_with("08")
+}
+
+/// What changed between two `hf search` reads of the same card: "card type",
+/// "UID", "ATQA", "SAK", or just "card" when the second read found nothing.
+/// Two random IDs always differ, so their UIDs aren't compared. Empty when
+/// the reads agree.
+pub fn hf_read_differences(
+    first_type: &CardType,
+    first: &CardData,
+    second: Option<&(CardType, CardData)>,
+) -> Vec<&'static str> {
+    let Some((second_type, second)) = second else {
+        return vec!["card"];
+    };
+    let mut differences = Vec::new();
+    if first_type != second_type {
+        differences.push("card type");
+    }
+    let both_random = is_random_uid(&first.uid) && is_random_uid(&second.uid);
+    if !both_random && !first.uid.eq_ignore_ascii_case(&second.uid) {
+        differences.push("UID");
+    }
+    for (key, label) in [("atqa", "ATQA"), ("sak", "SAK")] {
+        if first.decoded.get(key) != second.decoded.get(key) {
+            differences.push(label);
+        }
+    }
+    differences
+}
+
+/// Every tag in an `lf search` and an `hf search` of the same card, LF first.
+/// Either output may be missing (search failed). `hf search` itself runs the
+/// iCLASS and ISO 15693 probes, so those show up through `parse_hf_search`.
+pub fn parse_all_frequencies(
+    lf_output: Option<&str>,
+    hf_output: Option<&str>,
+) -> Vec<(CardType, CardData)> {
+    lf_output
+        .and_then(parse_lf_search)
+        .into_iter()
+        .chain(hf_output.and_then(parse_hf_search))
+        .collect()
+}
+
+/// User hint when a scan of one frequency found nothing but a card answered
+/// on `found_on`.
+pub fn wrong_frequency_hint(found_on: &Frequency) -> String {
+    match found_on {
+        Frequency::HF => "No 125 kHz tag found, but this looks like a 13.56 MHz card \
+                          — switch to HF mode and scan again."
+            .to_string(),
+        Frequency::LF => "No 13.56 MHz tag found, but this looks like a 125 kHz card \
+                          — switch to LF mode and scan again."
+            .to_string(),
+    }
+}
+
 /// Check if `hf mfu info` output indicates an Ultralight/NTAG magic card.
 /// Magic UL/NTAG cards respond to RATS with ATS (genuine never does).
 pub fn is_magic_ultralight(output: &str) -> bool {
@@ -1279,6 +1700,158 @@ pub fn is_magic_ultralight(output: &str) -> bool {
     lower.contains("magic") || lower.contains("gen1a") || lower.contains("directwrite")
 }
 
+/// Extract the 4 page bytes from `hf mfu rdbl -b <page>` output.
+/// Returns `None` if the page wasn't read (auth required, no card).
+pub fn parse_mfu_rdbl(output: &str, page: u8) -> Option<[u8; 4]> {
+    let clean = strip_ansi(output);
+    clean.lines().find_map(|line| {
+        let caps = MFU_RDBL_RE.captures(line)?;
+        if caps[1].parse::<u8>().ok()? != page {
+            return None;
+        }
+        let mut bytes = [0u8; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&caps[i + 2], 16).ok()?;
+        }
+        Some(bytes)
+    })
+}
+
+/// Exact Ultralight/NTAG chip, from its GET_VERSION response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum MfuVariant {
+    Ultralight,
+    UltralightC,
+    /// MF0UL11, 48 bytes of user memory.
+    UltralightEv1_48,
+    /// MF0UL21, 128 bytes of user memory.
+    UltralightEv1_128,
+    UltralightAes,
+    Ntag210,
+    Ntag212,
+    Ntag213,
+    Ntag215,
+    Ntag216,
+}
+
+impl MfuVariant {
+    /// Decode the 8 GET_VERSION bytes: header, vendor, product type,
+    /// subtype, major, minor, storage size, protocol. Only NXP parts.
+    pub fn from_version(version: [u8; 8]) -> Option<Self> {
+        if version[0] != 0x00 || version[1] != 0x04 {
+            return None;
+        }
+        match (version[2], version[4], version[6]) {
+            (0x03, 0x01, 0x0B) => Some(Self::UltralightEv1_48),
+            (0x03, 0x01, 0x0E) => Some(Self::UltralightEv1_128),
+            (0x03, 0x04, 0x0F) => Some(Self::UltralightAes),
+            (0x04, 0x01, 0x0B) => Some(Self::Ntag210),
+            (0x04, 0x01, 0x0E) => Some(Self::Ntag212),
+            (0x04, 0x01, 0x0F) => Some(Self::Ntag213),
+            (0x04, 0x01, 0x11) => Some(Self::Ntag215),
+            (0x04, 0x01, 0x13) => Some(Self::Ntag216),
+            _ => None,
+        }
+    }
+
+    /// Pages in a complete dump, config and counter pages included.
+    pub fn page_count(self) -> usize {
+        match self {
+            Self::Ultralight => 16,
+            Self::UltralightC => 48,
+            Self::UltralightEv1_48 | Self::Ntag210 => 20,
+            Self::UltralightEv1_128 | Self::Ntag212 => 41,
+            Self::UltralightAes => 60,
+            Self::Ntag213 => 45,
+            Self::Ntag215 => 135,
+            Self::Ntag216 => 231,
+        }
+    }
+
+    /// Pages a dump without authentication can contain: the Ultralight C
+    /// 3DES key (pages 44-47) is write-only.
+    pub fn readable_pages(self) -> usize {
+        match self {
+            Self::UltralightC => 44,
+            _ => self.page_count(),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ultralight => "Ultralight",
+            Self::UltralightC => "Ultralight C",
+            Self::UltralightEv1_48 => "Ultralight EV1 (48 bytes)",
+            Self::UltralightEv1_128 => "Ultralight EV1 (128 bytes)",
+            Self::UltralightAes => "Ultralight AES",
+            Self::Ntag210 => "NTAG210",
+            Self::Ntag212 => "NTAG212",
+            Self::Ntag213 => "NTAG213",
+            Self::Ntag215 => "NTAG215",
+            Self::Ntag216 => "NTAG216",
+        }
+    }
+}
+
+/// Identify the UL/NTAG chip from `hf mfu info`. The GET_VERSION bytes
+/// under "Tag Version" decide; Ultralight and Ultralight C don't answer
+/// GET_VERSION, so for those the TYPE line is used. `None` when neither
+/// pins the chip down.
+pub fn parse_mfu_version(output: &str) -> Option<MfuVariant> {
+    let clean = strip_ansi(output);
+    let version = clean
+        .lines()
+        .skip_while(|line| !line.contains("Tag Version"))
+        .find_map(|line| MFU_VERSION_RE.captures(line))
+        .and_then(|caps| {
+            let bytes: Vec<u8> = caps[1]
+                .split_whitespace()
+                .filter_map(|b| u8::from_str_radix(b, 16).ok())
+                .collect();
+            <[u8; 8]>::try_from(bytes).ok()
+        });
+    if let Some(version) = version {
+        return MfuVariant::from_version(version);
+    }
+    if HF_NTAG_TYPE_RE.is_match(&clean) {
+        return None;
+    }
+    let caps = HF_MFU_TYPE_RE.captures(&clean)?;
+    match caps.get(1).map(|m| m.as_str().to_ascii_uppercase()) {
+        None => Some(MfuVariant::Ultralight),
+        Some(v) if v == "C" => Some(MfuVariant::UltralightC),
+        Some(_) => None,
+    }
+}
+
+/// Pages in an `hf mfu dump` table (highest page row + 1); 0 when the
+/// output has no page rows.
+pub fn parse_mfu_dump_pages(output: &str) -> usize {
+    let clean = strip_ansi(output);
+    clean
+        .lines()
+        .filter_map(|line| MFU_RDBL_RE.captures(line)?[1].parse::<usize>().ok())
+        .max()
+        .map_or(0, |last| last + 1)
+}
+
+/// Extract the 16 block bytes from `hf mf rdbl --blk <blk>` output.
+/// Returns `None` if the block wasn't read (wrong key, no card).
+pub fn parse_mf_rdbl(output: &str, blk: u16) -> Option<[u8; 16]> {
+    let clean = strip_ansi(output);
+    clean.lines().find_map(|line| {
+        let caps = MF_RDBL_RE.captures(line.trim_end())?;
+        if caps[1].parse::<u16>().ok()? != blk {
+            return None;
+        }
+        let mut bytes = [0u8; 16];
+        for (byte, hex) in bytes.iter_mut().zip(caps[2].split_whitespace()) {
+            *byte = u8::from_str_radix(hex, 16).ok()?;
+        }
+        Some(bytes)
+    })
+}
+
 /// Check if `hf iclass info` output indicates an iCLASS/Picopass card is present.
 pub fn is_iclass_present(output: &str) -> bool {
     let clean = strip_ansi(output);
@@ -1430,6 +2003,8 @@ fn parse_awid(clean: &str) -> Option<(CardType, CardData)> {
         let cn = caps[2].to_string();
         decoded.insert("facility_code".to_string(), fc.clone());
         decoded.insert("card_number".to_string(), cn.clone());
+        let bit_len = decoded.get("format").and_then(|f| f.parse().ok());
+        insert_wiegand(&mut decoded, clean, bit_len);
         let uid = format!("FC{}:CN{}", fc, cn);
         return Some((
             CardType::AWID,
@@ -1500,9 +2075,42 @@ fn parse_fdxb(clean: &str) -> Option<(CardType, CardData)> {
     None
 }
 
+/// The Paradox part of `lf search` output: the line naming the tag and its
+/// continuation lines, up to a blank line, the `Valid ... found` banner or
+/// the chipset section. Generic labels (checksum, format) elsewhere in the
+/// output belong to other demods.
+fn paradox_block(clean: &str) -> String {
+    clean
+        .lines()
+        .skip_while(|line| !line.to_ascii_lowercase().contains("paradox"))
+        .enumerate()
+        .take_while(|(i, line)| {
+            *i == 0
+                || !(line.trim().is_empty()
+                    || line.contains("Valid ")
+                    || line.contains("Chipset"))
+        })
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn parse_paradox(clean: &str) -> Option<(CardType, CardData)> {
     let mut decoded = HashMap::new();
     decoded.insert("type".to_string(), "Paradox".to_string());
+    // Extra fields PM3 prints alongside FC/CN; informational only
+    let extras: [(&str, &LazyLock<Regex>); 4] = [
+        ("paradox_id", &PARADOX_ID_RE),
+        ("checksum", &PARADOX_CHECKSUM_RE),
+        ("format", &PARADOX_FMT_RE),
+        ("manufacturer", &PARADOX_MFR_RE),
+    ];
+    let block = paradox_block(clean);
+    for (key, re) in extras {
+        if let Some(caps) = re.captures(&block) {
+            decoded.insert(key.to_string(), caps[1].to_uppercase());
+        }
+    }
 
     // Try FC/CN first
     if let Some(caps) = PARADOX_FC_CN_RE.captures(clean) {
@@ -1555,6 +2163,19 @@ fn parse_paradox(clean: &str) -> Option<(CardType, CardData)> {
     None
 }
 
+/// Internal ID from a 64-bit Keri internal-format frame: `E0000000` preamble
+/// word, then bit 31 set and the 31-bit ID (`E000000080003039` -> 12345).
+fn decode_keri_internal_raw(raw: &str) -> Option<u32> {
+    if raw.len() != 16 {
+        return None;
+    }
+    let value = u64::from_str_radix(raw, 16).ok()?;
+    if value >> 32 != 0xE000_0000 || value & 0x8000_0000 == 0 {
+        return None;
+    }
+    Some((value & 0x7FFF_FFFF) as u32)
+}
+
 fn parse_keri(clean: &str) -> Option<(CardType, CardData)> {
     let mut decoded = HashMap::new();
     decoded.insert("type".to_string(), "Keri".to_string());
@@ -1575,6 +2196,12 @@ fn parse_keri(clean: &str) -> Option<(CardType, CardData)> {
         if let Some(raw_caps) = KERI_RE.captures(clean) {
             decoded.insert("raw".to_string(), raw_caps[1].to_uppercase());
         }
+        // PM3 always prints the MS descramble of the same bits too; keep it
+        // for display without switching the clone away from the internal ID
+        if let Some(ms_caps) = KERI_MS_FC_CN_RE.captures(clean) {
+            decoded.insert("ms_facility_code".to_string(), ms_caps[1].to_string());
+            decoded.insert("ms_card_number".to_string(), ms_caps[2].to_string());
+        }
         return Some((
             CardType::Keri,
             CardData {
@@ -1606,6 +2233,19 @@ fn parse_keri(clean: &str) -> Option<(CardType, CardData)> {
     if let Some(caps) = KERI_RE.captures(clean) {
         let raw = caps[1].to_uppercase();
         decoded.insert("raw".to_string(), raw.clone());
+        // A full 64-bit internal-format frame still yields a structured clone
+        if let Some(id) = decode_keri_internal_raw(&raw) {
+            decoded.insert("card_number".to_string(), id.to_string());
+            decoded.insert("keri_type".to_string(), "i".to_string());
+            return Some((
+                CardType::Keri,
+                CardData {
+                    uid: id.to_string(),
+                    raw,
+                    decoded,
+                },
+            ));
+        }
         return Some((
             CardType::Keri,
             CardData {
@@ -1634,6 +2274,8 @@ fn parse_pyramid(clean: &str) -> Option<(CardType, CardData)> {
         if let Some(raw_caps) = PYRAMID_RAW_RE.captures(clean) {
             decoded.insert("raw".to_string(), raw_caps[1].to_uppercase());
         }
+        let bit_len = LEN_RE.captures(clean).and_then(|c| c[1].parse().ok());
+        insert_wiegand(&mut decoded, clean, bit_len);
         return Some((
             CardType::Pyramid,
             CardData {
@@ -1798,6 +2440,12 @@ fn parse_noralsy(clean: &str) -> Option<(CardType, CardData)> {
 // T5577 detection
 // ---------------------------------------------------------------------------
 
+/// Whether `lf search` identified the tag's chip as a T55xx. Only then is
+/// `lf t55xx detect` worth the extra round trip.
+pub fn lf_search_found_t55xx(output: &str) -> bool {
+    LF_CHIPSET_T55XX_RE.is_match(&strip_ansi(output))
+}
+
 /// Parse `lf t55xx detect` output for password status and chip info.
 pub fn parse_t5577_detect(output: &str) -> T5577Status {
     let clean = strip_ansi(output);
@@ -1832,12 +2480,45 @@ pub fn parse_t5577_detect(output: &str) -> T5577Status {
         .captures(&clean)
         .map(|c| c[1].trim().to_string());
 
+    let locked = block0.as_deref().is_some_and(t5577_config_otp);
+
     T5577Status {
         detected,
         chip_type,
         password_set,
         block0,
         modulation,
+        locked,
+    }
+}
+
+/// Whether a T5577 config block (8 hex digits) has the OTP flag set. The
+/// flag is bit 23 (MSB first) and only counts in extended mode: master key
+/// 6 or 9 with the X-mode bit (bit 14) set, as `lf t55xx info` decodes it.
+/// A per-block lock bit doesn't show in a read, so this is all `detect`
+/// output can tell.
+pub fn t5577_config_otp(block0: &str) -> bool {
+    let Ok(config) = u32::from_str_radix(block0, 16) else {
+        return false;
+    };
+    let bit = |n: u32| (config >> (31 - n)) & 1 == 1;
+    matches!(config >> 28, 6 | 9) && bit(14) && bit(23)
+}
+
+/// How a password-protected T5577 can be unlocked after `lf t55xx chk`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum T5577Unlock {
+    /// Password recovered from PM3's dictionary.
+    Password(String),
+    /// Dictionary exhausted — only a user-supplied password can help.
+    PasswordUnknown,
+}
+
+/// Decide the next step for a locked T5577 from its `lf t55xx chk` output.
+pub fn resolve_t5577_unlock(chk_output: &str) -> T5577Unlock {
+    match parse_t5577_chk(chk_output) {
+        Some(password) => T5577Unlock::Password(password),
+        None => T5577Unlock::PasswordUnknown,
     }
 }
 
@@ -1850,6 +2531,25 @@ pub fn parse_t5577_chk(output: &str) -> Option<String> {
         .map(|c| c[1].to_uppercase())
 }
 
+/// Page-0 blocks (config block first) from `lf t55xx dump`, uppercased.
+/// `None` unless blocks 0..n were all read in order; page 1 is ignored.
+pub fn parse_t55xx_dump(output: &str) -> Option<Vec<String>> {
+    let clean = strip_ansi(output);
+    let mut blocks = Vec::new();
+    for line in clean.lines() {
+        if line.contains("Page 1") {
+            break;
+        }
+        if let Some(caps) = T55XX_DUMP_ROW_RE.captures(line.trim_end()) {
+            if caps[1].parse::<usize>().ok()? != blocks.len() {
+                return None;
+            }
+            blocks.push(caps[2].to_uppercase());
+        }
+    }
+    (!blocks.is_empty()).then_some(blocks)
+}
+
 // ---------------------------------------------------------------------------
 // Verification
 // ---------------------------------------------------------------------------
@@ -1957,21 +2657,316 @@ pub fn parse_em4305_info(output: &str) -> bool {
 /// Returns the hex string of word 0 (e.g., "00000000") or None if parse failed.
 /// Used to verify wipe succeeded: word 0 should be all zeros after a successful wipe.
 pub fn parse_em4305_word0(output: &str) -> Option<String> {
+    parse_em4305_word(output, 0)
+}
+
+/// Parse `lf em 4x05 read -a N` output for the value of word `address`
+/// ("Address 05 | 1234ABCD", "Word 5: 1234ABCD"). `None` if that word isn't
+/// in the output (read failed, or protected).
+pub fn parse_em4305_word(output: &str, address: u8) -> Option<String> {
     static EM4305_WORD_RE: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"(?i)(?:Word|Address)\s*0+\s*[:|]\s*([0-9A-Fa-f]{8})")
+        Regex::new(r"(?i)(?:Word|Address)\s*(\d{1,2})\s*[:|]\s*([0-9A-Fa-f]{8})")
             .expect("bad em4305 word regex")
     });
 
     let clean = strip_ansi(output);
     EM4305_WORD_RE
-        .captures(&clean)
-        .map(|c| c[1].to_uppercase())
+        .captures_iter(&clean)
+        .find(|c| c[1].parse::<u8>().ok() == Some(address))
+        .map(|c| c[2].to_uppercase())
+}
+
+// ---------------------------------------------------------------------------
+// EM4x50 dump
+// ---------------------------------------------------------------------------
+
+/// `lf em 4x50 dump` row: `  3 | 01 02 03 04 | 20 C0 40 80 | ...`
+/// (MSB-first word, then the same word LSB-first).
+static EM4X50_DUMP_ROW_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[.\]\s+(\d{1,2})\s*\|\s*((?:[0-9A-Fa-f]{2}\s+){3}[0-9A-Fa-f]{2})\s*\|")
+        .expect("bad em4x50 dump row regex")
+});
+
+/// Success line of an EM4x50 write or wipe: `Successfully wrote to word 3`,
+/// `Wiping data ok`.
+static EM4X50_WRITE_OK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\[\+\].*\b(?:successfully wrote|ok)\b").expect("bad em4x50 write regex")
+});
+
+/// Whether an `lf em 4x50 wrbl` / `wipe` reported success. The client only
+/// prints the success line once the reader confirmed the write; a failed
+/// login or write prints `[-] ... failed` instead.
+pub fn em4x50_write_ok(output: &str) -> bool {
+    EM4X50_WRITE_OK_RE.is_match(&strip_ansi(output))
+}
+
+/// Words 0-33 from `lf em 4x50 dump`, MSB-first and uppercased. `None`
+/// unless every word was read, in order.
+pub fn parse_em4x50_dump(output: &str) -> Option<Vec<String>> {
+    let clean = strip_ansi(output);
+    let mut words = Vec::new();
+    for line in clean.lines() {
+        if let Some(caps) = EM4X50_DUMP_ROW_RE.captures(line.trim_end()) {
+            if caps[1].parse::<usize>().ok()? != words.len() {
+                return None;
+            }
+            words.push(caps[2].split_whitespace().collect::<String>().to_uppercase());
+        }
+    }
+    (words.len() == EM4X50_WORD_COUNT).then_some(words)
+}
+
+// ---------------------------------------------------------------------------
+// iCLASS legacy credential (PACS) decoding
+// ---------------------------------------------------------------------------
+
+/// Block 7 of a legacy iCLASS application holds the Wiegand credential.
+const ICLASS_PACS_BLOCK: usize = 7;
+const ICLASS_BLOCK_SIZE: usize = 8;
+
+/// Decode the legacy HID iCLASS credential block from a *decrypted* dump into
+/// (facility code, card number).
+///
+/// Block 7 stores the Wiegand bits right-aligned behind a single sentinel `1`
+/// bit, e.g. `00 00 00 00 06 F6 23 AE` = sentinel + 26-bit H10301 FC 123 CN 4567.
+/// Only H10301 (26-bit) is decoded; both parity bits must check out, otherwise
+/// the block is treated as undecodable (still encrypted, SE/SEOS, or another
+/// format) and `None` is returned.
+pub fn parse_iclass_pacs(dump_bytes: &[u8]) -> Option<(u32, u32)> {
+    let start = ICLASS_PACS_BLOCK * ICLASS_BLOCK_SIZE;
+    let block = dump_bytes.get(start..start + ICLASS_BLOCK_SIZE)?;
+    let value = u64::from_be_bytes(block.try_into().ok()?);
+    if value == 0 {
+        return None;
+    }
+
+    // Sentinel is the highest set bit; everything below it is the Wiegand frame
+    let bit_len = 63 - value.leading_zeros();
+    if bit_len != 26 {
+        return None;
+    }
+    let wiegand = value & ((1u64 << bit_len) - 1);
+
+    // H10301: [even parity][FC 8][CN 16][odd parity]
+    let even_half = (wiegand >> 13) & 0x1FFF;
+    let odd_half = wiegand & 0x1FFF;
+    if even_half.count_ones() & 1 != 0 || odd_half.count_ones() & 1 != 1 {
+        return None;
+    }
+
+    let fc = ((wiegand >> 17) & 0xFF) as u32;
+    let cn = ((wiegand >> 1) & 0xFFFF) as u32;
+    Some((fc, cn))
+}
+
+/// iCLASS configuration (block 1): application limit, OTP, write locks,
+/// chip/memory config, EAS and fuses, as stored on the card.
+const ICLASS_CONFIG_BLOCK: usize = 1;
+
+// Fuse bits of config byte 7
+const ICLASS_FUSE_FPERS: u8 = 0x80;
+const ICLASS_FUSE_CODING1: u8 = 0x40;
+const ICLASS_FUSE_CODING0: u8 = 0x20;
+const ICLASS_FUSE_CRYPT: u8 = 0x18;
+const ICLASS_FUSE_RA: u8 = 0x01;
+
+/// Decoded iCLASS configuration block.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IClassConfig {
+    /// Last block of application 1 (the rest of the book is application 2).
+    pub app_limit: u8,
+    /// One-time-programmable bits, little-endian as stored.
+    pub otp: u16,
+    pub block_write_lock: u8,
+    pub chip_config: u8,
+    pub mem_config: u8,
+    pub eas: u8,
+    pub fuses: u8,
+    /// Fuse FPERS still set: the card is in personalization mode and its
+    /// configuration can be rewritten. Cleared = application mode (locked).
+    pub personalization_mode: bool,
+    pub coding: String,
+    pub crypt: String,
+    /// Fuse RA: block reads without authentication.
+    pub read_access: bool,
+}
+
+impl IClassConfig {
+    /// One line for the dump result, e.g. "Application mode (locked),
+    /// secured page, keys not locked, app limit 0x12, OTP FFFF".
+    pub fn summary(&self) -> String {
+        format!(
+            "{}, {}, app limit 0x{:02X}, OTP {:04X}",
+            if self.personalization_mode {
+                "Personalization mode (programmable)"
+            } else {
+                "Application mode (locked)"
+            },
+            self.crypt,
+            self.app_limit,
+            self.otp
+        )
+    }
+}
+
+/// Decode the configuration block of an iCLASS dump (8-byte blocks; block 1
+/// is the config). `None` if the dump is too short.
+pub fn decode_iclass_config(dump_bytes: &[u8]) -> Option<IClassConfig> {
+    let start = ICLASS_CONFIG_BLOCK * ICLASS_BLOCK_SIZE;
+    let block = dump_bytes.get(start..start + ICLASS_BLOCK_SIZE)?;
+    let fuses = block[7];
+
+    let coding = if fuses & ICLASS_FUSE_CODING1 != 0 {
+        "RFU"
+    } else if fuses & ICLASS_FUSE_CODING0 != 0 {
+        "ISO 14443-2 B / ISO 15693"
+    } else {
+        "ISO 14443-B only"
+    };
+    let crypt = match (fuses & ICLASS_FUSE_CRYPT) >> 3 {
+        0 => "no auth possible, read only if RA is enabled",
+        1 => "non-secured page",
+        2 => "secured page, keys locked",
+        _ => "secured page, keys not locked",
+    };
+
+    Some(IClassConfig {
+        app_limit: block[0],
+        otp: u16::from_le_bytes([block[1], block[2]]),
+        block_write_lock: block[3],
+        chip_config: block[4],
+        mem_config: block[5],
+        eas: block[6],
+        fuses,
+        personalization_mode: fuses & ICLASS_FUSE_FPERS != 0,
+        coding: coding.to_string(),
+        crypt: crypt.to_string(),
+        read_access: fuses & ICLASS_FUSE_RA != 0,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Parser debugging
+// ---------------------------------------------------------------------------
+
+/// How one named pattern fared against a piece of output (`debug_patterns`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternMatch {
+    pub name: &'static str,
+    pub pattern: String,
+    pub matched: bool,
+    /// Capture groups of the first match, in order; `None` for a group that
+    /// didn't take part.
+    pub captures: Vec<Option<String>>,
+}
+
+macro_rules! named_patterns {
+    ($($re:ident),* $(,)?) => {
+        [$((stringify!($re), &*$re)),*]
+    };
+}
+
+/// Every top-level pattern in this module, by name. Parsers run on
+/// ANSI-stripped text, so `ANSI_RE` itself is left out.
+fn named_patterns() -> Vec<(&'static str, &'static Regex)> {
+    named_patterns![
+        EM4100_ID_RE, HID_FC_CN_RE, HID_RAW_RE, STANDALONE_RAW_RE, HID_FORMAT_RE, INDALA_RAW_RE,
+        INDALA_UID_RE, INDALA_FC_CN_RE, INDALA_HEDEN_RE, IOPROX_FC_CN_RE, IOPROX_XSF_RE,
+        IOPROX_RAW_RE, AWID_RE, AWID_FMT_RE, FDXB_RE, FDXB_ANIMAL_ID_RE, PYRAMID_FC_CN_RE,
+        PYRAMID_RAW_RE, PARADOX_FC_CN_RE, PARADOX_RAW_RE, PARADOX_ID_RE, PARADOX_CHECKSUM_RE,
+        PARADOX_FMT_RE, PARADOX_MFR_RE, KERI_RE, KERI_INTERNAL_ID_RE, KERI_MS_FC_CN_RE, PRESCO_RE,
+        PRESCO_SC_UC_RE, NEDAP_CARD_RE, NEDAP_SUB_RE, NEDAP_CC_RE, GPROXII_FC_CN_RE,
+        GPROXII_XOR_RE, GPROXII_FMT_RE, GALLAGHER_RE, GALLAGHER_RC_RE, GALLAGHER_FC_RE,
+        GALLAGHER_CN_RE, GALLAGHER_IL_RE, GALLAGHER_CRC_RE, PAC_DETECT_RE, PAC_CN_RE, PAC_RAW_RE,
+        NORALSY_RE, NORALSY_RAW_RE, JABLOTRON_RE, SECURAKEY_RE, SECURAKEY_FC_CN_RE, WIEGAND_RE,
+        LEN_RE, VISA2000_RE, MOTOROLA_RE, MOTOROLA_FC_CN_RE, IDTECK_RE, NEXWATCH_ID_RE,
+        NEXWATCH_88BIT_ID_RE, NEXWATCH_RAW_RE, VIKING_ID_RE, VIKING_RAW_RE, COTAG_RE, EM4X50_RE,
+        HITAG_RE, HF_UID_RE, HF_ATQA_RE, HF_SAK_RE, HF_ATS_RE, HF_PRNG_RE, HF_ZUID_RE, HF_ONUID_RE,
+        HF_MAGIC_RE, HF_ICLASS_RE, HF_ICLASS_CSN_RE, HF_DESFIRE_RE, HF_NTAG_TYPE_RE, MFU_RDBL_RE,
+        MF_RDBL_RE, HF_MFU_TYPE_RE, AUTOPWN_KEYS_RE, AUTOPWN_KEY_FOUND_RE, MF_KEY_TABLE_ROW_RE,
+        AUTOPWN_DUMP_OK_RE, AUTOPWN_DUMP_PARTIAL_RE, AUTOPWN_DUMP_SAVED_RE, AUTOPWN_FAIL_RE,
+        AUTOPWN_TIME_RE, RESTORE_BLOCK_RE, VALID_TAG_RE, T5577_CHIP_RE, T5577_PASSWORD_RE,
+        T5577_BLOCK0_RE, T5577_MOD_RE,
+        T5577_PASSWORD_FOUND_RE, T55XX_DUMP_ROW_RE, LF_CLOCK_RE, EM4X50_DUMP_ROW_RE,
+        EM4X50_WRITE_OK_RE, MFU_VERSION_RE,
+    ]
+    .to_vec()
+}
+
+/// Run `output` through every named pattern and report what each captured,
+/// for working out why a card on new firmware doesn't parse.
+pub fn debug_patterns(output: &str) -> Vec<PatternMatch> {
+    let clean = strip_ansi(output);
+    named_patterns()
+        .into_iter()
+        .map(|(name, re)| {
+            let caps = re.captures(&clean);
+            PatternMatch {
+                name,
+                pattern: re.as_str().to_string(),
+                matched: caps.is_some(),
+                captures: caps
+                    .map(|c| c.iter().skip(1).map(|m| m.map(|m| m.as_str().to_string())).collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
 }
 
 // ---------------------------------------------------------------------------
 // Utility
 // ---------------------------------------------------------------------------
 
+/// Gallagher CRC check as reported by PM3. `None` when the output has no CRC
+/// line. A failed CRC almost always means a marginal read that will clone wrong.
+fn gallagher_crc_ok(clean: &str) -> Option<bool> {
+    let caps = GALLAGHER_CRC_RE.captures(clean)?;
+    match caps.get(3) {
+        Some(status) => Some(status.as_str().eq_ignore_ascii_case("ok")),
+        None => Some(caps[1].eq_ignore_ascii_case(&caps[2])),
+    }
+}
+
+/// Record a checksum result as `crc_ok` ("true"/"false"). The scan result
+/// shows a failed checksum as an unreliable read.
+fn insert_crc_ok(decoded: &mut HashMap<String, String>, crc_ok: Option<bool>) {
+    if let Some(ok) = crc_ok {
+        decoded.insert("crc_ok".to_string(), ok.to_string());
+    }
+}
+
+/// Whether a printed Wiegand message decodes to the parsed FC/CN. Only the
+/// 26-bit H10301 layout is checked (AWID 34/37 and vendor formats pack the
+/// fields differently), so `None` means "can't tell", not "mismatch".
+fn wiegand_matches_fc_cn(hex: &str, bit_len: Option<u32>, fc: u32, cn: u64) -> Option<bool> {
+    if bit_len.is_some_and(|b| b != 26) {
+        return None;
+    }
+    let decodes = wiegand::decode_hex(hex, Some("H10301")).ok()?;
+    let h10301 = decodes.first()?;
+    Some(h10301.facility_code == Some(fc) && h10301.card_number == cn)
+}
+
+/// Record the "Wiegand:" hex line as `wiegand`. When it decodes to a
+/// different FC/CN than the demod line printed, the read is flagged suspect
+/// via `crc_ok` like a failed checksum.
+fn insert_wiegand(decoded: &mut HashMap<String, String>, clean: &str, bit_len: Option<u32>) {
+    let Some(caps) = WIEGAND_RE.captures(clean) else {
+        return;
+    };
+    let hex = caps[1].to_uppercase();
+    let fc = decoded.get("facility_code").and_then(|f| f.parse().ok());
+    let cn = decoded.get("card_number").and_then(|c| c.parse().ok());
+    if let (Some(fc), Some(cn)) = (fc, cn) {
+        if wiegand_matches_fc_cn(&hex, bit_len, fc, cn) == Some(false) {
+            insert_crc_ok(decoded, Some(false));
+        }
+    }
+    decoded.insert("wiegand".to_string(), hex);
+}
+
 fn extract_first_hex_block(s: &str) -> Option<String> {
     static HEX_BLOCK_RE: LazyLock<Regex> =
         LazyLock::new(|| Regex::new(r"(?:\b0[xX])?([0-9A-Fa-f]{8,})\b").expect("bad hex block regex"));
@@ -1987,7 +2982,10 @@ fn extract_first_hex_block(s: &str) -> Option<String> {
 mod tests {
     use super::*;
     use crate::cards::types::CardType;
-    use crate::pm3::command_builder::build_clone_command;
+    use crate::pm3::command_builder::{
+        build_clone_command, build_clone_command_for_blank, build_clone_command_with,
+    };
+    use crate::pm3::parser_corpus::{LF_GALLAGHER, LF_KERI};
 
     // -----------------------------------------------------------------------
     // Helper: build realistic PM3 `lf search` output
@@ -2013,6 +3011,18 @@ mod tests {
     // 1. EM4100
     // =======================================================================
 
+    #[test]
+    fn debug_patterns_reports_em4100_capture() {
+        let output = pm3_lf_search_output("[+] EM 410x ID 0F0368568B");
+        let report = debug_patterns(&output);
+        let em = report.iter().find(|p| p.name == "EM4100_ID_RE").unwrap();
+        assert!(em.matched);
+        assert_eq!(em.captures, vec![Some("0F0368568B".to_string())]);
+        let hid = report.iter().find(|p| p.name == "HID_FC_CN_RE").unwrap();
+        assert!(!hid.matched && hid.captures.is_empty());
+        assert!(report.iter().all(|p| p.name != "ANSI_RE"));
+    }
+
     #[test]
     fn parse_em4100() {
         let output = pm3_lf_search_output(
@@ -2037,6 +3047,24 @@ mod tests {
         assert_eq!(cmd.unwrap(), "lf em 410x clone --id 0F00112233");
     }
 
+    #[test]
+    fn clone_em4100_non_default_clock() {
+        let output = pm3_lf_search_output(
+            "[+] EM 410x ID 0F00112233\n\
+             [+] EM410x ( RF/32 )"
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("clock").unwrap(), "32");
+        let cmd = build_clone_command(&CardType::EM4100, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf em 410x clone --id 0F00112233 --clk 32");
+
+        // The default rate needs no flag
+        let output = pm3_lf_search_output("[+] EM 410x ID 0F00112233\n[+] EM410x ( RF/64 )");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        let cmd = build_clone_command(&CardType::EM4100, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf em 410x clone --id 0F00112233");
+    }
+
     // =======================================================================
     // 2. HID Prox
     // =======================================================================
@@ -2085,21 +3113,101 @@ mod tests {
     fn parse_indala_raw() {
         let output = pm3_lf_search_output(
             "[+] Indala (len 64)  Raw: A0000000A0000000\n\
-             [=] Indala ID: 12345678"
+             [=] Indala ID: 12345678"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse Indala");
+        assert_eq!(card_type, CardType::Indala);
+        assert_eq!(data.decoded.get("raw").unwrap(), "A0000000A0000000");
+    }
+
+    #[test]
+    fn clone_indala() {
+        let output = pm3_lf_search_output(
+            "[+] Indala (len 64)  Raw: A0000000A0000000"
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        let cmd = build_clone_command(&CardType::Indala, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf indala clone --raw A0000000A0000000");
+        assert!(!data.decoded.contains_key("facility_code"));
+    }
+
+    #[test]
+    fn clone_indala_to_em4305() {
+        use crate::cards::types::BlankType;
+        use crate::pm3::blank_compat::check_clone_target;
+
+        assert!(check_clone_target(&CardType::Indala, &BlankType::EM4305).is_ok());
+        let output = pm3_lf_search_output("[+] Indala (len 64)  Raw: A0000000A0000000");
+        let (card_type, data) = parse_lf_search(&output).unwrap();
+        let blank = BlankType::EM4305;
+        let cmd =
+            build_clone_command_for_blank(&card_type, &data.uid, &data.decoded, &blank, false);
+        assert_eq!(cmd.unwrap(), "lf indala clone --raw A0000000A0000000 --em");
+
+        // Descrambled FC/CN clones carry the flag too
+        let output = pm3_lf_search_output(
+            "[+] Indala (len 64)  Raw: a0000000c2c436c1\n\
+             [+] Fmt 26 FC: 133  Card: 9000  Parity: ok"
+        );
+        let (card_type, data) = parse_lf_search(&output).unwrap();
+        let blank = BlankType::EM4305;
+        let cmd =
+            build_clone_command_for_blank(&card_type, &data.uid, &data.decoded, &blank, false);
+        assert_eq!(cmd.unwrap(), "lf indala clone --fc 133 --cn 9000 --em");
+        let blank = BlankType::T5577;
+        let cmd =
+            build_clone_command_for_blank(&card_type, &data.uid, &data.decoded, &blank, false);
+        assert_eq!(cmd.unwrap(), "lf indala clone --fc 133 --cn 9000");
+
+        // Types without --em get nothing for EM4305
+        let mut decoded = HashMap::new();
+        decoded.insert("raw".to_string(), "A0000000A0000000".to_string());
+        assert_eq!(
+            build_clone_command_for_blank(
+                &CardType::Motorola,
+                "A0000000A0000000",
+                &decoded,
+                &BlankType::EM4305,
+                false
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn indala_clock_kept_but_not_passed() {
+        let output = pm3_lf_search_output(
+            "[+] Indala (len 64)  Raw: A0000000A0000000\n\
+             [=] Auto-detected clock rate: 16"
         );
-        let (card_type, data) = parse_lf_search(&output).expect("should parse Indala");
-        assert_eq!(card_type, CardType::Indala);
-        assert_eq!(data.decoded.get("raw").unwrap(), "A0000000A0000000");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("clock").unwrap(), "16");
+        // `lf indala clone` has no --clk
+        let cmd = build_clone_command(&CardType::Indala, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf indala clone --raw A0000000A0000000");
     }
 
     #[test]
-    fn clone_indala() {
+    fn clone_indala_decoded_wiegand_prefers_fc_cn() {
+        // Heden/FlexSecur card PM3 could descramble
         let output = pm3_lf_search_output(
-            "[+] Indala (len 64)  Raw: A0000000A0000000"
+            "[+] Indala (len 64)  Raw: a0000000c2c436c1\n\
+             [+] Fmt 26 FC: 133  Card: 9000  Parity: ok\n\
+             [+] Possible de-scramble patterns\n\
+             [+] Printed     | __9000__ [0x2328]\n\
+             [+] Internal ID | 4358601\n\
+             [+] Heden-2L    | 888\n\
+             [+] Valid Indala ID found!"
         );
-        let (_, data) = parse_lf_search(&output).unwrap();
+        let (card_type, data) = parse_lf_search(&output).expect("should parse Indala");
+        assert_eq!(card_type, CardType::Indala);
+        assert_eq!(data.decoded.get("raw").unwrap(), "A0000000C2C436C1");
+        assert_eq!(data.decoded.get("facility_code").unwrap(), "133");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "9000");
+        assert_eq!(data.decoded.get("heden").unwrap(), "888");
+
         let cmd = build_clone_command(&CardType::Indala, &data.uid, &data.decoded);
-        assert_eq!(cmd.unwrap(), "lf indala clone --raw A0000000A0000000");
+        assert_eq!(cmd.unwrap(), "lf indala clone --fc 133 --cn 9000");
     }
 
     // =======================================================================
@@ -2128,6 +3236,29 @@ mod tests {
         assert_eq!(cmd.unwrap(), "lf awid clone --fmt 26 --fc 50 --cn 1234");
     }
 
+    #[test]
+    fn awid_and_hid_clock_parsed() {
+        let output = pm3_lf_search_output("[+] AWID 26 bit;  FC: 50  CN: 1234\n[=] Clock: 40");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("clock").unwrap(), "40");
+        let cmd = build_clone_command(&CardType::AWID, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf awid clone --fmt 26 --fc 50 --cn 1234");
+
+        let output = pm3_lf_search_output(
+            "[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334\n\
+             [+] raw: 200078BE5E1E\n\
+             [=] Clock: 100"
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("clock").unwrap(), "100");
+        let cmd = build_clone_command(&CardType::HIDProx, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf hid clone -r 200078BE5E1E");
+
+        // Not a T5577 rate
+        assert_eq!(parse_lf_clock("[=] Clock: 99"), None);
+        assert_eq!(parse_lf_clock("[+] Valid ID found!"), None);
+    }
+
     #[test]
     fn parse_awid_real_pm3_output() {
         // Real PM3 output: "AWID - len: 26 FC: 50 Card: 1234 - Wiegand: 26409a4, Raw: 011db288..."
@@ -2141,6 +3272,64 @@ mod tests {
         assert_eq!(data.decoded.get("format").unwrap(), "26");
     }
 
+    #[test]
+    fn wiegand_line_captured_and_cross_checked() {
+        let output = pm3_lf_search_output(
+            "[+] AWID - len: 26 FC: 50 Card: 1234 - Wiegand: 26409a4, Raw: 011db2881474411111111111"
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("wiegand").unwrap(), "26409A4");
+        assert!(!data.decoded.contains_key("crc_ok"));
+
+        let output = pm3_lf_search_output(
+            "[+] Securakey - len: 26 FC: 0x35 Card: 64169, Raw: 7FCB400001ADEA5344300000\n\
+             [+] Wiegand: 006BF553 parity ( ok )",
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("wiegand").unwrap(), "006BF553");
+        assert_eq!(data.decoded.get("facility_code").unwrap(), "53");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "64169");
+        assert!(!data.decoded.contains_key("crc_ok"));
+
+        // Garbled demod: the Wiegand message says FC 50 / CN 1234
+        let output = pm3_lf_search_output(
+            "[+] AWID - len: 26 FC: 58 Card: 1234 - Wiegand: 26409a4, Raw: 011db2881474411111111111"
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("crc_ok").unwrap(), "false");
+
+        // Non-H10301 lengths aren't judged
+        assert_eq!(wiegand_matches_fc_cn("1912d6a5e", Some(34), 200, 9876543), None);
+        assert_eq!(wiegand_matches_fc_cn("26409a4", None, 50, 1234), Some(true));
+    }
+
+    #[test]
+    fn parse_and_clone_awid_34bit() {
+        let output = pm3_lf_search_output(
+            "[+] AWID - len: 34 FC: 200 Card: 9876543 - Wiegand: 1912d6a5e, Raw: 0221c8b9b6b2b6ac53a11111"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse 34-bit AWID");
+        assert_eq!(card_type, CardType::AWID);
+        assert_eq!(data.decoded.get("format").unwrap(), "34");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "9876543");
+        let cmd = build_clone_command(&CardType::AWID, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf awid clone --fmt 34 --fc 200 --cn 9876543");
+    }
+
+    #[test]
+    fn parse_and_clone_awid_37bit() {
+        let output = pm3_lf_search_output(
+            "[+] AWID - len: 37 FC: 4321 Card: 250000 - Wiegand: 10e1a3d090, Raw: 0251e1b1a2d1e41111111111"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse 37-bit AWID");
+        assert_eq!(card_type, CardType::AWID);
+        assert_eq!(data.decoded.get("format").unwrap(), "37");
+        assert_eq!(data.decoded.get("facility_code").unwrap(), "4321");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "250000");
+        let cmd = build_clone_command(&CardType::AWID, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf awid clone --fmt 37 --fc 4321 --cn 250000");
+    }
+
     #[test]
     fn clone_awid_fails_without_fields() {
         // AWID has no raw fallback
@@ -2291,6 +3480,58 @@ mod tests {
         assert_eq!(cmd.unwrap(), "lf paradox clone --fc 96 --cn 40426");
     }
 
+    #[test]
+    fn paradox_prefers_raw_when_asked() {
+        let output = pm3_lf_search_output(
+            "[+] Paradox - ID: 258b9f7a3 FC: 96 Card: 40426, Checksum: 05, \
+             Raw: 0f55555695596a6a9999a59a",
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("paradox_id").unwrap(), "258B9F7A3");
+        assert_eq!(data.decoded.get("checksum").unwrap(), "05");
+        assert_eq!(data.decoded.get("raw").unwrap(), "0F55555695596A6A9999A59A");
+        assert_eq!(
+            build_clone_command(&CardType::Paradox, &data.uid, &data.decoded).unwrap(),
+            "lf paradox clone --fc 96 --cn 40426"
+        );
+
+        assert_eq!(
+            build_clone_command_with(&CardType::Paradox, &data.uid, &data.decoded, true).unwrap(),
+            "lf paradox clone --raw 0F55555695596A6A9999A59A"
+        );
+
+        // A short raw can't reproduce the card, so FC/CN still wins
+        let output = pm3_lf_search_output("[+] Paradox - FC: 96  Card: 40426  Raw: 0F0A00009E3A");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(
+            build_clone_command_with(&CardType::Paradox, &data.uid, &data.decoded, true).unwrap(),
+            "lf paradox clone --fc 96 --cn 40426"
+        );
+    }
+
+    #[test]
+    fn paradox_extras_only_from_paradox_block() {
+        let output = format!(
+            "{}\n[=] Chipset detection: T55xx\n[=] Format: 26  Checksum: AB  Mfr: 12\n",
+            pm3_lf_search_output(
+                "[+] Paradox - FC: 96  Card: 40426  Raw: 0F0A00009E3A\n\
+                 [+]   Fmt: 3  Manufacturer code: 07"
+            )
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("format").unwrap(), "3");
+        assert_eq!(data.decoded.get("manufacturer").unwrap(), "07");
+        assert!(!data.decoded.contains_key("checksum"));
+
+        let output = format!(
+            "{}\n[=] Format: 26  Checksum: AB\n",
+            pm3_lf_search_output("[+] Paradox - FC: 96  Card: 40426  Raw: 0F0A00009E3A")
+        );
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert!(!data.decoded.contains_key("format"));
+        assert!(!data.decoded.contains_key("checksum"));
+    }
+
     // =======================================================================
     // 8. Presco
     // =======================================================================
@@ -2557,6 +3798,42 @@ mod tests {
         assert!(cmd.is_none(), "GProxII without fc+card_number should return None");
     }
 
+    #[test]
+    fn clone_gproxii_raw_fallback() {
+        // FC/Card line missing — only the raw frame came through
+        let output = pm3_lf_search_output(
+            "[+] Guardall G-Prox II found\n\
+             [+] Raw: fac2a38c2b081af008eb0ac2"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse raw-only GProxII");
+        assert_eq!(card_type, CardType::GProxII);
+        assert_eq!(data.uid, "FAC2A38C2B081AF008EB0AC2");
+        assert_eq!(data.decoded.get("raw_fallback").unwrap(), "true");
+        let cmd = build_clone_command(&CardType::GProxII, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf gproxii clone --raw FAC2A38C2B081AF008EB0AC2");
+        assert!(crate::pm3::command_builder::validate_clone_fields(
+            &CardType::GProxII,
+            &data.uid,
+            &data.decoded
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn clone_gproxii_raw_fallback_rejects_wrong_length() {
+        let mut decoded = HashMap::new();
+        decoded.insert("raw_fallback".to_string(), "true".to_string());
+        let cmd = build_clone_command(&CardType::GProxII, "fac2a38c2b081af008eb", &decoded);
+        assert!(cmd.is_none(), "80-bit raw must not be cloned as G-Prox II");
+        let err = crate::pm3::command_builder::validate_clone_fields(
+            &CardType::GProxII,
+            "fac2a38c2b081af008eb",
+            &decoded,
+        )
+        .unwrap_err();
+        assert!(err.contains("expected 96 bits"), "{}", err);
+    }
+
     // =======================================================================
     // 13. Keri
     // =======================================================================
@@ -2617,6 +3894,45 @@ mod tests {
         assert_eq!(cmd.unwrap(), "lf keri clone -t i --cn 12345");
     }
 
+    #[test]
+    fn parse_keri_keeps_ms_descramble_alongside_internal() {
+        let output = pm3_lf_search_output(LF_KERI);
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.decoded.get("raw").unwrap(), "E000000080003039");
+        assert_eq!(data.decoded.get("ms_facility_code").unwrap(), "1");
+        assert_eq!(data.decoded.get("ms_card_number").unwrap(), "12544");
+        // MS fields must not leak into the structured clone
+        assert!(!data.decoded.contains_key("facility_code"));
+    }
+
+    #[test]
+    fn keri_raw_only_decodes_internal_id() {
+        let output = pm3_lf_search_output("[+] Keri - Raw: E000000080003039");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        assert_eq!(data.uid, "12345");
+        assert_eq!(data.decoded.get("keri_type").unwrap(), "i");
+        let cmd = build_clone_command(&CardType::Keri, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf keri clone -t i --cn 12345");
+
+        // Not an internal-format frame: no ID invented
+        assert_eq!(decode_keri_internal_raw("E000000000003039"), None);
+        assert_eq!(decode_keri_internal_raw("0000000012345"), None);
+    }
+
+    #[test]
+    fn keri_hex_card_number_rejected() {
+        let output = pm3_lf_search_output("[+] Keri - MS Raw: ABCDEF1234567");
+        let (_, data) = parse_lf_search(&output).unwrap();
+        let err = crate::pm3::command_builder::validate_clone_fields(
+            &CardType::Keri,
+            &data.uid,
+            &data.decoded,
+        )
+        .unwrap_err();
+        assert!(err.contains("Keri"), "{}", err);
+        assert!(build_clone_command(&CardType::Keri, &data.uid, &data.decoded).is_none());
+    }
+
     // =======================================================================
     // 14. Gallagher
     // =======================================================================
@@ -2665,6 +3981,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_gallagher_crc_ok() {
+        let (card_type, data) = parse_lf_search(&pm3_lf_search_output(LF_GALLAGHER))
+            .expect("should parse Gallagher");
+        assert_eq!(card_type, CardType::Gallagher);
+        assert_eq!(data.decoded.get("crc_ok").unwrap(), "true");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "3333");
+    }
+
+    #[test]
+    fn parse_gallagher_crc_fail_still_cloneable() {
+        let output = pm3_lf_search_output(
+            "[+] GALLAGHER - Region: 1 Facility: 22 Card No.: 3333 Issue Level: 1\n\
+             [+]    Raw: 7FEAA35854B86B0D1A8CB121\n\
+             [+]    CRC: 20 - 21 (fail)\n\
+             [+] Valid GALLAGHER ID found!"
+        );
+        let (_, data) = parse_lf_search(&output).expect("should parse Gallagher");
+        assert_eq!(data.decoded.get("crc_ok").unwrap(), "false");
+        // Still offered for cloning; the scan result carries the warning
+        let cmd = build_clone_command(&CardType::Gallagher, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf gallagher clone --rc 1 --fc 22 --cn 3333 --il 1");
+
+        // Firmware without the (ok)/(fail) suffix: compare the two values
+        let output = pm3_lf_search_output(
+            "[+] Cardax Region: 1 Facility: 22 Card No.: 3 Issue Level: 1 CRC: 3A - 3B"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse Cardax");
+        assert_eq!(card_type, CardType::Gallagher);
+        assert_eq!(data.decoded.get("crc_ok").unwrap(), "false");
+    }
+
     #[test]
     fn clone_gallagher_fails_without_fields() {
         let decoded = HashMap::new();
@@ -2984,6 +4332,36 @@ mod tests {
         assert_eq!(data.decoded.get("raw").unwrap(), "0000000100000000");
     }
 
+    #[test]
+    fn parse_motorola_fc_cn() {
+        let output = pm3_lf_search_output(
+            "[+] Motorola - fmt: 26 FC: 258 Card: 2, Raw: A0000000A0002021"
+        );
+        let (card_type, data) = parse_lf_search(&output).expect("should parse Motorola");
+        assert_eq!(card_type, CardType::Motorola);
+        assert_eq!(data.decoded.get("format").unwrap(), "26");
+        assert_eq!(data.decoded.get("facility_code").unwrap(), "258");
+        assert_eq!(data.decoded.get("card_number").unwrap(), "2");
+        assert_eq!(data.decoded.get("raw").unwrap(), "A0000000A0002021");
+        // Clone stays raw — PM3 has no structured Motorola clone
+        let cmd = build_clone_command(&CardType::Motorola, &data.uid, &data.decoded);
+        assert_eq!(cmd.unwrap(), "lf motorola clone --raw A0000000A0002021");
+    }
+
+    #[test]
+    fn motorola_truncated_raw_rejected() {
+        let mut decoded = HashMap::new();
+        decoded.insert("raw".to_string(), "A0000000A000".to_string());
+        let err = crate::pm3::command_builder::validate_clone_fields(
+            &CardType::Motorola,
+            "A0000000A000",
+            &decoded,
+        )
+        .unwrap_err();
+        assert!(err.contains("64 bits"), "{}", err);
+        assert!(build_clone_command(&CardType::Motorola, "A0000000A000", &decoded).is_none());
+    }
+
     #[test]
     fn clone_motorola() {
         let mut decoded = HashMap::new();
@@ -3191,6 +4569,17 @@ mod tests {
     // T5577 detection
     // =======================================================================
 
+    #[test]
+    fn lf_search_chipset_gates_t55xx_detect() {
+        let body = "[+] EM 410x ID 0F0368568B";
+        let search = pm3_lf_search_output(body);
+        let t55xx = format!("{}\n[+] Chipset detection: \x1b[32mT55xx\x1b[0m\n", search);
+        assert!(lf_search_found_t55xx(&t55xx));
+        let em4x05 = format!("{}\n[+] Chipset detection: EM4x05 / EM4x69\n", search);
+        assert!(!lf_search_found_t55xx(&em4x05));
+        assert!(!lf_search_found_t55xx(&pm3_lf_search_output(body)));
+    }
+
     #[test]
     fn parse_t5577_detect_basic() {
         let output = "\
@@ -3205,6 +4594,35 @@ mod tests {
         assert!(!status.password_set);
         assert_eq!(status.block0.unwrap(), "00148040");
         assert_eq!(status.modulation.unwrap(), "ASK/Manchester");
+        assert!(!status.locked);
+    }
+
+    #[test]
+    fn parse_t5577_detect_otp_lock() {
+        let detect = |block0: &str| {
+            format!(
+                "[=]      Chip type......... T55x7\n\
+                 [=]      Modulation........ ASK\n\
+                 [=]      Bit rate.......... 5 - RF/64\n\
+                 [=]      Inverted.......... No\n\
+                 [=]      Offset............ 33\n\
+                 [=]      Seq. terminator... Yes\n\
+                 [=]      Block0............ {} (auto detect)\n\
+                 [=]      Downlink mode..... default/fixed bit length\n\
+                 [=]      Password set...... No",
+                block0
+            )
+        };
+        // EM4100 config in extended mode (master key 6, X-mode) with OTP set
+        assert!(parse_t5577_detect(&detect("60028140")).locked);
+        // Same without OTP, OTP without X-mode, and the factory default
+        assert!(!parse_t5577_detect(&detect("60028040")).locked);
+        assert!(!parse_t5577_detect(&detect("60008140")).locked);
+        assert!(!parse_t5577_detect(&detect("000880E8")).locked);
+        // The flag is ignored outside extended mode
+        assert!(!t5577_config_otp("00028140"));
+        assert!(t5577_config_otp("90028140"));
+        assert!(!t5577_config_otp("not hex"));
     }
 
     #[test]
@@ -3229,6 +4647,91 @@ mod tests {
         assert!(parse_t5577_chk(output).is_none());
     }
 
+    #[test]
+    fn t5577_unlock_branches_on_chk_result() {
+        assert_eq!(
+            resolve_t5577_unlock("[+] Found valid password: 51243648"),
+            T5577Unlock::Password("51243648".to_string())
+        );
+        // chk ran to completion without a hit -> ask the user instead of failing
+        assert_eq!(
+            resolve_t5577_unlock("[=] Checking passwords...\n[-] No valid password found."),
+            T5577Unlock::PasswordUnknown
+        );
+    }
+
+    #[test]
+    fn parse_em4x50_dump_words() {
+        let mut output = String::from(
+            "[=]  # | word (msb)  | word (lsb)  | desc\n\
+             [=] ---+-------------+-------------+--------------------\n",
+        );
+        for w in 0..34 {
+            let desc = match w {
+                0 => "password, write only",
+                1 => "protection word, write inhibited",
+                32 => "device serial number, read only",
+                _ => "",
+            };
+            output.push_str(&format!(
+                "[=] {:2} | 00 00 00 {:02x} | {:02x} 00 00 00 | {}\n",
+                w, w, w, desc
+            ));
+        }
+        let words = parse_em4x50_dump(&output).expect("34 words");
+        assert_eq!(words[0], "00000000");
+        assert_eq!(words[31], "0000001F");
+        assert_eq!(words.len(), 34);
+
+        // Missing serial/ID rows: the read stopped early
+        let truncated: String = output.lines().take(34).map(|l| format!("{}\n", l)).collect();
+        assert!(parse_em4x50_dump(&truncated).is_none());
+        assert!(parse_em4x50_dump("[!] no EM4x50 tag found").is_none());
+    }
+
+    #[test]
+    fn em4x50_write_result() {
+        assert!(em4x50_write_ok("[+] Successfully wrote to word \x1b[32m3\x1b[0m"));
+        assert!(em4x50_write_ok("[+] Wiping data \x1b[32mok\x1b[0m"));
+        assert!(!em4x50_write_ok("[-] Writing \x1b[31mfailed\x1b[0m"));
+        assert!(!em4x50_write_ok("[-] Login failed"));
+        assert!(!em4x50_write_ok(""));
+    }
+
+    #[test]
+    fn parse_t55xx_dump_page0() {
+        let output = "\
+[+] Reading Page 0:
+[+] blk | hex data | binary                           | ascii
+[+] ----+----------+----------------------------------+-------
+[+]  00 | 00148040 | 00000000000101001000000001000000 | ..@
+[+]  01 | ff83c030 | 11111111100000111100000000110000 | ...0
+[+]  02 | 0C6B1D8E | 00001100011010110001110110001110 | .k..
+[+]  03 | 00000000 | 00000000000000000000000000000000 | ....
+[+]  04 | 00000000 | 00000000000000000000000000000000 | ....
+[+]  05 | 00000000 | 00000000000000000000000000000000 | ....
+[+]  06 | 00000000 | 00000000000000000000000000000000 | ....
+[+]  07 | 00000000 | 00000000000000000000000000000000 | ....
+[+] Reading Page 1:
+[+] blk | hex data | binary                           | ascii
+[+] ----+----------+----------------------------------+-------
+[+]  00 | 00148040 | 00000000000101001000000001000000 | ..@
+[+]  01 | E0150A48 | 11100000000101010000101001001000 | ...H";
+        let blocks = parse_t55xx_dump(output).expect("page 0 blocks");
+        assert_eq!(blocks.len(), 8);
+        assert_eq!(blocks[0], "00148040");
+        assert_eq!(blocks[1], "FF83C030");
+
+        // Round trip: the dump feeds the block writes for the target
+        let cmds = crate::pm3::command_builder::build_t55xx_clone_raw(&blocks).unwrap();
+        assert_eq!(cmds.last().unwrap(), "lf t55xx write -b 0 -d 00148040");
+        assert_eq!(cmds.len(), 3);
+
+        assert!(parse_t55xx_dump("[!] Could not detect modulation automatically").is_none());
+        // A skipped block means the read was incomplete
+        assert!(parse_t55xx_dump("[+]  00 | 00148040 |\n[+]  02 | 0C6B1D8E |").is_none());
+    }
+
     // =======================================================================
     // EM4305 detection
     // =======================================================================
@@ -3250,6 +4753,25 @@ mod tests {
         assert_eq!(parse_em4305_word0(output).unwrap(), "00000000");
     }
 
+    #[test]
+    fn parse_em4305_word_any_address() {
+        let output = "\
+[+] Address 00 | 0000A5A5 - ok
+[+] Address 01 | 600D1D5C - ok
+[!] Address 02 | failed reading
+[+] Address 04 | 0002008f - ok
+[+] Address 14 | 00000000 - ok
+[+] Word 15: DEADbeef";
+        assert_eq!(parse_em4305_word(output, 0).as_deref(), Some("0000A5A5"));
+        assert_eq!(parse_em4305_word(output, 1).as_deref(), Some("600D1D5C"));
+        assert_eq!(parse_em4305_word(output, 2), None);
+        assert_eq!(parse_em4305_word(output, 4).as_deref(), Some("0002008F"));
+        assert_eq!(parse_em4305_word(output, 14).as_deref(), Some("00000000"));
+        assert_eq!(parse_em4305_word(output, 15).as_deref(), Some("DEADBEEF"));
+        assert_eq!(parse_em4305_word(output, 5), None);
+        assert_eq!(parse_em4305_word0(output).as_deref(), Some("0000A5A5"));
+    }
+
     // =======================================================================
     // Verification
     // =======================================================================
@@ -3612,6 +5134,153 @@ mod tests {
         );
     }
 
+    #[test]
+    fn iso14a_identity_from_mf_info() {
+        let output = "\
+[=] --- ISO14443-a Information ---------------------
+[+]  UID: 04 11 22 33 44 55 66
+[+] ATQA: 00 44
+[+]  SAK: 08 [2]";
+        assert_eq!(
+            parse_14a_identity(output),
+            Some(Iso14aIdentity { uid_len: 7, atqa: Some(0x0044), sak: Some(0x08) })
+        );
+        assert_eq!(parse_14a_identity("[-] No card found"), None);
+    }
+
+    #[test]
+    fn dual_tech_card_reports_both_frequencies() {
+        let lf = "[+] EM 410x ID 0F0368568B\n[+] Valid EM410x ID found!";
+        let hf = crate::pm3::parser_corpus::HF_CLASSIC_1K_MAGIC;
+        let tags = parse_all_frequencies(Some(lf), Some(hf));
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].0, CardType::EM4100);
+        assert_eq!(tags[0].1.uid, "0F0368568B");
+        assert_eq!(tags[1].0, CardType::MifareClassic1K);
+        assert_eq!(tags[1].1.uid, "7DE9254E");
+
+        // Single-technology cards and failed searches
+        let nothing = "[-] No known 125/134 kHz tags found!";
+        let lf_only = parse_all_frequencies(Some(lf), None);
+        assert_eq!(lf_only.len(), 1);
+        let hf_only = parse_all_frequencies(Some(nothing), Some(hf));
+        assert_eq!(hf_only.len(), 1);
+        assert_eq!(hf_only[0].0, CardType::MifareClassic1K);
+        assert!(parse_all_frequencies(Some(nothing), None).is_empty());
+    }
+
+    #[test]
+    fn lf_watch_stream_reports_each_badge() {
+        // `lf hid watch`: lines with the seconds they arrived at
+        let stream = [
+            (0, "[=] Watching for HID Prox cards - place tag on antenna"),
+            (3, "[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334"),
+            (3, "[+] raw: 200078BE5E1E"),
+            (4, "[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334"),
+            (4, "[+] raw: 200078BE5E1E"),
+            (5, "[+] [H10301] HID Prox H10301 26-bit;  FC: 118  CN: 1603"),
+            (5, "[+] raw: 2006EC0C86"),
+            (6, "[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334"),
+            (6, "[+] raw: 200078BE5E1E"),
+            // Antenna quiet for 4s, then the same badge again
+            (10, "[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334"),
+            (10, "[+] raw: 200078BE5E1E"),
+        ];
+        let start = Instant::now();
+        let mut parser = LfWatchParser::new();
+        let reads: Vec<(CardType, CardData)> = stream
+            .iter()
+            .filter_map(|(secs, line)| parser.feed(line, start + Duration::from_secs(*secs)))
+            .collect();
+        // The badge held over two reads counts once; re-presenting it after
+        // the antenna went quiet counts again
+        let uids: Vec<&str> = reads.iter().map(|(_, d)| d.uid.as_str()).collect();
+        assert_eq!(uids, vec!["FC65:CN29334", "FC118:CN1603", "FC65:CN29334", "FC65:CN29334"]);
+        assert!(reads.iter().all(|(t, _)| *t == CardType::HIDProx));
+    }
+
+    #[test]
+    fn mf_rdbl_block_bytes() {
+        let output = "\
+[=]   # | sector 00 / 0x00                                | ascii
+[=] ----+-------------------------------------------------+-----------------
+[=]   0 | 01 02 03 04 04 08 04 00 00 00 00 00 00 00 BE EF | ................";
+        assert_eq!(
+            parse_mf_rdbl(output, 0),
+            Some([1, 2, 3, 4, 4, 8, 4, 0, 0, 0, 0, 0, 0, 0, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_mf_rdbl(output, 4), None);
+        assert_eq!(parse_mf_rdbl("[-] Read block 0 ( fail )", 0), None);
+    }
+
+    #[test]
+    fn mfu_rdbl_page_bytes() {
+        let output = "\
+[=] Block#  | Data        | Ascii
+[=] -----------------------------
+[=]   2/0x02 | 8B 48 38 80 | .H8.";
+        assert_eq!(parse_mfu_rdbl(output, 2), Some([0x8B, 0x48, 0x38, 0x80]));
+        assert_eq!(parse_mfu_rdbl(output, 3), None);
+        assert_eq!(parse_mfu_rdbl("[!] Cmd Error: 00", 2), None);
+    }
+
+    #[test]
+    fn mfu_get_version_decoded() {
+        let cases = [
+            ("00 04 03 01 01 00 0B 03", MfuVariant::UltralightEv1_48, 20),
+            ("00 04 03 02 01 00 0E 03", MfuVariant::UltralightEv1_128, 41),
+            ("00 04 03 01 04 00 0F 03", MfuVariant::UltralightAes, 60),
+            ("00 04 04 02 01 00 0F 03", MfuVariant::Ntag213, 45),
+            ("00 04 04 02 01 00 11 03", MfuVariant::Ntag215, 135),
+            ("00 04 04 02 01 00 13 03", MfuVariant::Ntag216, 231),
+        ];
+        for (raw, variant, pages) in cases {
+            let output = format!(
+                "[=] --- Tag Version\n\
+                 [=]        Raw bytes: {}\n\
+                 [=]        Vendor ID: 04, NXP Semiconductors",
+                raw
+            );
+            assert_eq!(parse_mfu_version(&output), Some(variant), "{}", raw);
+            assert_eq!(variant.page_count(), pages);
+        }
+
+        // No GET_VERSION on Ultralight C: the TYPE line decides
+        let ulc = "[=] --- Tag Information\n[+]       TYPE: MIFARE Ultralight C (MF0ULC)";
+        assert_eq!(parse_mfu_version(ulc), Some(MfuVariant::UltralightC));
+        // Non-NXP version bytes aren't guessed at
+        let clone = "[=] --- Tag Version\n[=]        Raw bytes: 00 53 04 02 01 00 0F 03";
+        assert_eq!(parse_mfu_version(clone), None);
+    }
+
+    #[test]
+    fn mfu_dump_page_count() {
+        let output = "\
+[=]  block#   | data        |lck| ascii
+[=] ---------+-------------+---+------
+[=]   0/0x00 | 04 A3 B1 8A | 0 | ....
+[=]   1/0x01 | 12 34 56 78 | 0 | .4Vx
+[=]  19/0x13 | 00 00 00 00 | 0 | ....";
+        assert_eq!(parse_mfu_dump_pages(output), 20);
+        assert_eq!(parse_mfu_dump_pages("[!] Authentication failed"), 0);
+    }
+
+    #[test]
+    fn restore_block_lines() {
+        assert_eq!(parse_restore_line("[+] Writing block 12"), Some(12));
+        assert_eq!(parse_restore_line("[=] Writing to block  63: FF FF FF FF FF FF FF 07 80 69 FF FF FF FF FF FF"), Some(63));
+        assert_eq!(parse_restore_line("[=] block   0: 01 02 03 04 04 08 04 00 00 00 00 00 00 00 00 00"), Some(0));
+        assert_eq!(parse_restore_line("\x1b[32m[+]\x1b[0m Writing block 5"), Some(5));
+    }
+
+    #[test]
+    fn restore_summary_lines_ignored() {
+        assert_eq!(parse_restore_line("[=] Restoring hf-mf-01020304-dump.bin to card"), None);
+        assert_eq!(parse_restore_line("[+] Done!"), None);
+        assert_eq!(parse_restore_line("[!!] Write block 12 failed"), None);
+        assert_eq!(parse_restore_line(""), None);
+    }
+
     #[test]
     fn autopwn_dump_partial() {
         let line = "[!] Dump file is PARTIAL complete";
@@ -3747,6 +5416,31 @@ mod tests {
         assert_eq!(parse_magic_detection(""), None);
     }
 
+    #[test]
+    fn magic_detect_verbose_mf_info() {
+        // `hf mf info -v` with client debug output (`-d 1`) interleaved
+        let output = "\
+[=] --- ISO14443-a Information ---------------------\n\
+[+]  UID: \x1b[32m01 02 03 04\x1b[0m\n\
+[+] ATQA: 00 04\n\
+[+]  SAK: 08 [2]\n\
+[#] DBG: 14a select ok, cascade 1\n\
+[=] --- Keys Information\n\
+[+] loaded 2 user keys\n\
+[+] loaded 61 hardcoded keys\n\
+[+] Backdoor key..... A396EFA4E24F\n\
+[=] --- Magic Tag Information\n\
+[+] Magic capabilities... \x1b[32mGen 1a\x1b[0m\n\
+[+] Magic capabilities... Gen 2 / CUID\n\
+[#] DBG: wupC1 answered 0A\n\
+[=] --- PRNG Information\n\
+[+] Prng....... weak";
+        assert_eq!(parse_magic_detection(output), Some(MagicGeneration::Gen1a));
+        let identity = parse_14a_identity(output).unwrap();
+        assert_eq!(identity.uid_len, 4);
+        assert_eq!(identity.sak, Some(0x08));
+    }
+
     #[test]
     fn magic_detect_ufuid() {
         let output = "[+] Magic capabilities : UFUID";
@@ -3781,6 +5475,24 @@ mod tests {
         assert!(!is_iclass_present(output));
     }
 
+    #[test]
+    fn lf_scan_empty_but_hf_classic_redirects() {
+        let lf_output = "[=] No known 125/134 kHz tags found!";
+        let hf_output = crate::pm3::parser_corpus::HF_CLASSIC_1K_MAGIC;
+        assert!(!card_on_frequency(&Frequency::LF, lf_output));
+        assert!(card_on_frequency(&Frequency::HF, hf_output));
+        let hint = wrong_frequency_hint(&Frequency::HF);
+        assert!(hint.contains("13.56 MHz") && hint.contains("HF mode"), "{}", hint);
+    }
+
+    #[test]
+    fn hf_scan_empty_but_lf_tag_redirects() {
+        let hf_output = "[!] No known/supported 13.56 MHz tags found";
+        assert!(!card_on_frequency(&Frequency::HF, hf_output));
+        assert!(card_on_frequency(&Frequency::LF, crate::pm3::parser_corpus::LF_EM4100));
+        assert!(wrong_frequency_hint(&Frequency::LF).contains("LF mode"));
+    }
+
     // -----------------------------------------------------------------------
     // extract_dump_file_path tests
     // -----------------------------------------------------------------------
@@ -3823,6 +5535,60 @@ mod tests {
     // parse_hf_search edge case tests
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn parse_partial_autopwn_key_table() {
+        let output = "\
+[+] -----+-----+--------------+---+--------------+----
+[+]  Sec | Blk | key A        |res| key B        |res
+[+] -----+-----+--------------+---+--------------+----
+[+]  000 | 003 | FFFFFFFFFFFF | D | FFFFFFFFFFFF | D
+[+]  001 | 007 | a0a1a2a3a4a5 | N | ------------ | 0
+[+]  002 | 011 | ------------ | 0 | ------------ | 0
+[+]  003 | 015 | FFFFFFFFFFFF | 0 | B0B1B2B3B4B5 | H
+[+] -----+-----+--------------+---+--------------+----
+[=] ( D:Dictionary / S:Darkside / U:User / R:Reused / N:Nested / H:Hardnested / C:statiCnested / A:keyA  )";
+        let sectors = parse_mf_key_table(output);
+        assert_eq!(sectors.len(), 4);
+        assert_eq!(sectors[0].key_a.as_deref(), Some("FFFFFFFFFFFF"));
+        assert_eq!(sectors[1].key_a.as_deref(), Some("A0A1A2A3A4A5"));
+        assert_eq!(sectors[1].key_b, None);
+        assert_eq!(sectors[2], SectorKeys::default());
+        // Result 0 = not found, even if a key is shown
+        assert_eq!(sectors[3].key_a, None);
+        assert_eq!(sectors[3].key_b.as_deref(), Some("B0B1B2B3B4B5"));
+        assert!(parse_mf_key_table("[+] autopwn finished").is_empty());
+
+        assert_eq!(
+            parse_found_key("[+] Target block   23 key type B -- found valid key [ b0b1b2b3b4b5 ]"),
+            Some("B0B1B2B3B4B5".to_string())
+        );
+        assert_eq!(parse_found_key("[-] No valid key found"), None);
+    }
+
+    #[test]
+    fn hf_parse_mifare_mini_vs_1k() {
+        let mini = "\
+[+]  UID: 4A 1B 2C 3D
+[+] ATQA: 00 04
+[+]  SAK: 09 [2]
+[+] Possible types:
+[+]    MIFARE Mini 0.3K";
+        let (ct, cd) = parse_hf_search(mini).expect("should parse Mini");
+        assert_eq!(ct, CardType::MifareMini);
+        assert_eq!(cd.decoded.get("type").unwrap(), "MifareMini");
+        assert_eq!(ct.mifare_sectors(), Some(5));
+        assert_eq!(ct.mifare_key_total(), Some(10));
+
+        // Same card with SAK 08 is a 1K (16 sectors, 32 keys)
+        let (ct, _) = parse_hf_search(&mini.replace("SAK: 09", "SAK: 08").replace("MIFARE Mini 0.3K", "MIFARE Classic 1K")).unwrap();
+        assert_eq!(ct, CardType::MifareClassic1K);
+        assert_eq!(ct.mifare_key_total(), Some(32));
+
+        // No SAK line: the "Mini" text decides
+        let (ct, _) = parse_hf_search("[+]  UID: 4A 1B 2C 3D\n[+]    MIFARE Mini 0.3K").unwrap();
+        assert_eq!(ct, CardType::MifareMini);
+    }
+
     #[test]
     fn hf_parse_classic_1k_sak98() {
         // SAK 0x98 = Classic 4K with cascaded UID (7-byte)
@@ -3991,6 +5757,22 @@ mod tests {
         assert_eq!(gen, MagicGeneration::Gen4GDM);
     }
 
+    #[test]
+    fn gen1_wakeup_markers() {
+        assert!(parse_gen1_wakeup(crate::pm3::parser_corpus::HF_CLASSIC_1K_MAGIC));
+        assert!(parse_gen1_wakeup(
+            "[+] Magic capabilities... Gen 4 GDM / USCUID ( ZUID Gen1 Magic Wakeup )"
+        ));
+        assert!(parse_gen1_wakeup(
+            "[+]  UID: 7D E9 25 4E   ( ONUID, re-used )\n[+] Magic capabilities... Gen 1a"
+        ));
+        // ONUID alone is just a re-used UID
+        assert!(!parse_gen1_wakeup(
+            "[+]  UID: 7D E9 25 4E   ( ONUID, re-used )\n[+] Magic capabilities... Gen 2 / CUID"
+        ));
+        assert!(!parse_gen1_wakeup("[+] Magic capabilities... Gen 2 / CUID"));
+    }
+
     #[test]
     fn magic_detect_uscuid_standalone() {
         // USCUID without GDM prefix
@@ -4079,4 +5861,79 @@ mod tests {
         let (_, data) = parse_hf_search(output).expect("should parse PRNG HARD with dots");
         assert_eq!(data.decoded.get("prng").unwrap(), "HARD");
     }
+
+    // -----------------------------------------------------------------------
+    // parse_iclass_pacs() tests
+    // -----------------------------------------------------------------------
+
+    fn iclass_dump_with_block7(block7: [u8; 8]) -> Vec<u8> {
+        let mut dump = vec![0u8; 19 * 8];
+        dump[56..64].copy_from_slice(&block7);
+        dump
+    }
+
+    #[test]
+    fn iclass_pacs_h10301() {
+        let dump = iclass_dump_with_block7([0x00, 0x00, 0x00, 0x00, 0x06, 0xF6, 0x23, 0xAE]);
+        assert_eq!(parse_iclass_pacs(&dump), Some((123, 4567)));
+    }
+
+    #[test]
+    fn iclass_pacs_bad_parity() {
+        // Same frame with the trailing odd-parity bit flipped
+        let dump = iclass_dump_with_block7([0x00, 0x00, 0x00, 0x00, 0x06, 0xF6, 0x23, 0xAF]);
+        assert_eq!(parse_iclass_pacs(&dump), None);
+    }
+
+    #[test]
+    fn iclass_pacs_encrypted_block() {
+        // Still-encrypted block 7 looks like random data with no 26-bit sentinel
+        let dump = iclass_dump_with_block7([0xA3, 0x1F, 0x44, 0x9C, 0x02, 0x7E, 0xD1, 0x58]);
+        assert_eq!(parse_iclass_pacs(&dump), None);
+    }
+
+    #[test]
+    fn iclass_pacs_empty_block() {
+        let dump = iclass_dump_with_block7([0u8; 8]);
+        assert_eq!(parse_iclass_pacs(&dump), None);
+    }
+
+    #[test]
+    fn iclass_config_decodes_fuses() {
+        // Typical HID iCLASS 2k config: 12 FF FF FF 7F 1F FF 3C
+        let mut dump = [0u8; 64];
+        dump[8..16].copy_from_slice(&[0x12, 0xFF, 0xFF, 0xFF, 0x7F, 0x1F, 0xFF, 0x3C]);
+        let cfg = decode_iclass_config(&dump).unwrap();
+        assert_eq!(cfg.app_limit, 0x12);
+        assert_eq!(cfg.otp, 0xFFFF);
+        assert_eq!(cfg.block_write_lock, 0xFF);
+        assert_eq!(cfg.mem_config, 0x1F);
+        assert!(!cfg.personalization_mode);
+        assert_eq!(cfg.coding, "ISO 14443-2 B / ISO 15693");
+        assert_eq!(cfg.crypt, "secured page, keys not locked");
+        assert!(!cfg.read_access);
+        assert_eq!(
+            cfg.summary(),
+            "Application mode (locked), secured page, keys not locked, app limit 0x12, OTP FFFF"
+        );
+
+        // Card still in personalization, non-secured page with read access
+        dump[8..16].copy_from_slice(&[0x1F, 0x03, 0x00, 0xFF, 0x7F, 0x1F, 0xFF, 0x89]);
+        let cfg = decode_iclass_config(&dump).unwrap();
+        assert!(cfg.personalization_mode);
+        assert_eq!(cfg.otp, 0x0003);
+        assert_eq!(cfg.coding, "ISO 14443-B only");
+        assert_eq!(cfg.crypt, "non-secured page");
+        assert!(cfg.read_access);
+
+        // Keys locked
+        dump[15] = 0x30;
+        assert_eq!(decode_iclass_config(&dump).unwrap().crypt, "secured page, keys locked");
+        assert_eq!(decode_iclass_config(&dump[..12]), None);
+    }
+
+    #[test]
+    fn iclass_pacs_dump_too_short() {
+        assert_eq!(parse_iclass_pacs(&[0u8; 60]), None);
+    }
 }
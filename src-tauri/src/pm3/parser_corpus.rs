@@ -0,0 +1,261 @@
+use serde::Serialize;
+
+use crate::cards::types::AutopwnEvent;
+use crate::pm3::output_parser::{parse_autopwn_line, parse_hf_search, parse_lf_search};
+
+// Real PM3 output samples shared by the parser unit tests and the runtime
+// `run_parser_selfcheck` command. When the Iceman client changes its wording,
+// a failing sample here points at the parser rather than the hardware.
+
+/// Which parser a corpus sample is fed through.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ParserKind {
+    LfSearch,
+    HfSearch,
+    AutopwnLine,
+}
+
+/// One captured PM3 output with the result the parser is expected to produce.
+/// `expected` is a `CardType` variant name for LF/HF samples and an
+/// `AutopwnEvent` variant name for autopwn lines.
+#[derive(Debug, Clone, Copy)]
+pub struct ParserSample {
+    pub name: &'static str,
+    pub parser: ParserKind,
+    pub output: &'static str,
+    pub expected: &'static str,
+}
+
+/// Outcome of running a single corpus sample.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckResult {
+    pub name: &'static str,
+    pub parser: ParserKind,
+    pub expected: &'static str,
+    pub actual: Option<String>,
+    pub passed: bool,
+}
+
+// ---------------------------------------------------------------------------
+// LF samples (`lf search` bodies)
+// ---------------------------------------------------------------------------
+
+pub const LF_EM4100: &str = "\
+[+] EM 410x ID 0F00112233\n\
+[+] EM410x ( RF/64 )\n\
+[=] EM 410x ID 0F00112233 (Full)\n\
+[=]     Possible de:tag ID: 4276803383";
+
+pub const LF_HID_H10301: &str = "\
+[+] [H10301] HID Prox H10301 26-bit;  FC: 65  CN: 29334\n\
+[+] raw: 200078BE5E1E";
+
+pub const LF_AWID: &str =
+    "[+] AWID - len: 26 FC: 50 Card: 1234 - Wiegand: 26409a4, Raw: 011db2881474411111111111";
+
+pub const LF_IOPROX: &str = "[+] IO Prox - XSF(01)65:01337, Raw: 007859603059cdaf ( ok )";
+
+pub const LF_FDXB: &str = "\
+[+] FDX-B / ISO 11784/5 Animal\n\
+[+] Animal ID......... 999-123456789012\n\
+[+] National Code..... 123456789012 ( 0x1CBE991A14 )\n\
+[+] Country Code...... 999 - Test range\n\
+[+] Raw............... 28 58 99 7D 3B 9F 00 00 C0 CC 00 00 00";
+
+pub const LF_NEDAP: &str =
+    "[+] NEDAP (64b) - ID: 12345 subtype: 1 customer code: 101 / 0x065 Raw: FF820CA58960F8F3";
+
+pub const LF_GPROXII: &str = "\
+[+] G-Prox-II - Len: 26 FC: 123 Card: 1234 xor: 141, Raw: fac2a38c2b081af008eb0ac2\n\
+\n\
+[+] Valid Guardall G-Prox II ID found!";
+
+pub const LF_KERI: &str = "\
+[+] KERI - Internal ID: 12345, Raw: E000000080003039\n\
+[+] Descrambled MS - FC: 1 Card: 12544";
+
+pub const LF_GALLAGHER: &str = "\
+[+] GALLAGHER - Region: 1 Facility: 22 Card No.: 3333 Issue Level: 1\n\
+[+]    Displayed: B22\n\
+[+]    Raw: 7FEAA35854B86B0D1A8CB120\n\
+[+]    CRC: 20 - 20 (ok)\n\
+[+] Valid GALLAGHER ID found!";
+
+pub const LF_NEXWATCH: &str = "\
+[+]  NexWatch raw id : 0x40c00080\n\
+[+]      fingerprint : Quadrakey\n\
+[+]         88bit id : 521512301 (0x1f15a56d)\n\
+[+]             mode : 1\n\
+[=]  Raw : 5600000000213C9F8F150C00";
+
+// ---------------------------------------------------------------------------
+// HF samples (`hf search` output)
+// ---------------------------------------------------------------------------
+
+pub const HF_CLASSIC_1K_MAGIC: &str = "\
+[-] Searching for ISO14443-A tag...\n\
+[=] ---------- ISO14443-A Information ----------\n\
+[+]  UID: 7D E9 25 4E   ( ONUID, re-used )\n\
+[+] ATQA: 00 04\n\
+[+]  SAK: 08 [2]\n\
+[+] Possible types:\n\
+[+]    MIFARE Classic 1K\n\
+[=] \n\
+[=] Proprietary non iso14443-4 card found\n\
+[=] RATS not supported\n\
+\n\
+[+] Magic capabilities... Gen 1a\n\
+[+] Magic capabilities... Gen 4 GDM / USCUID ( ZUID Gen1 Magic Wakeup )\n\
+[+] Prng detection..... weak\n\
+\n\
+[?] Hint: Use `hf mf c*` magic commands\n\
+[?] Hint: Use `hf mf gdm* --gen1a` magic commands\n\
+[?] Hint: Try `hf mf info`\n\
+\n\
+\n\
+[+] Valid ISO 14443-A tag found\n\
+\n\
+[-] Searching for iCLASS / PicoPass tag...\n\
+[-] Searching for FeliCa tag...";
+
+pub const HF_NTAG215: &str = "\
+[+] UID: 04 AA BB CC DD EE FF\n\
+[+] ATQA: 00 44\n\
+[+] SAK: 00 [2]\n\
+[+] NTAG 215";
+
+pub const HF_ULTRALIGHT_EV1: &str = "\
+[+] UID: 04 11 22 33 44 55 66\n\
+[+] ATQA: 00 44\n\
+[+] SAK: 00\n\
+[+] MIFARE Ultralight EV1";
+
+pub const HF_DESFIRE_EV1: &str = "\
+[+] UID: 04 AA BB CC DD EE FF\n\
+[+] ATQA: 03 44\n\
+[+] SAK: 20 [2]\n\
+[+] ATS: 06 75 77 81 02 80\n\
+[+] MIFARE DESFire EV1";
+
+pub const HF_ICLASS: &str = "\
+[+] iCLASS / Picopass card found\n\
+[+] CSN: 00 0B 0F FF F7 FF 12 E0";
+
+// ---------------------------------------------------------------------------
+// Corpus
+// ---------------------------------------------------------------------------
+
+const fn lf(name: &'static str, output: &'static str, expected: &'static str) -> ParserSample {
+    ParserSample { name, parser: ParserKind::LfSearch, output, expected }
+}
+
+const fn hf(name: &'static str, output: &'static str, expected: &'static str) -> ParserSample {
+    ParserSample { name, parser: ParserKind::HfSearch, output, expected }
+}
+
+const fn autopwn(name: &'static str, output: &'static str, expected: &'static str) -> ParserSample {
+    ParserSample { name, parser: ParserKind::AutopwnLine, output, expected }
+}
+
+pub const PARSER_CORPUS: &[ParserSample] = &[
+    lf("lf_em4100", LF_EM4100, "EM4100"),
+    lf("lf_hid_h10301", LF_HID_H10301, "HIDProx"),
+    lf("lf_awid", LF_AWID, "AWID"),
+    lf("lf_ioprox_xsf", LF_IOPROX, "IOProx"),
+    lf("lf_fdxb", LF_FDXB, "FDX_B"),
+    lf("lf_nedap", LF_NEDAP, "Nedap"),
+    lf("lf_gproxii", LF_GPROXII, "GProxII"),
+    lf("lf_keri", LF_KERI, "Keri"),
+    lf("lf_gallagher", LF_GALLAGHER, "Gallagher"),
+    lf("lf_nexwatch", LF_NEXWATCH, "NexWatch"),
+    hf("hf_classic_1k_magic", HF_CLASSIC_1K_MAGIC, "MifareClassic1K"),
+    hf("hf_ntag215", HF_NTAG215, "NTAG"),
+    hf("hf_ultralight_ev1", HF_ULTRALIGHT_EV1, "MifareUltralight"),
+    hf("hf_desfire_ev1", HF_DESFIRE_EV1, "DESFire"),
+    hf("hf_iclass", HF_ICLASS, "IClass"),
+    autopwn("autopwn_dictionary", "[=] found 12/32 keys (D)", "DictionaryProgress"),
+    autopwn("autopwn_key_found", "[+] found valid key [ FFFFFFFFFFFF ]", "KeyFound"),
+    autopwn("autopwn_darkside", "[!] Darkside attack starting...", "DarksideStarted"),
+    autopwn("autopwn_nested", "[=] Nested attack starting...", "NestedStarted"),
+    autopwn("autopwn_hardnested", "[=] Hardnested attack starting...", "HardnestedStarted"),
+    autopwn("autopwn_dump_saved", "[+] saved 64 blocks to file hf-mf-01020304-dump.bin", "DumpComplete"),
+    autopwn("autopwn_dump_partial", "[!] Dump file is PARTIAL complete", "DumpPartial"),
+    autopwn("autopwn_failed", "[!] all key recovery attempts failed", "Failed"),
+    autopwn("autopwn_finished", "[=] autopwn execution time: 45 seconds", "Finished"),
+];
+
+fn autopwn_event_name(event: &AutopwnEvent) -> &'static str {
+    match event {
+        AutopwnEvent::DictionaryProgress { .. } => "DictionaryProgress",
+        AutopwnEvent::KeyFound { .. } => "KeyFound",
+        AutopwnEvent::DarksideStarted => "DarksideStarted",
+        AutopwnEvent::NestedStarted => "NestedStarted",
+        AutopwnEvent::HardnestedStarted => "HardnestedStarted",
+        AutopwnEvent::StaticnestedStarted => "StaticnestedStarted",
+        AutopwnEvent::DumpComplete { .. } => "DumpComplete",
+        AutopwnEvent::DumpPartial { .. } => "DumpPartial",
+        AutopwnEvent::Failed { .. } => "Failed",
+        AutopwnEvent::Finished { .. } => "Finished",
+    }
+}
+
+/// Run one sample through its parser and compare against the expected result.
+pub fn check_sample(sample: &ParserSample) -> SelfCheckResult {
+    let actual = match sample.parser {
+        ParserKind::LfSearch => parse_lf_search(sample.output).map(|(ct, _)| format!("{:?}", ct)),
+        ParserKind::HfSearch => parse_hf_search(sample.output).map(|(ct, _)| format!("{:?}", ct)),
+        ParserKind::AutopwnLine => {
+            parse_autopwn_line(sample.output).map(|e| autopwn_event_name(&e).to_string())
+        }
+    };
+    let passed = actual.as_deref() == Some(sample.expected);
+
+    SelfCheckResult {
+        name: sample.name,
+        parser: sample.parser,
+        expected: sample.expected,
+        actual,
+        passed,
+    }
+}
+
+/// Run the whole corpus. Returns one result per sample, in corpus order.
+pub fn run_selfcheck() -> Vec<SelfCheckResult> {
+    PARSER_CORPUS.iter().map(check_sample).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_all_samples_pass() {
+        let failures: Vec<_> = run_selfcheck().into_iter().filter(|r| !r.passed).collect();
+        assert!(failures.is_empty(), "corpus regressions: {:?}", failures);
+    }
+
+    #[test]
+    fn corpus_names_unique() {
+        let mut names: Vec<_> = PARSER_CORPUS.iter().map(|s| s.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), PARSER_CORPUS.len());
+    }
+
+    #[test]
+    fn check_sample_reports_mismatch() {
+        let sample = lf("wrong_expectation", LF_EM4100, "HIDProx");
+        let result = check_sample(&sample);
+        assert!(!result.passed);
+        assert_eq!(result.actual.as_deref(), Some("EM4100"));
+    }
+
+    #[test]
+    fn check_sample_reports_no_parse() {
+        let sample = hf("no_card", "[-] No known/supported 13.56 MHz tags found", "NTAG");
+        let result = check_sample(&sample);
+        assert!(!result.passed);
+        assert_eq!(result.actual, None);
+    }
+}
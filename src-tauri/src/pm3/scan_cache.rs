@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::cards::types::{CardData, CardType};
+
+// In-memory LRU of recent scan results, one entry per card (type + UID).
+// Re-scanning a card already seen this session still parses the search
+// output, which is what identifies it, but reuses the earlier result and so
+// skips the enrichment reads (`lf t55xx detect`, `hf 14a info`, `hf mf info`,
+// the HF re-read). Any write may change what the card reports, so writes
+// clear the whole cache.
+
+/// Cards remembered per session; the oldest is evicted first.
+const SCAN_CACHE_CAPACITY: usize = 16;
+
+struct CacheEntry {
+    card_type: CardType,
+    card_data: CardData,
+}
+
+/// Managed via `app.manage()`.
+pub struct ScanCache {
+    entries: Mutex<VecDeque<CacheEntry>>,
+}
+
+impl ScanCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(SCAN_CACHE_CAPACITY)),
+        }
+    }
+
+    /// Cached result for the `card_type` card with `uid`. A hit becomes the
+    /// most recently used entry.
+    pub fn get(&self, card_type: &CardType, uid: &str) -> Option<CardData> {
+        let mut entries = self.entries.lock().ok()?;
+        let pos = entries
+            .iter()
+            .position(|e| e.card_type == *card_type && e.card_data.uid == uid)?;
+        let entry = entries.remove(pos)?;
+        let hit = entry.card_data.clone();
+        entries.push_back(entry);
+        Some(hit)
+    }
+
+    /// Remember the scan result for a card, replacing any earlier entry for
+    /// it (the card was re-read and may report something new).
+    pub fn insert(&self, card_type: &CardType, card_data: &CardData) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        entries.retain(|e| e.card_type != *card_type || e.card_data.uid != card_data.uid);
+        if entries.len() >= SCAN_CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(CacheEntry {
+            card_type: card_type.clone(),
+            card_data: card_data.clone(),
+        });
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Drop all cached scans. Called at the start of every write/wipe.
+pub fn invalidate(app: &AppHandle) {
+    if let Some(cache) = app.try_state::<ScanCache>() {
+        cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm3::output_parser;
+
+    fn len(cache: &ScanCache) -> usize {
+        cache.entries.lock().unwrap().len()
+    }
+
+    const EM4100_SEARCH: &str = "\
+[=] NOTE: some demods output possible binary
+[+] EM 410x ID 0F0368568B
+[+] EM410x ( RF/64 )
+[+] Valid EM410x ID found!";
+
+    /// What `scan_card` does with a search: parse, then reuse or enrich.
+    fn scan(cache: &ScanCache, output: &str) -> (CardData, bool) {
+        let (card_type, mut card_data) = output_parser::parse_lf_search(output).unwrap();
+        if let Some(hit) = cache.get(&card_type, &card_data.uid) {
+            return (hit, true);
+        }
+        card_data.decoded.insert("enriched".into(), "yes".into());
+        cache.insert(&card_type, &card_data);
+        (card_data, false)
+    }
+
+    #[test]
+    fn same_card_hits_cache() {
+        let cache = ScanCache::new();
+        let (first, hit) = scan(&cache, EM4100_SEARCH);
+        assert!(!hit);
+        let (second, hit) = scan(&cache, EM4100_SEARCH);
+        assert!(hit);
+        assert_eq!(first.uid, second.uid);
+        assert_eq!(second.decoded.get("enriched").map(String::as_str), Some("yes"));
+
+        // Output that differs but reads the same card still hits
+        let noisy = format!("[=] extra debug line\n{}\n[=] another", EM4100_SEARCH);
+        assert!(scan(&cache, &noisy).1);
+        // Another card type with the same UID does not
+        assert!(cache.get(&CardType::HIDProx, "0F0368568B").is_none());
+    }
+
+    #[test]
+    fn write_clears_cache() {
+        let cache = ScanCache::new();
+        scan(&cache, EM4100_SEARCH);
+        assert_eq!(len(&cache), 1);
+        cache.clear();
+        assert_eq!(len(&cache), 0);
+        let (_, hit) = scan(&cache, EM4100_SEARCH);
+        assert!(!hit);
+    }
+
+    #[test]
+    fn one_entry_per_card_and_lru_eviction() {
+        let cache = ScanCache::new();
+        let (card_type, card_data) = output_parser::parse_lf_search(EM4100_SEARCH).unwrap();
+        // The same card read again replaces the entry
+        cache.insert(&card_type, &card_data);
+        let mut reread = card_data.clone();
+        reread.raw = "reread".into();
+        cache.insert(&card_type, &reread);
+        assert_eq!(len(&cache), 1);
+        assert_eq!(cache.get(&card_type, &card_data.uid).unwrap().raw, "reread");
+
+        for i in 0..SCAN_CACHE_CAPACITY + 1 {
+            let mut data = card_data.clone();
+            data.uid = format!("{:010X}", i);
+            cache.insert(&card_type, &data);
+        }
+        assert_eq!(len(&cache), SCAN_CACHE_CAPACITY);
+        assert!(cache.get(&card_type, &format!("{:010X}", 0)).is_none());
+        let newest = format!("{:010X}", SCAN_CACHE_CAPACITY);
+        assert!(cache.get(&card_type, &newest).is_some());
+    }
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+
+use crate::cards::types::CardType;
+
+// Pre-write UID checks for magic cards (ISO/IEC 14443-3, 6.5.4). A UID the
+// standard reserves, or a block 0 whose BCC doesn't match, anticollides
+// wrongly: readers skip the card or see a different UID than the source.
+
+/// Cascade tag — announces that more UID bytes follow in the next cascade level.
+const CASCADE_TAG: u8 = 0x88;
+
+/// Why a UID is unsafe to write.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum UidWarning {
+    /// Not a valid length (in bytes) for the card type.
+    InvalidLength { len: usize, expected: Vec<usize> },
+    /// 4-byte UID starting `0x08`: marks a random ID, which readers expect to
+    /// change on every power-up and often refuse as an access credential.
+    RandomId,
+    /// A byte position the reader treats as the cascade tag (`0x88`).
+    CascadeTag { position: usize },
+    /// 4-byte UID prefix reserved for future use (`0xX8` with X = 1-7, or `0xF8`).
+    ReservedPrefix { prefix: u8 },
+    /// Block 0 BCC isn't the XOR of the four UID bytes.
+    BccMismatch { expected: u8, found: u8 },
+}
+
+impl std::fmt::Display for UidWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength { len, expected } => {
+                let expected = expected
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                write!(f, "UID is {} bytes; this card type needs {} bytes", len, expected)
+            }
+            Self::RandomId => write!(
+                f,
+                "UID starts with 08 (random ID) — readers treat it as non-unique and may reject it"
+            ),
+            Self::CascadeTag { position } => write!(
+                f,
+                "UID byte {} is 88 (cascade tag) — readers will misread the UID length",
+                position
+            ),
+            Self::ReservedPrefix { prefix } => write!(
+                f,
+                "UID prefix {:02X} is reserved by ISO 14443-3 — readers may reject the card",
+                prefix
+            ),
+            Self::BccMismatch { expected, found } => write!(
+                f,
+                "Block 0 BCC is {:02X} but the UID needs {:02X} — the card won't anticollide",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl UidWarning {
+    /// A UID of the wrong length can't be written at all; the other warnings
+    /// can be overridden once the user has seen them.
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, Self::InvalidLength { .. })
+    }
+}
+
+/// BCC of a 4-byte UID (or one cascade level): XOR of its bytes.
+pub fn compute_bcc(uid: &[u8]) -> u8 {
+    uid.iter().fold(0, |acc, b| acc ^ b)
+}
+
+fn uid_lengths(card_type: &CardType) -> &'static [usize] {
+    match card_type {
+        CardType::MifareUltralight | CardType::NTAG => &[7],
+        _ => &[4, 7],
+    }
+}
+
+fn parse_hex_bytes(hex: &str) -> Vec<u8> {
+    let clean: Vec<u8> = hex
+        .bytes()
+        .filter(|b| b.is_ascii_hexdigit())
+        .collect();
+    clean
+        .chunks(2)
+        .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// Check a UID before writing it to a magic card (block 0, `gen3uid`, UL
+/// `setuid`). Colons and spaces are ignored.
+pub fn validate_uid_for_write(uid: &str, card_type: &CardType) -> Result<(), UidWarning> {
+    let bytes = parse_hex_bytes(uid);
+    let expected = uid_lengths(card_type);
+    if !expected.contains(&bytes.len()) {
+        return Err(UidWarning::InvalidLength {
+            len: bytes.len(),
+            expected: expected.to_vec(),
+        });
+    }
+
+    let uid0 = bytes[0];
+    if uid0 == CASCADE_TAG {
+        return Err(UidWarning::CascadeTag { position: 0 });
+    }
+    if bytes.len() == 4 {
+        match uid0 {
+            0x08 => return Err(UidWarning::RandomId),
+            p if p & 0x0F == 0x08 && (p >> 4 <= 7 || p == 0xF8) => {
+                return Err(UidWarning::ReservedPrefix { prefix: p });
+            }
+            _ => {}
+        }
+    } else if bytes[3] == CASCADE_TAG {
+        // First byte of cascade level 2
+        return Err(UidWarning::CascadeTag { position: 3 });
+    }
+    Ok(())
+}
+
+/// Check the BCC byte of a MIFARE Classic block 0 (hex) carrying a 4-byte UID.
+/// 7-byte UIDs have no BCC in block 0.
+pub fn check_block0_bcc(block0: &str) -> Result<(), UidWarning> {
+    let bytes = parse_hex_bytes(block0);
+    if bytes.len() < 5 {
+        return Err(UidWarning::InvalidLength { len: bytes.len(), expected: vec![16] });
+    }
+    let expected = compute_bcc(&bytes[..4]);
+    if bytes[4] != expected {
+        return Err(UidWarning::BccMismatch { expected, found: bytes[4] });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn good_uids_pass() {
+        assert!(validate_uid_for_write("DEADBEEF", &CardType::MifareClassic1K).is_ok());
+        assert!(validate_uid_for_write("04:A2:3B:1C:5D:80:00", &CardType::MifareClassic1K).is_ok());
+        assert!(validate_uid_for_write("04A23B1C5D8000", &CardType::NTAG).is_ok());
+        // 0x08 is only special for single-size UIDs
+        assert!(validate_uid_for_write("08A23B1C5D8000", &CardType::MifareClassic4K).is_ok());
+    }
+
+    #[test]
+    fn reserved_prefixes_rejected() {
+        assert_eq!(
+            validate_uid_for_write("08123456", &CardType::MifareClassic1K),
+            Err(UidWarning::RandomId)
+        );
+        assert_eq!(
+            validate_uid_for_write("38123456", &CardType::MifareClassic1K),
+            Err(UidWarning::ReservedPrefix { prefix: 0x38 })
+        );
+        assert_eq!(
+            validate_uid_for_write("F8123456", &CardType::MifareClassic1K),
+            Err(UidWarning::ReservedPrefix { prefix: 0xF8 })
+        );
+        assert_eq!(
+            validate_uid_for_write("88123456", &CardType::MifareClassic1K),
+            Err(UidWarning::CascadeTag { position: 0 })
+        );
+        assert_eq!(
+            validate_uid_for_write("04112288AABBCC", &CardType::MifareClassic1K),
+            Err(UidWarning::CascadeTag { position: 3 })
+        );
+        // 0x98 / 0xA8 are ordinary fixed UIDs
+        assert!(validate_uid_for_write("98123456", &CardType::MifareClassic1K).is_ok());
+    }
+
+    #[test]
+    fn wrong_length_rejected() {
+        let err = validate_uid_for_write("DEADBEEF", &CardType::MifareUltralight).unwrap_err();
+        assert_eq!(err, UidWarning::InvalidLength { len: 4, expected: vec![7] });
+        assert!(err.to_string().contains("needs 7 bytes"));
+        assert!(validate_uid_for_write("DEADBE", &CardType::MifareClassic1K).is_err());
+    }
+
+    #[test]
+    fn block0_bcc_checked() {
+        // DE^AD^BE^EF = 0x22
+        assert_eq!(compute_bcc(&[0xDE, 0xAD, 0xBE, 0xEF]), 0x22);
+        assert!(check_block0_bcc("DEADBEEF22080400626364656667686A").is_ok());
+        assert_eq!(
+            check_block0_bcc("DEADBEEF23080400626364656667686A"),
+            Err(UidWarning::BccMismatch { expected: 0x22, found: 0x23 })
+        );
+    }
+}
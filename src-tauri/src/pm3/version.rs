@@ -15,6 +15,43 @@ pub struct HwVersionInfo {
     /// "rdv4", "rdv4-bt", "generic", or "generic-256"
     pub hardware_variant: String,
     pub versions_match: bool,
+    pub fork: FirmwareFork,
+}
+
+/// Which Proxmark3 codebase the client/firmware was built from. Phosphor's
+/// commands follow the Iceman (RRG) fork; the official repository lacks many
+/// of them (`hf mf autopwn`) and predates the CLI-style `--flag` syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirmwareFork {
+    Iceman,
+    Official,
+    Unknown,
+}
+
+/// Shown when `hw version` reports the official firmware.
+pub const OFFICIAL_FORK_WARNING: &str = "This Proxmark3 runs the official firmware. \
+Phosphor needs the Iceman (RRG) fork for most features — flash the bundled firmware \
+to use autopwn and the clone commands.";
+
+/// Oldest Iceman client build the command builders work with: the CLIParser
+/// `--flag` syntax they emit landed in v4.13441.
+pub const MIN_CLIENT_BUILD: u32 = 13441;
+
+/// The resolved `proxmark3` binary, from its client-only `--version` output.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientBinaryInfo {
+    /// Where it was found: `"bundled"` or the shell scope name (`"proxmark3"` = PATH).
+    pub binary: String,
+    /// Full client version, e.g. `Iceman/master/v4.20728-358-ga2ba91043-suspect`.
+    pub version: String,
+    pub fork: FirmwareFork,
+    /// Iceman build number (`20728`), when the version has one.
+    pub build: Option<u32>,
+    /// Iceman build at or above `MIN_CLIENT_BUILD`.
+    pub supported: bool,
+    pub warning: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -39,6 +76,17 @@ static OS_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?im)^\s*os[\s.:]+(.+)").expect("bad os version regex")
 });
 
+/// Matches a non-empty bootrom version line: `Bootrom.... Iceman/master/v4.20728-...`
+static BOOTROM_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^[ \t]*bootrom[ \t.:]+[^\s.:]").expect("bad bootrom version regex")
+});
+
+/// Like `OS_VERSION_RE` but stays on one line, so an empty `OS.........`
+/// doesn't borrow the next line as its value.
+static OS_PRESENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^[ \t]*os[ \t.:]+[^\s.:]").expect("bad os present regex")
+});
+
 /// Extracts commit hash from version string: `v4.20728-234-g1a2b3c4d5-dirty` → `1a2b3c4d5`
 static COMMIT_HASH_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"-g([0-9a-fA-F]{7,})").expect("bad commit hash regex")
@@ -49,6 +97,12 @@ static BASE_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"v(\d+\.\d+)").expect("bad base version regex")
 });
 
+/// Official builds report a bare `master/v3.1.0-...` (or `v4.0...`) version
+/// with no `Iceman` prefix.
+static OFFICIAL_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(?:^|[\s:])master/v\d+\.\d+").expect("bad official version regex")
+});
+
 /// Detects AT91SAM7S256 (256K flash variant)
 static UC_256K_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)AT91SAM7S256").expect("bad uc 256k regex")
@@ -77,6 +131,7 @@ pub fn parse_detailed_hw_version(output: &str) -> HwVersionInfo {
         .unwrap_or_default();
     let hardware_variant = detect_hardware_variant(&clean);
     let versions_match = compare_versions(&client_version, &os_version);
+    let fork = device_fork(&clean);
 
     HwVersionInfo {
         model,
@@ -84,6 +139,83 @@ pub fn parse_detailed_hw_version(output: &str) -> HwVersionInfo {
         os_version,
         hardware_variant,
         versions_match,
+        fork,
+    }
+}
+
+/// Identify the fork from a version string. Iceman builds tag every version
+/// with `Iceman/` (older ones `RRG/Iceman/`); official builds carry a plain
+/// `master/vX.Y` string.
+pub fn detect_fork(output: &str) -> FirmwareFork {
+    let clean = strip_ansi(output);
+    if clean.to_lowercase().contains("iceman") {
+        FirmwareFork::Iceman
+    } else if OFFICIAL_VERSION_RE.is_match(&clean) {
+        FirmwareFork::Official
+    } else {
+        FirmwareFork::Unknown
+    }
+}
+
+/// Fork of the firmware on the device, from the OS and bootrom lines of the
+/// `hw version` banner. The client line is left out: the bundled client is
+/// always Iceman, whatever the device runs.
+pub fn device_fork(hw_version_output: &str) -> FirmwareFork {
+    let clean = strip_ansi(hw_version_output);
+    let device_lines: Vec<&str> = clean
+        .lines()
+        .filter(|line| OS_PRESENT_RE.is_match(line) || BOOTROM_VERSION_RE.is_match(line))
+        .collect();
+    detect_fork(&device_lines.join("\n"))
+}
+
+/// Parse `proxmark3 --version` (no device needed) for the binary found as
+/// `binary`, and say what's wrong with it if it isn't a supported Iceman build.
+pub fn parse_client_version(binary: &str, output: &str) -> ClientBinaryInfo {
+    let clean = strip_ansi(output);
+    let version = CLIENT_VERSION_RE
+        .captures(&clean)
+        .or_else(|| CLIENT_SECTION_RE.captures(&clean))
+        .map(|c| c[1].trim().to_string())
+        .or_else(|| {
+            clean
+                .lines()
+                .find(|l| BASE_VERSION_RE.is_match(l))
+                .map(|l| l.trim().to_string())
+        })
+        .unwrap_or_default();
+    let fork = detect_fork(&version);
+    let build = firmware_build(&version);
+    let supported = fork == FirmwareFork::Iceman && build.is_some_and(|b| b >= MIN_CLIENT_BUILD);
+
+    let warning = if version.is_empty() {
+        Some(format!(
+            "'{}' printed no version — it may not be a Proxmark3 client.",
+            binary
+        ))
+    } else if fork == FirmwareFork::Official {
+        Some(format!(
+            "The proxmark3 client ({}) is an official-repository build. Install the \
+             Iceman client or remove the old binary from PATH.",
+            version
+        ))
+    } else if !supported {
+        Some(format!(
+            "The proxmark3 client ({}) is older than the supported v4.{} or not an Iceman \
+             build. Update it or remove the old binary from PATH.",
+            version, MIN_CLIENT_BUILD
+        ))
+    } else {
+        None
+    };
+
+    ClientBinaryInfo {
+        binary: binary.to_string(),
+        version,
+        fork,
+        build,
+        supported,
+        warning,
     }
 }
 
@@ -121,6 +253,34 @@ pub fn compare_versions(client_ver: &str, os_ver: &str) -> bool {
     false
 }
 
+/// Optional hardware reported in the `[ Hardware ]` section of `hw version`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareFeatures {
+    pub external_flash: bool,
+    pub smartcard: bool,
+    /// FPC USART — Blueshark Bluetooth add-on on the RDV4.
+    pub fpc_usart: bool,
+}
+
+/// Parse which optional hardware the device reports as `present`.
+pub fn parse_hardware_features(output: &str) -> HardwareFeatures {
+    let present = |needle: &str| {
+        output.lines().any(|l| {
+            let l = l.to_lowercase();
+            l.contains(needle)
+                && l.contains("present")
+                && !l.contains("not present")
+                && !l.contains("absent")
+        })
+    };
+    HardwareFeatures {
+        external_flash: present("external flash"),
+        smartcard: present("smartcard"),
+        fpc_usart: present("fpc usart"),
+    }
+}
+
 /// Detect hardware variant from `hw version` output.
 ///
 /// - `AT91SAM7S256` in uC line → `"generic-256"`
@@ -132,19 +292,11 @@ pub fn detect_hardware_variant(output: &str) -> String {
         return "generic-256".to_string();
     }
 
-    let has_ext_flash = output
-        .lines()
-        .any(|l| l.to_lowercase().contains("external flash") && l.to_lowercase().contains("present"));
-    let has_smartcard = output
-        .lines()
-        .any(|l| l.to_lowercase().contains("smartcard") && l.to_lowercase().contains("present"));
+    let features = parse_hardware_features(output);
 
-    if has_ext_flash && has_smartcard {
+    if features.external_flash && features.smartcard {
         // RDV4 with BlueShark BT addon has FPC USART support
-        let has_bt = output
-            .lines()
-            .any(|l| l.to_lowercase().contains("fpc usart") && l.to_lowercase().contains("present"));
-        if has_bt {
+        if features.fpc_usart {
             "rdv4-bt".to_string()
         } else {
             "rdv4".to_string()
@@ -154,10 +306,46 @@ pub fn detect_hardware_variant(output: &str) -> String {
     }
 }
 
+/// Phrases the PM3 client prints when the device answers from its bootloader
+/// instead of the main OS image (e.g. after a flash was interrupted).
+const BOOTLOADER_MARKERS: &[&str] = &[
+    "bootloader mode",
+    "bootrom mode",
+    "running bootrom",
+    "in bootloader",
+    "only the bootloader",
+];
+
+/// Whether `hw version` or flasher output shows a device stuck in bootloader
+/// mode. Besides explicit client messages, a version report that lists a
+/// bootrom but no OS image means the main firmware never came up.
+pub fn is_bootloader_mode(output: &str) -> bool {
+    let clean = strip_ansi(output);
+    let lower = clean.to_lowercase();
+    if BOOTLOADER_MARKERS.iter().any(|m| lower.contains(m)) {
+        return true;
+    }
+
+    let has_bootrom = BOOTROM_VERSION_RE.is_match(&clean);
+    let has_os = OS_PRESENT_RE.is_match(&clean);
+    has_bootrom && !has_os
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// Iceman build number from a version string: `v4.20728` → `20728`. Only
+/// 4.x builds number this way; anything else is `None`.
+pub fn firmware_build(version: &str) -> Option<u32> {
+    let base = extract_base_version(version)?;
+    let (major, build) = base.split_once('.')?;
+    if major != "4" {
+        return None;
+    }
+    build.parse().ok()
+}
+
 fn extract_commit_hash(version: &str) -> Option<String> {
     COMMIT_HASH_RE
         .captures(version)
@@ -370,4 +558,99 @@ OS......... Iceman/master/v4.20469-164-g0e95c62ad-suspect 2025-08-02 22:16:55 ef
         assert!(info.os_version.contains("v4.20469"), "os: {}", info.os_version);
         assert!(!info.versions_match, "should NOT match — different commits");
     }
+
+    /// Official Proxmark3 repository, `hw version` on v3.1.0
+    const SAMPLE_OFFICIAL: &str = r#"
+Prox/RFID mark3 RFID instrument
+ [ ARM ]
+ bootrom: master/v3.1.0-150-gb41be3c-suspect 2018-10-30 13:11:26
+      os: master/v3.1.0-150-gb41be3c-suspect 2018-10-30 13:11:27
+ [ FPGA ]
+ LF image built for 2s30vq100 on 2015/03/06 at 07:38:04
+ HF image built for 2s30vq100 on 2018/09/03 at 21:40:23
+uC: AT91SAM7S512 Rev B
+"#;
+
+    /// Official firmware driven by the bundled Iceman client
+    const SAMPLE_OFFICIAL_ICEMAN_CLIENT: &str = r#"
+[ Proxmark3 ]
+[ Client ]
+Iceman/master/v4.20728-358-ga2ba91043-suspect 2026-02-09 00:22:45 c0679a575
+Compiler.................. MinGW-w64 15.2.0
+Platform.................. Windows (64b) / x86_64
+[ ARM ]
+ bootrom: master/v3.1.0-150-gb41be3c-suspect 2018-10-30 13:11:26
+      os: master/v3.1.0-150-gb41be3c-suspect 2018-10-30 13:11:27
+[ Hardware ]
+--= uC: AT91SAM7S512 Rev B
+"#;
+
+    #[test]
+    fn test_detect_fork() {
+        assert_eq!(device_fork(SAMPLE_REAL_PM3), FirmwareFork::Iceman);
+        assert_eq!(device_fork(SAMPLE_HW_VERSION), FirmwareFork::Iceman);
+        assert_eq!(
+            detect_fork("  client: RRG/Iceman/master/v4.14831-12-g4ad1bbe3"),
+            FirmwareFork::Iceman
+        );
+        assert_eq!(device_fork(SAMPLE_OFFICIAL), FirmwareFork::Official);
+        assert_eq!(parse_detailed_hw_version(SAMPLE_OFFICIAL).fork, FirmwareFork::Official);
+        assert_eq!(device_fork("[ Proxmark3 ]\nuC: AT91SAM7S512"), FirmwareFork::Unknown);
+
+        // The client line names Iceman; the device doesn't
+        assert_eq!(device_fork(SAMPLE_OFFICIAL_ICEMAN_CLIENT), FirmwareFork::Official);
+        assert_eq!(
+            parse_detailed_hw_version(SAMPLE_OFFICIAL_ICEMAN_CLIENT).fork,
+            FirmwareFork::Official
+        );
+    }
+
+    #[test]
+    fn test_parse_client_version() {
+        let current = parse_client_version(
+            "proxmark3",
+            " [ Proxmark3 ]\n\n [ Client ]\n    \
+             Iceman/master/v4.20728-358-ga2ba91043-suspect 2026-02-09 00:22:45 c0679a575\n    \
+             Compiler.................. GCC 13.2.0\n    \
+             Platform.................. Linux / x86_64\n",
+        );
+        assert!(current.version.starts_with("Iceman/master/v4.20728-358"));
+        assert_eq!(current.build, Some(20728));
+        assert_eq!(current.fork, FirmwareFork::Iceman);
+        assert!(current.supported);
+        assert!(current.warning.is_none());
+
+        // Pre-CLIParser Iceman client
+        let stale =
+            parse_client_version("proxmark3", "client: RRG/Iceman/master/v4.9237-2-g1e2d3c4");
+        assert_eq!(stale.build, Some(9237));
+        assert!(!stale.supported);
+        assert!(stale.warning.unwrap().contains("older than the supported v4.13441"));
+
+        let official =
+            parse_client_version("proxmark3-linux-usr", "client: master/v3.1.0-44-gabcdef12");
+        assert_eq!(official.fork, FirmwareFork::Official);
+        assert_eq!(official.build, None);
+        assert!(official.warning.unwrap().contains("official-repository"));
+
+        let junk = parse_client_version("proxmark3", "usage: something else");
+        assert!(!junk.supported);
+        assert!(junk.version.is_empty());
+        assert!(junk.warning.unwrap().contains("no version"));
+    }
+
+    #[test]
+    fn test_bootloader_mode_detection() {
+        assert!(!is_bootloader_mode(SAMPLE_REAL_PM3));
+        assert!(!is_bootloader_mode(SAMPLE_HW_VERSION));
+
+        // Client message when the device only answers from the bootrom
+        assert!(is_bootloader_mode(
+            "[!!] Device is in bootloader mode, flash the fullimage to recover"
+        ));
+
+        // Version report with a bootrom but no OS image
+        let bootrom_only = "[ Proxmark3 ]\n[ ARM ]\n  Bootrom.... Iceman/master/v4.20728-358-ga2ba91043 2026-02-09\n  OS.........\n[ Hardware ]\n";
+        assert!(is_bootloader_mode(bootrom_only));
+    }
 }
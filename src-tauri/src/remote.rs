@@ -0,0 +1,320 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::cards::types::{BlankType, CardType};
+use crate::commands;
+use crate::state::WizardMachine;
+
+// Optional websocket transport for remote/headless control (`websocket`
+// feature). Mirrors backend events to every connected client and accepts a
+// small command subset, dispatched to the same functions the IPC handlers use.
+//
+// Started only when `PHOSPHOR_WS_TOKEN` is set; clients must connect with
+// `ws://<addr>/?token=<token>`. Binds 127.0.0.1 unless `PHOSPHOR_WS_ADDR`
+// says otherwise.
+
+/// Events forwarded to websocket clients, unchanged from their IPC payloads.
+pub const MIRRORED_EVENTS: &[&str] = &["pm3-output", "hf-progress", "write-progress"];
+
+const DEFAULT_ADDR: &str = "127.0.0.1:7780";
+
+/// Slow clients that fall this far behind skip events rather than stall PM3.
+const EVENT_BUFFER: usize = 256;
+
+pub struct RemoteConfig {
+    pub addr: SocketAddr,
+    pub token: String,
+}
+
+impl RemoteConfig {
+    /// `None` when no token is configured — the server never runs unauthenticated.
+    pub fn from_env() -> Option<Self> {
+        let token = std::env::var("PHOSPHOR_WS_TOKEN").ok().filter(|t| !t.is_empty())?;
+        let addr = std::env::var("PHOSPHOR_WS_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.into());
+        match addr.parse() {
+            Ok(addr) => Some(Self { addr, token }),
+            Err(e) => {
+                log::warn!("Websocket disabled: invalid PHOSPHOR_WS_ADDR '{}': {}", addr, e);
+                None
+            }
+        }
+    }
+}
+
+/// Commands accepted from websocket clients: `{"command": "scan"}`, etc.
+/// `clone` takes the same arguments as `write_clone_with_data`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum RemoteCommand {
+    Scan,
+    Clone {
+        port: String,
+        card_type: CardType,
+        uid: String,
+        #[serde(default)]
+        decoded: std::collections::HashMap<String, String>,
+        blank_type: Option<BlankType>,
+        password: Option<String>,
+        set_password: Option<String>,
+    },
+    Cancel,
+}
+
+/// Wrap an event payload for the wire: `{"event": <name>, "payload": <json>}`.
+pub fn event_message(event: &str, payload: &str) -> String {
+    let payload = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    serde_json::json!({ "event": event, "payload": payload }).to_string()
+}
+
+/// Start the websocket server if configured. Called once from app setup.
+pub fn start(app: &AppHandle) {
+    let Some(config) = RemoteConfig::from_env() else {
+        return;
+    };
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    for &event in MIRRORED_EVENTS {
+        let events = events.clone();
+        app.listen_any(event, move |e| {
+            // No receivers just means no client is connected
+            let _ = events.send(event_message(event, e.payload()));
+        });
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(config.addr).await {
+            Ok(l) => l,
+            Err(e) => {
+                log::warn!("Websocket server failed to bind {}: {}", config.addr, e);
+                return;
+            }
+        };
+        log::info!("Websocket control listening on {}", config.addr);
+        serve(listener, config.token, events, move |cmd| {
+            dispatch(app.clone(), cmd)
+        })
+        .await;
+    });
+}
+
+/// Accept clients until the listener fails. Each client gets the event stream
+/// and may send commands; `handler` returns the JSON reply for a command.
+pub async fn serve<H, Fut>(
+    listener: TcpListener,
+    token: String,
+    events: broadcast::Sender<String>,
+    handler: H,
+) where
+    H: Fn(RemoteCommand) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = serde_json::Value> + Send + 'static,
+{
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("Websocket accept failed: {}", e);
+                return;
+            }
+        };
+        let token = token.clone();
+        let events = events.subscribe();
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(stream, &token, events, handler).await {
+                log::debug!("Websocket client {} disconnected: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_client<H, Fut>(
+    stream: TcpStream,
+    token: &str,
+    mut events: broadcast::Receiver<String>,
+    handler: H,
+) -> Result<(), tokio_tungstenite::tungstenite::Error>
+where
+    H: Fn(RemoteCommand) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = serde_json::Value> + Send + 'static,
+{
+    // Signature is fixed by tungstenite's handshake `Callback`
+    #[allow(clippy::result_large_err)]
+    let check_token = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        if query_token(req.uri().query()) == Some(token) {
+            Ok(resp)
+        } else {
+            let mut err = ErrorResponse::new(Some("invalid token".into()));
+            *err.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(err)
+        }
+    };
+    let ws = tokio_tungstenite::accept_hdr_async(stream, check_token).await?;
+    let (mut sink, mut incoming) = ws.split();
+
+    // Commands run on their own task so a long clone doesn't stall the event
+    // stream (the client still wants `write-progress` while it waits).
+    let (reply_tx, mut replies) = mpsc::unbounded_channel::<serde_json::Value>();
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(msg) => sink.send(Message::text(msg)).await?,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    log::debug!("Websocket client lagged, dropped {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            Some(reply) = replies.recv() => sink.send(Message::text(reply.to_string())).await?,
+            msg = incoming.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<RemoteCommand>(&text) {
+                    Ok(cmd) => {
+                        let handler = handler.clone();
+                        let reply_tx = reply_tx.clone();
+                        tokio::spawn(async move {
+                            let _ = reply_tx.send(handler(cmd).await);
+                        });
+                    }
+                    Err(e) => {
+                        let reply = serde_json::json!({ "error": format!("Invalid command: {}", e) });
+                        sink.send(Message::text(reply.to_string())).await?;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+            },
+        }
+    }
+}
+
+fn query_token(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Run a remote command through the same code path as its IPC counterpart.
+async fn dispatch(app: AppHandle, cmd: RemoteCommand) -> serde_json::Value {
+    let result = match cmd {
+        RemoteCommand::Scan => commands::scan::scan_card(
+            app.clone(),
+            app.state::<Mutex<WizardMachine>>(),
+            app.state(),
+            app.state(),
+            None,
+            None,
+        )
+        .await
+        .map(|state| serde_json::to_value(state).unwrap_or_default()),
+        RemoteCommand::Clone {
+            port,
+            card_type,
+            uid,
+            decoded,
+            blank_type,
+            password,
+            set_password,
+        } => commands::write::write_clone_with_data(
+            app.clone(),
+            port,
+            card_type,
+            uid,
+            decoded,
+            blank_type,
+            password,
+            set_password,
+            app.state::<Mutex<WizardMachine>>(),
+        )
+        .await
+        .map(|result| serde_json::to_value(result).unwrap_or_default()),
+        RemoteCommand::Cancel => commands::hf_clone::cancel_hf_operation(app.state())
+            .await
+            .map(|_| serde_json::Value::Null),
+    };
+    match result {
+        Ok(value) => serde_json::json!({ "result": value }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    async fn start_test_server() -> (SocketAddr, broadcast::Sender<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        tokio::spawn(serve(listener, "secret".into(), events.clone(), |cmd| async move {
+            serde_json::json!({ "result": format!("{:?}", cmd) })
+        }));
+        (addr, events)
+    }
+
+    #[test]
+    fn token_from_query() {
+        assert_eq!(query_token(Some("token=abc")), Some("abc"));
+        assert_eq!(query_token(Some("x=1&token=abc")), Some("abc"));
+        assert_eq!(query_token(Some("x=1")), None);
+        assert_eq!(query_token(None), None);
+    }
+
+    #[test]
+    fn parse_remote_commands() {
+        assert!(matches!(
+            serde_json::from_str::<RemoteCommand>(r#"{"command":"scan"}"#).unwrap(),
+            RemoteCommand::Scan
+        ));
+        let clone: RemoteCommand = serde_json::from_str(
+            r#"{"command":"clone","port":"COM3","cardType":"EM4100","uid":"0F00112233"}"#,
+        )
+        .unwrap();
+        assert!(matches!(clone, RemoteCommand::Clone { ref uid, .. } if uid == "0F00112233"));
+        assert!(serde_json::from_str::<RemoteCommand>(r#"{"command":"wipe"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn events_broadcast_to_connected_socket() {
+        let (addr, events) = start_test_server().await;
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("ws://{}/?token=secret", addr).into_client_request().unwrap();
+        let (mut ws, _) = tokio_tungstenite::client_async(request, stream).await.unwrap();
+
+        // The subscription is taken on accept; wait until it exists
+        while events.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        events
+            .send(event_message("pm3-output", r#"{"text":"[+] Done!","isError":false}"#))
+            .unwrap();
+
+        let msg = ws.next().await.unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+        assert_eq!(value["event"], "pm3-output");
+        assert_eq!(value["payload"]["text"], "[+] Done!");
+
+        ws.send(Message::text(r#"{"command":"cancel"}"#)).await.unwrap();
+        let reply = ws.next().await.unwrap().unwrap();
+        assert!(reply.to_text().unwrap().contains("Cancel"));
+    }
+
+    #[tokio::test]
+    async fn connection_without_token_rejected() {
+        let (addr, _events) = start_test_server().await;
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("ws://{}/?token=wrong", addr).into_client_request().unwrap();
+        assert!(tokio_tungstenite::client_async(request, stream).await.is_err());
+    }
+}
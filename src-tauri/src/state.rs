@@ -5,7 +5,11 @@ use serde::{Deserialize, Serialize};
 use crate::cards::types::{
     BlankType, CardData, CardSummary, CardType, Frequency, ProcessPhase, RecoveryAction,
 };
+use crate::db::models::CloneRecord;
 use crate::error::AppError;
+use crate::pm3::clone_summary::OperationSummary;
+use crate::pm3::dump_report::SectorKeys;
+use crate::pm3::version::FirmwareFork;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "step", content = "data")]
@@ -24,6 +28,9 @@ pub enum WizardState {
         card_data: CardData,
         cloneable: bool,
         recommended_blank: BlankType,
+        /// Earlier clones of this UID from history, newest first.
+        #[serde(default)]
+        previous_clones: Vec<CloneRecord>,
     },
     HfProcessing {
         phase: ProcessPhase,
@@ -41,6 +48,9 @@ pub enum WizardState {
         blank_type: BlankType,
         ready_to_write: bool,
         existing_data_type: Option<String>,
+        /// The blank can't present the source's UID length (e.g. a fixed
+        /// 4-byte Gen1a for a 7-byte source).
+        uid_length_warning: Option<String>,
     },
     Writing {
         progress: f32,
@@ -51,11 +61,22 @@ pub enum WizardState {
     VerificationComplete {
         success: bool,
         mismatched_blocks: Vec<u16>,
+        /// End-of-clone report; `None` if the clone wasn't tracked.
+        summary: Option<OperationSummary>,
     },
     Complete {
         source: CardSummary,
         target: CardSummary,
         timestamp: String,
+        /// Door-reader outcome from the optional field test; `None` if skipped.
+        field_verified: Option<bool>,
+    },
+    /// Clone verified against the PM3; waiting for the user to try it on the
+    /// real reader and report whether it worked.
+    FieldTesting {
+        source: CardSummary,
+        target: CardSummary,
+        timestamp: String,
     },
     Error {
         message: String,
@@ -81,6 +102,7 @@ pub enum WizardAction {
         card_data: CardData,
         cloneable: bool,
         recommended_blank: BlankType,
+        previous_clones: Vec<CloneRecord>,
     },
     StartHfProcess,
     UpdateHfProgress {
@@ -99,6 +121,7 @@ pub enum WizardAction {
     BlankReady {
         blank_type: BlankType,
         existing_data_type: Option<String>,
+        uid_length_warning: Option<String>,
     },
     StartWrite,
     UpdateWriteProgress {
@@ -110,11 +133,16 @@ pub enum WizardAction {
     VerificationResult {
         success: bool,
         mismatched_blocks: Vec<u16>,
+        summary: Option<OperationSummary>,
     },
     MarkComplete {
         source: CardSummary,
         target: CardSummary,
     },
+    StartFieldTest,
+    FieldTestResult {
+        works: bool,
+    },
     ReportError {
         message: String,
         user_message: String,
@@ -153,6 +181,7 @@ fn state_name(s: &WizardState) -> &str {
         WizardState::Verifying => "Verifying",
         WizardState::VerificationComplete { .. } => "VerificationComplete",
         WizardState::Complete { .. } => "Complete",
+        WizardState::FieldTesting { .. } => "FieldTesting",
         WizardState::Error { .. } => "Error",
     }
 }
@@ -174,6 +203,8 @@ fn action_name(a: &WizardAction) -> &str {
         WizardAction::WriteFinished => "WriteFinished",
         WizardAction::VerificationResult { .. } => "VerificationResult",
         WizardAction::MarkComplete { .. } => "MarkComplete",
+        WizardAction::StartFieldTest => "StartFieldTest",
+        WizardAction::FieldTestResult { .. } => "FieldTestResult",
         WizardAction::ReportError { .. } => "ReportError",
         WizardAction::Retry => "Retry",
         WizardAction::Reset => "Reset",
@@ -190,6 +221,86 @@ pub struct WizardMachine {
     pub port: Option<String>,
     pub model: Option<String>,
     pub firmware: Option<String>,
+    /// Set by `detect_device` from the `hw version` banner.
+    pub fork: Option<FirmwareFork>,
+    /// UID length (bytes) of the HF card last identified, kept so blank
+    /// detection can warn before the write if the blank can't present it.
+    pub source_uid_len: Option<usize>,
+    /// Card last identified and blank last chosen, kept for checkpoints
+    /// since later states no longer carry them.
+    source_card: Option<CheckpointCard>,
+    blank_type: Option<BlankType>,
+}
+
+/// Source card of a checkpointed session, as identified by the scan.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointCard {
+    pub frequency: Frequency,
+    pub card_type: CardType,
+    pub card_data: CardData,
+    pub cloneable: bool,
+    pub recommended_blank: BlankType,
+}
+
+/// Enough of a session in progress to pick it up after the app restarts:
+/// the state plus the context later states don't carry.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WizardCheckpoint {
+    pub state: WizardState,
+    pub port: Option<String>,
+    pub source_uid_len: Option<usize>,
+    pub card: Option<CheckpointCard>,
+    pub blank_type: Option<BlankType>,
+    /// HF dump the write would use; set by the caller, the machine doesn't
+    /// know it.
+    pub dump_path: Option<String>,
+    /// Key file and key table of that dump, for archiving the source and
+    /// flagging sectors whose keys were never recovered. Set by the caller.
+    #[serde(default)]
+    pub key_path: Option<String>,
+    #[serde(default)]
+    pub sector_keys: Option<Vec<SectorKeys>>,
+}
+
+impl WizardCheckpoint {
+    /// The state to resume into. Anything that was running (key recovery,
+    /// a write, verification) restarts from the step before it, since the
+    /// PM3 process didn't survive. `None` if the checkpoint lacks the
+    /// context to rebuild it.
+    pub fn resume_state(&self) -> Option<WizardState> {
+        let card_identified = |card: &CheckpointCard| WizardState::CardIdentified {
+            frequency: card.frequency.clone(),
+            card_type: card.card_type.clone(),
+            card_data: card.card_data.clone(),
+            cloneable: card.cloneable,
+            recommended_blank: card.recommended_blank.clone(),
+            previous_clones: Vec::new(),
+        };
+        match &self.state {
+            WizardState::CardIdentified { .. } | WizardState::HfDumpReady { .. } => {
+                Some(self.state.clone())
+            }
+            WizardState::HfProcessing { .. } => self.card.as_ref().map(card_identified),
+            WizardState::WaitingForBlank { .. }
+            | WizardState::BlankDetected { .. }
+            | WizardState::Writing { .. }
+            | WizardState::Verifying => {
+                self.blank_type.clone().map(|blank| WizardState::WaitingForBlank {
+                    expected_blank: blank,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The resumed session writes an HF dump, which must still be on disk.
+    pub fn needs_dump(&self) -> bool {
+        let hf = self.card.as_ref().is_some_and(|c| c.frequency == Frequency::HF);
+        hf && matches!(
+            self.resume_state(),
+            Some(WizardState::HfDumpReady { .. } | WizardState::WaitingForBlank { .. })
+        )
+    }
 }
 
 impl WizardMachine {
@@ -199,9 +310,60 @@ impl WizardMachine {
             port: None,
             model: None,
             firmware: None,
+            fork: None,
+            source_uid_len: None,
+            source_card: None,
+            blank_type: None,
         }
     }
 
+    /// Card last identified, which states after `CardIdentified` no longer
+    /// carry.
+    pub fn source_card(&self) -> Option<&CheckpointCard> {
+        self.source_card.as_ref()
+    }
+
+    /// Checkpoint of the session, `None` when there's nothing to resume
+    /// (idle, connected, finished or failed). Only the state and the card
+    /// context are filled in; the caller adds the HF dump and keys.
+    pub fn checkpoint(&self) -> Option<WizardCheckpoint> {
+        let checkpoint = WizardCheckpoint {
+            state: self.current.clone(),
+            port: self.port.clone(),
+            source_uid_len: self.source_uid_len,
+            card: self.source_card.clone(),
+            blank_type: self.blank_type.clone(),
+            dump_path: None,
+            key_path: None,
+            sector_keys: None,
+        };
+        checkpoint.resume_state().is_some().then_some(checkpoint)
+    }
+
+    /// Restore a checkpointed session onto a freshly detected device.
+    pub fn restore(
+        &mut self,
+        checkpoint: WizardCheckpoint,
+        port: String,
+        model: String,
+        firmware: String,
+    ) -> Result<&WizardState, AppError> {
+        let state = checkpoint.resume_state().ok_or_else(|| {
+            AppError::InvalidTransition(format!(
+                "Cannot resume a session checkpointed in {}",
+                state_name(&checkpoint.state)
+            ))
+        })?;
+        self.port = Some(port);
+        self.model = Some(model);
+        self.firmware = Some(firmware);
+        self.source_uid_len = checkpoint.source_uid_len;
+        self.source_card = checkpoint.card;
+        self.blank_type = checkpoint.blank_type;
+        self.current = state;
+        Ok(&self.current)
+    }
+
     pub fn transition(&mut self, action: WizardAction) -> Result<&WizardState, AppError> {
         // Reset is always valid from any state — full reset to idle
         if matches!(action, WizardAction::Reset) {
@@ -209,6 +371,10 @@ impl WizardMachine {
             self.port = None;
             self.model = None;
             self.firmware = None;
+            self.fork = None;
+            self.source_uid_len = None;
+            self.source_card = None;
+            self.blank_type = None;
             return Ok(&self.current);
         }
 
@@ -218,6 +384,10 @@ impl WizardMachine {
             self.port = None;
             self.model = None;
             self.firmware = None;
+            self.fork = None;
+            self.source_uid_len = None;
+            self.source_card = None;
+            self.blank_type = None;
             return Ok(&self.current);
         }
 
@@ -275,6 +445,7 @@ impl WizardMachine {
                     card_data,
                     cloneable,
                     recommended_blank,
+                    previous_clones,
                 },
             ) => WizardState::CardIdentified {
                 frequency: frequency.clone(),
@@ -282,10 +453,15 @@ impl WizardMachine {
                 card_data: card_data.clone(),
                 cloneable: *cloneable,
                 recommended_blank: recommended_blank.clone(),
+                previous_clones: previous_clones.clone(),
             },
 
-            // CardIdentified -> HfProcessing (start key recovery)
-            (WizardState::CardIdentified { .. }, WizardAction::StartHfProcess) => {
+            // CardIdentified -> HfProcessing (start key recovery), or
+            // HfDumpReady -> HfProcessing (resume a partial recovery)
+            (
+                WizardState::CardIdentified { .. } | WizardState::HfDumpReady { .. },
+                WizardAction::StartHfProcess,
+            ) => {
                 WizardState::HfProcessing {
                     phase: ProcessPhase::KeyCheck,
                     keys_found: 0,
@@ -367,13 +543,15 @@ impl WizardMachine {
             },
 
             // WaitingForBlank -> BlankDetected
-            (WizardState::WaitingForBlank { .. }, WizardAction::BlankReady { blank_type, existing_data_type }) => {
-                WizardState::BlankDetected {
-                    blank_type: blank_type.clone(),
-                    ready_to_write: true,
-                    existing_data_type: existing_data_type.clone(),
-                }
-            }
+            (
+                WizardState::WaitingForBlank { .. },
+                WizardAction::BlankReady { blank_type, existing_data_type, uid_length_warning },
+            ) => WizardState::BlankDetected {
+                blank_type: blank_type.clone(),
+                ready_to_write: true,
+                existing_data_type: existing_data_type.clone(),
+                uid_length_warning: uid_length_warning.clone(),
+            },
 
             // BlankDetected -> WaitingForBlank (re-detect after erase)
             (WizardState::BlankDetected { .. }, WizardAction::ReDetectBlank) => {
@@ -418,10 +596,12 @@ impl WizardMachine {
                 WizardAction::VerificationResult {
                     success,
                     mismatched_blocks,
+                    summary,
                 },
             ) => WizardState::VerificationComplete {
                 success: *success,
                 mismatched_blocks: mismatched_blocks.clone(),
+                summary: summary.clone(),
             },
 
             // VerificationComplete -> Complete
@@ -432,6 +612,28 @@ impl WizardMachine {
                 source: source.clone(),
                 target: target.clone(),
                 timestamp: chrono::Local::now().to_rfc3339(),
+                field_verified: None,
+            },
+
+            // Complete -> FieldTesting (optional "present to reader" step, once)
+            (
+                WizardState::Complete { source, target, timestamp, field_verified: None },
+                WizardAction::StartFieldTest,
+            ) => WizardState::FieldTesting {
+                source: source.clone(),
+                target: target.clone(),
+                timestamp: timestamp.clone(),
+            },
+
+            // FieldTesting -> Complete with the user's answer
+            (
+                WizardState::FieldTesting { source, target, timestamp },
+                WizardAction::FieldTestResult { works },
+            ) => WizardState::Complete {
+                source: source.clone(),
+                target: target.clone(),
+                timestamp: timestamp.clone(),
+                field_verified: Some(*works),
             },
 
             // Error + Retry -> Idle (user can restart the flow)
@@ -462,8 +664,9 @@ impl WizardMachine {
                 }
             }
 
-            // SoftReset: Complete/Error -> DeviceConnected using persistent device info
+            // SoftReset: Complete/FieldTesting/Error -> DeviceConnected using persistent device info
             (WizardState::Complete { .. }, WizardAction::SoftReset)
+            | (WizardState::FieldTesting { .. }, WizardAction::SoftReset)
             | (WizardState::Error { .. }, WizardAction::SoftReset) => {
                 match (&self.port, &self.model, &self.firmware) {
                     (Some(p), Some(m), Some(f)) => WizardState::DeviceConnected {
@@ -501,6 +704,7 @@ impl WizardMachine {
                 },
                 cloneable: *cloneable,
                 recommended_blank: recommended_blank.clone(),
+                previous_clones: Vec::new(),
             },
 
             _ => {
@@ -512,7 +716,243 @@ impl WizardMachine {
             }
         };
 
+        match &next {
+            WizardState::CardIdentified {
+                frequency,
+                card_type,
+                card_data,
+                cloneable,
+                recommended_blank,
+                ..
+            } => {
+                self.source_uid_len = matches!(frequency, Frequency::HF)
+                    .then(|| card_data.uid.chars().filter(|c| c.is_ascii_hexdigit()).count() / 2);
+                self.source_card = Some(CheckpointCard {
+                    frequency: frequency.clone(),
+                    card_type: card_type.clone(),
+                    card_data: card_data.clone(),
+                    cloneable: *cloneable,
+                    recommended_blank: recommended_blank.clone(),
+                });
+            }
+            WizardState::WaitingForBlank { expected_blank: blank_type }
+            | WizardState::BlankDetected { blank_type, .. } => {
+                self.blank_type = Some(blank_type.clone());
+            }
+            _ => {}
+        }
         self.current = next;
         Ok(&self.current)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(uid: &str) -> CardSummary {
+        CardSummary {
+            card_type: "EM4100".into(),
+            uid: uid.into(),
+            display_name: "EM4100".into(),
+        }
+    }
+
+    fn verified_machine() -> WizardMachine {
+        let mut machine = WizardMachine::new();
+        machine.port = Some("COM3".into());
+        machine.model = Some("Proxmark3 RDV4".into());
+        machine.firmware = Some("v4.18994".into());
+        machine.current = WizardState::VerificationComplete {
+            success: true,
+            mismatched_blocks: vec![],
+            summary: None,
+        };
+        machine
+    }
+
+    #[test]
+    fn field_test_records_outcome() {
+        let mut machine = verified_machine();
+        machine
+            .transition(WizardAction::MarkComplete {
+                source: summary("0F0368568B"),
+                target: summary("0F0368568B"),
+            })
+            .unwrap();
+        assert!(matches!(machine.current, WizardState::Complete { field_verified: None, .. }));
+
+        machine.transition(WizardAction::StartFieldTest).unwrap();
+        assert!(matches!(machine.current, WizardState::FieldTesting { .. }));
+
+        machine.transition(WizardAction::FieldTestResult { works: false }).unwrap();
+        match &machine.current {
+            WizardState::Complete { field_verified, target, .. } => {
+                assert_eq!(*field_verified, Some(false));
+                assert_eq!(target.uid, "0F0368568B");
+            }
+            other => panic!("expected Complete, got {}", state_name(other)),
+        }
+
+        // Only one field test per clone
+        assert!(machine.transition(WizardAction::StartFieldTest).is_err());
+    }
+
+    #[test]
+    fn source_uid_length_carried_to_blank_detection() {
+        use crate::cards::types::MagicGeneration;
+        use crate::pm3::blank_compat::{self, Iso14aIdentity};
+
+        let mut machine = WizardMachine::new();
+        machine.current = WizardState::ScanningCard;
+        machine
+            .transition(WizardAction::CardFound {
+                frequency: Frequency::HF,
+                card_type: CardType::MifareClassic1K,
+                card_data: CardData {
+                    uid: "04 A2 2B 1A 3C 5D 80".into(),
+                    raw: String::new(),
+                    decoded: Default::default(),
+                },
+                cloneable: true,
+                recommended_blank: BlankType::MagicMifareGen1a,
+                previous_clones: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(machine.source_uid_len, Some(7));
+
+        // A fixed 4-byte Gen1a goes on the reader
+        let blank = Iso14aIdentity { uid_len: 4, atqa: Some(0x0004), sak: Some(0x08) };
+        let warning = blank_compat::early_uid_length_warning(
+            machine.source_uid_len,
+            Some(&MagicGeneration::Gen1a),
+            Some(&blank),
+        );
+        assert!(warning.is_some());
+        machine.current = WizardState::WaitingForBlank {
+            expected_blank: BlankType::MagicMifareGen1a,
+        };
+        machine
+            .transition(WizardAction::BlankReady {
+                blank_type: BlankType::MagicMifareGen1a,
+                existing_data_type: None,
+                uid_length_warning: warning,
+            })
+            .unwrap();
+        assert!(matches!(
+            &machine.current,
+            WizardState::BlankDetected { uid_length_warning: Some(w), .. } if w.contains("7-byte")
+        ));
+
+        machine.transition(WizardAction::Reset).unwrap();
+        assert_eq!(machine.source_uid_len, None);
+    }
+
+    #[test]
+    fn field_test_requires_completed_clone() {
+        let mut machine = verified_machine();
+        assert!(machine.transition(WizardAction::StartFieldTest).is_err());
+        assert!(machine.transition(WizardAction::FieldTestResult { works: true }).is_err());
+
+        machine
+            .transition(WizardAction::MarkComplete {
+                source: summary("0F0368568B"),
+                target: summary("0F0368568B"),
+            })
+            .unwrap();
+        machine.transition(WizardAction::StartFieldTest).unwrap();
+        // Skipping the test goes back to scanning like any finished clone
+        machine.transition(WizardAction::SoftReset).unwrap();
+        assert!(matches!(machine.current, WizardState::DeviceConnected { .. }));
+    }
+
+    #[test]
+    fn checkpoint_restores_interrupted_write() {
+        let mut machine = WizardMachine::new();
+        machine.transition(WizardAction::StartDetection).unwrap();
+        machine
+            .transition(WizardAction::DeviceFound {
+                port: "COM3".into(),
+                model: "Proxmark3 RDV4".into(),
+                firmware: "v4.18994".into(),
+            })
+            .unwrap();
+        assert!(machine.checkpoint().is_none());
+        machine.transition(WizardAction::StartScan).unwrap();
+        machine
+            .transition(WizardAction::CardFound {
+                frequency: Frequency::HF,
+                card_type: CardType::MifareClassic1K,
+                card_data: CardData {
+                    uid: "01020304".into(),
+                    raw: String::new(),
+                    decoded: HashMap::new(),
+                },
+                cloneable: true,
+                recommended_blank: BlankType::MagicMifareGen1a,
+                previous_clones: Vec::new(),
+            })
+            .unwrap();
+        machine.transition(WizardAction::StartHfProcess).unwrap();
+        machine
+            .transition(WizardAction::HfProcessComplete { dump_info: "16 sectors".into() })
+            .unwrap();
+        machine
+            .transition(WizardAction::ProceedToWrite { blank_type: BlankType::MagicMifareGen2 })
+            .unwrap();
+        machine
+            .transition(WizardAction::BlankReady {
+                blank_type: BlankType::MagicMifareGen2,
+                existing_data_type: None,
+                uid_length_warning: None,
+            })
+            .unwrap();
+        machine.transition(WizardAction::StartWrite).unwrap();
+
+        // The app dies mid-write; the checkpoint goes through the database as JSON
+        let mut checkpoint = machine.checkpoint().unwrap();
+        checkpoint.dump_path = Some("hf-mf-01020304-dump.bin".to_string());
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let checkpoint: WizardCheckpoint = serde_json::from_str(&json).unwrap();
+        assert!(checkpoint.needs_dump());
+
+        let mut resumed = WizardMachine::new();
+        resumed
+            .restore(checkpoint.clone(), "COM4".into(), "Proxmark3 RDV4".into(), "v4.18994".into())
+            .unwrap();
+        match &resumed.current {
+            WizardState::WaitingForBlank { expected_blank } => {
+                assert_eq!(*expected_blank, BlankType::MagicMifareGen2);
+            }
+            other => panic!("expected WaitingForBlank, got {}", state_name(other)),
+        }
+        assert_eq!(resumed.port.as_deref(), Some("COM4"));
+        assert_eq!(resumed.source_uid_len, Some(4));
+        let card = resumed.source_card.as_ref().unwrap();
+        assert_eq!(card.card_type, CardType::MifareClassic1K);
+        assert_eq!(card.card_data.uid, "01020304");
+        assert_eq!(checkpoint.dump_path.as_deref(), Some("hf-mf-01020304-dump.bin"));
+
+        // Key recovery can't continue without its PM3 process: back to the card
+        let mut recovering = checkpoint;
+        recovering.state = WizardState::HfProcessing {
+            phase: ProcessPhase::Nested,
+            keys_found: 10,
+            keys_total: 32,
+            elapsed_secs: 60,
+        };
+        assert!(matches!(
+            recovering.resume_state(),
+            Some(WizardState::CardIdentified { card_type: CardType::MifareClassic1K, .. })
+        ));
+
+        // A state saved before previous_clones existed still loads
+        let mut json = serde_json::to_value(recovering.resume_state().unwrap()).unwrap();
+        json["data"].as_object_mut().unwrap().remove("previous_clones").unwrap();
+        let state: WizardState = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            state,
+            WizardState::CardIdentified { previous_clones, .. } if previous_clones.is_empty()
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+// Fixtures shared by the unit tests.
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::db::models::CloneRecord;
+
+/// A fresh, empty directory under the system temp dir, removed again when
+/// dropped. Names are unique per process and per call, so tests running in
+/// parallel never share one.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "phosphor-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A successful EM4100 -> T5577 clone, as the history tests store it.
+pub fn clone_record() -> CloneRecord {
+    CloneRecord {
+        id: None,
+        source_type: "EM4100".into(),
+        source_uid: "0F0368568B".into(),
+        target_type: "T5577".into(),
+        target_uid: "0F0368568B".into(),
+        port: "COM3".into(),
+        success: true,
+        timestamp: "2026-01-15T10:30:00Z".into(),
+        notes: None,
+        field_verified: None,
+        source_dump: None,
+    }
+}
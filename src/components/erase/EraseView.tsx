@@ -63,7 +63,10 @@ export function EraseView({ port }: EraseViewProps) {
     try {
       const result = await wipeChip(port, chip.chipType);
       if (result.success) {
-        setMessage(result.message);
+        const readBack = result.before || result.after
+          ? ` (${result.before ?? '?'} -> ${result.after ?? '?'})`
+          : '';
+        setMessage(result.message + readBack);
         setPhase('complete');
       } else {
         setMessage(result.message);
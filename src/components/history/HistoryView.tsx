@@ -1,6 +1,6 @@
 import { useEffect, useState } from 'react';
 import { TerminalPanel } from '../shared/TerminalPanel';
-import { getHistory } from '../../lib/api';
+import { getDatabaseStatus, getHistory } from '../../lib/api';
 import type { CloneRecord } from '../../machines/types';
 
 interface HistoryRecord {
@@ -36,6 +36,14 @@ export function HistoryView() {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [refreshKey, setRefreshKey] = useState(0);
+  const [dbWarning, setDbWarning] = useState<string | null>(null);
+
+  // Startup DB problems (corrupt file reset, in-memory fallback) are non-fatal
+  useEffect(() => {
+    getDatabaseStatus()
+      .then((status) => setDbWarning(status.warning))
+      .catch(() => {});
+  }, []);
 
   useEffect(() => {
     let cancelled = false;
@@ -117,6 +125,7 @@ export function HistoryView() {
   if (records.length === 0) {
     return (
       <TerminalPanel title="CLONE HISTORY">
+        {dbWarning && <DbWarning message={dbWarning} />}
         <div style={{
           fontFamily: 'var(--font-mono)',
           fontSize: '12px',
@@ -133,6 +142,7 @@ export function HistoryView() {
 
   return (
     <TerminalPanel title="CLONE HISTORY">
+      {dbWarning && <DbWarning message={dbWarning} />}
       <table
         style={{
           width: '100%',
@@ -198,3 +208,16 @@ export function HistoryView() {
     </TerminalPanel>
   );
 }
+
+function DbWarning({ message }: { message: string }) {
+  return (
+    <div style={{
+      fontFamily: 'var(--font-mono)',
+      fontSize: '12px',
+      color: 'var(--red-bright)',
+      padding: '0 0 8px',
+    }}>
+      [!!] {message}
+    </div>
+  );
+}
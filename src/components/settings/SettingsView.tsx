@@ -4,51 +4,97 @@ import { TerminalPanel } from '../shared/TerminalPanel';
 
 export function SettingsView() {
   const { settings, updateSettings } = useSettings();
-  const sfx = useSfx();
-
-  const toggleExpert = () => {
-    sfx.click();
-    updateSettings({ expertMode: !settings.expertMode });
-  };
-
-  const statusText = settings.expertMode ? '[ON]' : '[OFF]';
-  const statusColor = settings.expertMode ? 'var(--green-bright)' : 'var(--green-dim)';
 
   return (
     <TerminalPanel title="SETTINGS">
       <div style={{ display: 'flex', flexDirection: 'column', gap: '12px' }}>
-        {/* Expert Mode */}
-        <div>
-          <div style={{ color: 'var(--green-mid)', fontSize: '13px', fontWeight: 600 }}>
-            EXPERT MODE
-          </div>
-          <div style={{ color: 'var(--green-dim)', fontSize: '12px', marginTop: '4px' }}>
-            Allow raw PM3 command input in terminal
-          </div>
-          <div style={{ marginTop: '8px', fontSize: '13px' }}>
-            <span style={{ color: 'var(--green-mid)' }}>STATUS: </span>
-            <span
-              onClick={toggleExpert}
-              onMouseEnter={(e) => {
-                sfx.hover();
-                e.currentTarget.style.textShadow = '0 0 6px var(--green-bright)';
-              }}
-              onMouseLeave={(e) => {
-                e.currentTarget.style.textShadow = 'none';
-              }}
-              style={{
-                color: statusColor,
-                cursor: 'pointer',
-                userSelect: 'none',
-                fontWeight: 600,
-                transition: 'color 0.15s, text-shadow 0.15s',
-              }}
-            >
-              {statusText}
-            </span>
-          </div>
-        </div>
+        <SettingToggle
+          label="EXPERT MODE"
+          description="Allow raw PM3 command input in terminal"
+          enabled={settings.expertMode}
+          onToggle={() => updateSettings({ expertMode: !settings.expertMode })}
+        />
+        <SettingToggle
+          label="PM3 COLORS"
+          description="Show the Proxmark3 client's own colors in the terminal"
+          enabled={settings.preserveAnsi}
+          onToggle={() => updateSettings({ preserveAnsi: !settings.preserveAnsi })}
+        />
+        <SettingToggle
+          label="AUTO-WIPE BLANK"
+          description="Wipe T5577 blanks before every clone to clear leftover config"
+          enabled={settings.autoWipeBeforeClone}
+          onToggle={() => updateSettings({ autoWipeBeforeClone: !settings.autoWipeBeforeClone })}
+        />
+        <SettingToggle
+          label="LF FIELD ASSIST"
+          description="Tune and settle the LF field before clones; retry a failed readback (PM3 Easy)"
+          enabled={settings.lfFieldAssist}
+          onToggle={() => updateSettings({ lfFieldAssist: !settings.lfFieldAssist })}
+        />
+        <SettingToggle
+          label="VERIFY IN PLACE"
+          description="Read LF clones back in the same PM3 run as the write, so the card can't move"
+          enabled={settings.verifyInPlace}
+          onToggle={() => updateSettings({ verifyInPlace: !settings.verifyInPlace })}
+        />
+        <SettingToggle
+          label="ARCHIVE SOURCE DUMP"
+          description="Save every HF source card's dump to the library before writing"
+          enabled={settings.alwaysDumpSource}
+          onToggle={() => updateSettings({ alwaysDumpSource: !settings.alwaysDumpSource })}
+        />
       </div>
     </TerminalPanel>
   );
 }
+
+interface SettingToggleProps {
+  label: string;
+  description: string;
+  enabled: boolean;
+  onToggle: () => void;
+}
+
+function SettingToggle({ label, description, enabled, onToggle }: SettingToggleProps) {
+  const sfx = useSfx();
+
+  const statusText = enabled ? '[ON]' : '[OFF]';
+  const statusColor = enabled ? 'var(--green-bright)' : 'var(--green-dim)';
+
+  return (
+    <div>
+      <div style={{ color: 'var(--green-mid)', fontSize: '13px', fontWeight: 600 }}>
+        {label}
+      </div>
+      <div style={{ color: 'var(--green-dim)', fontSize: '12px', marginTop: '4px' }}>
+        {description}
+      </div>
+      <div style={{ marginTop: '8px', fontSize: '13px' }}>
+        <span style={{ color: 'var(--green-mid)' }}>STATUS: </span>
+        <span
+          onClick={() => {
+            sfx.click();
+            onToggle();
+          }}
+          onMouseEnter={(e) => {
+            sfx.hover();
+            e.currentTarget.style.textShadow = '0 0 6px var(--green-bright)';
+          }}
+          onMouseLeave={(e) => {
+            e.currentTarget.style.textShadow = 'none';
+          }}
+          style={{
+            color: statusColor,
+            cursor: 'pointer',
+            userSelect: 'none',
+            fontWeight: 600,
+            transition: 'color 0.15s, text-shadow 0.15s',
+          }}
+        >
+          {statusText}
+        </span>
+      </div>
+    </div>
+  );
+}
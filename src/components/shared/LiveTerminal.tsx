@@ -165,15 +165,50 @@ export function LiveTerminal() {
 }
 
 function TerminalLine({ line }: { line: LogLine }) {
+  const baseColor = line.isError ? 'var(--red-bright)' : 'var(--green-mid)';
   return (
     <div style={{ whiteSpace: 'pre-wrap', wordBreak: 'break-all' }}>
-      <span style={{ color: line.isError ? 'var(--red-bright)' : 'var(--green-mid)' }}>
-        {line.text}
-      </span>
+      {line.ansi ? (
+        ansiSegments(line.ansi).map((seg, i) => (
+          <span key={i} style={{ color: seg.color ?? baseColor }}>{seg.text}</span>
+        ))
+      ) : (
+        <span style={{ color: baseColor }}>{line.text}</span>
+      )}
     </div>
   );
 }
 
+// SGR foreground codes PM3 uses (green = success, red = error, ...)
+const ANSI_COLORS: Record<string, string> = {
+  '31': 'var(--red-bright)',
+  '32': 'var(--green-bright)',
+  '33': '#e5c07b',
+  '34': '#61afef',
+  '35': '#c678dd',
+  '36': '#56b6c2',
+};
+
+const SGR_RE = /\x1b\[([0-9;]*)m/g;
+
+/** Split a line on SGR escape codes into coloured text runs. */
+function ansiSegments(raw: string): { text: string; color?: string }[] {
+  const segments: { text: string; color?: string }[] = [];
+  let color: string | undefined;
+  let last = 0;
+  for (const match of raw.matchAll(SGR_RE)) {
+    const start = match.index ?? 0;
+    if (start > last) segments.push({ text: raw.slice(last, start), color });
+    for (const code of match[1].split(';')) {
+      if (code === '' || code === '0' || code === '39') color = undefined;
+      else if (ANSI_COLORS[code]) color = ANSI_COLORS[code];
+    }
+    last = start + match[0].length;
+  }
+  if (last < raw.length) segments.push({ text: raw.slice(last), color });
+  return segments;
+}
+
 interface ExpertInputProps {
   port: string | null;
   cmdInput: string;
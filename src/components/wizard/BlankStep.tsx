@@ -11,6 +11,8 @@ interface BlankStepProps {
   blankType?: BlankType | null;
   readyToWrite?: boolean;
   existingData?: string | null;
+  /** Blank can't present the source's UID length; shown before writing. */
+  uidLengthWarning?: string | null;
   onReset?: () => void;
   onBack?: () => void;
   frequency?: 'LF' | 'HF' | null;
@@ -28,7 +30,7 @@ const btnBase: React.CSSProperties = {
   border: '2px solid',
 };
 
-export function BlankStep({ onReady, onErase, isLoading, expectedBlank, blankType, readyToWrite, existingData, onReset, onBack, frequency }: BlankStepProps) {
+export function BlankStep({ onReady, onErase, isLoading, expectedBlank, blankType, readyToWrite, existingData, uidLengthWarning, onReset, onBack, frequency }: BlankStepProps) {
   const sfx = useSfx();
   const [frameIdx, setFrameIdx] = useState(0);
   const [erasing, setErasing] = useState(false);
@@ -110,6 +112,12 @@ export function BlankStep({ onReady, onErase, isLoading, expectedBlank, blankTyp
               </div>
             )}
 
+            {uidLengthWarning && (
+              <div style={{ color: 'var(--red-bright)', marginTop: '8px', fontSize: '12px' }}>
+                [!] {uidLengthWarning} Use a blank that supports this UID length.
+              </div>
+            )}
+
             <div style={{ marginTop: '16px', display: 'flex', gap: '12px', flexWrap: 'wrap' }}>
               {onBack && (
                 <button
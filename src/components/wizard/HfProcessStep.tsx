@@ -38,6 +38,7 @@ function phaseLabel(phase: string | null): string {
 /** Card type display name */
 function cardLabel(ct: CardType | null): string {
   switch (ct) {
+    case 'MifareMini': return 'MIFARE Mini';
     case 'MifareClassic1K': return 'MIFARE Classic 1K';
     case 'MifareClassic4K': return 'MIFARE Classic 4K';
     case 'MifareUltralight': return 'MIFARE Ultralight';
@@ -49,7 +50,7 @@ function cardLabel(ct: CardType | null): string {
 
 /** Check if this is a Classic card (long autopwn) vs simple dump */
 function isClassic(ct: CardType | null): boolean {
-  return ct === 'MifareClassic1K' || ct === 'MifareClassic4K';
+  return ct === 'MifareMini' || ct === 'MifareClassic1K' || ct === 'MifareClassic4K';
 }
 
 export function HfProcessStep({
@@ -115,6 +115,12 @@ export function ScanStep({
             </div>
           ))}
 
+          {cardData.decoded?.crc_ok === 'false' && (
+            <div style={{ color: 'var(--amber)', marginTop: '12px', fontWeight: 600 }}>
+              [!!] Checksum failed -- this read is probably corrupt and may clone wrong. Re-scan before writing.
+            </div>
+          )}
+
           {cloneable === false && (
             <div style={{ color: 'var(--amber)', marginTop: '12px', fontWeight: 600 }}>
               [!!] This card type cannot be cloned
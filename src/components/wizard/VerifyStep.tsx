@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { TerminalPanel } from '../shared/TerminalPanel';
 import { useSfx } from '../../hooks/useSfx';
+import type { OperationSummary } from '../../machines/types';
 
 interface VerifyStepProps {
   onContinue: () => void;
@@ -9,11 +10,27 @@ interface VerifyStepProps {
   isLoading?: boolean;
   success?: boolean | null;
   mismatchedBlocks?: number[];
+  summary?: OperationSummary | null;
 }
 
 const SPINNER_FRAMES = ['|', '/', '-', '\\'];
 
-export function VerifyStep({ onContinue, onRetryWrite, onReset, isLoading, success, mismatchedBlocks }: VerifyStepProps) {
+function SummaryDetails({ summary }: { summary: OperationSummary }) {
+  return (
+    <div style={{ marginTop: '8px', fontSize: '12px' }}>
+      <div style={{ color: 'var(--green-dim)' }}>
+        {summary.commandsRun.length} PM3 commands in {(summary.durationMs / 1000).toFixed(1)}s
+      </div>
+      {summary.warnings.map((w) => (
+        <div key={w} style={{ color: 'var(--amber)' }}>
+          [!] {w}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+export function VerifyStep({ onContinue, onRetryWrite, onReset, isLoading, success, mismatchedBlocks, summary }: VerifyStepProps) {
   const sfx = useSfx();
   const [spinnerIdx, setSpinnerIdx] = useState(0);
 
@@ -54,6 +71,7 @@ export function VerifyStep({ onContinue, onRetryWrite, onReset, isLoading, succe
                 <div style={{ color: 'var(--green-bright)', fontSize: '16px', fontWeight: 700 }}>
                   [OK] CLONE SUCCESSFUL
                 </div>
+                {summary && <SummaryDetails summary={summary} />}
                 <button
                   onClick={() => { sfx.action(); onContinue(); }}
                   style={{
@@ -83,6 +101,7 @@ export function VerifyStep({ onContinue, onRetryWrite, onReset, isLoading, succe
                     Mismatched blocks: {mismatchedBlocks.join(', ')}
                   </div>
                 )}
+                {summary && <SummaryDetails summary={summary} />}
                 <div style={{ display: 'flex', gap: '12px', marginTop: '16px' }}>
                   {onRetryWrite && (
                     <button
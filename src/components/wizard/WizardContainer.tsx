@@ -162,6 +162,7 @@ export function WizardContainer() {
             blankType={wizard.context.blankType}
             readyToWrite={wizard.context.readyToWrite}
             existingData={wizard.context.blankExistingData}
+            uidLengthWarning={wizard.context.blankUidLengthWarning}
             isLoading={false}
             onReady={wizard.write}
             onBack={wizard.backToScan}
@@ -183,6 +184,7 @@ export function WizardContainer() {
             progress={wizard.context.writeProgress}
             currentBlock={wizard.context.currentBlock}
             totalBlocks={wizard.context.totalBlocks}
+            jobElapsed={wizard.context.jobElapsed}
             cardType={wizard.context.cardType}
             blankType={wizard.context.blankType}
             isLoading={true}
@@ -201,6 +203,7 @@ export function WizardContainer() {
           <VerifyStep
             success={wizard.context.verifySuccess}
             mismatchedBlocks={wizard.context.mismatchedBlocks}
+            summary={wizard.context.operationSummary}
             isLoading={false}
             onContinue={wizard.finish}
             onRetryWrite={wizard.reset}
@@ -9,6 +9,8 @@ interface WriteStepProps {
   progress?: number;
   currentBlock?: number | null;
   totalBlocks?: number | null;
+  /** Seconds since the HF job began, carried over from key recovery */
+  jobElapsed?: number | null;
   cardType?: CardType | null;
   blankType?: BlankType | null;
 }
@@ -33,6 +35,13 @@ const EM4305_PHASES: { label: string; start: number; end: number }[] = [
   { label: 'FINALIZE', start: 75, end: 100 },
 ];
 
+/** Format seconds as MM:SS */
+function formatTime(secs: number): string {
+  const m = Math.floor(secs / 60);
+  const s = secs % 60;
+  return `${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}`;
+}
+
 function getPhaseSteps(progress: number, blankType?: BlankType | null): Step[] {
   const phases = blankType === 'EM4305' ? EM4305_PHASES : T5577_PHASES;
 
@@ -52,6 +61,7 @@ export function WriteStep({
   progress = 0,
   currentBlock,
   totalBlocks,
+  jobElapsed,
   cardType,
   blankType,
 }: WriteStepProps) {
@@ -77,6 +87,11 @@ export function WriteStep({
               {blockInfo}
             </span>
           )}
+          {jobElapsed !== null && jobElapsed !== undefined && (
+            <span style={{ color: 'var(--green-dim)', fontSize: '12px', marginLeft: '12px' }}>
+              Job time {formatTime(jobElapsed)}
+            </span>
+          )}
         </div>
 
         <StepIndicator steps={steps} />
@@ -106,6 +106,11 @@ interface WriteProgressPayload {
   progress: number;
   current_block: number | null;
   total_blocks: number | null;
+  // Label of the current step for flows with named steps (T5577)
+  step_name: string | null;
+  // HF job elapsed time (absent for LF writes)
+  total_elapsed_secs?: number | null;
+  operation_id: number | null;
 }
 
 export function WizardProvider({ children }: { children: ReactNode }) {
@@ -120,6 +125,7 @@ export function WizardProvider({ children }: { children: ReactNode }) {
         progress: Math.min(100, Math.max(0, event.payload.progress * 100)),
         currentBlock: event.payload.current_block,
         totalBlocks: event.payload.total_blocks,
+        jobElapsed: event.payload.total_elapsed_secs ?? null,
       });
     });
     return () => {
@@ -446,7 +452,7 @@ export function WizardProvider({ children }: { children: ReactNode }) {
       // Write progress
       ctx.writeProgress, ctx.currentBlock, ctx.totalBlocks,
       // Verification
-      ctx.verifySuccess, ctx.mismatchedBlocks,
+      ctx.verifySuccess, ctx.mismatchedBlocks, ctx.operationSummary,
       // Completion
       ctx.completionTimestamp,
       // Error
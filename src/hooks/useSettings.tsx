@@ -1,11 +1,33 @@
 import { createContext, useCallback, useContext, useEffect, useState, type ReactNode } from 'react';
+import {
+  setAlwaysDumpSource,
+  setAutoWipeBeforeClone,
+  setLfFieldAssist,
+  setTerminalAnsi,
+  setVerifyInPlace,
+} from '../lib/api';
 
 interface PhosphorSettings {
   expertMode: boolean;
+  // Show PM3's own colours in the terminal panel
+  preserveAnsi: boolean;
+  // Wipe the blank before every clone, not only locked ones
+  autoWipeBeforeClone: boolean;
+  // Tune + settle the LF field before clones (weak antennas)
+  lfFieldAssist: boolean;
+  // Read LF clones back in the same PM3 run as the write
+  verifyInPlace: boolean;
+  // Archive the HF source dump to the library before every write
+  alwaysDumpSource: boolean;
 }
 
 const DEFAULT_SETTINGS: PhosphorSettings = {
   expertMode: false,
+  preserveAnsi: false,
+  autoWipeBeforeClone: false,
+  lfFieldAssist: false,
+  verifyInPlace: false,
+  alwaysDumpSource: false,
 };
 
 const STORAGE_KEY = 'phosphor-settings';
@@ -46,6 +68,27 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
     saveSettings(settings);
   }, [settings]);
 
+  // Backend decides per line whether to forward the coloured original
+  useEffect(() => {
+    setTerminalAnsi(settings.preserveAnsi).catch(() => {});
+  }, [settings.preserveAnsi]);
+
+  useEffect(() => {
+    setAutoWipeBeforeClone(settings.autoWipeBeforeClone).catch(() => {});
+  }, [settings.autoWipeBeforeClone]);
+
+  useEffect(() => {
+    setLfFieldAssist(settings.lfFieldAssist).catch(() => {});
+  }, [settings.lfFieldAssist]);
+
+  useEffect(() => {
+    setVerifyInPlace(settings.verifyInPlace).catch(() => {});
+  }, [settings.verifyInPlace]);
+
+  useEffect(() => {
+    setAlwaysDumpSource(settings.alwaysDumpSource).catch(() => {});
+  }, [settings.alwaysDumpSource]);
+
   const updateSettings = useCallback((partial: Partial<PhosphorSettings>) => {
     setSettings(prev => ({ ...prev, ...partial }));
   }, []);
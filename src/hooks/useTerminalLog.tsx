@@ -5,11 +5,16 @@ export interface LogLine {
   text: string;
   isError: boolean;
   timestamp: number;
+  // Original line with ANSI colour codes, when colour preservation is on
+  ansi?: string;
 }
 
 interface Pm3OutputPayload {
   text: string;
   isError: boolean;
+  // Null for one-shot commands (scans, chip tools, raw commands)
+  operationId: number | null;
+  ansi: string | null;
 }
 
 const MAX_LINES = 500;
@@ -32,6 +37,7 @@ export function TerminalLogProvider({ children }: { children: ReactNode }) {
         text: event.payload.text,
         isError: event.payload.isError,
         timestamp: Date.now(),
+        ansi: event.payload.ansi ?? undefined,
       };
       setLines(prev => {
         const next = [...prev, newLine];
@@ -1,7 +1,33 @@
 // Typed Tauri invoke wrappers for PM3 backend commands.
 
 import { invoke } from '@tauri-apps/api/core';
-import type { WizardState, CloneRecord, BlankType, FirmwareCheckResult } from '../machines/types';
+import type {
+  WizardState,
+  ResumeResult,
+  CloneRecord,
+  BlankType,
+  FirmwareCheckResult,
+  ClientBinaryInfo,
+  DeviceCapabilities,
+  AboutInfo,
+  FeatureStatus,
+  DeviceStatus,
+  WiegandDecode,
+  BlankCompatibility,
+  SequenceResult,
+  Frequency,
+  DatabaseStatus,
+  LastOperation,
+  CardMetadata,
+  UidOnlyClone,
+  DictionaryKind,
+  Pm3Dictionary,
+  CardData,
+  JobSpec,
+  JobStatus,
+  PatternMatch,
+  CardIdentification,
+} from '../machines/types';
 
 export interface SavedCard {
   id: number | null;
@@ -24,12 +50,91 @@ export async function detectDevice(): Promise<WizardState> {
   return invoke<WizardState>('detect_device');
 }
 
+/**
+ * Device model, optional hardware and currently available operations.
+ * Use to show/hide actions the connected device can't perform.
+ */
+export async function getCapabilities(): Promise<DeviceCapabilities> {
+  return invoke<DeviceCapabilities>('get_capabilities');
+}
+
+/**
+ * Device housekeeping from `hw status` (uptime, memory, flash dictionaries,
+ * battery). Only when capabilities list 'DeviceStatus'.
+ */
+export async function deviceStatus(): Promise<DeviceStatus> {
+  return invoke<DeviceStatus>('device_status');
+}
+
+/**
+ * Whether the connected firmware knows a subcommand, e.g. 'lf gallagher clone'
+ * or 'hf mf gdmsetblk'. Use to gate clone paths on real support.
+ */
+export async function probeCommandSupport(cmdPrefix: string): Promise<boolean> {
+  return invoke<boolean>('probe_command_support', { cmdPrefix });
+}
+
+/**
+ * App version, last detected PM3 version, build target and enabled features.
+ * Cheap: uses cached device info, never probes the device.
+ */
+export async function aboutInfo(): Promise<AboutInfo> {
+  return invoke<AboutInfo>('about_info');
+}
+
+/**
+ * Expected availability of firmware-dependent features (autopwn, GDM
+ * commands, newer LF clone types) for the detected firmware version, from the
+ * built-in compatibility matrix. Never probes the device.
+ */
+export async function featureAvailability(): Promise<FeatureStatus[]> {
+  return invoke<FeatureStatus[]>('feature_availability');
+}
+
 /**
  * Scan a card on the connected device.
  * Identifies card type, frequency, and reads data.
+ * With `frequency`, only that band is searched; if the card is on the other
+ * band the wizard reports a "switch to HF/LF mode" hint.
+ * With `deep`, `lf search` is retried (up to 5 times) for intermittent cards;
+ * each attempt emits `scan-progress` ({ attempt, total }).
+ */
+export async function scanCard(frequency?: Frequency, deep?: boolean): Promise<WizardState> {
+  return invoke<WizardState>('scan_card', { frequency: frequency ?? null, deep: deep ?? null });
+}
+
+export interface DetectedTag {
+  frequency: Frequency;
+  cardType: string;
+  cardData: CardData;
+  cloneable: boolean;
+}
+
+/**
+ * Search both LF and HF and return every tag found, LF first.
+ * Dual-tech cards come back as two entries. Does not change wizard state;
+ * an empty list means no card answered on either frequency.
  */
-export async function scanCard(): Promise<WizardState> {
-  return invoke<WizardState>('scan_card');
+export async function scanAll(port: string): Promise<DetectedTag[]> {
+  return invoke<DetectedTag[]>('scan_all', { port });
+}
+
+/**
+ * Read LF badges continuously until cancelHfOperation is called: HID Prox
+ * only (`lf hid watch`), or every LF protocol with `allProtocols` (repeated
+ * `lf search`). Each badge presented emits `card-read` ({ tag, count });
+ * resolves with all of them.
+ */
+export async function watchLf(port: string, allProtocols?: boolean): Promise<DetectedTag[]> {
+  return invoke<DetectedTag[]>('watch_lf', { port, allProtocols: allProtocols ?? null });
+}
+
+/**
+ * Display metadata (name, frequency, category, description) for every card
+ * type. Static: doesn't touch the device.
+ */
+export async function getCardMetadata(): Promise<CardMetadata[]> {
+  return invoke<CardMetadata[]>('card_metadata');
 }
 
 /**
@@ -40,9 +145,40 @@ export async function detectBlank(port: string): Promise<WizardState> {
   return invoke<WizardState>('detect_blank', { port });
 }
 
+/**
+ * Check whether the magic blank on the reader can take a MIFARE Classic
+ * clone (UID length + 4K memory). Does not change wizard state.
+ */
+export async function recommendHfBlank(
+  port: string,
+  sourceCardType: 'MifareMini' | 'MifareClassic1K' | 'MifareClassic4K',
+  sourceUid: string,
+): Promise<BlankCompatibility> {
+  return invoke<BlankCompatibility>('recommend_hf_blank', { port, sourceCardType, sourceUid });
+}
+
+export interface WriteTestResult {
+  verdict: 'Gen2' | 'NotWritable' | 'Inconclusive' | 'RestoreFailed';
+  /** Block 0 before the test, for a manual rewrite if the restore failed. */
+  originalBlock0: string;
+  message: string;
+}
+
+/**
+ * Check whether a MIFARE Classic card that wasn't detected as magic accepts
+ * direct block 0 writes (Gen2). WRITES to the card (block 0 byte 15, then
+ * restored): only call with `consent` after the user agreed.
+ */
+export async function mifareWriteTest(port: string, consent: boolean): Promise<WriteTestResult> {
+  return invoke<WriteTestResult>('mifare_write_test', { port, consent });
+}
+
 /**
  * Execute the clone write operation with full card context.
  * Writes source card data to the blank card.
+ * `setPassword` (8 hex chars, EM4100 on T5577 only) locks the clone with
+ * that password once written. `preferRaw` clones from the raw frame even
+ * when FC/CN decoded (Paradox).
  */
 export async function writeCloneWithData(
   port: string,
@@ -50,6 +186,9 @@ export async function writeCloneWithData(
   uid: string,
   decoded: Record<string, string>,
   blankType?: string,
+  password?: string,
+  setPassword?: string,
+  preferRaw?: boolean,
 ): Promise<WizardState> {
   return invoke<WizardState>('write_clone_with_data', {
     port,
@@ -57,9 +196,87 @@ export async function writeCloneWithData(
     uid,
     decoded,
     blankType,
+    preferRaw: preferRaw ?? null,
+    password,
+    setPassword,
   });
 }
 
+/**
+ * Wipe every T5577 before cloning, not only password-protected ones.
+ */
+export async function setAutoWipeBeforeClone(enabled: boolean): Promise<void> {
+  return invoke<void>('set_auto_wipe_before_clone', { enabled });
+}
+
+/**
+ * Weak LF antennas (PM3 Easy): run `lf tune` and let the field settle in the
+ * same PM3 run as each LF clone, and retry a failed readback once.
+ */
+export async function setLfFieldAssist(enabled: boolean): Promise<void> {
+  return invoke<void>('set_lf_field_assist', { enabled });
+}
+
+/**
+ * Write LF clones and read them back in one PM3 run, so the card can't move
+ * between write and verify.
+ */
+export async function setVerifyInPlace(enabled: boolean): Promise<void> {
+  return invoke<void>('set_verify_in_place', { enabled });
+}
+
+/**
+ * Archive the source card's dump (bundle + library entry) before every HF
+ * write, linked from the clone's history entry.
+ */
+export async function setAlwaysDumpSource(enabled: boolean): Promise<void> {
+  return invoke<void>('set_always_dump_source', { enabled });
+}
+
+/**
+ * Clone `count` cards with consecutive card numbers (same FC) for enrollment
+ * testing. Pauses with a `sequence-progress` "swap-blank" event between cards.
+ */
+export async function cloneSequence(
+  port: string,
+  cardType: string,
+  decoded: Record<string, string>,
+  count: number,
+): Promise<SequenceResult> {
+  return invoke<SequenceResult>('clone_sequence', { port, cardType, decoded, count });
+}
+
+/** Resume a clone sequence waiting for the next blank. */
+export async function continueSequence(): Promise<void> {
+  return invoke<void>('continue_sequence');
+}
+
+/** Stop a clone sequence before its next PM3 command. */
+export async function cancelSequence(): Promise<void> {
+  return invoke<void>('cancel_sequence');
+}
+
+/**
+ * Queue a clone in the background and return its job ID immediately.
+ * Jobs use the device one at a time; poll `getJobStatus` for the result.
+ */
+export async function submitJob(spec: JobSpec): Promise<number> {
+  return invoke<number>('submit_job', { spec });
+}
+
+/** Phase and, once finished, result or error of a submitted job. */
+export async function getJobStatus(jobId: number): Promise<JobStatus> {
+  return invoke<JobStatus>('get_job_status', { jobId });
+}
+
+/**
+ * Cancel a queued or running job. Resolves false if it had already finished.
+ * Rejects for a running LF write, which can't be interrupted safely.
+ */
+export async function cancelJob(jobId: number): Promise<boolean> {
+  return invoke<boolean>('cancel_job', { jobId });
+}
+
 /**
  * Verify the written clone against source data.
  * Reads back the blank and compares block-by-block.
@@ -81,6 +298,43 @@ export async function verifyCloneWithData(
   });
 }
 
+/** Evidence gathered around a failed clone; omit what isn't known. */
+export interface FailureSignals {
+  sourceCrcOk?: boolean;
+  antennaVolts?: number;
+  genericHardware?: boolean;
+  blankCompatible?: boolean;
+  readbackTypeMismatch?: boolean;
+  readbackEmpty?: boolean;
+  uidLengthMismatch?: boolean;
+  partialDump?: boolean;
+}
+
+export type FailureCause =
+  | 'BlankIncompatible'
+  | 'UidLengthMismatch'
+  | 'PartialDump'
+  | 'WrongModulation'
+  | 'CorruptSource'
+  | 'WeakAntenna'
+  | 'CardPlacement';
+
+export interface LikelyCause {
+  cause: FailureCause;
+  /** 0-100, how strongly the signals point to this cause. */
+  score: number;
+  explanation: string;
+  fix: string;
+}
+
+/**
+ * Rank the likely causes of a failed clone verification, most likely first,
+ * each with a suggested fix. Never empty.
+ */
+export async function diagnoseCloneFailure(signals: FailureSignals): Promise<LikelyCause[]> {
+  return invoke<LikelyCause[]>('diagnose_clone_failure', { signals });
+}
+
 /**
  * Retrieve clone history from the local database.
  * Returns all past clone operations with metadata.
@@ -97,6 +351,36 @@ export async function saveCloneRecord(record: CloneRecord): Promise<number> {
   return invoke<number>('save_clone_record', { record });
 }
 
+/**
+ * Whether history is being saved to disk, plus any non-fatal startup problem
+ * with the database (corrupt file reset, in-memory fallback).
+ */
+export async function getDatabaseStatus(): Promise<DatabaseStatus> {
+  return invoke<DatabaseStatus>('database_status');
+}
+
+/**
+ * The last clone started, from this or an earlier session, so the UI can
+ * offer to continue it. Null before the first clone.
+ */
+export async function getLastOperation(): Promise<LastOperation | null> {
+  return invoke<LastOperation | null>('get_last_operation');
+}
+
+/**
+ * Set the note on an existing history entry. Pass null/empty to clear it.
+ */
+export async function updateCloneNote(id: number, note: string | null): Promise<void> {
+  return invoke<void>('update_clone_note', { id, note });
+}
+
+/**
+ * Record whether a clone opened the real door reader (field test outcome).
+ */
+export async function recordFieldTest(id: number, works: boolean): Promise<void> {
+  return invoke<void>('record_field_test', { id, works });
+}
+
 /**
  * Check firmware version match between bundled client and device OS.
  * Returns version info and whether they match.
@@ -105,6 +389,15 @@ export async function checkFirmwareVersion(port: string): Promise<FirmwareCheckR
   return invoke<FirmwareCheckResult>('check_firmware_version', { port });
 }
 
+/**
+ * Check the resolved proxmark3 binary is a supported Iceman client, via its
+ * client-only `--version`. No device needed; `warning` explains a stale or
+ * wrong binary on PATH.
+ */
+export async function verifyPm3Binary(): Promise<ClientBinaryInfo> {
+  return invoke<ClientBinaryInfo>('verify_pm3_binary');
+}
+
 /**
  * Start flashing firmware to the connected PM3 device.
  * Returns immediately — progress is streamed via Tauri events:
@@ -114,6 +407,14 @@ export async function flashFirmware(port: string, hardwareVariant: string): Prom
   return invoke<void>('flash_firmware', { port, hardwareVariant });
 }
 
+/**
+ * Re-flash bootrom + full image on a device stuck in bootloader mode.
+ * Same progress events as flashFirmware.
+ */
+export async function recoverFirmware(port: string, hardwareVariant: string): Promise<void> {
+  return invoke<void>('recover_firmware', { port, hardwareVariant });
+}
+
 /**
  * Cancel an in-progress firmware flash by killing the child process.
  */
@@ -132,6 +433,12 @@ export interface DetectChipResult {
 export interface WipeResult {
   success: boolean;
   message: string;
+  passwordRequired: boolean;
+  // Read-back after the wipe shows a blank chip
+  wiped: boolean;
+  // T5577 block 0 / EM4305 word 0 before and after the wipe
+  before: string | null;
+  after: string | null;
 }
 
 /**
@@ -146,8 +453,100 @@ export async function detectChip(port: string): Promise<DetectChipResult> {
  * Wipe a chip that was previously detected by detectChip.
  * Independent of the wizard FSM.
  */
-export async function wipeChip(port: string, chipType: string): Promise<WipeResult> {
-  return invoke<WipeResult>('wipe_chip', { port, chipType });
+export async function wipeChip(
+  port: string,
+  chipType: string,
+  password?: string,
+): Promise<WipeResult> {
+  return invoke<WipeResult>('wipe_chip', { port, chipType, password });
+}
+
+export interface T55xxConfigResult {
+  success: boolean;
+  message: string;
+  // Block 0 before the write and as read back after it
+  before: string | null;
+  after: string | null;
+}
+
+/**
+ * Write a T5577 config block (block 0) directly and read it back, to force
+ * a modulation the app didn't detect. Pass the tag's password if it has one,
+ * or the password to set when the config turns password mode on.
+ * Independent of the wizard FSM.
+ */
+export async function writeT55xxConfig(
+  port: string,
+  config: string,
+  password?: string,
+): Promise<T55xxConfigResult> {
+  return invoke<T55xxConfigResult>('write_t55xx_config', { port, config, password });
+}
+
+export interface Em4305Word {
+  address: number;
+  /** 8 hex chars; null if unreadable (password word, read-protected). */
+  value: string | null;
+}
+
+/**
+ * Read EM4305 words 0-15 for inspection.
+ * Independent of the wizard FSM.
+ */
+export async function em4305Dump(port: string): Promise<Em4305Word[]> {
+  return invoke<Em4305Word[]>('em4305_dump', { port });
+}
+
+export interface Em4x50CloneResult {
+  success: boolean;
+  message: string;
+  /** Data words written, in order. */
+  wordsWritten: number[];
+  /** Written words that read back differently. */
+  mismatchedWords: number[];
+}
+
+/**
+ * Read EM4x50 words 0-33 (MSB-first hex) for cloning.
+ * Independent of the wizard FSM.
+ */
+export async function em4x50Dump(port: string): Promise<string[]> {
+  return invoke<string[]>('em4x50_dump', { port });
+}
+
+/**
+ * Clone an em4x50Dump onto the EM4x50 blank on the reader and read it back.
+ * The blank must have the factory password.
+ */
+export async function em4x50Clone(port: string, words: string[]): Promise<Em4x50CloneResult> {
+  return invoke<Em4x50CloneResult>('em4x50_clone', { port, words });
+}
+
+export interface LfCapture {
+  /** File name in the capture directory; pass to demodLfCapture. */
+  fileName: string;
+  sizeBytes: number;
+}
+
+/**
+ * Sample the LF field and save the raw samples for later demodulation.
+ * `label` prefixes the capture's file name.
+ */
+export async function captureLfBuffer(port: string, label?: string): Promise<LfCapture> {
+  return invoke<LfCapture>('capture_lf_buffer', { port, label: label ?? null });
+}
+
+/** Saved LF captures, newest first. */
+export async function listLfCaptures(): Promise<LfCapture[]> {
+  return invoke<LfCapture[]>('list_lf_captures');
+}
+
+/**
+ * Load a saved capture and run lf search over it, without the card.
+ * Returns null when no known modulation is found.
+ */
+export async function demodLfCapture(port: string, fileName: string): Promise<DetectedTag | null> {
+  return invoke<DetectedTag | null>('demod_lf_capture', { port, fileName });
 }
 
 /**
@@ -160,6 +559,22 @@ export async function resetWizard(): Promise<WizardState> {
   });
 }
 
+/** A restored session and its source card (for the write commands). */
+export interface ResumedSession {
+  state: WizardState;
+  card: Omit<CardIdentification, 'previous_clones'> | null;
+}
+
+/**
+ * Restore the session checkpointed before the app last closed. Interrupted
+ * steps restart from the step before (e.g. a write resumes at blank
+ * detection). Re-detects the device; fails if the HF dump is gone.
+ * Null when there is nothing to resume.
+ */
+export async function resumeSession(): Promise<ResumedSession | null> {
+  return invoke<ResumedSession | null>('resume_session');
+}
+
 /**
  * Send ProceedToWrite action to advance Rust FSM from CardIdentified → WaitingForBlank.
  * @param blankType The blank card type selected by the user.
@@ -183,6 +598,26 @@ export async function markComplete(
   });
 }
 
+/**
+ * Send StartFieldTest to advance Rust FSM from Complete → FieldTesting
+ * ("now tap it on your door reader").
+ */
+export async function startFieldTest(): Promise<WizardState> {
+  return invoke<WizardState>('wizard_action', {
+    action: { action: 'StartFieldTest' },
+  });
+}
+
+/**
+ * Send FieldTestResult to return the Rust FSM from FieldTesting → Complete
+ * with the user's answer recorded.
+ */
+export async function fieldTestResult(works: boolean): Promise<WizardState> {
+  return invoke<WizardState>('wizard_action', {
+    action: { action: 'FieldTestResult', payload: { works } },
+  });
+}
+
 // -- HF Clone Operations -----------------------------------------------
 
 /**
@@ -194,6 +629,50 @@ export async function hfAutopwn(): Promise<WizardState> {
   return invoke<WizardState>('hf_autopwn');
 }
 
+/**
+ * After a partial autopwn, attack only the sectors still missing keys and
+ * re-dump with the completed key file. Progress via `hf-progress` events.
+ */
+export async function resumeAutopwn(
+  port: string,
+  cardType: string,
+  prng: string | null,
+): Promise<ResumeResult> {
+  return invoke<ResumeResult>('resume_autopwn', { port, cardType, prng });
+}
+
+export interface SectorDump {
+  sector: number;
+  /** Block hex per block of the sector; null where the key couldn't read it. */
+  blocks: (string | null)[];
+}
+
+/**
+ * Read only sectors `firstSector..=lastSector` of a MIFARE Classic card with
+ * the keys from the last autopwn. Faster than a full dump on 4K cards.
+ */
+export async function dumpSectors(
+  port: string,
+  cardType: string,
+  firstSector: number,
+  lastSector: number,
+): Promise<SectorDump[]> {
+  return invoke<SectorDump[]>('dump_sectors', { port, cardType, firstSector, lastSector });
+}
+
+/**
+ * Read one MIFARE Classic block with a given key. Use key type 'B' for
+ * sectors whose access bits only allow key B reads. Returns 32 hex chars.
+ */
+export async function readMfBlock(
+  port: string,
+  block: number,
+  key: string,
+  keyType: 'A' | 'B' = 'A',
+): Promise<string> {
+  return invoke<string>('read_mf_block', { port, block, key, keyType });
+}
+
 /**
  * Dump UL/NTAG or iCLASS card memory (no key recovery needed).
  * Fast operation. Rust handles FSM: CardIdentified → HfProcessing → HfDumpReady.
@@ -205,25 +684,40 @@ export async function hfDump(): Promise<WizardState> {
 /**
  * Write HF clone to magic blank card.
  * Dispatches to the correct write workflow based on blank type.
+ * Pass `acknowledgeUidWarning` to retry after an `AcknowledgeUidWarning` error.
  */
 export async function hfWriteClone(
   sourceUid: string,
   cardType: string,
   blankType: string,
+  acknowledgeUidWarning?: boolean,
 ): Promise<WizardState> {
-  return invoke<WizardState>('hf_write_clone', { sourceUid, cardType, blankType });
+  return invoke<WizardState>('hf_write_clone', {
+    sourceUid,
+    cardType,
+    blankType,
+    acknowledgeUidWarning,
+  });
 }
 
 /**
  * Verify an HF clone by reading back and comparing with source.
  * 2-layer: UID match (primary) + dump file comparison (secondary).
+ * Pass a saved card's dump as `referenceDumpPath` to compare against it
+ * instead of this session's dump; it must be a file in the data dir.
  */
 export async function hfVerifyClone(
   sourceUid: string,
   cardType: string,
   blankType: string,
+  referenceDumpPath?: string,
 ): Promise<WizardState> {
-  return invoke<WizardState>('hf_verify_clone', { sourceUid, cardType, blankType });
+  return invoke<WizardState>('hf_verify_clone', {
+    sourceUid,
+    cardType,
+    blankType,
+    referenceDumpPath: referenceDumpPath ?? null,
+  });
 }
 
 /**
@@ -233,6 +727,110 @@ export async function cancelHfOperation(): Promise<void> {
   return invoke<void>('cancel_hf_operation');
 }
 
+/**
+ * Copy only a DESFire card's 7-byte UID onto a Gen3 / Gen4 GTU / magic UL
+ * blank. Application data is NOT copied; `acknowledged` must be true once the
+ * user has seen that warning. An unsafe UID is refused until
+ * `acknowledgeUidWarning` confirms it.
+ */
+export async function cloneDesfireUid(
+  port: string,
+  uid: string,
+  blankType: BlankType,
+  acknowledged: boolean,
+  acknowledgeUidWarning?: boolean,
+): Promise<UidOnlyClone> {
+  return invoke<UidOnlyClone>('clone_desfire_uid', {
+    port, uid, blankType, acknowledged, acknowledgeUidWarning,
+  });
+}
+
+// -- Project Keyfile ---------------------------------------------------
+
+/**
+ * Add MIFARE Classic keys (12 hex chars each) to the persistent project
+ * keyfile used by autopwn. Returns the full deduplicated key list.
+ */
+export async function addKeysToProject(keys: string[]): Promise<string[]> {
+  return invoke<string[]>('add_keys_to_project', { keys });
+}
+
+/**
+ * Get all keys in the project keyfile.
+ */
+export async function getProjectKeys(): Promise<string[]> {
+  return invoke<string[]>('get_project_keys');
+}
+
+/**
+ * Remove every key from the project keyfile.
+ */
+export async function clearProjectKeys(): Promise<void> {
+  return invoke<void>('clear_project_keys');
+}
+
+/**
+ * Key/password dictionaries shipped with (or added to) the PM3 client.
+ */
+export async function listDictionaries(port: string): Promise<Pm3Dictionary[]> {
+  return invoke<Pm3Dictionary[]>('list_dictionaries', { port });
+}
+
+/**
+ * Use a dictionary for MIFARE Classic autopwn or T55xx password checks.
+ * Pass `null` to go back to the default choice.
+ */
+export async function selectDictionary(kind: DictionaryKind, path: string | null): Promise<void> {
+  return invoke<void>('select_dictionary', { kind, path });
+}
+
+// -- Dump Report -------------------------------------------------------
+
+/**
+ * Write a markdown report (UID, keys, access bits, value blocks) for a
+ * MIFARE Classic dump. keyPath defaults to the PM3 key file next to the dump.
+ */
+export async function exportDumpReport(
+  dumpPath: string,
+  keyPath?: string,
+): Promise<{ path: string; report: string }> {
+  return invoke<{ path: string; report: string }>('export_dump_report', {
+    dumpPath,
+    keyPath: keyPath ?? null,
+  });
+}
+
+/** Delete the last HF dump/key files and drop cached scans. Returns deleted paths. */
+export async function clearSensitiveState(): Promise<string[]> {
+  return invoke<string[]>('clear_sensitive_state');
+}
+
+/**
+ * Save a card plus the last HF dump/key files as one JSON bundle at `path`.
+ * Returns the path written.
+ */
+export async function exportCardBundle(card: SavedCard, path: string): Promise<string> {
+  return invoke<string>('export_card_bundle', { card, path });
+}
+
+/**
+ * Load a card bundle into the saved library and make its dump/key files the
+ * current HF files, ready to write. Returns the saved card with its new id.
+ */
+export async function importCardBundle(path: string): Promise<SavedCard> {
+  return invoke<SavedCard>('import_card_bundle', { path });
+}
+
+// -- Wiegand ----------------------------------------------------------
+
+/**
+ * Decode a raw Wiegand hex value under every known format (or only `format`,
+ * by name or bit length). Returns all plausible FC/CN readings, best first.
+ */
+export async function decodeRaw(hex: string, format?: string): Promise<WiegandDecode[]> {
+  return invoke<WiegandDecode[]>('decode_raw', { hex, format: format ?? null });
+}
+
 // -- Saved Cards -------------------------------------------------------
 
 /**
@@ -268,6 +866,34 @@ export async function deleteSavedCard(id: number): Promise<void> {
   return invoke<void>('delete_saved_card', { id });
 }
 
+export interface ClonePreview {
+  blankType: BlankType;
+  commands: string[];
+  /** Why no clone command can be built; `commands` is empty when set. */
+  error: string | null;
+}
+
+/**
+ * Show the PM3 commands a clone of a saved card onto `blankType` (default:
+ * the card's recommended blank) would send, including locking it with
+ * `setPassword`. No device interaction.
+ */
+export async function previewCloneCommand(
+  cardId: number,
+  blankType?: BlankType,
+  password?: string,
+  preferRaw?: boolean,
+  setPassword?: string,
+): Promise<ClonePreview> {
+  return invoke<ClonePreview>('preview_clone_command', {
+    cardId,
+    blankType: blankType ?? null,
+    preferRaw: preferRaw ?? null,
+    password: password ?? null,
+    setPassword: setPassword ?? null,
+  });
+}
+
 // -- Raw PM3 Command ---------------------------------------------------
 
 /**
@@ -277,3 +903,69 @@ export async function deleteSavedCard(id: number): Promise<void> {
 export async function runRawCommand(port: string, command: string): Promise<string> {
   return invoke<string>('run_raw_command', { port, command });
 }
+
+/**
+ * Run pasted PM3 output through every parser regex and report which matched
+ * and what they captured. For debugging parsing on new firmware.
+ */
+export async function debugParse(output: string): Promise<PatternMatch[]> {
+  return invoke<PatternMatch[]>('debug_parse', { output });
+}
+
+/**
+ * Forward PM3's ANSI colour codes to the terminal panel (`ansi` field on
+ * `pm3-output` events). Parsing is unaffected.
+ */
+export async function setTerminalAnsi(enabled: boolean): Promise<void> {
+  return invoke<void>('set_terminal_ansi', { enabled });
+}
+
+export type Pm3Verbosity = 'normal' | 'verbose' | 'debug';
+
+/**
+ * Default PM3 output verbosity for app-built commands: `verbose` adds `-v` to
+ * commands that take it, `debug` also raises the client debug level. Terminal
+ * commands run as typed. Parsing is unaffected.
+ */
+export async function setPm3Verbosity(verbosity: Pm3Verbosity): Promise<void> {
+  return invoke<void>('set_pm3_verbosity', { verbosity });
+}
+
+// -- Diagnostics -------------------------------------------------------
+
+export interface ParserSelfCheckResult {
+  name: string;
+  parser: 'LfSearch' | 'HfSearch' | 'AutopwnLine';
+  expected: string;
+  actual: string | null;
+  passed: boolean;
+}
+
+/**
+ * Run the bundled PM3 output corpus through the backend parsers.
+ * Needs no hardware; returns one pass/fail entry per sample.
+ */
+export async function runParserSelfcheck(): Promise<ParserSelfCheckResult[]> {
+  return invoke<ParserSelfCheckResult[]>('run_parser_selfcheck');
+}
+
+export interface BenchmarkReport {
+  cardType: string;
+  uid: string;
+  phases: { phase: 'scan' | 'detect' | 'clone' | 'verify'; millis: number }[];
+  totalMillis: number;
+  verified: boolean;
+}
+
+/**
+ * Advanced: time a clone -> verify cycle of the identified LF card onto the
+ * blank on the reader. Writes the blank; the source must be off the reader.
+ */
+export async function benchmarkClone(
+  port: string,
+  cardType: CardType,
+  uid: string,
+  decoded: Record<string, string>,
+): Promise<BenchmarkReport> {
+  return invoke<BenchmarkReport>('benchmark_clone', { port, cardType, uid, decoded });
+}
@@ -167,6 +167,12 @@ export const CARD_DISPLAY: Record<CardType, CardDisplayInfo> = {
   },
 
   // -- HF cards (13.56 MHz) --
+  MifareMini: {
+    displayName: 'MIFARE Mini',
+    frequency: 'HF',
+    blankType: 'MagicMifareGen1a',
+    description: '13.56 MHz MIFARE Classic Mini with 320 bytes of memory, 5 sectors',
+  },
   MifareClassic1K: {
     displayName: 'MIFARE Classic 1K',
     frequency: 'HF',
@@ -225,6 +231,17 @@ export const BLANK_DISPLAY: Record<BlankType, BlankDisplayInfo> = {
       'Noralsy', 'Jablotron', 'SecuraKey', 'Visa2000', 'Motorola', 'IDTECK',
     ],
   },
+  Q5: {
+    displayName: 'Q5 (T5555) Blank',
+    frequency: 'LF',
+    description: 'Budget T55xx-family 125 kHz blank, use with --q5 flag',
+    compatibleWith: [
+      'EM4100', 'HIDProx', 'Indala', 'IOProx', 'AWID', 'FDX_B',
+      'Paradox', 'Viking', 'Pyramid', 'Keri', 'NexWatch',
+      'Presco', 'Nedap', 'GProxII', 'Gallagher', 'PAC',
+      'Noralsy', 'Jablotron', 'SecuraKey', 'Visa2000', 'Motorola', 'IDTECK',
+    ],
+  },
   EM4305: {
     displayName: 'EM4305 Blank',
     frequency: 'LF',
@@ -235,31 +252,31 @@ export const BLANK_DISPLAY: Record<BlankType, BlankDisplayInfo> = {
     displayName: 'Magic MIFARE Gen1a',
     frequency: 'HF',
     description: 'UID-writable MIFARE Classic blank, backdoor commands supported',
-    compatibleWith: ['MifareClassic1K', 'MifareClassic4K'],
+    compatibleWith: ['MifareMini', 'MifareClassic1K', 'MifareClassic4K'],
   },
   MagicMifareGen2: {
     displayName: 'Magic MIFARE Gen2 (CUID)',
     frequency: 'HF',
     description: 'Direct-write MIFARE blank, no backdoor command needed',
-    compatibleWith: ['MifareClassic1K', 'MifareClassic4K'],
+    compatibleWith: ['MifareMini', 'MifareClassic1K', 'MifareClassic4K'],
   },
   MagicMifareGen3: {
     displayName: 'Magic MIFARE Gen3 (APDU)',
     frequency: 'HF',
     description: 'APDU-locked UID change, anti-detection features',
-    compatibleWith: ['MifareClassic1K', 'MifareClassic4K'],
+    compatibleWith: ['MifareMini', 'MifareClassic1K', 'MifareClassic4K'],
   },
   MagicMifareGen4GTU: {
     displayName: 'Magic MIFARE Gen4 GTU',
     frequency: 'HF',
     description: 'Ultimate magic card with password-protected configuration',
-    compatibleWith: ['MifareClassic1K', 'MifareClassic4K'],
+    compatibleWith: ['MifareMini', 'MifareClassic1K', 'MifareClassic4K'],
   },
   MagicMifareGen4GDM: {
     displayName: 'Magic MIFARE Gen4 GDM',
     frequency: 'HF',
     description: 'Gen4 with GDM write mode, supports DESFire emulation',
-    compatibleWith: ['MifareClassic1K', 'MifareClassic4K', 'DESFire'],
+    compatibleWith: ['MifareMini', 'MifareClassic1K', 'MifareClassic4K', 'DESFire'],
   },
   MagicUltralight: {
     displayName: 'Magic Ultralight Blank',
@@ -305,12 +322,14 @@ export const CARD_DECODED_FIELDS: Partial<Record<CardType, string[]>> = {
   EM4x50: [],
   Hitag: [],
   // -- HF cards (13.56 MHz) --
-  MifareClassic1K: ['uid', 'atqa', 'sak', 'prng', 'magic', 'uid_size'],
-  MifareClassic4K: ['uid', 'atqa', 'sak', 'prng', 'magic', 'uid_size'],
-  MifareUltralight: ['uid', 'atqa', 'sak', 'uid_size', 'ul_type'],
-  NTAG: ['uid', 'atqa', 'sak', 'uid_size', 'ntag_type'],
-  DESFire: ['uid', 'atqa', 'sak', 'ats'],
-  IClass: ['uid'],
+  // read_warning: set when two reads of the card disagreed
+  MifareMini: ['uid', 'atqa', 'sak', 'prng', 'magic', 'uid_size', 'read_warning'],
+  MifareClassic1K: ['uid', 'atqa', 'sak', 'prng', 'magic', 'uid_size', 'read_warning'],
+  MifareClassic4K: ['uid', 'atqa', 'sak', 'prng', 'magic', 'uid_size', 'read_warning'],
+  MifareUltralight: ['uid', 'atqa', 'sak', 'uid_size', 'ul_type', 'read_warning'],
+  NTAG: ['uid', 'atqa', 'sak', 'uid_size', 'ntag_type', 'read_warning'],
+  DESFire: ['uid', 'atqa', 'sak', 'ats', 'read_warning'],
+  IClass: ['uid', 'read_warning'],
 };
 
 /**
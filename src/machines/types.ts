@@ -32,6 +32,7 @@ export type CardType =
   | 'EM4x50'
   | 'Hitag'
   // HF cards
+  | 'MifareMini'
   | 'MifareClassic1K'
   | 'MifareClassic4K'
   | 'MifareUltralight'
@@ -41,6 +42,7 @@ export type CardType =
 
 export type BlankType =
   | 'T5577'
+  | 'Q5'
   | 'EM4305'
   | 'MagicMifareGen1a'
   | 'MagicMifareGen2'
@@ -50,7 +52,26 @@ export type BlankType =
   | 'MagicUltralight'
   | 'IClassBlank';
 
-export type RecoveryAction = 'Retry' | 'GoBack' | 'Reconnect' | 'Manual';
+// Matches Rust CardCategory enum — what a card type is used for
+export type CardCategory = 'Access' | 'Animal' | 'Industrial' | 'Nfc';
+
+// Display metadata for one card type (card_metadata command)
+export interface CardMetadata {
+  cardType: CardType;
+  name: string;
+  frequency: Frequency;
+  category: CardCategory;
+  description: string;
+  cloneable: boolean;
+}
+
+export type RecoveryAction =
+  | 'Retry'
+  | 'GoBack'
+  | 'Reconnect'
+  | 'Manual'
+  | 'EnterPassword'
+  | 'AcknowledgeUidWarning';
 
 // Matches Rust ProcessPhase enum — autopwn attack phases
 export type ProcessPhase = 'KeyCheck' | 'Darkside' | 'Nested' | 'Hardnested' | 'StaticNested' | 'Dumping';
@@ -61,6 +82,9 @@ export interface HfProgressPayload {
   keys_found: number;
   keys_total: number;
   elapsed_secs: number;
+  // Seconds since the whole HF job (autopwn/dump → write → verify) started
+  total_elapsed_secs: number;
+  operation_id: number | null;
 }
 
 export interface CardData {
@@ -85,6 +109,22 @@ export interface CloneRecord {
   success: boolean;
   timestamp: string;
   notes: string | null;
+  field_verified?: boolean | null;
+  // Archived source card bundle (always_dump_source); null if none was kept
+  source_dump?: string | null;
+}
+
+// Result of a DESFire UID-only clone (application data is never copied)
+export interface UidOnlyClone {
+  uid: string;
+  verified: boolean;
+  warning: string;
+}
+
+// History database health, from `database_status`
+export interface DatabaseStatus {
+  persistent: boolean;
+  warning: string | null;
 }
 
 // Device information returned on successful connection
@@ -101,6 +141,8 @@ export interface CardIdentification {
   card_data: CardData;
   cloneable: boolean;
   recommended_blank: BlankType;
+  // Earlier clones of this UID from history, newest first
+  previous_clones: CloneRecord[];
 }
 
 // Write progress during clone operation
@@ -110,10 +152,33 @@ export interface WriteProgress {
   total_blocks: number | null;
 }
 
+// End-of-clone report, LF or HF (camelCase from Rust)
+export interface OperationSummary {
+  sourceType: CardType;
+  sourceUid: string;
+  blankType: BlankType;
+  commandsRun: string[];
+  success: boolean;
+  verified: boolean;
+  durationMs: number;
+  warnings: string[];
+  // Source card bundle archived before the write, if any
+  sourceDump?: string | null;
+}
+
+// Last clone started, persisted across restarts
+export interface LastOperation {
+  summary: OperationSummary;
+  // False while running, or if the app closed before the clone finished
+  finished: boolean;
+  updatedAt: string;
+}
+
 // Verification result after clone
 export interface VerificationResult {
   success: boolean;
   mismatched_blocks: number[];
+  summary: OperationSummary | null;
 }
 
 // Clone completion summary
@@ -121,6 +186,15 @@ export interface CloneCompletion {
   source: CardSummary;
   target: CardSummary;
   timestamp: string;
+  // Door-reader test outcome; null if the user skipped it
+  field_verified: boolean | null;
+}
+
+// Waiting for the user to try the clone on the real reader
+export interface FieldTestInfo {
+  source: CardSummary;
+  target: CardSummary;
+  timestamp: string;
 }
 
 // Error details from any failed operation
@@ -139,13 +213,14 @@ export type WizardState =
   | { step: 'ScanningCard' }
   | { step: 'CardIdentified'; data: CardIdentification }
   | { step: 'WaitingForBlank'; data: { expected_blank: BlankType } }
-  | { step: 'BlankDetected'; data: { blank_type: BlankType; ready_to_write: boolean; existing_data_type: string | null } }
+  | { step: 'BlankDetected'; data: { blank_type: BlankType; ready_to_write: boolean; existing_data_type: string | null; uid_length_warning: string | null } }
   | { step: 'Writing'; data: WriteProgress }
   | { step: 'HfProcessing'; data: { phase: string; keys_found: number; keys_total: number; elapsed_secs: number } }
   | { step: 'HfDumpReady'; data: { dump_info: string } }
   | { step: 'Verifying' }
   | { step: 'VerificationComplete'; data: VerificationResult }
   | { step: 'Complete'; data: CloneCompletion }
+  | { step: 'FieldTesting'; data: FieldTestInfo }
   | { step: 'Error'; data: ErrorDetails };
 
 // Extract the step name as a string literal type
@@ -168,6 +243,98 @@ export interface FirmwareCheckResult {
   deviceFirmwareVersion: string;
   hardwareVariant: 'rdv4' | 'rdv4-bt' | 'generic' | 'generic-256' | 'unknown';
   firmwarePathExists: boolean;
+  bootloaderMode: boolean;
+  fork: FirmwareFork;
+}
+
+// Proxmark3 codebase from the `hw version` banner; most features need Iceman
+export type FirmwareFork = 'iceman' | 'official' | 'unknown';
+
+// Matches Rust ClientBinaryInfo — returned by verify_pm3_binary
+export interface ClientBinaryInfo {
+  binary: string;
+  version: string;
+  fork: FirmwareFork;
+  build: number | null;
+  supported: boolean;
+  warning: string | null;
+}
+
+// Feature-availability snapshot from get_capabilities
+export type DeviceOperation =
+  | 'DetectDevice'
+  | 'ScanCard'
+  | 'WriteClone'
+  | 'HfAutopwn'
+  | 'WipeChip'
+  | 'RawCommand'
+  | 'FlashFirmware'
+  | 'FlashDictionary'
+  | 'Smartcard'
+  | 'DeviceStatus'
+  | 'Cancel';
+
+export interface DeviceCapabilities {
+  connected: boolean;
+  busy: boolean;
+  port: string | null;
+  model: string | null;
+  firmware: string | null;
+  hardwareVariant: 'rdv4' | 'rdv4-bt' | 'generic' | 'generic-256' | null;
+  firmwareMatchesClient: boolean;
+  fork: FirmwareFork | null;
+  hardware: {
+    externalFlash: boolean;
+    smartcard: boolean;
+    fpcUsart: boolean;
+  };
+  availableOperations: DeviceOperation[];
+}
+
+// Matches Rust DeviceStatus — parsed `hw status` (RDV4 only)
+export interface DeviceStatus {
+  uptime: string | null;
+  availableMemory: number | null;
+  flashSize: string | null;
+  dictionaries: { name: string; keys: number }[];
+  standaloneMode: string | null;
+  batteryMv: number | null;
+}
+
+// Matches Rust Dictionary — a PM3 client key/password dictionary (list_dictionaries)
+export type DictionaryKind = 'mifareClassic' | 't55xx' | 'iClass' | 'other';
+
+export interface Pm3Dictionary {
+  name: string;
+  path: string;
+  kind: DictionaryKind;
+}
+
+// Matches Rust AboutInfo — returned by about_info
+export interface AboutInfo {
+  appVersion: string;
+  pm3Model: string | null;
+  pm3Version: string | null;
+  targetOs: string;
+  targetArch: string;
+  features: string[];
+}
+
+// Matches Rust FirmwareFeature / FeatureStatus — returned by feature_availability
+export type FirmwareFeature =
+  | 'Autopwn'
+  | 'CliSyntax'
+  | 'Gen4Gtu'
+  | 'MfInfo'
+  | 'Gen4Gdm'
+  | 'GallagherClone'
+  | 'NexwatchClone';
+
+export interface FeatureStatus {
+  feature: FirmwareFeature;
+  available: boolean | null;
+  minVersion: string;
+  reason: string | null;
 }
 
 // Firmware flash progress event payload (emitted via Tauri events)
@@ -197,4 +364,79 @@ export interface T5577Status {
   password_set: boolean;
   block0: string | null;
   modulation: string | null;
+  // Block 0 has the OTP flag set; the clone keeps the current modulation
+  locked: boolean;
+}
+
+// One interpretation of a raw Wiegand value (decode_raw)
+export interface WiegandDecode {
+  format: string;
+  bitLength: number;
+  facilityCode: number | null;
+  cardNumber: number;
+  parityValid: boolean;
+  sentinel: boolean;
+}
+
+// Result of recommend_hf_blank: can the blank on the reader take the clone?
+export interface BlankCompatibility {
+  compatible: boolean;
+  generation: 'Gen1a' | 'Gen2' | 'Gen3' | 'Gen4GTU' | 'Gen4GDM' | null;
+  blankType: BlankType | null;
+  reason: string;
+}
+
+// Result of resume_autopwn (Rust ResumeResult, flattened into OperationResult)
+export interface ResumeResult {
+  operationId: number;
+  recovered: number;
+  stillMissing: number[];
+  dumpPath: string | null;
+}
+
+// Matches Rust JobSpec — work queued by submit_job
+export type JobSpec =
+  | {
+      kind: 'lfClone';
+      port: string;
+      cardType: string;
+      uid: string;
+      decoded?: Record<string, string>;
+      blankType?: string;
+      preferRaw?: boolean;
+      password?: string;
+      setPassword?: string;
+    }
+  | {
+      kind: 'hfClone';
+      sourceUid: string;
+      cardType: string;
+      blankType: string;
+      acknowledgeUidWarning?: boolean;
+    };
+
+export type JobPhase = 'Queued' | 'Running' | 'Succeeded' | 'Failed' | 'Cancelled';
+
+// Matches Rust JobStatus — returned by get_job_status
+export interface JobStatus {
+  jobId: number;
+  phase: JobPhase;
+  result: unknown | null;
+  error: string | null;
+}
+
+// Matches Rust PatternMatch — returned by debug_parse
+export interface PatternMatch {
+  name: string;
+  pattern: string;
+  matched: boolean;
+  captures: (string | null)[];
+}
+
+// Result of clone_sequence (Rust SequenceResult, flattened into OperationResult)
+export interface SequenceResult {
+  operationId: number;
+  written: number[];
+  total: number;
+  cancelled: boolean;
 }
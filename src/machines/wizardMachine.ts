@@ -10,6 +10,7 @@ import type {
   RecoveryAction,
   WizardState,
   FirmwareCheckResult,
+  OperationSummary,
 } from './types';
 import * as api from '../lib/api';
 
@@ -50,15 +51,19 @@ export interface WizardContext {
   blankType: BlankType | null;
   readyToWrite: boolean;
   blankExistingData: string | null;
+  blankUidLengthWarning: string | null;
 
   // Write progress
   writeProgress: number;
   currentBlock: number | null;
   totalBlocks: number | null;
+  // Seconds since the HF job began (autopwn/dump), null for LF writes
+  jobElapsed: number | null;
 
   // Verification
   verifySuccess: boolean | null;
   mismatchedBlocks: number[];
+  operationSummary: OperationSummary | null;
 
   // Completion
   completionTimestamp: string | null;
@@ -100,11 +105,14 @@ const initialContext: WizardContext = {
   blankType: null,
   readyToWrite: false,
   blankExistingData: null,
+  blankUidLengthWarning: null,
   writeProgress: 0,
   currentBlock: null,
   totalBlocks: null,
+  jobElapsed: null,
   verifySuccess: null,
   mismatchedBlocks: [],
+  operationSummary: null,
   completionTimestamp: null,
   errorMessage: null,
   errorUserMessage: null,
@@ -136,11 +144,14 @@ const clearCardFields: Partial<WizardContext> = {
   blankType: null,
   readyToWrite: false,
   blankExistingData: null,
+  blankUidLengthWarning: null,
   writeProgress: 0,
   currentBlock: null,
   totalBlocks: null,
+  jobElapsed: null,
   verifySuccess: null,
   mismatchedBlocks: [],
+  operationSummary: null,
   completionTimestamp: null,
   errorMessage: null,
   errorUserMessage: null,
@@ -164,7 +175,13 @@ export type WizardEvent =
   | { type: 'SKIP_TO_BLANK'; expectedBlank: BlankType }
   | { type: 'BLANK_READY'; blankType: BlankType; readyToWrite: boolean }
   | { type: 'WRITE' }
-  | { type: 'WRITE_PROGRESS'; progress: number; currentBlock: number | null; totalBlocks: number | null }
+  | {
+      type: 'WRITE_PROGRESS';
+      progress: number;
+      currentBlock: number | null;
+      totalBlocks: number | null;
+      jobElapsed: number | null;
+    }
   | { type: 'WRITE_COMPLETE' }
   | { type: 'VERIFY_RESULT'; success: boolean; mismatchedBlocks: number[] }
   | { type: 'FINISH' }
@@ -251,7 +268,11 @@ export const wizardMachine = setup({
     // HF: autopwn (Classic) or simple dump (UL/NTAG/iCLASS)
     runHfProcess: fromPromise<WizardState, WizardContext>(async ({ input }) => {
       if (!input.cardType) throw new Error('No card type identified');
-      if (input.cardType === 'MifareClassic1K' || input.cardType === 'MifareClassic4K') {
+      if (
+        input.cardType === 'MifareMini' ||
+        input.cardType === 'MifareClassic1K' ||
+        input.cardType === 'MifareClassic4K'
+      ) {
         return api.hfAutopwn();
       }
       return api.hfDump();
@@ -700,6 +721,11 @@ export const wizardMachine = setup({
                 if (ws.step === 'BlankDetected') return ws.data.existing_data_type;
                 return null;
               },
+              blankUidLengthWarning: ({ event }) => {
+                const ws = event.output;
+                if (ws.step === 'BlankDetected') return ws.data.uid_length_warning;
+                return null;
+              },
             }),
           },
           {
@@ -741,6 +767,7 @@ export const wizardMachine = setup({
             blankType: () => null,
             readyToWrite: () => false,
             blankExistingData: () => null,
+            blankUidLengthWarning: () => null,
           }),
         },
         RESET: { target: 'idle', actions: assign(() => initialContext) },
@@ -803,6 +830,7 @@ export const wizardMachine = setup({
             writeProgress: ({ event }) => event.progress,
             currentBlock: ({ event }) => event.currentBlock,
             totalBlocks: ({ event }) => event.totalBlocks,
+            jobElapsed: ({ event }) => event.jobElapsed,
           }),
         },
         RESET: { target: 'idle', actions: assign(() => initialContext) },
@@ -828,6 +856,11 @@ export const wizardMachine = setup({
                 if (ws.step === 'VerificationComplete') return ws.data.mismatched_blocks;
                 return [];
               },
+              operationSummary: ({ event }) => {
+                const ws = event.output;
+                if (ws.step === 'VerificationComplete') return ws.data.summary;
+                return null;
+              },
             }),
           },
           {